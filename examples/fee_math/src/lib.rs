@@ -0,0 +1,7 @@
+//! Fee computation rounding through `f64`, checked with
+//! `solana-program-analyzer --manifest-path examples/fee_math`.
+
+/// Fee in lamports for `amount` at `rate_bps` basis points.
+pub fn fee(amount: u64, rate_bps: u16) -> u64 {
+    (amount as f64 * f64::from(rate_bps) / 10_000.0).round() as u64
+}
@@ -0,0 +1,527 @@
+//! Per-crate analysis reports and their combination across the programs of a
+//! workspace.
+//!
+//! [`AnalysisReport`] is the schema of every JSON output: `--report`,
+//! `--analyzer-format=json`, the reports `--analyzer-diff` reads and, combined,
+//! `cargo solana-analyze --format json`. Bump `SCHEMA_VERSION` when a field is
+//! renamed, removed or changes meaning; adding a field does not need it.
+//!
+//! Each crate is analyzed by its own rustc process, which writes an
+//! [`AnalysisReport`] with `--report <path>`. [`combine`] then resolves the CPIs
+//! of each program against the program ids and names of its siblings.
+//! Without the other reports, [`workspace_callee`] resolves a CPI against the
+//! programs found in the crate's workspace alone.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::metadata::{ProgramType, WorkspaceProgram};
+
+/// Module of the CPI helpers Anchor generates for each instruction of a program,
+/// e.g. `other_program::cpi::deposit`.
+const CPI_MODULE: &str = "::cpi::";
+
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Directory the analyzer writes the report of each crate it analyzes into, as
+/// `<crate>.json`, when no `--report` path is given.
+pub const REPORT_DIR_ENV: &str = "SOLANA_ANALYZER_REPORT_DIR";
+/// Exit code of the analyzer and its wrappers when a finding reaches the
+/// `--analyzer-deny` severity, apart from the 1 of a failed build and the 101
+/// cargo exits with on any failure and rustc with on an ICE.
+pub const DENIED_EXIT_CODE: u8 = 3;
+/// File a denied crate appends its name to, instead of failing, when the
+/// analyzer runs under cargo for a wrapper: cargo exits with 101 on any
+/// failure, so the wrapper tells denials from build errors by this file.
+pub const DENIED_FILE_ENV: &str = "SOLANA_ANALYZER_DENIED_FILE";
+/// Severities of findings as `ReportFinding::severity` names them, from the
+/// lowest.
+pub const SEVERITIES: [&str; 3] = ["info", "warning", "error"];
+
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error("failed to read {0}: {1}")]
+    Read(String, io::Error),
+    #[error("failed to write {0}: {1}")]
+    Write(String, io::Error),
+    #[error("failed to parse {0}: {1}")]
+    Parse(String, serde_json::Error),
+}
+
+/// What the analyzer extracted from one crate and found in it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AnalysisReport {
+    pub schema_version: u32,
+    pub tool: Tool,
+    pub crate_name: String,
+    /// The declared program id, in base58.
+    pub program_id: Option<String>,
+    /// The framework the program is written in, by its dependencies; `None`
+    /// when the manifest could not be read.
+    #[serde(default)]
+    pub program_type: Option<ProgramType>,
+    pub cpi_calls: Vec<ReportCpi>,
+    /// Sorted by checker and then message.
+    pub findings: Vec<ReportFinding>,
+    /// The findings again, under each instruction handler reaching the fn they
+    /// originate in.
+    #[serde(default)]
+    pub handlers: Vec<ReportHandler>,
+    #[serde(default)]
+    pub model: ModelSummary,
+    /// The other programs of the crate's workspace.
+    #[serde(default)]
+    pub workspace_programs: Vec<WorkspaceProgram>,
+    /// What each extraction pass and checker took, with `--analyzer-timings`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<Vec<PassTiming>>,
+}
+
+/// The analyzer that wrote a report.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub version: String,
+}
+
+impl Default for Tool {
+    fn default() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReportCpi {
+    pub handler: String,
+    pub location: String,
+    pub target: ReportTarget,
+    /// The `CpiContext` helper or instruction builder called, if any.
+    pub instruction: Option<String>,
+    pub accounts: Vec<String>,
+    /// The program of the workspace it calls into, `None` for a program from
+    /// outside the workspace or a target the crate does not tell.
+    #[serde(default)]
+    pub workspace_callee: Option<String>,
+}
+
+/// The program a CPI calls, as far as the crate alone tells.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportTarget {
+    /// A constant program id, in base58.
+    Const(String),
+    /// The account field passed as the program.
+    Field(String),
+    Dynamic,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReportFinding {
+    pub checker: String,
+    /// `info`, `warning` or `error`, after the levels of the config.
+    pub severity: String,
+    pub message: String,
+    /// What the finding points at.
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+    /// Other places the finding involves, e.g. the handlers taking an Accounts
+    /// struct.
+    #[serde(default)]
+    pub related: Vec<SourceLocation>,
+    /// Stays the same across runs and unrelated edits, for tracking findings.
+    #[serde(default)]
+    pub fingerprint: String,
+    /// Whether the fingerprint is in the `--analyzer-baseline` file.
+    #[serde(default)]
+    pub baselined: bool,
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+/// A place in the source, with the file as rustc names it and the line and
+/// column it starts at, both from 1.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// The findings in one instruction handler and the local fns it calls.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReportHandler {
+    pub handler: String,
+    pub findings: Vec<ReportFinding>,
+}
+
+/// What was extracted from the crate.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ModelSummary {
+    /// The handlers of an Anchor `#[program]`.
+    pub instructions: Vec<ReportInstruction>,
+    /// The instructions a native entrypoint dispatches to, by tag.
+    pub native_instructions: Vec<ReportNativeInstruction>,
+    /// The Accounts structs of an Anchor program.
+    pub accounts: Vec<ReportAccounts>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReportInstruction {
+    pub name: String,
+    /// Path of the handler fn.
+    pub handler: String,
+    /// Short name of the `T` of its `Context<T>`.
+    pub accounts: String,
+    pub args: Vec<ReportArg>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReportArg {
+    pub name: String,
+    pub ty: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReportNativeInstruction {
+    pub tag: u128,
+    /// The variant of the instruction enum, when the data is decoded into one.
+    pub variant: Option<String>,
+    pub handler: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReportAccounts {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PassKind {
+    /// A part of the `AnalysisContext`, extracted on first use.
+    Extraction,
+    /// A `Checker` of the registry.
+    Checker,
+}
+
+impl fmt::Display for PassKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassKind::Extraction => write!(f, "extraction"),
+            PassKind::Checker => write!(f, "checker"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PassTiming {
+    /// The part of the context, or the checker id.
+    pub pass: String,
+    pub kind: PassKind,
+    /// Wall time, in microseconds.
+    pub micros: u64,
+    /// The items extracted, or the findings of a checker.
+    pub items: usize,
+}
+
+impl PassTiming {
+    pub fn new(pass: impl Into<String>, kind: PassKind, took: Duration, items: usize) -> Self {
+        Self {
+            pass: pass.into(),
+            kind,
+            micros: took.as_micros().try_into().unwrap_or(u64::MAX),
+            items,
+        }
+    }
+}
+
+/// An empty report of the current schema, by this analyzer.
+impl Default for AnalysisReport {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            tool: Tool::default(),
+            crate_name: String::new(),
+            program_id: None,
+            program_type: None,
+            cpi_calls: vec![],
+            findings: vec![],
+            handlers: vec![],
+            model: ModelSummary::default(),
+            workspace_programs: vec![],
+            timings: None,
+        }
+    }
+}
+
+impl AnalysisReport {
+    pub fn read(path: &Path) -> Result<Self, ReportError> {
+        let display = path.display().to_string();
+        let json =
+            fs::read_to_string(path).map_err(|err| ReportError::Read(display.clone(), err))?;
+        serde_json::from_str(&json).map_err(|err| ReportError::Parse(display, err))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ReportError> {
+        fs::write(path, self.to_json())
+            .map_err(|err| ReportError::Write(path.display().to_string(), err))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reports serialize to JSON")
+    }
+
+    /// Whether a finding not in the baseline is at or above `level`, one of
+    /// `SEVERITIES`.
+    pub fn has_findings_at(&self, level: &str) -> bool {
+        let rank = |severity: &str| SEVERITIES.iter().position(|known| *known == severity);
+        self.findings
+            .iter()
+            .any(|finding| !finding.baselined && rank(&finding.severity) >= rank(level))
+    }
+}
+
+/// The reports of several crates, with the CPIs between them.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CombinedReport {
+    pub reports: Vec<AnalysisReport>,
+    pub cross_program_cpis: Vec<CrossProgramCpi>,
+}
+
+/// A CPI of one analyzed crate into another.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CrossProgramCpi {
+    pub caller: String,
+    pub handler: String,
+    pub location: String,
+    pub instruction: Option<String>,
+    pub callee: String,
+}
+
+/// One line per crate, then one per CPI between them.
+impl fmt::Display for CombinedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for report in &self.reports {
+            writeln!(
+                f,
+                "crate {}: program id {}, {} CPIs, {} findings",
+                report.crate_name,
+                report.program_id.as_deref().unwrap_or("unknown"),
+                report.cpi_calls.len(),
+                report.findings.len()
+            )?;
+        }
+        for cpi in &self.cross_program_cpis {
+            writeln!(
+                f,
+                "cross-program cpi at {} in {}: {} into {}",
+                cpi.location,
+                cpi.handler,
+                cpi.instruction.as_deref().unwrap_or("<instruction>"),
+                cpi.callee
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Combine the reports of the crates of a workspace. A CPI resolves to a sibling
+/// when its constant target is the sibling's program id, or when it calls a
+/// helper of the sibling's generated `cpi` module.
+pub fn combine(reports: Vec<AnalysisReport>) -> CombinedReport {
+    let mut cross_program_cpis = vec![];
+    for caller in &reports {
+        for cpi in &caller.cpi_calls {
+            let callee = reports.iter().find(|callee| {
+                callee.crate_name != caller.crate_name && reports_call_into(cpi, callee)
+            });
+            if let Some(callee) = callee {
+                cross_program_cpis.push(CrossProgramCpi {
+                    caller: caller.crate_name.clone(),
+                    handler: cpi.handler.clone(),
+                    location: cpi.location.clone(),
+                    instruction: cpi.instruction.clone(),
+                    callee: callee.crate_name.clone(),
+                });
+            }
+        }
+    }
+    CombinedReport {
+        reports,
+        cross_program_cpis,
+    }
+}
+
+/// The program of `programs` that `cpi`, made by a program with id
+/// `caller_id`, calls into, by the same rules as [`combine`]. Programs sharing
+/// the caller's id are left out, as a CPI to it is the caller calling itself.
+pub fn workspace_callee<'a>(
+    cpi: &ReportCpi,
+    caller_id: Option<&str>,
+    programs: &'a [WorkspaceProgram],
+) -> Option<&'a WorkspaceProgram> {
+    programs.iter().find(|program| {
+        (caller_id.is_none() || program.program_id.as_deref() != caller_id)
+            && calls_into(cpi, &program.name, program.program_id.as_deref())
+    })
+}
+
+fn reports_call_into(cpi: &ReportCpi, callee: &AnalysisReport) -> bool {
+    calls_into(cpi, &callee.crate_name, callee.program_id.as_deref())
+}
+
+fn calls_into(cpi: &ReportCpi, crate_name: &str, program_id: Option<&str>) -> bool {
+    if let ReportTarget::Const(id) = &cpi.target
+        && program_id == Some(id.as_str())
+    {
+        return true;
+    }
+    let cpi_module = format!("{}{CPI_MODULE}", crate_name.replace('-', "_"));
+    cpi.instruction
+        .as_ref()
+        .is_some_and(|instruction| instruction.starts_with(&cpi_module))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_findings_at() {
+        let finding = |severity: &str| ReportFinding {
+            checker: "seeds".to_string(),
+            severity: severity.to_string(),
+            message: String::new(),
+            location: None,
+            related: vec![],
+            fingerprint: String::new(),
+            baselined: false,
+            notes: vec![],
+        };
+        let mut report = AnalysisReport::default();
+        assert!(!report.has_findings_at("warning"));
+        report.findings.push(finding("warning"));
+        assert!(report.has_findings_at("warning"));
+        assert!(!report.has_findings_at("error"));
+        report.findings.push(finding("error"));
+        assert!(report.has_findings_at("error"));
+        report.findings[1].baselined = true;
+        assert!(!report.has_findings_at("error"));
+    }
+
+    fn cpi(target: ReportTarget, instruction: Option<&str>) -> ReportCpi {
+        ReportCpi {
+            handler: "caller::forward".to_string(),
+            location: "lib.rs:10".to_string(),
+            target,
+            instruction: instruction.map(String::from),
+            accounts: vec![],
+            workspace_callee: None,
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let vault = AnalysisReport {
+            crate_name: "vault".to_string(),
+            program_id: Some("Vau1t11111111111111111111111111111111111111".to_string()),
+            ..Default::default()
+        };
+        let caller = AnalysisReport {
+            crate_name: "caller".to_string(),
+            program_id: Some("Ca11er1111111111111111111111111111111111111".to_string()),
+            cpi_calls: vec![
+                cpi(
+                    ReportTarget::Field("vault_program".to_string()),
+                    Some("vault::cpi::deposit"),
+                ),
+                cpi(
+                    ReportTarget::Const("Vau1t11111111111111111111111111111111111111".to_string()),
+                    None,
+                ),
+                cpi(
+                    ReportTarget::Const("11111111111111111111111111111111".to_string()),
+                    None,
+                ),
+                cpi(ReportTarget::Dynamic, Some("anchor_spl::token::transfer")),
+            ],
+            ..Default::default()
+        };
+        let combined = combine(vec![vault, caller]);
+        let resolved: Vec<_> = combined
+            .cross_program_cpis
+            .iter()
+            .map(|cpi| {
+                (
+                    cpi.caller.as_str(),
+                    cpi.instruction.as_deref(),
+                    cpi.callee.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            resolved,
+            [
+                ("caller", Some("vault::cpi::deposit"), "vault"),
+                ("caller", None, "vault"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_workspace_callee() {
+        let program = |name: &str, id: &str| WorkspaceProgram {
+            name: name.to_string(),
+            path: name.into(),
+            program_id: Some(id.to_string()),
+        };
+        let programs = [
+            program("caller", "Ca11er1111111111111111111111111111111111111"),
+            program("twin", "Ca11er1111111111111111111111111111111111111"),
+            program("vault", "Vau1t11111111111111111111111111111111111111"),
+        ];
+        let caller_id = Some("Ca11er1111111111111111111111111111111111111");
+        let callee = |cpi: ReportCpi| {
+            workspace_callee(&cpi, caller_id, &programs).map(|program| program.name.as_str())
+        };
+        assert_eq!(
+            callee(cpi(
+                ReportTarget::Const("Vau1t11111111111111111111111111111111111111".to_string()),
+                None
+            )),
+            Some("vault")
+        );
+        assert_eq!(
+            callee(cpi(ReportTarget::Dynamic, Some("vault::cpi::deposit"))),
+            Some("vault")
+        );
+        assert_eq!(
+            callee(cpi(
+                ReportTarget::Const("Ca11er1111111111111111111111111111111111111".to_string()),
+                None
+            )),
+            None
+        );
+        assert_eq!(
+            callee(cpi(
+                ReportTarget::Dynamic,
+                Some("anchor_spl::token::transfer")
+            )),
+            None
+        );
+    }
+}
@@ -1,8 +1,17 @@
 use std::collections::HashSet;
+use std::rc::Rc;
 
-use rustc_public::{mir::{mono::Instance, TerminatorKind}, ty::{RigidTy, TyKind}, ItemKind};
+use rustc_public::{
+    ItemKind,
+    mir::{Body, TerminatorKind, mono::Instance},
+    ty::{RigidTy, TyKind},
+};
 
-pub fn compute_instances() -> HashSet<Instance> {
+use crate::analysis::graph::DirectedGraph;
+use crate::analysis::internal::reachability::reachable_in_order;
+
+/// Local fns that can be analyzed without monomorphization.
+pub fn local_instances() -> Vec<Instance> {
     let mut local_instances = vec![];
     for item in rustc_public::all_local_items() {
         if let ItemKind::Fn = item.kind()
@@ -11,30 +20,55 @@ pub fn compute_instances() -> HashSet<Instance> {
                 local_instances.push(instance);
         }
     }
-    // for instance in local_instances {
-        // println!("{}", instance.name());
-    // }
+    local_instances
+}
+
+/// The call graph of the local fns and every instance they call, transitively,
+/// with an edge per direct call. `body` builds or looks up the body of an
+/// instance, so a caller caching bodies shares them with the walk.
+pub fn call_graph(mut body: impl FnMut(Instance) -> Option<Rc<Body>>) -> DirectedGraph<Instance> {
+    // Closures and the instances of generic fns are only found through the
+    // fns using them.
+    let local_instances = reachable_in_order(&local_instances());
 
-    let mut worklist = local_instances.clone();
-    let mut nodes: HashSet<Instance> = local_instances.into_iter().collect();
+    let mut graph = DirectedGraph::new();
+    for instance in &local_instances {
+        graph.add_node(*instance);
+    }
+    let mut worklist = local_instances;
+    let mut nodes: HashSet<Instance> = worklist.iter().copied().collect();
     while let Some(curr) = worklist.pop() {
-        if let Some(ref body) = curr.body() {
-            for block in &body.blocks {
-                if let TerminatorKind::Call {
-                    ref func,
-                    ..
-                } = block.terminator.kind {
-                    let fn_ty = func.ty(body.locals()).unwrap();
-                    if let TyKind::RigidTy(RigidTy::FnDef(fn_def, args)) = fn_ty.kind() {
-                        let instance = Instance::resolve(fn_def, &args).unwrap();
-                        if nodes.insert(instance) {
-                            worklist.push(instance);
-                        }
-                    }
-                }
+        let Some(body) = body(curr) else {
+            continue;
+        };
+        for bb in 0..body.blocks.len() {
+            let Some(instance) = callee(&body, bb) else {
+                continue;
+            };
+            if nodes.insert(instance) {
+                graph.add_node(instance);
+                worklist.push(instance);
             }
+            graph.add_edge(curr, instance);
         }
     }
 
-    return nodes
-}
\ No newline at end of file
+    graph
+}
+/// The instance called by the terminator of `block`, if it is a direct call.
+pub fn callee(body: &Body, block: usize) -> Option<Instance> {
+    let TerminatorKind::Call { ref func, .. } = body.blocks[block].terminator.kind else {
+        return None;
+    };
+    let fn_ty = func.ty(body.locals()).ok()?;
+    let TyKind::RigidTy(RigidTy::FnDef(fn_def, args)) = fn_ty.kind() else {
+        return None;
+    };
+    Instance::resolve(fn_def, &args).ok()
+}
+
+/// Local instances transitively reachable from `entry`, in discovery order.
+/// Calls into other crates are not followed.
+pub fn reachable_local_instances(entry: Instance) -> Vec<Instance> {
+    reachable_in_order(&[entry])
+}
@@ -0,0 +1,110 @@
+//! The control-flow graph of a MIR body, as printed by `--dump-cfg`.
+use rustc_public::mir::{Body, TerminatorKind, UnwindAction};
+
+use super::graph::DirectedGraph;
+
+/// Blocks of `body` connected by the successors of their terminators.
+pub fn cfg(body: &Body) -> DirectedGraph<usize> {
+    let mut graph = DirectedGraph::new();
+    for (block, data) in body.blocks.iter().enumerate() {
+        graph.add_node(block);
+        let mut succs: Vec<usize> = vec![];
+        for (succ, _) in labeled_successors(&data.terminator.kind) {
+            if !succs.contains(&succ) {
+                succs.push(succ);
+                graph.add_edge(block, succ);
+            }
+        }
+    }
+    graph
+}
+
+/// The CFG of `body` in DOT. Nodes show the block index and terminator kind,
+/// edges the kind and the branch taken, e.g. `SwitchInt 0` or `Call unwind`.
+pub fn cfg_dot(name: &str, body: &Body) -> String {
+    cfg(body).to_dot(
+        name,
+        |block| {
+            format!(
+                "bb{block}: {}",
+                terminator_name(&body.blocks[*block].terminator.kind)
+            )
+        },
+        |from, to| {
+            let kind = &body.blocks[*from].terminator.kind;
+            let branches: Vec<String> = labeled_successors(kind)
+                .into_iter()
+                .filter(|(succ, _)| succ == to)
+                .map(|(_, branch)| branch)
+                .collect();
+            format!("{} {}", terminator_name(kind), branches.join(", "))
+        },
+    )
+}
+
+/// Name of the `TerminatorKind` variant.
+pub fn terminator_name(kind: &TerminatorKind) -> &'static str {
+    match kind {
+        TerminatorKind::Goto { .. } => "Goto",
+        TerminatorKind::SwitchInt { .. } => "SwitchInt",
+        TerminatorKind::Resume => "Resume",
+        TerminatorKind::Abort => "Abort",
+        TerminatorKind::Return => "Return",
+        TerminatorKind::Unreachable => "Unreachable",
+        TerminatorKind::Drop { .. } => "Drop",
+        TerminatorKind::Call { .. } => "Call",
+        TerminatorKind::Assert { .. } => "Assert",
+        TerminatorKind::InlineAsm { .. } => "InlineAsm",
+    }
+}
+
+/// Successors of a terminator with the branch leading to each, in the order
+/// `TerminatorKind::successors` lists them.
+fn labeled_successors(kind: &TerminatorKind) -> Vec<(usize, String)> {
+    let unwind = |unwind: &UnwindAction| match unwind {
+        UnwindAction::Cleanup(cleanup) => Some((*cleanup, "unwind".to_string())),
+        _ => None,
+    };
+    match kind {
+        TerminatorKind::Goto { target } => vec![(*target, "goto".to_string())],
+        TerminatorKind::SwitchInt { targets, .. } => targets
+            .branches()
+            .map(|(value, target)| (target, value.to_string()))
+            .chain([(targets.otherwise(), "otherwise".to_string())])
+            .collect(),
+        TerminatorKind::Drop {
+            target,
+            unwind: on_unwind,
+            ..
+        } => [(*target, "return".to_string())]
+            .into_iter()
+            .chain(unwind(on_unwind))
+            .collect(),
+        TerminatorKind::Assert {
+            target,
+            unwind: on_unwind,
+            ..
+        } => [(*target, "success".to_string())]
+            .into_iter()
+            .chain(unwind(on_unwind))
+            .collect(),
+        TerminatorKind::Call {
+            target,
+            unwind: on_unwind,
+            ..
+        }
+        | TerminatorKind::InlineAsm {
+            destination: target,
+            unwind: on_unwind,
+            ..
+        } => target
+            .map(|target| (target, "return".to_string()))
+            .into_iter()
+            .chain(unwind(on_unwind))
+            .collect(),
+        TerminatorKind::Resume
+        | TerminatorKind::Abort
+        | TerminatorKind::Return
+        | TerminatorKind::Unreachable => vec![],
+    }
+}
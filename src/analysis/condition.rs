@@ -0,0 +1,295 @@
+//! Extract comparisons feeding branch conditions and reconstruct what their
+//! operands were computed from.
+use std::collections::{HashMap, HashSet};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::{
+    BinOp, Body, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind,
+};
+use rustc_public::ty::{ConstantKind, RigidTy, Ty, UintTy};
+
+/// How far we follow copies and arithmetic back from an operand.
+const MAX_DEPTH: usize = 8;
+
+/// A comparison `lhs op rhs`, either assigned to a boolean local or folded into
+/// a `switchInt` on an integer (`x == 5` becomes `switchInt(x) -> [5: ..]`).
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub block: usize,
+    pub op: BinOp,
+    pub lhs: Side,
+    pub rhs: Side,
+}
+
+#[derive(Clone, Debug)]
+pub enum Side {
+    Operand(Operand),
+    /// A `switchInt` target value.
+    Const(i128),
+}
+
+impl Comparison {
+    pub fn op_str(&self) -> &'static str {
+        bin_op_str(self.op).unwrap_or("?")
+    }
+}
+
+/// The source operator of a comparison or arithmetic `BinOp`.
+pub fn bin_op_str(op: BinOp) -> Option<&'static str> {
+    let op = match op {
+        BinOp::Eq => "==",
+        BinOp::Ne => "!=",
+        BinOp::Lt => "<",
+        BinOp::Le => "<=",
+        BinOp::Gt => ">",
+        BinOp::Ge => ">=",
+        BinOp::Add | BinOp::AddUnchecked => "+",
+        BinOp::Sub | BinOp::SubUnchecked => "-",
+        BinOp::Mul | BinOp::MulUnchecked => "*",
+        BinOp::Div => "/",
+        BinOp::Rem => "%",
+        BinOp::BitAnd => "&",
+        BinOp::BitOr => "|",
+        BinOp::BitXor => "^",
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Where an integer operand comes from: a constant, or a field of some struct
+/// plus a constant offset.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Origin {
+    Const(i128),
+    Field {
+        /// `Type.field`, stable across separate reads of the same field.
+        path: String,
+        offset: i128,
+    },
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Const(value) => write!(f, "{value}"),
+            Origin::Field { path, offset: 0 } => write!(f, "{path}"),
+            Origin::Field { path, offset } if *offset > 0 => write!(f, "{path} + {offset}"),
+            Origin::Field { path, offset } => write!(f, "{path} - {}", -offset),
+        }
+    }
+}
+
+/// All `Eq`/`Ne`/`Lt`/`Le`/`Gt`/`Ge` comparisons in `body`.
+pub fn comparisons(body: &Body) -> Vec<Comparison> {
+    let mut comparisons = vec![];
+    let mut discriminants = HashSet::new();
+    for data in &body.blocks {
+        for stmt in &data.statements {
+            if let StatementKind::Assign(lhs, Rvalue::Discriminant(_)) = &stmt.kind {
+                discriminants.insert(lhs.local);
+            }
+        }
+    }
+    for (block, data) in body.blocks.iter().enumerate() {
+        if let TerminatorKind::SwitchInt { discr, targets } = &data.terminator.kind
+            && let Operand::Copy(place) | Operand::Move(place) = discr
+            && !discriminants.contains(&place.local)
+            && discr.ty(body.locals()).is_ok_and(|ty| {
+                matches!(ty.kind().rigid(), Some(RigidTy::Int(_) | RigidTy::Uint(_)))
+            })
+        {
+            for (value, _) in targets.branches() {
+                comparisons.push(Comparison {
+                    block,
+                    op: BinOp::Eq,
+                    lhs: Side::Operand(discr.clone()),
+                    rhs: Side::Const(value as i128),
+                });
+            }
+        }
+        for stmt in &data.statements {
+            if let StatementKind::Assign(_, Rvalue::BinaryOp(op, lhs, rhs)) = &stmt.kind
+                && matches!(
+                    op,
+                    BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge
+                )
+            {
+                comparisons.push(Comparison {
+                    block,
+                    op: *op,
+                    lhs: Side::Operand(lhs.clone()),
+                    rhs: Side::Operand(rhs.clone()),
+                });
+            }
+        }
+    }
+    comparisons
+}
+
+/// Definitions of locals assigned without projection. MIR temporaries are
+/// assigned once, which is all the reconstruction below relies on.
+pub struct Definitions<'a> {
+    body: &'a Body,
+    defs: HashMap<Local, &'a Rvalue>,
+}
+
+impl<'a> Definitions<'a> {
+    pub fn new(body: &'a Body) -> Self {
+        let mut defs = HashMap::new();
+        for block in &body.blocks {
+            for stmt in &block.statements {
+                if let StatementKind::Assign(lhs, rvalue) = &stmt.kind
+                    && lhs.projection.is_empty()
+                {
+                    defs.insert(lhs.local, rvalue);
+                }
+            }
+        }
+        Self { body, defs }
+    }
+
+    /// The rvalue assigned to `local`, if it is assigned without projection.
+    pub fn rvalue(&self, local: Local) -> Option<&'a Rvalue> {
+        self.defs.get(&local).copied()
+    }
+
+    /// Follow `operand` back through copies and casts to the place it was read from.
+    pub fn source_place(&self, operand: &Operand) -> Option<Place> {
+        let mut place = match operand {
+            Operand::Copy(place) | Operand::Move(place) => place.clone(),
+            Operand::Constant(_) => return None,
+        };
+        for _ in 0..MAX_DEPTH {
+            if !place.projection.is_empty() {
+                return Some(place);
+            }
+            match self.defs.get(&place.local) {
+                Some(
+                    Rvalue::Use(Operand::Copy(next) | Operand::Move(next))
+                    | Rvalue::Cast(_, Operand::Copy(next) | Operand::Move(next), _)
+                    | Rvalue::CopyForDeref(next),
+                ) => place = next.clone(),
+                _ => return Some(place),
+            }
+        }
+        Some(place)
+    }
+
+    /// The struct type a field place is projected from, with the field index.
+    pub fn field_parent(&self, place: &Place) -> Option<(Ty, usize)> {
+        let (last, parent) = place.projection.split_last()?;
+        let ProjectionElem::Field(field_idx, _) = last else {
+            return None;
+        };
+        let parent_ty = parent
+            .iter()
+            .try_fold(self.body.locals()[place.local].ty, |ty, elem| elem.ty(ty))
+            .ok()?;
+        Some((parent_ty, *field_idx))
+    }
+
+    /// Reconstruct `side` as a constant or a field read plus a constant offset.
+    pub fn origin(&self, side: &Side) -> Option<Origin> {
+        match side {
+            Side::Operand(operand) => self.origin_at(operand, 0),
+            Side::Const(value) => Some(Origin::Const(*value)),
+        }
+    }
+
+    fn origin_at(&self, operand: &Operand, depth: usize) -> Option<Origin> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let place = match operand {
+            Operand::Constant(constant) => {
+                return match constant.const_.kind() {
+                    ConstantKind::Allocated(alloc) => alloc.read_int().ok().map(Origin::Const),
+                    // An associated const such as `<Vault as Space>::INIT_SPACE`.
+                    ConstantKind::Unevaluated(_)
+                        if constant.ty().kind().rigid() == Some(&RigidTy::Uint(UintTy::Usize)) =>
+                    {
+                        let value = constant.const_.eval_target_usize().ok()?;
+                        Some(Origin::Const(value.into()))
+                    }
+                    _ => None,
+                };
+            }
+            Operand::Copy(place) | Operand::Move(place) => place,
+        };
+        if let Some((parent_ty, field_idx)) = self.field_parent(place) {
+            // `(_5.0)` of a checked `_5 = AddWithOverflow(..)` is the sum itself.
+            if place.projection.len() == 1
+                && let Some(Rvalue::CheckedBinaryOp(op, lhs, rhs)) = self.defs.get(&place.local)
+            {
+                return self.arith_origin(*op, lhs, rhs, depth);
+            }
+            return Some(Origin::Field {
+                path: field_path(parent_ty, field_idx),
+                offset: 0,
+            });
+        }
+        if !place.projection.is_empty() {
+            return None;
+        }
+        match self.defs.get(&place.local)? {
+            Rvalue::Use(operand) | Rvalue::Cast(_, operand, _) => {
+                self.origin_at(operand, depth + 1)
+            }
+            Rvalue::CopyForDeref(next) => self.origin_at(&Operand::Copy(next.clone()), depth + 1),
+            Rvalue::BinaryOp(op, lhs, rhs) | Rvalue::CheckedBinaryOp(op, lhs, rhs) => {
+                self.arith_origin(*op, lhs, rhs, depth)
+            }
+            _ => None,
+        }
+    }
+
+    fn arith_origin(
+        &self,
+        op: BinOp,
+        lhs: &Operand,
+        rhs: &Operand,
+        depth: usize,
+    ) -> Option<Origin> {
+        let sign = match op {
+            BinOp::Add | BinOp::AddUnchecked => 1,
+            BinOp::Sub | BinOp::SubUnchecked => -1,
+            BinOp::Mul | BinOp::MulUnchecked => 0,
+            _ => return None,
+        };
+        let lhs = self.origin_at(lhs, depth + 1)?;
+        let rhs = self.origin_at(rhs, depth + 1)?;
+        match (lhs, rhs) {
+            // Only products of constants, e.g. `space = 8 + 32 * 4`.
+            (Origin::Const(a), Origin::Const(b)) if sign == 0 => {
+                a.checked_mul(b).map(Origin::Const)
+            }
+            (_, _) if sign == 0 => None,
+            (Origin::Const(a), Origin::Const(b)) => Some(Origin::Const(a + sign * b)),
+            (Origin::Field { path, offset }, Origin::Const(k)) => Some(Origin::Field {
+                path,
+                offset: offset + sign * k,
+            }),
+            (Origin::Const(k), Origin::Field { path, offset }) if sign == 1 => {
+                Some(Origin::Field {
+                    path,
+                    offset: offset + k,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `Type.field` for the field `field_idx` of the struct `ty`.
+pub fn field_path(ty: Ty, field_idx: usize) -> String {
+    if let Some(RigidTy::Adt(adt_def, _)) = ty.kind().rigid()
+        && let Some(variant) = adt_def.variants_iter().next()
+        && let Some(field) = variant.fields().get(field_idx)
+    {
+        let ty_name = adt_def.name();
+        let short = ty_name.rsplit("::").next().unwrap_or(&ty_name);
+        format!("{short}.{}", field.name)
+    } else {
+        format!("{ty}.{field_idx}")
+    }
+}
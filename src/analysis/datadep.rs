@@ -0,0 +1,63 @@
+//! Def-use facts over the locals of a MIR body.
+use std::collections::HashSet;
+
+use rustc_public::mir::visit::{Location, PlaceContext};
+use rustc_public::mir::{
+    Body, Local, MirVisitor, Place, ProjectionElem, Statement, StatementKind, Terminator,
+    TerminatorKind, VarDebugInfo,
+};
+
+/// Locals whose value some statement or terminator of `body` reads. Storage
+/// markers, the place mentions `let _ = ..` leaves behind, drops and debug info
+/// are not reads.
+pub fn used_locals(body: &Body) -> HashSet<Local> {
+    let mut uses = Uses::default();
+    uses.visit_body(body);
+    uses.used
+}
+
+#[derive(Default)]
+struct Uses {
+    used: HashSet<Local>,
+}
+
+impl MirVisitor for Uses {
+    fn visit_statement(&mut self, stmt: &Statement, location: Location) {
+        match &stmt.kind {
+            StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::FakeRead(..)
+            | StatementKind::PlaceMention(_) => {}
+            _ => self.super_statement(stmt, location),
+        }
+    }
+
+    fn visit_terminator(&mut self, term: &Terminator, location: Location) {
+        if !matches!(term.kind, TerminatorKind::Drop { .. }) {
+            self.super_terminator(term, location);
+        }
+    }
+
+    fn visit_var_debug_info(&mut self, _: &VarDebugInfo) {}
+
+    fn visit_place(&mut self, place: &Place, ptx: PlaceContext, location: Location) {
+        if !ptx.is_mutating() {
+            self.super_place(place, ptx, location);
+            return;
+        }
+        // Writing through a pointer reads the pointer, and indices are read
+        // wherever they appear.
+        if place.projection.contains(&ProjectionElem::Deref) {
+            self.used.insert(place.local);
+        }
+        for elem in &place.projection {
+            if let ProjectionElem::Index(local) = elem {
+                self.used.insert(*local);
+            }
+        }
+    }
+
+    fn visit_local(&mut self, local: &Local, _: PlaceContext, _: Location) {
+        self.used.insert(*local);
+    }
+}
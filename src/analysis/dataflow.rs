@@ -0,0 +1,2 @@
+//! Dataflow analyses across the bodies of several fns.
+pub mod interproc;
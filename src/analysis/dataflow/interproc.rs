@@ -0,0 +1,259 @@
+//! Interprocedural analyses by fn summaries. Each fn is summarized once, from
+//! the summaries of the fns it calls, walking the components of the call graph
+//! callees first; within a cycle of recursive calls, the fns are summarized
+//! again until none of their summaries changes.
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::rc::Rc;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{Body, RETURN_LOCAL, StatementKind, TerminatorKind};
+use rustc_public::ty::{FnDef, RigidTy, TyKind};
+
+use crate::analysis::graph::DirectedGraph;
+use crate::analysis::scc::strongly_connected_components;
+use crate::analysis::taint::{Taint, add, labels_of, operand_local, rvalue_locals};
+
+/// What an interprocedural analysis computes per fn.
+pub trait SummaryAnalysis<NodeId> {
+    type Summary: Clone + Default + Eq;
+
+    /// The summary of `node` given those of the fns found so far, or `None` if
+    /// it cannot be summarized, e.g. without a body. Within a cycle, a callee
+    /// not summarized yet has the `Default` summary, so a summary must only grow
+    /// as those of the callees do for the fixpoint to be reached.
+    fn summarize(
+        &self,
+        node: &NodeId,
+        summaries: &HashMap<NodeId, Self::Summary>,
+    ) -> Option<Self::Summary>;
+}
+
+/// The summaries of the nodes of the call graph `graph` that `analysis` can
+/// summarize.
+pub fn summarize<NodeId, A>(
+    graph: &DirectedGraph<NodeId>,
+    analysis: &A,
+) -> HashMap<NodeId, A::Summary>
+where
+    NodeId: Eq + Hash + Clone,
+    A: SummaryAnalysis<NodeId>,
+{
+    let mut summaries = HashMap::new();
+    for component in strongly_connected_components(graph) {
+        let members: HashSet<&NodeId> = component.iter().collect();
+        let recursive =
+            component.len() > 1 || graph.successors(&component[0]).contains(&component[0]);
+        if recursive {
+            for node in &component {
+                summaries.insert(node.clone(), A::Summary::default());
+            }
+        }
+        let mut worklist: VecDeque<NodeId> = component.iter().cloned().collect();
+        let mut queued: HashSet<NodeId> = component.iter().cloned().collect();
+        while let Some(node) = worklist.pop_front() {
+            queued.remove(&node);
+            let summary = analysis.summarize(&node, &summaries);
+            if summaries.get(&node) == summary.as_ref() {
+                continue;
+            }
+            match summary {
+                Some(summary) => summaries.insert(node.clone(), summary),
+                None => summaries.remove(&node),
+            };
+            // The callers in the component read the previous summary.
+            for caller in graph.predecessors(&node) {
+                if members.contains(caller) && queued.insert(caller.clone()) {
+                    worklist.push_back(caller.clone());
+                }
+            }
+        }
+    }
+    summaries
+}
+
+/// Where the arguments of a fn flow: into its return value, and into the sinks
+/// it calls, directly or through the fns it calls. Arguments are numbered from
+/// 0.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FlowSummary {
+    pub to_return: BTreeSet<usize>,
+    /// The names of the sinks each argument reaches.
+    pub to_sinks: BTreeMap<usize, BTreeSet<String>>,
+}
+
+/// Flow-insensitive taint of the arguments of each fn `body` returns a body
+/// for, as `taint::propagate` has it, into the return value and into the
+/// arguments of the calls to the fns `is_sink` picks, e.g. CPIs. The result of
+/// a sink carries none of its arguments, as a CPI only tells whether it
+/// succeeded. A call to a summarized fn carries the taint of its arguments as
+/// its summary says; other calls, such as those into other crates, pass the
+/// taint of every argument on to their result, even when it is only an error
+/// as that of `next_account_info`. Writes through references passed as
+/// arguments are not followed.
+pub struct ArgumentFlows<B, S> {
+    pub body: B,
+    pub is_sink: S,
+}
+
+impl<B, S> SummaryAnalysis<Instance> for ArgumentFlows<B, S>
+where
+    B: Fn(Instance) -> Option<Rc<Body>>,
+    S: Fn(FnDef) -> bool,
+{
+    type Summary = FlowSummary;
+
+    fn summarize(
+        &self,
+        instance: &Instance,
+        summaries: &HashMap<Instance, FlowSummary>,
+    ) -> Option<FlowSummary> {
+        let body = (self.body)(*instance)?;
+        let mut taint: Taint<usize> = (0..body.arg_locals().len())
+            .map(|arg| (arg + 1, BTreeSet::from([arg])))
+            .collect();
+        let mut summary = FlowSummary::default();
+        loop {
+            let mut changed = false;
+            for block in &body.blocks {
+                for stmt in &block.statements {
+                    if let StatementKind::Assign(lhs, rvalue) = &stmt.kind {
+                        let labels = labels_of(&taint, rvalue_locals(rvalue));
+                        changed |= add(&mut taint, lhs.local, labels);
+                    }
+                }
+                let TerminatorKind::Call {
+                    func,
+                    args,
+                    destination,
+                    ..
+                } = &block.terminator.kind
+                else {
+                    continue;
+                };
+                let arg_labels: Vec<BTreeSet<usize>> = args
+                    .iter()
+                    .map(|arg| labels_of(&taint, operand_local(arg)))
+                    .collect();
+                let all_labels = || arg_labels.iter().flatten().copied().collect();
+                let Ok(fn_ty) = func.ty(body.locals()) else {
+                    changed |= add(&mut taint, destination.local, all_labels());
+                    continue;
+                };
+                let TyKind::RigidTy(RigidTy::FnDef(fn_def, generic_args)) = fn_ty.kind() else {
+                    changed |= add(&mut taint, destination.local, all_labels());
+                    continue;
+                };
+                if (self.is_sink)(fn_def) {
+                    for label in arg_labels.iter().flatten() {
+                        summary
+                            .to_sinks
+                            .entry(*label)
+                            .or_default()
+                            .insert(fn_def.name());
+                    }
+                    continue;
+                }
+                let callee = Instance::resolve(fn_def, &generic_args)
+                    .ok()
+                    .and_then(|callee| summaries.get(&callee));
+                let Some(callee) = callee else {
+                    changed |= add(&mut taint, destination.local, all_labels());
+                    continue;
+                };
+                let returned = callee
+                    .to_return
+                    .iter()
+                    .filter_map(|arg| arg_labels.get(*arg))
+                    .flatten()
+                    .copied()
+                    .collect();
+                changed |= add(&mut taint, destination.local, returned);
+                for (arg, sinks) in &callee.to_sinks {
+                    for label in arg_labels.get(*arg).into_iter().flatten() {
+                        summary
+                            .to_sinks
+                            .entry(*label)
+                            .or_default()
+                            .extend(sinks.iter().cloned());
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        summary.to_return = taint.remove(&RETURN_LOCAL).unwrap_or_default();
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Summarizes a node as the sinks it reaches: itself if it is one, and
+    /// those of its callees.
+    struct ReachedSinks<'g> {
+        graph: &'g DirectedGraph<&'static str>,
+        sinks: &'static [&'static str],
+    }
+
+    impl SummaryAnalysis<&'static str> for ReachedSinks<'_> {
+        type Summary = BTreeSet<&'static str>;
+
+        fn summarize(
+            &self,
+            node: &&'static str,
+            summaries: &HashMap<&'static str, Self::Summary>,
+        ) -> Option<Self::Summary> {
+            if node.starts_with("extern") {
+                return None;
+            }
+            let mut reached: BTreeSet<_> = self
+                .sinks
+                .iter()
+                .copied()
+                .filter(|sink| sink == node)
+                .collect();
+            for callee in self.graph.successors(node) {
+                reached.extend(summaries.get(callee).into_iter().flatten().copied());
+            }
+            Some(reached)
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        // `main` calls `helper` and the recursive `even` and `odd`, of which
+        // only `odd` reaches `sink`; `extern` cannot be summarized.
+        let mut graph = DirectedGraph::new();
+        for node in ["main", "helper", "even", "odd", "sink", "extern_fn"] {
+            graph.add_node(node);
+        }
+        for (from, to) in [
+            ("main", "helper"),
+            ("main", "even"),
+            ("helper", "extern_fn"),
+            ("even", "odd"),
+            ("odd", "even"),
+            ("odd", "sink"),
+        ] {
+            graph.add_edge(from, to);
+        }
+        let summaries = summarize(
+            &graph,
+            &ReachedSinks {
+                graph: &graph,
+                sinks: &["sink"],
+            },
+        );
+        let reached = |node| summaries[node].iter().copied().collect::<Vec<_>>();
+        assert_eq!(reached("main"), ["sink"]);
+        assert_eq!(reached("even"), ["sink"]);
+        assert_eq!(reached("odd"), ["sink"]);
+        assert!(reached("helper").is_empty());
+        assert!(!summaries.contains_key("extern_fn"));
+    }
+}
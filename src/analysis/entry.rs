@@ -0,0 +1,89 @@
+//! Program entrypoints: the `entry` fn Anchor generates, or, for a native
+//! program, the fn handed to `entrypoint!`.
+use std::fmt;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::ty::{RigidTy, TyKind};
+
+use super::callgraph::callee;
+use crate::anchor_info::entry_instance;
+use crate::anchor_info::program_id::is_pubkey;
+
+/// The `#[no_mangle]` fn `entrypoint!` expands to. It deserializes the input and
+/// calls the fn the macro was given.
+const ENTRYPOINT_SYMBOL: &str = "entrypoint";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EntryKind {
+    Anchor,
+    Native,
+}
+
+impl fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryKind::Anchor => write!(f, "anchor"),
+            EntryKind::Native => write!(f, "native"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EntryPoint {
+    pub kind: EntryKind,
+    /// The fn taking `(program_id, accounts, instruction_data)`.
+    pub instance: Instance,
+}
+
+/// The entrypoints of the local program: Anchor's `entry` if there is one, else
+/// the local fns the `entrypoint!` expansion calls with the program id,
+/// accounts and instruction data. Empty for a crate built without an
+/// entrypoint, e.g. with the `no-entrypoint` feature.
+pub fn find_entrypoints() -> Vec<EntryPoint> {
+    if let Some(instance) = entry_instance() {
+        return vec![EntryPoint {
+            kind: EntryKind::Anchor,
+            instance,
+        }];
+    }
+    let Some(symbol) = rustc_public::all_local_items()
+        .into_iter()
+        .filter(|item| item.name() == ENTRYPOINT_SYMBOL && !item.requires_monomorphization())
+        .find_map(|item| Instance::try_from(item).ok())
+    else {
+        return vec![];
+    };
+    let Some(body) = symbol.body() else {
+        return vec![];
+    };
+    let mut entrypoints: Vec<EntryPoint> = vec![];
+    for block in 0..body.blocks.len() {
+        if let Some(instance) = callee(&body, block)
+            && instance.def.krate().is_local
+            && is_process_instruction(instance)
+            && !entrypoints.iter().any(|entry| entry.instance == instance)
+        {
+            entrypoints.push(EntryPoint {
+                kind: EntryKind::Native,
+                instance,
+            });
+        }
+    }
+    entrypoints
+}
+
+/// Whether `instance` takes `(&Pubkey, &[AccountInfo], &[u8])`, the signature
+/// `entrypoint!` requires.
+fn is_process_instruction(instance: Instance) -> bool {
+    let Some(body) = instance.body() else {
+        return false;
+    };
+    let [program_id, _, _] = body.arg_locals() else {
+        return false;
+    };
+    match program_id.ty.kind() {
+        TyKind::RigidTy(RigidTy::Ref(_, ty, _)) => is_pubkey(ty),
+        _ => false,
+    }
+}
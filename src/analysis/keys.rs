@@ -0,0 +1,164 @@
+//! Comparisons between the keys of two accounts, e.g. `from.key != to.key` or
+//! `a.key() == b.key()`, the check that rules out a client passing the same
+//! account twice, and between the key of an account and an id, e.g.
+//! `*clock.key == sysvar::clock::ID`, the check that it is the account the
+//! program expects.
+use std::collections::BTreeSet;
+
+use rustc_public::mir::{Body, StatementKind, TerminatorKind};
+
+use crate::analysis::condition::{Definitions, field_path};
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::anchor_info::remaining::{callee_name, read_place};
+
+/// The `key` field of a native `AccountInfo`.
+const KEY_FIELD: &str = "AccountInfo.key";
+/// `Key::key` of Anchor accounts, and the `key()` of pinocchio's `AccountInfo`.
+const KEY_FN: &str = "::key";
+/// The `check_id` fn of the modules declaring an id with `declare_id!` or
+/// `declare_sysvar_id!`.
+const CHECK_ID_FN: &str = "::check_id";
+
+/// A comparison of the keys of two different accounts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyComparison<T> {
+    /// The block calling `PartialEq::eq` or `ne`.
+    pub block: usize,
+    pub lhs: T,
+    pub rhs: T,
+}
+
+impl<T: PartialEq> KeyComparison<T> {
+    /// Whether it compares the keys of `a` and `b`, in either order.
+    pub fn between(&self, a: &T, b: &T) -> bool {
+        (&self.lhs == a && &self.rhs == b) || (&self.lhs == b && &self.rhs == a)
+    }
+}
+
+/// A comparison of the key of an account with a value that is not the key of
+/// another account, such as an id constant, or a `check_id` call on it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IdCheck<T> {
+    /// The block calling `PartialEq::eq` or `ne`, or `check_id`.
+    pub block: usize,
+    pub account: T,
+}
+
+/// The comparisons in `body` between the key of an account `accounts` labels
+/// and the key of another. Either operator counts: what matters is that the
+/// handler tells the two apart before going on.
+pub fn key_comparisons<T: Copy + Ord>(body: &Body, accounts: &Taint<T>) -> Vec<KeyComparison<T>> {
+    let keys = account_keys(body, accounts);
+    if keys.is_empty() {
+        return vec![];
+    }
+    let mut comparisons = vec![];
+    for (block, lhs, rhs) in key_equalities(body, &keys) {
+        for &a in &lhs {
+            for &b in rhs.iter().filter(|&&b| b != a) {
+                comparisons.push(KeyComparison {
+                    block,
+                    lhs: a,
+                    rhs: b,
+                });
+            }
+        }
+    }
+    comparisons
+}
+
+/// The comparisons in `body` between the key of an account `accounts` labels
+/// and a value no account's key reaches, and the `check_id` calls on the key
+/// of an account.
+pub fn id_checks<T: Copy + Ord>(body: &Body, accounts: &Taint<T>) -> Vec<IdCheck<T>> {
+    let keys = account_keys(body, accounts);
+    if keys.is_empty() {
+        return vec![];
+    }
+    let mut checks = vec![];
+    for (block, lhs, rhs) in key_equalities(body, &keys) {
+        let checked = match (lhs.is_empty(), rhs.is_empty()) {
+            (false, true) => lhs,
+            (true, false) => rhs,
+            _ => continue,
+        };
+        for account in checked {
+            checks.push(IdCheck { block, account });
+        }
+    }
+    for (block, data) in body.blocks.iter().enumerate() {
+        if let TerminatorKind::Call { func, args, .. } = &data.terminator.kind
+            && callee_name(body, func).is_some_and(|name| name.ends_with(CHECK_ID_FN))
+        {
+            for account in labels_of(&keys, args.iter().filter_map(operand_local)) {
+                checks.push(IdCheck { block, account });
+            }
+        }
+    }
+    checks
+}
+
+/// The locals of `body` holding the key of an account `accounts` labels, with
+/// its label.
+fn account_keys<T: Copy + Ord>(body: &Body, accounts: &Taint<T>) -> Taint<T> {
+    let defs = Definitions::new(body);
+    let mut keys: Taint<T> = Taint::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(lhs, rvalue) = &stmt.kind
+                && let Some(place) = read_place(rvalue)
+                && defs
+                    .field_parent(place)
+                    .is_some_and(|(ty, field_idx)| field_path(ty, field_idx) == KEY_FIELD)
+                && let labels = labels_of(accounts, [place.local])
+                && !labels.is_empty()
+            {
+                keys.entry(lhs.local).or_default().extend(labels);
+            }
+        }
+        if let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+            && callee_name(body, func).is_some_and(|name| name.ends_with(KEY_FN))
+            && let labels = labels_of(accounts, args.first().and_then(operand_local))
+            && !labels.is_empty()
+        {
+            keys.entry(destination.local).or_default().extend(labels);
+        }
+    }
+    if keys.is_empty() {
+        return keys;
+    }
+    propagate(body, keys)
+}
+
+/// The `PartialEq::eq` and `ne` calls of `body`, with the accounts whose keys
+/// reach each side.
+fn key_equalities<T: Copy + Ord>(
+    body: &Body,
+    keys: &Taint<T>,
+) -> Vec<(usize, BTreeSet<T>, BTreeSet<T>)> {
+    let mut equalities = vec![];
+    for (block, data) in body.blocks.iter().enumerate() {
+        let TerminatorKind::Call { func, args, .. } = &data.terminator.kind else {
+            continue;
+        };
+        if !callee_name(body, func).is_some_and(|name| {
+            name.contains("PartialEq") && (name.ends_with("::eq") || name.ends_with("::ne"))
+        }) {
+            continue;
+        }
+        let [lhs, rhs] = &args[..] else {
+            continue;
+        };
+        equalities.push((
+            block,
+            labels_of(keys, operand_local(lhs)),
+            labels_of(keys, operand_local(rhs)),
+        ));
+    }
+    equalities
+}
@@ -0,0 +1,62 @@
+//! Natural loops of a MIR body, found from back edges in the dominator tree.
+use std::collections::BTreeSet;
+
+use rustc_public::mir::Body;
+
+use super::graph::Fixpoints;
+
+/// A natural loop: a header that dominates every block of the loop, and the
+/// blocks that can reach a back edge to the header without passing through it.
+#[derive(Clone, Debug)]
+pub struct NaturalLoop {
+    pub head: usize,
+    pub blocks: BTreeSet<usize>,
+}
+
+/// Deduplicated predecessors of every basic block.
+pub fn predecessors(body: &Body) -> Vec<Vec<usize>> {
+    let mut preds = vec![vec![]; body.blocks.len()];
+    for (bb, block) in body.blocks.iter().enumerate() {
+        for succ in block.terminator.successors() {
+            if !preds[succ].contains(&bb) {
+                preds[succ].push(bb);
+            }
+        }
+    }
+    preds
+}
+
+/// Natural loops of `body`, one per header and ordered by header. Back edges
+/// sharing a header are merged into a single loop.
+pub fn natural_loops(body: &Body, fixpoints: &Fixpoints) -> Vec<NaturalLoop> {
+    let preds = predecessors(body);
+    let dominators = fixpoints.dominators(body.blocks.len(), &preds);
+    let mut loops: Vec<NaturalLoop> = vec![];
+    for (head, head_preds) in preds.iter().enumerate() {
+        let latches: Vec<usize> = head_preds
+            .iter()
+            .copied()
+            .filter(|&pred| dominators.dominates(&head, &pred))
+            .collect();
+        if latches.is_empty() {
+            continue;
+        }
+        let mut blocks = BTreeSet::from([head]);
+        let mut worklist = latches;
+        while let Some(block) = worklist.pop() {
+            if blocks.insert(block) {
+                worklist.extend(preds[block].iter().copied());
+            }
+        }
+        loops.push(NaturalLoop { head, blocks });
+    }
+    loops
+}
+
+/// The smallest loop containing `block`, i.e. the innermost one.
+pub fn innermost_loop(loops: &[NaturalLoop], block: usize) -> Option<&NaturalLoop> {
+    loops
+        .iter()
+        .filter(|natural_loop| natural_loop.blocks.contains(&block))
+        .min_by_key(|natural_loop| natural_loop.blocks.len())
+}
@@ -1,2 +1,17 @@
-// pub mod graph;
 pub mod callgraph;
+pub mod cfg;
+pub mod condition;
+pub mod datadep;
+pub mod dataflow;
+pub mod entry;
+// Generic graph algorithms; not every query has a user in the binary yet.
+#[allow(dead_code)]
+pub mod graph;
+pub mod internal;
+pub mod keys;
+pub mod loops;
+pub mod reaching;
+pub mod scc;
+pub mod taint;
+pub mod types;
+pub mod visit;
@@ -0,0 +1,167 @@
+//! Reaching definitions over the locals of a MIR body, and the backward slices
+//! built from them.
+use std::collections::BTreeSet;
+
+use rustc_public::mir::{
+    BasicBlockIdx, Body, Local, Place, ProjectionElem, StatementKind, TerminatorKind,
+};
+use rustc_public::ty::Span;
+
+use super::loops::predecessors;
+use super::taint::{operand_local, rvalue_locals};
+
+/// Index of a statement in its block; the number of statements of the block
+/// stands for its terminator.
+pub type StmtIdx = usize;
+
+/// The definitions of each local that reach the start of each block, as the
+/// locations of the statements and calls assigning it.
+pub struct ReachingDefs {
+    entry: Vec<BTreeSet<(Local, (BasicBlockIdx, StmtIdx))>>,
+}
+
+impl ReachingDefs {
+    pub fn new(body: &Body) -> Self {
+        let preds = predecessors(body);
+        let mut entry = vec![BTreeSet::new(); body.blocks.len()];
+        let mut exit: Vec<BTreeSet<_>> = vec![BTreeSet::new(); body.blocks.len()];
+        loop {
+            let mut changed = false;
+            for (bb, block) in body.blocks.iter().enumerate() {
+                let mut defs: BTreeSet<_> = preds[bb]
+                    .iter()
+                    .flat_map(|pred| exit[*pred].iter().copied())
+                    .collect();
+                entry[bb] = defs.clone();
+                for stmt_idx in 0..=block.statements.len() {
+                    transfer(body, (bb, stmt_idx), &mut defs);
+                }
+                if defs != exit[bb] {
+                    exit[bb] = defs;
+                    changed = true;
+                }
+            }
+            if !changed {
+                return Self { entry };
+            }
+        }
+    }
+
+    /// The definitions of `local` that reach `location`, before it runs.
+    /// Arguments have none until the body assigns them.
+    pub fn reaching(
+        &self,
+        body: &Body,
+        (bb, stmt_idx): (BasicBlockIdx, StmtIdx),
+        local: Local,
+    ) -> BTreeSet<(BasicBlockIdx, StmtIdx)> {
+        let mut defs = self.entry[bb].clone();
+        for idx in 0..stmt_idx {
+            transfer(body, (bb, idx), &mut defs);
+        }
+        defs.into_iter()
+            .filter(|(def_local, _)| *def_local == local)
+            .map(|(_, location)| location)
+            .collect()
+    }
+}
+
+/// The statements and calls the value of `local` at `target` is computed by:
+/// the definitions of `local` reaching `target`, and, transitively, those of
+/// the locals they read. Sorted by location.
+pub fn backward_slice(
+    body: &Body,
+    target: (BasicBlockIdx, StmtIdx),
+    local: Local,
+) -> Vec<(BasicBlockIdx, StmtIdx)> {
+    let reaching = ReachingDefs::new(body);
+    let mut slice = BTreeSet::new();
+    let mut worklist = vec![(target, local)];
+    while let Some((location, local)) = worklist.pop() {
+        for def in reaching.reaching(body, location, local) {
+            if slice.insert(def) {
+                worklist.extend(read_locals(body, def).into_iter().map(|read| (def, read)));
+            }
+        }
+    }
+    slice.into_iter().collect()
+}
+
+/// The span of the statement or terminator at `location`.
+pub fn location_span(body: &Body, (bb, stmt_idx): (BasicBlockIdx, StmtIdx)) -> Span {
+    let block = &body.blocks[bb];
+    block
+        .statements
+        .get(stmt_idx)
+        .map_or(block.terminator.span, |stmt| stmt.span)
+}
+
+/// The local `location` assigns, and whether it assigns all of it. Writes
+/// through a pointer assign the pointee, not the local.
+fn defined_local(body: &Body, (bb, stmt_idx): (BasicBlockIdx, StmtIdx)) -> Option<(Local, bool)> {
+    let block = &body.blocks[bb];
+    let place = match block.statements.get(stmt_idx) {
+        Some(stmt) => match &stmt.kind {
+            StatementKind::Assign(place, _) => place,
+            StatementKind::SetDiscriminant { place, .. } => {
+                return defined_place(place).map(|(local, _)| (local, false));
+            }
+            _ => return None,
+        },
+        None => match &block.terminator.kind {
+            TerminatorKind::Call { destination, .. } => destination,
+            _ => return None,
+        },
+    };
+    defined_place(place)
+}
+
+fn defined_place(place: &Place) -> Option<(Local, bool)> {
+    if place.projection.contains(&ProjectionElem::Deref) {
+        return None;
+    }
+    Some((place.local, place.projection.is_empty()))
+}
+
+/// Apply the definition at `location`, if any, to `defs`: a whole assignment
+/// replaces the earlier definitions of its local, a partial one adds to them.
+fn transfer(
+    body: &Body,
+    location: (BasicBlockIdx, StmtIdx),
+    defs: &mut BTreeSet<(Local, (BasicBlockIdx, StmtIdx))>,
+) {
+    let Some((local, whole)) = defined_local(body, location) else {
+        return;
+    };
+    if whole {
+        defs.retain(|(def_local, _)| *def_local != local);
+    }
+    defs.insert((local, location));
+}
+
+/// Locals the definition at `location` computes its value from. A partial
+/// assignment keeps the rest of the local, so it reads the local too.
+fn read_locals(body: &Body, (bb, stmt_idx): (BasicBlockIdx, StmtIdx)) -> Vec<Local> {
+    let block = &body.blocks[bb];
+    let (place, mut reads) = match block.statements.get(stmt_idx) {
+        Some(stmt) => match &stmt.kind {
+            StatementKind::Assign(place, rvalue) => (place, rvalue_locals(rvalue)),
+            StatementKind::SetDiscriminant { place, .. } => (place, vec![]),
+            _ => return vec![],
+        },
+        None => match &block.terminator.kind {
+            TerminatorKind::Call {
+                args, destination, ..
+            } => (destination, args.iter().filter_map(operand_local).collect()),
+            _ => return vec![],
+        },
+    };
+    if !place.projection.is_empty() {
+        reads.push(place.local);
+    }
+    reads.extend(place.projection.iter().filter_map(|elem| match elem {
+        ProjectionElem::Index(local) => Some(*local),
+        _ => None,
+    }));
+    reads
+}
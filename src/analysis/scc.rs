@@ -0,0 +1,164 @@
+//! Strongly connected components of a `DirectedGraph`, e.g. the cycles of
+//! recursive calls in the call graph.
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use super::graph::DirectedGraph;
+
+/// The strongly connected components of `graph`, found with Tarjan's
+/// algorithm, in reverse topological order: each component comes after every
+/// component it has an edge into, so the callees of a call graph come before
+/// their callers.
+pub fn strongly_connected_components<NodeId>(graph: &DirectedGraph<NodeId>) -> Vec<Vec<NodeId>>
+where
+    NodeId: Eq + Hash + Clone,
+{
+    let mut tarjan = Tarjan {
+        graph,
+        index: HashMap::new(),
+        lowlink: HashMap::new(),
+        stack: vec![],
+        on_stack: HashSet::new(),
+        components: vec![],
+    };
+    for node in graph.nodes() {
+        if !tarjan.index.contains_key(node) {
+            tarjan.visit(node.clone());
+        }
+    }
+    tarjan.components
+}
+
+struct Tarjan<'g, NodeId> {
+    graph: &'g DirectedGraph<NodeId>,
+    /// The order in which each node was first visited.
+    index: HashMap<NodeId, usize>,
+    /// The smallest index reachable from each node through the nodes on the
+    /// stack.
+    lowlink: HashMap<NodeId, usize>,
+    stack: Vec<NodeId>,
+    on_stack: HashSet<NodeId>,
+    components: Vec<Vec<NodeId>>,
+}
+
+impl<NodeId> Tarjan<'_, NodeId>
+where
+    NodeId: Eq + Hash + Clone,
+{
+    /// Walk the nodes reachable from `root` with an explicit stack of the
+    /// nodes being visited and their next successor, as call graphs can be
+    /// deeper than the native stack allows to recurse.
+    fn visit(&mut self, root: NodeId) {
+        self.enter(root.clone());
+        let mut path = vec![(root, 0)];
+        while let Some((node, next)) = path.last_mut() {
+            let node = node.clone();
+            if let Some(successor) = self.graph.successors(&node).get(*next).cloned() {
+                *next += 1;
+                if !self.index.contains_key(&successor) {
+                    self.enter(successor.clone());
+                    path.push((successor, 0));
+                } else if self.on_stack.contains(&successor) {
+                    self.lower(&node, self.index[&successor]);
+                }
+                continue;
+            }
+            path.pop();
+            if let Some((parent, _)) = path.last() {
+                let parent = parent.clone();
+                self.lower(&parent, self.lowlink[&node]);
+            }
+            if self.lowlink[&node] == self.index[&node] {
+                let mut component = vec![];
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(&member);
+                    let is_root = member == node;
+                    component.push(member);
+                    if is_root {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    fn enter(&mut self, node: NodeId) {
+        let index = self.index.len();
+        self.index.insert(node.clone(), index);
+        self.lowlink.insert(node.clone(), index);
+        self.on_stack.insert(node.clone());
+        self.stack.push(node);
+    }
+
+    fn lower(&mut self, node: &NodeId, index: usize) {
+        let lowlink = self
+            .lowlink
+            .get_mut(node)
+            .expect("visited nodes have a lowlink");
+        *lowlink = (*lowlink).min(index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut component: Vec<&'static str>) -> Vec<&'static str> {
+        component.sort();
+        component
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // a <-> b -> c <-> d, d -> e -> e, and f alone.
+        let mut graph = DirectedGraph::new();
+        for node in ["a", "b", "c", "d", "e", "f"] {
+            graph.add_node(node);
+        }
+        for (from, to) in [
+            ("a", "b"),
+            ("b", "a"),
+            ("b", "c"),
+            ("c", "d"),
+            ("d", "c"),
+            ("d", "e"),
+            ("e", "e"),
+        ] {
+            graph.add_edge(from, to);
+        }
+
+        let components: Vec<_> = strongly_connected_components(&graph)
+            .into_iter()
+            .map(sorted)
+            .collect();
+        assert_eq!(components.len(), 4, "{components:?}");
+        let position = |node| {
+            components
+                .iter()
+                .position(|component| component.contains(&node))
+                .unwrap()
+        };
+        assert_eq!(components[position("a")], ["a", "b"]);
+        assert_eq!(components[position("c")], ["c", "d"]);
+        assert_eq!(components[position("e")], ["e"]);
+        assert_eq!(components[position("f")], ["f"]);
+        // Callees first.
+        assert!(position("e") < position("c"));
+        assert!(position("c") < position("a"));
+    }
+
+    #[test]
+    fn test_deep_chain() {
+        let mut graph = DirectedGraph::new();
+        for node in 0..100_000 {
+            graph.add_node(node);
+            if node > 0 {
+                graph.add_edge(node - 1, node);
+            }
+        }
+        let components = strongly_connected_components(&graph);
+        assert_eq!(components.len(), 100_000);
+        assert_eq!(components[0], [99_999]);
+    }
+}
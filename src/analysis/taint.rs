@@ -0,0 +1,138 @@
+//! Flow-insensitive forward taint over the locals of a MIR body.
+use std::collections::{BTreeSet, HashMap};
+
+use rustc_public::mir::{
+    Body, Local, Operand, ProjectionElem, Rvalue, StatementKind, TerminatorKind,
+};
+
+/// Labels per local. A local holds every label that reaches it anywhere in the
+/// body, regardless of the order of the assignments.
+pub type Taint<T> = HashMap<Local, BTreeSet<T>>;
+
+/// Propagate the labels of `sources` through assignments (including into a
+/// projection of the assigned local, e.g. an array element) and from call
+/// arguments to the call's destination.
+pub fn propagate<T: Copy + Ord>(body: &Body, mut taint: Taint<T>) -> Taint<T> {
+    loop {
+        let mut changed = false;
+        for block in &body.blocks {
+            for stmt in &block.statements {
+                if let StatementKind::Assign(lhs, rvalue) = &stmt.kind {
+                    let labels = labels_of(&taint, rvalue_locals(rvalue));
+                    changed |= add(&mut taint, lhs.local, labels);
+                }
+            }
+            if let TerminatorKind::Call {
+                args, destination, ..
+            } = &block.terminator.kind
+            {
+                let labels = labels_of(&taint, args.iter().filter_map(operand_local));
+                changed |= add(&mut taint, destination.local, labels);
+            }
+        }
+        if !changed {
+            return taint;
+        }
+    }
+}
+
+/// `propagate`, also tainting the locals a pointer was computed from when a
+/// tainted value is written through it, e.g. the `Box` that `vec![a, b]` writes
+/// its elements into with `(*_43) = [move _29, move _31]`.
+pub fn propagate_through_pointers<T: Copy + Ord>(body: &Body, mut taint: Taint<T>) -> Taint<T> {
+    let statements: Vec<_> = body
+        .blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .filter_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(lhs, rvalue) => Some((lhs, rvalue)),
+            _ => None,
+        })
+        .collect();
+    loop {
+        taint = propagate(body, taint);
+        let mut changed = false;
+        for (lhs, rvalue) in &statements {
+            if !lhs.projection.contains(&ProjectionElem::Deref) {
+                continue;
+            }
+            let labels = labels_of(&taint, rvalue_locals(rvalue));
+            if labels.is_empty() {
+                continue;
+            }
+            // The locals the pointer is computed from, transitively.
+            let mut origins = vec![lhs.local];
+            let mut i = 0;
+            while let Some(&local) = origins.get(i) {
+                for (assigned, rvalue) in &statements {
+                    if assigned.local == local && assigned.projection.is_empty() {
+                        for origin in rvalue_locals(rvalue) {
+                            if !origins.contains(&origin) {
+                                origins.push(origin);
+                            }
+                        }
+                    }
+                }
+                i += 1;
+            }
+            for origin in origins {
+                changed |= add(&mut taint, origin, labels.clone());
+            }
+        }
+        if !changed {
+            return taint;
+        }
+    }
+}
+
+/// Labels of the locals an operand list reads.
+pub fn labels_of<T: Copy + Ord>(
+    taint: &Taint<T>,
+    locals: impl IntoIterator<Item = Local>,
+) -> BTreeSet<T> {
+    locals
+        .into_iter()
+        .filter_map(|local| taint.get(&local))
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/// Add `labels` to those of `local`, returning whether it gained any.
+pub fn add<T: Ord>(taint: &mut Taint<T>, local: Local, labels: BTreeSet<T>) -> bool {
+    if labels.is_empty() {
+        return false;
+    }
+    let entry = taint.entry(local).or_default();
+    let known = entry.len();
+    entry.extend(labels);
+    entry.len() != known
+}
+
+pub fn operand_local(operand: &Operand) -> Option<Local> {
+    match operand {
+        Operand::Copy(place) | Operand::Move(place) => Some(place.local),
+        Operand::Constant(_) => None,
+    }
+}
+
+/// Locals an rvalue reads from.
+pub fn rvalue_locals(rvalue: &Rvalue) -> Vec<Local> {
+    match rvalue {
+        Rvalue::Use(operand)
+        | Rvalue::Cast(_, operand, _)
+        | Rvalue::UnaryOp(_, operand)
+        | Rvalue::Repeat(operand, _)
+        | Rvalue::ShallowInitBox(operand, _) => operand_local(operand).into_iter().collect(),
+        Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+            [lhs, rhs].into_iter().filter_map(operand_local).collect()
+        }
+        Rvalue::Aggregate(_, operands) => operands.iter().filter_map(operand_local).collect(),
+        Rvalue::Ref(_, _, place)
+        | Rvalue::AddressOf(_, place)
+        | Rvalue::CopyForDeref(place)
+        | Rvalue::Discriminant(place)
+        | Rvalue::Len(place) => vec![place.local],
+        Rvalue::ThreadLocalRef(_) | Rvalue::NullaryOp(..) => vec![],
+    }
+}
@@ -0,0 +1,15 @@
+//! The SDK types of `metadata::known_types` on MIR types.
+use rustc_public::CrateDef;
+use rustc_public::ty::{RigidTy, Ty};
+
+use crate::metadata::{Concept, known_types};
+
+/// Whether `ty`, or what it references, is the type of `concept` in the SDK the
+/// program is written against.
+pub fn is_known_ty(concept: Concept, ty: Ty) -> bool {
+    match ty.kind().rigid() {
+        Some(RigidTy::Ref(_, inner, _)) => is_known_ty(concept, *inner),
+        Some(RigidTy::Adt(adt_def, _)) => known_types().is(concept, &adt_def.name()),
+        _ => false,
+    }
+}
@@ -0,0 +1,88 @@
+//! A walk over the statements and terminators of every block of a MIR body, in
+//! block order. Unlike `rustc_public`'s `MirVisitor`, which goes down to every
+//! place, type and span, each visit gets the block it is in, and the walk stops
+//! at the operands; a checker overrides the visits it needs and calls the
+//! matching `walk_*` fn to keep walking below them.
+use rustc_public::mir::{
+    BasicBlockIdx, Body, Operand, Place, Rvalue, Statement, StatementKind, Terminator,
+    TerminatorKind,
+};
+
+pub trait MirVisitor {
+    /// An assignment visits its rvalue.
+    fn visit_statement(&mut self, bb: BasicBlockIdx, stmt: &Statement) {
+        walk_statement(self, bb, stmt);
+    }
+
+    /// A call visits its callee and args, a `SwitchInt` the value switched on
+    /// and an `Assert` its condition.
+    fn visit_terminator(&mut self, bb: BasicBlockIdx, terminator: &Terminator) {
+        walk_terminator(self, bb, terminator);
+    }
+
+    /// The rvalue assigned to `place`; visits its operands.
+    fn visit_rvalue(&mut self, bb: BasicBlockIdx, place: &Place, rvalue: &Rvalue) {
+        let _ = place;
+        walk_rvalue(self, bb, rvalue);
+    }
+
+    fn visit_operand(&mut self, _bb: BasicBlockIdx, _operand: &Operand) {}
+}
+
+/// Visit the statements, then the terminator, of each block of `body`.
+pub fn walk_body<V: MirVisitor + ?Sized>(body: &Body, visitor: &mut V) {
+    for (bb, block) in body.blocks.iter().enumerate() {
+        for stmt in &block.statements {
+            visitor.visit_statement(bb, stmt);
+        }
+        visitor.visit_terminator(bb, &block.terminator);
+    }
+}
+
+pub fn walk_statement<V: MirVisitor + ?Sized>(
+    visitor: &mut V,
+    bb: BasicBlockIdx,
+    stmt: &Statement,
+) {
+    if let StatementKind::Assign(place, rvalue) = &stmt.kind {
+        visitor.visit_rvalue(bb, place, rvalue);
+    }
+}
+
+pub fn walk_terminator<V: MirVisitor + ?Sized>(
+    visitor: &mut V,
+    bb: BasicBlockIdx,
+    terminator: &Terminator,
+) {
+    match &terminator.kind {
+        TerminatorKind::Call { func, args, .. } => {
+            visitor.visit_operand(bb, func);
+            for arg in args {
+                visitor.visit_operand(bb, arg);
+            }
+        }
+        TerminatorKind::SwitchInt { discr, .. } => visitor.visit_operand(bb, discr),
+        TerminatorKind::Assert { cond, .. } => visitor.visit_operand(bb, cond),
+        _ => {}
+    }
+}
+
+pub fn walk_rvalue<V: MirVisitor + ?Sized>(visitor: &mut V, bb: BasicBlockIdx, rvalue: &Rvalue) {
+    match rvalue {
+        Rvalue::Use(operand)
+        | Rvalue::Repeat(operand, _)
+        | Rvalue::Cast(_, operand, _)
+        | Rvalue::UnaryOp(_, operand)
+        | Rvalue::ShallowInitBox(operand, _) => visitor.visit_operand(bb, operand),
+        Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+            visitor.visit_operand(bb, lhs);
+            visitor.visit_operand(bb, rhs);
+        }
+        Rvalue::Aggregate(_, operands) => {
+            for operand in operands {
+                visitor.visit_operand(bb, operand);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,491 @@
+//! Track how an instruction handler touches the fields of its `Accounts` struct.
+//!
+//! Starting from a handler `fn(ctx: Context<S>, ..)`, we follow `ctx.accounts` and
+//! references to its fields through plain moves, re-borrows, pointer coercions
+//! and calls into local helpers, and record which field indices are read and which may be written.
+use std::collections::{HashMap, HashSet};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{
+    AggregateKind, Body, BorrowKind, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind,
+    TerminatorKind,
+};
+use rustc_public::ty::{RigidTy, TyKind};
+
+use super::{ACCOUNTS_EXIT, ANCHOR_ACCOUNTS, accounts_impl_fn};
+use crate::analysis::internal::coercion::coercion_source;
+use crate::analysis::types::is_known_ty;
+use crate::metadata::Concept;
+
+/// Field index of `accounts` in `anchor_lang::context::Context`.
+const CONTEXT_ACCOUNTS_FIELD: usize = 1;
+
+/// Callees that may write the account behind a field reference passed to them.
+const WRITE_CALLS: [&str; 8] = [
+    "deref_mut",
+    "load_mut",
+    "load_init",
+    "set_inner",
+    "close",
+    "borrow_mut",
+    "realloc",
+    "assign",
+];
+
+/// How deep we follow `ctx` or field references into local helpers.
+const MAX_DEPTH: usize = 4;
+
+/// What a local holds, relative to the `Accounts` struct being tracked.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Role {
+    /// `Context<S>` by value.
+    Ctx,
+    /// `&Context<S>`.
+    CtxRef,
+    /// `&S` or `&mut S`.
+    Accounts,
+    /// `&S.field` or `&mut S.field`.
+    Field(usize),
+}
+
+/// The state reached after applying a place's projections to a tracked local.
+#[derive(Clone, Copy, Debug)]
+enum Target {
+    Role(Role),
+    /// `*accounts`: the struct itself.
+    AccountsValue,
+    /// The field value; `deep` if the place goes further into the field.
+    FieldValue(usize, bool),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FieldAccesses {
+    pub reads: HashSet<usize>,
+    pub writes: HashSet<usize>,
+    /// The whole accounts struct reached code we could not analyze, so any field
+    /// may have been written.
+    pub escaped: bool,
+    /// Fields whose `key()` is compared (`==`/`!=`) against some other value.
+    pub key_checks: HashSet<usize>,
+}
+
+impl FieldAccesses {
+    pub fn may_write(&self, field_idx: usize) -> bool {
+        self.escaped || self.writes.contains(&field_idx)
+    }
+}
+
+/// Accesses made by an instruction handler through its `ctx` argument.
+pub fn handler_field_accesses(handler: Instance) -> FieldAccesses {
+    let mut accesses = FieldAccesses::default();
+    let mut visited = HashSet::new();
+    collect_accesses(handler, 1, Role::Ctx, 0, &mut accesses, &mut visited);
+    accesses
+}
+
+/// Accesses made by `instance` through the argument `arg` holding `role`.
+pub fn field_accesses(instance: Instance, arg: Local, role: Role) -> FieldAccesses {
+    let mut accesses = FieldAccesses::default();
+    let mut visited = HashSet::new();
+    collect_accesses(instance, arg, role, 0, &mut accesses, &mut visited);
+    accesses
+}
+
+fn collect_accesses(
+    instance: Instance,
+    arg: Local,
+    role: Role,
+    depth: usize,
+    accesses: &mut FieldAccesses,
+    visited: &mut HashSet<(Instance, Local, Role)>,
+) {
+    if !visited.insert((instance, arg, role)) {
+        return;
+    }
+    let Some(body) = instance.body() else {
+        accesses.escaped = true;
+        return;
+    };
+    let mut roles = HashMap::new();
+    roles.insert(arg, role);
+    propagate_roles(&body, &mut roles, accesses);
+
+    // Results of `field.key()`, by the field they were taken from.
+    let mut keys = HashMap::new();
+    for block in &body.blocks {
+        let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+        else {
+            continue;
+        };
+        let Ok(fn_ty) = func.ty(body.locals()) else {
+            continue;
+        };
+        let TyKind::RigidTy(RigidTy::FnDef(fn_def, generic_args)) = fn_ty.kind() else {
+            continue;
+        };
+        let callee_name = fn_def.name();
+        let local_callee = if fn_def.krate().is_local {
+            Instance::resolve(fn_def, &generic_args).ok()
+        } else {
+            None
+        };
+        let returns_account_info = destination
+            .ty(body.locals())
+            .map(|ty| is_known_ty(Concept::AccountInfo, ty))
+            .unwrap_or(false);
+
+        for (idx, arg) in args.iter().enumerate() {
+            let (Operand::Copy(place) | Operand::Move(place)) = arg else {
+                continue;
+            };
+            let callee_arg = idx + 1;
+            match resolve(place, &roles) {
+                Some(Target::Role(Role::Field(field_idx))) => {
+                    if WRITE_CALLS.iter().any(|name| callee_name.contains(name))
+                        || returns_account_info
+                    {
+                        // Either a mutating accessor or the raw `AccountInfo` escapes,
+                        // e.g. into a CPI or a lamports/data borrow.
+                        accesses.writes.insert(field_idx);
+                    } else if let Some(callee) = local_callee
+                        && depth < MAX_DEPTH
+                    {
+                        collect_accesses(
+                            callee,
+                            callee_arg,
+                            Role::Field(field_idx),
+                            depth + 1,
+                            accesses,
+                            visited,
+                        );
+                    } else {
+                        if callee_name.ends_with("::key") {
+                            keys.insert(destination.local, field_idx);
+                        }
+                        accesses.reads.insert(field_idx);
+                    }
+                }
+                Some(Target::Role(role)) => {
+                    if let Some(callee) = local_callee
+                        && depth < MAX_DEPTH
+                    {
+                        collect_accesses(callee, callee_arg, role, depth + 1, accesses, visited);
+                    } else {
+                        accesses.escaped = true;
+                    }
+                }
+                Some(Target::FieldValue(field_idx, _)) => {
+                    accesses.reads.insert(field_idx);
+                }
+                _ => {}
+            }
+        }
+    }
+    if !keys.is_empty() {
+        collect_key_checks(&body, keys, accesses);
+    }
+}
+
+/// Locals of `body` holding `ctx`, the accounts struct or a field reference,
+/// given the argument `arg` holding `role`.
+pub fn local_roles(body: &Body, arg: Local, role: Role) -> HashMap<Local, Role> {
+    let mut roles = HashMap::from([(arg, role)]);
+    propagate_roles(body, &mut roles, &mut FieldAccesses::default());
+    roles
+}
+
+/// The field `place` is a reference to, e.g. `&ctx.accounts.pool`.
+pub fn field_ref(place: &Place, roles: &HashMap<Local, Role>) -> Option<usize> {
+    match resolve(place, roles)? {
+        Target::Role(Role::Field(field_idx)) => Some(field_idx),
+        _ => None,
+    }
+}
+
+/// The role `place` holds, e.g. `ctx.accounts` or `&ctx`.
+pub fn resolve_role(place: &Place, roles: &HashMap<Local, Role>) -> Option<Role> {
+    match resolve(place, roles)? {
+        Target::Role(role) => Some(role),
+        _ => None,
+    }
+}
+
+fn propagate_roles(body: &Body, roles: &mut HashMap<Local, Role>, accesses: &mut FieldAccesses) {
+    // Roles only flow forward through assignments, but blocks are not laid out in
+    // execution order, so iterate until no new local picks up a role.
+    loop {
+        let known = roles.len();
+        for block in &body.blocks {
+            for stmt in &block.statements {
+                if let StatementKind::Assign(lhs, rvalue) = &stmt.kind {
+                    visit_assign(body, lhs, rvalue, roles, accesses);
+                }
+            }
+        }
+        if roles.len() == known {
+            break;
+        }
+    }
+}
+
+/// Record the fields whose key, or a reference to it, reaches `PartialEq::eq/ne`.
+pub(super) fn collect_key_checks(
+    body: &Body,
+    mut keys: HashMap<Local, usize>,
+    accesses: &mut FieldAccesses,
+) {
+    loop {
+        let known = keys.len();
+        for block in &body.blocks {
+            for stmt in &block.statements {
+                if let StatementKind::Assign(lhs, rvalue) = &stmt.kind
+                    && lhs.projection.is_empty()
+                    && let Rvalue::Ref(_, _, place)
+                    | Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) = rvalue
+                    && place.projection.is_empty()
+                    && let Some(field_idx) = keys.get(&place.local).copied()
+                {
+                    keys.insert(lhs.local, field_idx);
+                }
+            }
+        }
+        if keys.len() == known {
+            break;
+        }
+    }
+    for block in &body.blocks {
+        if let TerminatorKind::Call { func, args, .. } = &block.terminator.kind
+            && let Ok(fn_ty) = func.ty(body.locals())
+            && let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind()
+            && let callee_name = fn_def.name()
+            && callee_name.contains("PartialEq")
+            && (callee_name.ends_with("::eq") || callee_name.ends_with("::ne"))
+        {
+            for arg in args {
+                if let Operand::Copy(place) | Operand::Move(place) = arg
+                    && let Some(field_idx) = keys.get(&place.local)
+                {
+                    accesses.key_checks.insert(*field_idx);
+                }
+            }
+        }
+    }
+}
+
+fn visit_assign(
+    body: &Body,
+    lhs: &Place,
+    rvalue: &Rvalue,
+    roles: &mut HashMap<Local, Role>,
+    accesses: &mut FieldAccesses,
+) {
+    if let Some(Target::FieldValue(field_idx, _)) = resolve(lhs, roles) {
+        accesses.writes.insert(field_idx);
+    }
+    let plain_lhs = lhs.projection.is_empty();
+    match rvalue {
+        Rvalue::Ref(_, kind, place) => {
+            let is_mut = matches!(kind, BorrowKind::Mut { .. });
+            match resolve(place, roles) {
+                Some(Target::Role(Role::Ctx)) if plain_lhs => {
+                    roles.insert(lhs.local, Role::CtxRef);
+                }
+                Some(Target::AccountsValue) if plain_lhs => {
+                    roles.insert(lhs.local, Role::Accounts);
+                }
+                Some(Target::FieldValue(field_idx, deep)) => {
+                    if is_mut {
+                        accesses.writes.insert(field_idx);
+                    } else {
+                        accesses.reads.insert(field_idx);
+                    }
+                    if !deep && plain_lhs {
+                        roles.insert(lhs.local, Role::Field(field_idx));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) | Rvalue::CopyForDeref(place) => {
+            match resolve(place, roles) {
+                Some(Target::Role(role)) if plain_lhs => {
+                    roles.insert(lhs.local, role);
+                }
+                Some(Target::FieldValue(field_idx, _)) => {
+                    accesses.reads.insert(field_idx);
+                }
+                _ => {}
+            }
+        }
+        // A field reference coerced into a trait object still points to the
+        // field, e.g. `&ctx.accounts.vault as &dyn ToAccountInfo`.
+        Rvalue::Cast(..) => {
+            if plain_lhs
+                && let Some(place) = coercion_source(body, rvalue)
+                && let Some(Target::Role(role)) = resolve(&place, roles)
+            {
+                roles.insert(lhs.local, role);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn resolve(place: &Place, roles: &HashMap<Local, Role>) -> Option<Target> {
+    let mut target = Target::Role(*roles.get(&place.local)?);
+    for elem in &place.projection {
+        target = match (target, elem) {
+            (Target::Role(Role::Ctx), ProjectionElem::Field(CONTEXT_ACCOUNTS_FIELD, _)) => {
+                Target::Role(Role::Accounts)
+            }
+            (Target::Role(Role::CtxRef), ProjectionElem::Deref) => Target::Role(Role::Ctx),
+            (Target::Role(Role::Accounts), ProjectionElem::Deref) => Target::AccountsValue,
+            (Target::AccountsValue, ProjectionElem::Field(field_idx, _)) => {
+                Target::FieldValue(*field_idx, false)
+            }
+            (Target::Role(Role::Field(field_idx)), ProjectionElem::Deref) => {
+                Target::FieldValue(field_idx, false)
+            }
+            (Target::FieldValue(field_idx, _), _) => Target::FieldValue(field_idx, true),
+            _ => return None,
+        };
+    }
+    Some(target)
+}
+
+/// Fields of the `Accounts` struct `name` that Anchor itself writes outside the
+/// handler: accounts created by `init`, the `payer` funding them, and accounts
+/// touched by `close` in `AccountsExit::exit`.
+pub fn framework_written_fields(name: &str) -> HashSet<usize> {
+    let mut written = HashSet::new();
+    if let Some(try_accounts) = accounts_impl_fn(name, ANCHOR_ACCOUNTS, "try_accounts")
+        && let Some(body) = try_accounts.body()
+        && let Some(field_locals) = constructed_fields(&body, name)
+    {
+        written.extend(init_written_fields(&body, &field_locals));
+    }
+    if let Some(exit) = accounts_impl_fn(name, ACCOUNTS_EXIT, "exit") {
+        // Every mutable account is serialized back by its own `exit`, which is
+        // not a write the handler asked for. Only `close` shows up as a call
+        // taking the field itself or its `AccountInfo`.
+        written.extend(field_accesses(exit, 1, Role::Accounts).writes);
+    }
+    written
+}
+
+pub(crate) fn short_name(path: &str) -> String {
+    path.rsplit("::").next().unwrap_or(path).to_string()
+}
+
+/// The locals moved into the final `S { .. }` aggregate of `try_accounts`, by field.
+fn constructed_fields(body: &Body, name: &str) -> Option<Vec<Option<Local>>> {
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(_, Rvalue::Aggregate(AggregateKind::Adt(adt_def, ..), ops)) =
+                &stmt.kind
+                && short_name(&adt_def.name()) == name
+            {
+                return Some(
+                    ops.iter()
+                        .map(|op| match op {
+                            Operand::Copy(place) | Operand::Move(place)
+                                if place.projection.is_empty() =>
+                            {
+                                Some(place.local)
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                );
+            }
+        }
+    }
+    None
+}
+
+/// Fields built by an `init` closure, and fields captured by reference into one
+/// (the payer is debited by `create_account`).
+fn init_written_fields(body: &Body, field_locals: &[Option<Local>]) -> HashSet<usize> {
+    let mut defs: HashMap<Local, Local> = HashMap::new();
+    let mut ref_of: HashMap<Local, Local> = HashMap::new();
+    let mut captured = HashSet::new();
+    let mut from_closure_call = HashSet::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            if !lhs.projection.is_empty() {
+                continue;
+            }
+            match rvalue {
+                Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) => {
+                    defs.insert(lhs.local, place.local);
+                }
+                Rvalue::Ref(_, _, place) if place.projection.is_empty() => {
+                    ref_of.insert(lhs.local, place.local);
+                }
+                Rvalue::Aggregate(AggregateKind::Closure(..), ops) => {
+                    for op in ops {
+                        if let Operand::Copy(place) | Operand::Move(place) = op {
+                            captured.insert(place.local);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+            && let Ok(fn_ty) = func.ty(body.locals())
+            && let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind()
+        {
+            let callee_name = fn_def.name();
+            if callee_name.contains("Fn") && callee_name.ends_with("::call") {
+                from_closure_call.insert(destination.local);
+            } else if (callee_name.ends_with("::branch") || callee_name.ends_with("::map_err"))
+                && let Some(Operand::Copy(place) | Operand::Move(place)) = args.first()
+            {
+                defs.insert(destination.local, place.local);
+            }
+        }
+    }
+
+    let captured: HashSet<Local> = captured
+        .iter()
+        .map(|local| ref_of.get(local).copied().unwrap_or(*local))
+        .collect();
+    let mut written = HashSet::new();
+    for (field_idx, field_local) in field_locals.iter().enumerate() {
+        let Some(field_local) = field_local else {
+            continue;
+        };
+        if captured.contains(field_local) {
+            written.insert(field_idx);
+            continue;
+        }
+        // Walk back through moves, `?` and `map_err` to where the value came from.
+        let mut local = *field_local;
+        for _ in 0..16 {
+            if from_closure_call.contains(&local) {
+                written.insert(field_idx);
+                break;
+            }
+            match defs.get(&local) {
+                Some(prev) => local = *prev,
+                None => break,
+            }
+        }
+    }
+    written
+}
@@ -0,0 +1,185 @@
+//! Guards run before an instruction handler's body, as `#[access_control(..)]`
+//! does: it prepends `guard(..)?;` for each guard it lists.
+use std::collections::{BTreeSet, HashMap};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{Body, Local, Operand, TerminatorKind};
+use rustc_public::ty::{RigidTy, TyKind};
+
+use super::access::{
+    FieldAccesses, Role, collect_key_checks, field_accesses, field_ref, handler_field_accesses,
+    local_roles, resolve_role,
+};
+use crate::report::location;
+
+const RESULT: &str = "std::result::Result";
+const BRANCH: &str = "::branch";
+const KEY: &str = "::key";
+/// Discriminant of `ControlFlow::Continue`, the arm `?` takes on `Ok`.
+const CONTINUE: u128 = 0;
+
+/// A guard called at the top of a handler, its error propagated with `?`.
+#[derive(Clone, Debug)]
+pub struct GuardInfo {
+    pub instance: Instance,
+    /// `file.rs:line` of the call in the handler.
+    pub location: String,
+    /// Fields of the Accounts struct the guard reads.
+    pub reads: BTreeSet<usize>,
+    /// Fields whose key the guard compares against some other value, given the
+    /// field, `ctx` or the key itself.
+    pub key_checks: BTreeSet<usize>,
+}
+
+/// The guards `handler` runs before its body, in call order.
+///
+/// Nothing of the attribute is left in MIR, so these are the local fns returning
+/// a `Result` that the handler calls with `?` before anything else. A check the
+/// user writes the same way as the first statement of the body is a guard too.
+pub fn handler_guards(handler: Instance) -> Vec<GuardInfo> {
+    let Some(body) = handler.body() else {
+        return vec![];
+    };
+    let roles = local_roles(&body, 1, Role::Ctx);
+    let mut keys = HashMap::new();
+    let mut guards = vec![];
+    let mut block = 0;
+    // Each block is visited at most once along the path.
+    for _ in 0..body.blocks.len() {
+        let terminator = &body.blocks[block].terminator;
+        let (func, args, destination, target) = match &terminator.kind {
+            TerminatorKind::Goto { target } => {
+                block = *target;
+                continue;
+            }
+            TerminatorKind::Call {
+                func,
+                args,
+                destination,
+                target: Some(target),
+                ..
+            } => (func, args, destination, *target),
+            _ => break,
+        };
+        let Ok(fn_ty) = func.ty(body.locals()) else {
+            break;
+        };
+        let TyKind::RigidTy(RigidTy::FnDef(fn_def, generic_args)) = fn_ty.kind() else {
+            break;
+        };
+        let name = fn_def.name();
+        if name.ends_with(BRANCH) {
+            let Some(next) = continue_target(&body, target) else {
+                break;
+            };
+            block = next;
+            continue;
+        }
+        if !fn_def.krate().is_local {
+            // Evaluating the guard's arguments, e.g. `ctx.accounts.config.key()`.
+            if name.ends_with(KEY)
+                && let Some(Operand::Copy(place) | Operand::Move(place)) = args.first()
+                && let Some(field_idx) = field_ref(place, &roles)
+            {
+                keys.insert(destination.local, field_idx);
+            }
+            block = target;
+            continue;
+        }
+        let returns_result = destination
+            .ty(body.locals())
+            .is_ok_and(|ty| ty.to_string().starts_with(RESULT));
+        let Ok(instance) = Instance::resolve(fn_def, &generic_args) else {
+            break;
+        };
+        if !returns_result || !is_propagated(&body, target, destination.local) {
+            break;
+        }
+        guards.push(guard_info(
+            instance,
+            location(&terminator.span),
+            args,
+            &roles,
+            &keys,
+        ));
+        block = target;
+    }
+    guards
+}
+
+/// What `handler` does to the fields of its Accounts struct, with the key
+/// comparisons its guards make on keys passed to them.
+pub fn guarded_field_accesses(handler: Instance) -> FieldAccesses {
+    let mut accesses = handler_field_accesses(handler);
+    for guard in handler_guards(handler) {
+        accesses.key_checks.extend(guard.key_checks);
+    }
+    accesses
+}
+
+fn guard_info(
+    instance: Instance,
+    location: String,
+    args: &[Operand],
+    roles: &HashMap<Local, Role>,
+    keys: &HashMap<Local, usize>,
+) -> GuardInfo {
+    let mut accesses = FieldAccesses::default();
+    let mut param_keys = HashMap::new();
+    for (idx, arg) in args.iter().enumerate() {
+        let (Operand::Copy(place) | Operand::Move(place)) = arg else {
+            continue;
+        };
+        let param = idx + 1;
+        if let Some(role) = resolve_role(place, roles) {
+            let guard_accesses = field_accesses(instance, param, role);
+            accesses.reads.extend(guard_accesses.reads);
+            accesses.key_checks.extend(guard_accesses.key_checks);
+        } else if place.projection.is_empty()
+            && let Some(field_idx) = keys.get(&place.local)
+        {
+            param_keys.insert(param, *field_idx);
+        }
+    }
+    if !param_keys.is_empty()
+        && let Some(body) = instance.body()
+    {
+        accesses.reads.extend(param_keys.values().copied());
+        collect_key_checks(&body, param_keys, &mut accesses);
+    }
+    GuardInfo {
+        instance,
+        location,
+        reads: accesses.reads.into_iter().collect(),
+        key_checks: accesses.key_checks.into_iter().collect(),
+    }
+}
+
+/// Whether the `Result` in `result` goes straight into `?`, i.e. the call
+/// returning into `block` is followed by `Try::branch` on it.
+fn is_propagated(body: &Body, block: usize, result: Local) -> bool {
+    let TerminatorKind::Call { func, args, .. } = &body.blocks[block].terminator.kind else {
+        return false;
+    };
+    let Ok(fn_ty) = func.ty(body.locals()) else {
+        return false;
+    };
+    let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind() else {
+        return false;
+    };
+    fn_def.name().ends_with(BRANCH)
+        && matches!(args.first(), Some(Operand::Move(place)) if place.local == result)
+}
+
+/// The block `?` continues in on `Ok`, after the `branch` call returning into
+/// `block`.
+fn continue_target(body: &Body, block: usize) -> Option<usize> {
+    let TerminatorKind::SwitchInt { targets, .. } = &body.blocks[block].terminator.kind else {
+        return None;
+    };
+    targets
+        .branches()
+        .find(|(value, _)| *value == CONTINUE)
+        .map(|(_, target)| target)
+}
@@ -0,0 +1,170 @@
+//! Which instructions use which account types: a graph with a node per
+//! handler and per account type of the Accounts structs, and an edge per field
+//! from the handler to the type it holds, flagged as its client meta is.
+use serde::Serialize;
+
+use super::{AccountMetaInfo, AnchorAccountKind, AnchorAccounts, HandlerInfo};
+use crate::analysis::graph::dot_escape;
+
+#[derive(Debug, Default, Serialize)]
+pub struct AccountGraph {
+    pub instructions: Vec<InstructionNode>,
+    /// In order of first use.
+    pub accounts: Vec<AccountNode>,
+    pub edges: Vec<AccountEdge>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstructionNode {
+    pub id: String,
+    pub name: String,
+    /// Short name of the `T` of its `Context<T>`.
+    pub context: String,
+}
+
+/// An account type, e.g. `Account<Vault>` as `Vault`, or `Signer`.
+#[derive(Debug, Serialize)]
+pub struct AccountNode {
+    pub id: String,
+    pub name: String,
+    /// E.g. `account`, `signer` or `unchecked`, after `AnchorAccountKind`.
+    pub kind: &'static str,
+}
+
+/// A field of the Accounts struct of `instruction`, holding an `account`.
+#[derive(Debug, Serialize)]
+pub struct AccountEdge {
+    pub instruction: String,
+    pub account: String,
+    pub field: String,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+impl AccountGraph {
+    /// The graph of `handlers`, whose fields are looked up in `accounts` and
+    /// flagged after the off-chain client `metas`. Without a meta, a field is
+    /// readonly, and a signer only if it is a `Signer`.
+    pub fn new(
+        handlers: &[HandlerInfo],
+        accounts: &[AnchorAccounts],
+        metas: &[AccountMetaInfo],
+    ) -> Self {
+        let mut graph = Self::default();
+        for handler in handlers {
+            let instruction = format!("instruction:{}", handler.name);
+            graph.instructions.push(InstructionNode {
+                id: instruction.clone(),
+                name: handler.name.clone(),
+                context: handler.context_struct.clone(),
+            });
+            let Some(fields) = accounts
+                .iter()
+                .find(|accounts| accounts.name == handler.context_struct)
+            else {
+                continue;
+            };
+            for (field_idx, field) in fields.anchor_accounts.iter().enumerate() {
+                let (name, kind) = node_of(&field.kind);
+                let account = format!("account:{name}");
+                if !graph.accounts.iter().any(|node| node.id == account) {
+                    graph.accounts.push(AccountNode {
+                        id: account.clone(),
+                        name,
+                        kind,
+                    });
+                }
+                let meta = metas.iter().find(|meta| {
+                    !meta.is_cpi
+                        && meta.struct_name == handler.context_struct
+                        && meta.field_idx == field_idx
+                });
+                graph.edges.push(AccountEdge {
+                    instruction: instruction.clone(),
+                    account,
+                    field: field.name.clone(),
+                    writable: meta.is_some_and(|meta| meta.writable),
+                    signer: meta
+                        .map_or(field.kind == AnchorAccountKind::Signer, |meta| meta.signer),
+                });
+            }
+        }
+        graph
+    }
+
+    /// Render as a Graphviz `digraph`: instructions are boxes, account types
+    /// are shaped and colored by kind, and edges are labeled with the field
+    /// and `mut`/`signer`, mutable ones in bold.
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph \"accounts\" {\n    rankdir=LR;\n".to_string();
+        for node in &self.instructions {
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\\n{}\", shape=box, style=filled, fillcolor=lightgrey];\n",
+                dot_escape(&node.id),
+                dot_escape(&node.name),
+                dot_escape(&format!("Context<{}>", node.context))
+            ));
+        }
+        for node in &self.accounts {
+            let (shape, color) = dot_style(node.kind);
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\", shape={shape}, style=filled, fillcolor={color}];\n",
+                dot_escape(&node.id),
+                dot_escape(&node.name)
+            ));
+        }
+        for edge in &self.edges {
+            let mut label = edge.field.clone();
+            let flags: Vec<_> = [(edge.writable, "mut"), (edge.signer, "signer")]
+                .into_iter()
+                .filter_map(|(set, flag)| set.then_some(flag))
+                .collect();
+            if !flags.is_empty() {
+                label = format!("{label}: {}", flags.join(", "));
+            }
+            let style = if edge.writable { ", style=bold" } else { "" };
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"{style}];\n",
+                dot_escape(&edge.instruction),
+                dot_escape(&edge.account),
+                dot_escape(&label)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("account graphs serialize to JSON")
+    }
+}
+
+/// The name of the node of an account of `kind`, and its kind in the JSON.
+fn node_of(kind: &AnchorAccountKind) -> (String, &'static str) {
+    let short = |symbol: &str| symbol.rsplit("::").next().unwrap_or(symbol).to_string();
+    match kind {
+        AnchorAccountKind::Account(ty) => (short(ty), "account"),
+        AnchorAccountKind::InterfaceAccount(ty) => (short(ty), "interface_account"),
+        AnchorAccountKind::Signer => ("Signer".to_string(), "signer"),
+        AnchorAccountKind::Program => ("Program".to_string(), "program"),
+        AnchorAccountKind::Interface(ty) => (short(ty), "interface"),
+        AnchorAccountKind::Sysvar(ty) => (format!("Sysvar<{}>", short(ty)), "sysvar"),
+        AnchorAccountKind::SystemAccount => ("SystemAccount".to_string(), "system_account"),
+        AnchorAccountKind::Unchecked => ("UncheckedAccount".to_string(), "unchecked"),
+        AnchorAccountKind::Unknown => ("unknown".to_string(), "unknown"),
+    }
+}
+
+/// Shape and fill color of the nodes of a kind of `node_of`.
+fn dot_style(kind: &str) -> (&'static str, &'static str) {
+    match kind {
+        "account" => ("ellipse", "lightblue"),
+        "interface_account" => ("ellipse", "lightcyan"),
+        "signer" => ("diamond", "palegreen"),
+        "program" | "interface" => ("hexagon", "plum"),
+        "sysvar" => ("note", "khaki"),
+        "system_account" => ("ellipse", "lightyellow"),
+        "unchecked" => ("octagon", "salmon"),
+        _ => ("ellipse", "white"),
+    }
+}
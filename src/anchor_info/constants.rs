@@ -0,0 +1,150 @@
+//! The constants of a program: its `const` items, which `#[constant]` exposes in
+//! the IDL, and the byte strings its Accounts structs derive PDAs from.
+use std::fmt;
+
+use rustc_public::mir::{Operand, Rvalue, StatementKind};
+use rustc_public::ty::{ConstantKind, RigidTy};
+use rustc_public::{CrateDef, CrateItem, ItemKind};
+
+use super::constraint::{
+    AnchorConstraint, Value, constant_value, local_const_bytes, pda_constraints,
+};
+use super::local_anchor_accounts;
+use super::program_id::{ProgramId, is_pubkey, item_bytes};
+
+/// Where a constant is defined or used.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConstantSource {
+    /// A local `const` item.
+    Item,
+    /// Component `idx` of the `seeds` of `field` in the Accounts struct
+    /// `accounts`.
+    Seed {
+        accounts: String,
+        field: String,
+        idx: usize,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConstantInfo {
+    /// Item path for a `const`, e.g. `seeds::VAULT_SEED`, and
+    /// `Struct.field[idx]` for a seed.
+    pub name: String,
+    pub ty: String,
+    pub value: Value,
+    pub source: ConstantSource,
+}
+
+impl ConstantInfo {
+    /// The bytes of a byte string constant, as hashed into a PDA.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match &self.value {
+            Value::Bytes(bytes) | Value::Const(_, Some(bytes)) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConstantInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} = {}", self.name, self.ty, self.value)
+    }
+}
+
+/// The local `const` items whose value we can read, then the byte string seeds
+/// of every PDA field, literal or named, in the order of the Accounts structs.
+///
+/// `#[constant]` only adds IDL entries under the `idl-build` feature, so every
+/// `const` is taken, except the associated consts the derives generate, such as
+/// `DISCRIMINATOR`.
+pub fn extract_constants() -> Vec<ConstantInfo> {
+    let mut constants = vec![];
+    for item in rustc_public::all_local_items() {
+        let name = item.name();
+        if !matches!(item.kind(), ItemKind::Const) || name.starts_with('<') {
+            continue;
+        }
+        if let Some(value) = const_value(item) {
+            constants.push(ConstantInfo {
+                name,
+                ty: item.ty().to_string(),
+                value,
+                source: ConstantSource::Item,
+            });
+        }
+    }
+    constants.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for anchor_accounts in local_anchor_accounts() {
+        let mut pdas: Vec<_> = pda_constraints(&anchor_accounts.name).into_iter().collect();
+        pdas.sort_by(|a, b| a.0.cmp(&b.0));
+        for (field, pda) in pdas {
+            for constraint in pda {
+                let AnchorConstraint::Seeds(seeds) = constraint else {
+                    continue;
+                };
+                for (idx, seed) in seeds.into_iter().enumerate() {
+                    let len = match &seed {
+                        Value::Bytes(bytes) | Value::Const(_, Some(bytes)) => bytes.len(),
+                        _ => continue,
+                    };
+                    constants.push(ConstantInfo {
+                        name: format!("{}.{field}[{idx}]", anchor_accounts.name),
+                        ty: format!("[u8; {len}]"),
+                        value: seed,
+                        source: ConstantSource::Seed {
+                            accounts: anchor_accounts.name.clone(),
+                            field: field.clone(),
+                            idx,
+                        },
+                    });
+                }
+            }
+        }
+    }
+    constants
+}
+
+/// The value a `const` item evaluates to: a `Pubkey`, an integer, the bytes a
+/// reference such as a byte string points to, or whatever else rustc evaluated.
+pub fn const_value(item: CrateItem) -> Option<Value> {
+    let ty = item.ty();
+    if is_pubkey(ty) {
+        let id = ProgramId::try_from(&item_bytes(item)?[..]).ok()?;
+        return Some(Value::Pubkey(id));
+    }
+    if matches!(ty.kind().rigid(), Some(RigidTy::Ref(..))) {
+        return local_const_bytes(&item.name()).map(Value::Bytes);
+    }
+    let body = item.body()?;
+    let constant = body
+        .blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(lhs, Rvalue::Use(Operand::Constant(constant)))
+                if lhs.local == 0 && lhs.projection.is_empty() =>
+            {
+                Some(constant.const_.clone())
+            }
+            _ => None,
+        })?;
+    let integer = match ty.kind().rigid() {
+        Some(RigidTy::Int(_)) => Some(true),
+        Some(RigidTy::Uint(_) | RigidTy::Bool) => Some(false),
+        _ => None,
+    };
+    match (integer, constant.kind()) {
+        (Some(signed), ConstantKind::Allocated(alloc)) => {
+            let value = if signed {
+                alloc.read_int().ok()?
+            } else {
+                alloc.read_uint().ok()?.try_into().ok()?
+            };
+            Some(Value::Int(value))
+        }
+        (Some(_), _) => None,
+        (None, _) => constant_value(&constant),
+    }
+}
@@ -0,0 +1,1061 @@
+//! Recover which `#[account(..)]` constraints Anchor checks in `try_accounts`.
+//!
+//! Every failing constraint builds an `anchor_lang::error::ErrorCode::ConstraintXxx`
+//! and, for most kinds, attaches the account name with `Error::with_account_name`.
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use rustc_public::mir::alloc::GlobalAlloc;
+use rustc_public::mir::{
+    AggregateKind, Body, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind,
+    TerminatorKind, UnOp,
+};
+use rustc_public::ty::{AdtKind, Allocation, ConstantKind, MirConst, RigidTy, TyKind};
+use rustc_public::{CrateDef, ItemKind};
+
+use super::access::short_name;
+use super::program_id::ProgramId;
+use super::{ACCOUNTS_EXIT, ANCHOR_ACCOUNTS, AnchorAccounts, accounts_impl_fn};
+use crate::analysis::condition::{Definitions, Origin, Side, bin_op_str, field_path};
+use crate::analysis::loops::predecessors;
+use crate::analysis::taint::operand_local;
+use crate::analysis::types::is_known_ty;
+use crate::metadata::Concept;
+
+/// A constraint from an `#[account(..)]` attribute.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AnchorConstraint {
+    /// `seeds = [..]`, in order.
+    Seeds(Vec<Value>),
+    /// `bump` without a value: `find_program_address` derives the canonical bump.
+    CanonicalBump,
+    /// `bump = <expr>`, checked with `create_program_address`.
+    Bump(Value),
+    /// `has_one = <field>`: the account's data stores the key of `field`.
+    HasOne(String),
+    /// `address = <expr>`: the account's key, usually a constant `Pubkey`.
+    Address(Value),
+    /// `owner = <expr>`: the program owning the account.
+    Owner(Value),
+    /// `token::mint = <expr>` or `associated_token::mint = <expr>`: the mint of a
+    /// token account. `Unknown` for `associated_token`, whose mint is only
+    /// checked through the derived address.
+    TokenMint(Value),
+    /// `constraint = <expr>`, summarized as the condition that must hold.
+    Raw(String),
+    /// `init` or `init_if_needed` with `payer = <field>`. `space` is `None` when
+    /// it is not a constant.
+    Init {
+        payer: String,
+        space: Option<u64>,
+        if_needed: bool,
+    },
+    /// `close = <field>`: `exit` moves the account's lamports to `destination`.
+    Close { destination: String },
+}
+
+/// A value Anchor feeds into a constraint check: a component of `seeds = [..]`,
+/// the value given to `bump = ..`, or a key an account is compared against.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Value {
+    /// A byte string: a literal such as `b"vault"`, or a `const` rustc has
+    /// already evaluated in place.
+    Bytes(Vec<u8>),
+    /// A `Pubkey` constant evaluated in place, e.g. from `pubkey!(..)`.
+    Pubkey(ProgramId),
+    /// An integer or `bool` constant, e.g. `const MAX_FEE_BPS: u16 = 500;`.
+    Int(i128),
+    /// A `static`, or a `const` left unevaluated, with its bytes when they can be
+    /// read.
+    Const(String, Option<Vec<u8>>),
+    /// `account.key()` of another field of the struct.
+    Key(String),
+    /// An `#[instruction(..)]` argument.
+    Arg(String),
+    /// A field of another account's data, e.g. `vault.bump` as `("vault", "bump")`.
+    Data(String, String),
+    /// Anything else, including seed arrays that rustc promoted to a constant as
+    /// a whole (e.g. `seeds = [b"config"]`), whose elements are not visible.
+    Unknown,
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bytes(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) if text.chars().all(|c| c.is_ascii_graphic() || c == ' ') => {
+                    write!(f, "b{text:?}")
+                }
+                _ => write!(f, "{bytes:?}"),
+            },
+            Value::Pubkey(id) => write!(f, "{id}"),
+            Value::Int(value) => write!(f, "{value}"),
+            Value::Const(name, _) => write!(f, "{name}"),
+            Value::Key(account) => write!(f, "{account}.key()"),
+            Value::Arg(name) => write!(f, "{name}"),
+            Value::Data(account, field) => write!(f, "{account}.{field}"),
+            Value::Unknown => write!(f, "_"),
+        }
+    }
+}
+
+const ERROR_CODE: &str = "anchor_lang::error::ErrorCode";
+const WITH_ACCOUNT_NAME: &str = "with_account_name";
+/// Raised by `try_accounts` when a `mut` account is passed readonly.
+const CONSTRAINT_MUT: &str = "ConstraintMut";
+
+/// How many blocks we walk from the error code to its `with_account_name` call.
+const MAX_ERROR_CHAIN: usize = 4;
+
+/// Map each field of the Accounts struct `name` to the `ErrorCode` variants
+/// (e.g. `ConstraintAddress`, `ConstraintOwner`) that `try_accounts` raises for it.
+pub fn constraint_error_codes(name: &str) -> HashMap<String, HashSet<String>> {
+    let mut codes: HashMap<String, HashSet<String>> = HashMap::new();
+    let Some(body) = accounts_impl_fn(name, ANCHOR_ACCOUNTS, "try_accounts").and_then(|f| f.body())
+    else {
+        return codes;
+    };
+    for (bb, block) in body.blocks.iter().enumerate() {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(
+                _,
+                Rvalue::Aggregate(AggregateKind::Adt(adt_def, idx, ..), _),
+            ) = &stmt.kind
+                && adt_def.name() == ERROR_CODE
+                && let Some(variant) = adt_def.variant(*idx)
+                && let Some(field) = error_account_name(&body, bb)
+            {
+                codes.entry(field).or_default().insert(variant.name());
+            }
+        }
+    }
+    codes
+}
+
+/// Indices of the fields of `anchor_accounts` declared mutable: `mut` fields,
+/// which `try_accounts` checks with `ConstraintMut`, and `init` and `close`
+/// fields, which imply `mut`. Call with the error codes of the struct, from
+/// [`constraint_error_codes`].
+pub fn declared_mut_fields(
+    anchor_accounts: &AnchorAccounts,
+    codes: &HashMap<String, HashSet<String>>,
+) -> BTreeSet<usize> {
+    anchor_accounts
+        .anchor_accounts
+        .iter()
+        .enumerate()
+        .filter(|(_, account)| {
+            codes
+                .get(&account.name)
+                .is_some_and(|codes| codes.contains(CONSTRAINT_MUT))
+                || account.constraints.iter().any(|constraint| {
+                    matches!(
+                        constraint,
+                        AnchorConstraint::Init { .. } | AnchorConstraint::Close { .. }
+                    )
+                })
+        })
+        .map(|(field_idx, _)| field_idx)
+        .collect()
+}
+
+const FIND_PROGRAM_ADDRESS: &str = "Pubkey::find_program_address";
+const CREATE_PROGRAM_ADDRESS: &str = "Pubkey::create_program_address";
+const CONSTRAINT_SEEDS: &str = "ConstraintSeeds";
+const KEY: &str = "anchor_lang::Key::key";
+/// Calls that reinterpret a value without changing where it comes from.
+const VALUE_CONVERSIONS: [&str; 7] = [
+    "::as_ref",
+    "::as_bytes",
+    "::as_slice",
+    "::to_le_bytes",
+    "::to_be_bytes",
+    "::deref",
+    "::index",
+];
+
+/// Map each PDA field of the Accounts struct `name` to its `seeds` and `bump`
+/// constraints.
+///
+/// Anchor derives each PDA with `find_program_address` (plain `bump`) or
+/// `create_program_address` (`bump = ..`, passed as the last seed), then compares
+/// the field's key against the result and raises `ConstraintSeeds` tagged with
+/// the field name. Each derivation is attributed to the first such error after it.
+pub fn pda_constraints(name: &str) -> HashMap<String, Vec<AnchorConstraint>> {
+    let mut constraints = HashMap::new();
+    let Some(body) = accounts_impl_fn(name, ANCHOR_ACCOUNTS, "try_accounts").and_then(|f| f.body())
+    else {
+        return constraints;
+    };
+    let tracer = ValueTracer::new(&body, name);
+    for (bb, block) in body.blocks.iter().enumerate() {
+        let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+            continue;
+        };
+        let Some(callee) = fn_name(&body, func) else {
+            continue;
+        };
+        let canonical = callee.ends_with(FIND_PROGRAM_ADDRESS);
+        if !canonical && !callee.ends_with(CREATE_PROGRAM_ADDRESS) {
+            continue;
+        }
+        let Some(field) = next_seeds_error(&body, bb) else {
+            continue;
+        };
+        let mut seeds = args
+            .first()
+            .map(|arg| tracer.seed_array(arg))
+            .unwrap_or_default();
+        let bump = if canonical {
+            AnchorConstraint::CanonicalBump
+        } else {
+            AnchorConstraint::Bump(seeds.pop().unwrap_or(Value::Unknown))
+        };
+        constraints.insert(field, vec![AnchorConstraint::Seeds(seeds), bump]);
+    }
+    constraints
+}
+
+/// Map each field of the Accounts struct `name` to its `has_one`, `address`,
+/// `owner`, `constraint` and token mint checks.
+///
+/// Each check branches on a comparison straight into its error: `ConstraintHasOne`,
+/// `ConstraintAddress`, `ConstraintOwner`, `ConstraintTokenMint`,
+/// `ConstraintAssociated`, or for `constraint = ..` either `ConstraintRaw` or the
+/// program's own error given with `@`. `token::mint` on an account that is not
+/// `init` raises its error without the account name, so the account is the one
+/// whose `mint` is compared.
+pub fn check_constraints(name: &str) -> HashMap<String, Vec<AnchorConstraint>> {
+    let mut constraints: HashMap<String, Vec<AnchorConstraint>> = HashMap::new();
+    let Some(body) = accounts_impl_fn(name, ANCHOR_ACCOUNTS, "try_accounts").and_then(|f| f.body())
+    else {
+        return constraints;
+    };
+    let tracer = ValueTracer::new(&body, name);
+    let preds = predecessors(&body);
+    for (bb, block) in body.blocks.iter().enumerate() {
+        for stmt in &block.statements {
+            let StatementKind::Assign(
+                _,
+                Rvalue::Aggregate(AggregateKind::Adt(adt_def, idx, ..), _),
+            ) = &stmt.kind
+            else {
+                continue;
+            };
+            let code = if adt_def.name() == ERROR_CODE {
+                adt_def.variant(*idx).map(|variant| variant.name())
+            } else if adt_def.krate().is_local && adt_def.kind() == AdtKind::Enum {
+                Some(CONSTRAINT_RAW.to_string())
+            } else {
+                None
+            };
+            let Some(code) = code else {
+                continue;
+            };
+            if !CHECK_ERRORS.contains(&code.as_str()) {
+                continue;
+            }
+            let Some((condition, fails_on_false)) = guard(&body, &preds[bb], bb) else {
+                continue;
+            };
+            let field = error_account_name(&body, bb).or_else(|| {
+                (code == CONSTRAINT_TOKEN_MINT)
+                    .then(|| tracer.mint_owner(&condition))
+                    .flatten()
+            });
+            let Some(field) = field else {
+                continue;
+            };
+            let constraint = match code.as_str() {
+                CONSTRAINT_RAW => {
+                    let summary = tracer.describe(&condition);
+                    if fails_on_false {
+                        AnchorConstraint::Raw(summary)
+                    } else {
+                        AnchorConstraint::Raw(format!("!({summary})"))
+                    }
+                }
+                CONSTRAINT_ASSOCIATED => AnchorConstraint::TokenMint(Value::Unknown),
+                code => {
+                    let Some(other) = tracer.compared_value(&condition, &field) else {
+                        continue;
+                    };
+                    match (code, other) {
+                        (CONSTRAINT_HAS_ONE, Value::Key(target)) => {
+                            AnchorConstraint::HasOne(target)
+                        }
+                        (CONSTRAINT_HAS_ONE, _) => continue,
+                        (CONSTRAINT_ADDRESS, value) => AnchorConstraint::Address(value),
+                        (CONSTRAINT_TOKEN_MINT, value) => AnchorConstraint::TokenMint(value),
+                        (_, value) => AnchorConstraint::Owner(value),
+                    }
+                }
+            };
+            constraints.entry(field).or_default().push(constraint);
+        }
+    }
+    constraints
+}
+
+const FN_CALL: &str = "std::ops::Fn::call";
+const CREATE_ACCOUNT: &str = "system_program::create_account";
+const CREATE_ACCOUNT_ACCOUNTS: &str = "system_program::CreateAccount";
+/// `init_if_needed` deserializes an account that already exists with the
+/// checked `try_from`; plain `init` only uses `try_from_unchecked`.
+const CHECKED_DESERIALIZE: &str = "::try_from";
+const TO_ACCOUNT_INFO: &str = "::to_account_info";
+const ACCOUNTS_CLOSE: &str = "anchor_lang::AccountsClose::close";
+
+/// Map each field of the Accounts struct `name` to its `init`/`init_if_needed`
+/// and `close` constraints.
+///
+/// `try_accounts` builds an `init` field by calling a closure that funds it with
+/// `system_program::create_account`, the payer being the `from` account of the
+/// CPI. `close` is a call to `AccountsClose::close` in `exit`.
+pub fn lifecycle_constraints(name: &str) -> HashMap<String, Vec<AnchorConstraint>> {
+    let mut constraints: HashMap<String, Vec<AnchorConstraint>> = HashMap::new();
+    if let Some(body) =
+        accounts_impl_fn(name, ANCHOR_ACCOUNTS, "try_accounts").and_then(|f| f.body())
+    {
+        let tracer = ValueTracer::new(&body, name);
+        for block in &body.blocks {
+            let TerminatorKind::Call {
+                func,
+                args,
+                destination,
+                ..
+            } = &block.terminator.kind
+            else {
+                continue;
+            };
+            if fn_name(&body, func).as_deref() != Some(FN_CALL) {
+                continue;
+            }
+            let Some((closure, captures)) = args.first().and_then(|arg| tracer.closure(arg)) else {
+                continue;
+            };
+            let Some(init) = InitClosure::new(&closure) else {
+                continue;
+            };
+            let Some(field) = tracer.field_built_from(destination.local) else {
+                continue;
+            };
+            let Some(payer) = captures
+                .get(init.payer_capture)
+                .and_then(|capture| tracer.account_of_operand(capture, 0))
+            else {
+                continue;
+            };
+            constraints
+                .entry(field)
+                .or_default()
+                .push(AnchorConstraint::Init {
+                    payer,
+                    space: init.space,
+                    if_needed: init.if_needed,
+                });
+        }
+    }
+    if let Some(body) = accounts_impl_fn(name, ACCOUNTS_EXIT, "exit").and_then(|f| f.body()) {
+        for (field, destination) in closed_fields(&body) {
+            constraints
+                .entry(field)
+                .or_default()
+                .push(AnchorConstraint::Close { destination });
+        }
+    }
+    constraints
+}
+
+/// What the `init` closure of one field does.
+struct InitClosure {
+    /// Index of the capture holding the payer.
+    payer_capture: usize,
+    space: Option<u64>,
+    if_needed: bool,
+}
+
+impl InitClosure {
+    fn new(body: &Body) -> Option<Self> {
+        let defs = Definitions::new(body);
+        let mut space = None;
+        let mut if_needed = false;
+        let mut created = false;
+        for block in &body.blocks {
+            let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+                continue;
+            };
+            let Some(callee) = fn_name(body, func) else {
+                continue;
+            };
+            if callee.ends_with(CREATE_ACCOUNT) {
+                created = true;
+                // `create_account(ctx, lamports, space, owner)`
+                space = args
+                    .get(2)
+                    .and_then(|arg| defs.origin(&Side::Operand(arg.clone())))
+                    .and_then(|origin| match origin {
+                        Origin::Const(value) => u64::try_from(value).ok(),
+                        Origin::Field { .. } => None,
+                    });
+            } else if callee.ends_with(CHECKED_DESERIALIZE) {
+                if_needed = true;
+            }
+        }
+        if !created {
+            return None;
+        }
+        Some(Self {
+            payer_capture: create_account_payer(body)?,
+            space,
+            if_needed,
+        })
+    }
+}
+
+/// The capture the `from` account of `CreateAccount { from, to }` is built from,
+/// as in `from: payer.to_account_info()` with `payer` captured by reference.
+fn create_account_payer(body: &Body) -> Option<usize> {
+    let mut calls = HashMap::new();
+    let mut derefs = HashMap::new();
+    let mut from = None;
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            match rvalue {
+                Rvalue::CopyForDeref(place) => {
+                    derefs.insert(lhs.local, place.clone());
+                }
+                Rvalue::Aggregate(AggregateKind::Adt(adt_def, ..), ops)
+                    if adt_def.name().ends_with(CREATE_ACCOUNT_ACCOUNTS) =>
+                {
+                    from = ops.first().and_then(operand_local);
+                }
+                _ => {}
+            }
+        }
+        if let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+            && fn_name(body, func).is_some_and(|callee| callee.ends_with(TO_ACCOUNT_INFO))
+        {
+            calls.insert(destination.local, args.first().and_then(operand_local)?);
+        }
+    }
+    let account = calls.get(&from?)?;
+    // `CopyForDeref((*_1).k)`: capture `k` of the closure `_1`.
+    match derefs.get(account)?.projection.as_slice() {
+        [ProjectionElem::Deref, ProjectionElem::Field(capture, _)] => Some(*capture),
+        _ => None,
+    }
+}
+
+/// `(closed field, destination field)` for each `AccountsClose::close(&self.a,
+/// self.b.to_account_info())` in `exit`.
+fn closed_fields(body: &Body) -> Vec<(String, String)> {
+    let Some(names) = self_field_names(body) else {
+        return vec![];
+    };
+    let field_of = |place: &Place| match place.projection.as_slice() {
+        [ProjectionElem::Deref, ProjectionElem::Field(field_idx, _)] if place.local == 1 => {
+            names.get(*field_idx).cloned()
+        }
+        _ => None,
+    };
+    let mut refs = HashMap::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(lhs, Rvalue::Ref(_, _, place)) = &stmt.kind
+                && let Some(field) = field_of(place)
+            {
+                refs.insert(lhs.local, field);
+            }
+        }
+    }
+    let mut account_infos = HashMap::new();
+    let mut closed = vec![];
+    for block in &body.blocks {
+        let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+        else {
+            continue;
+        };
+        let Some(callee) = fn_name(body, func) else {
+            continue;
+        };
+        let arg_field = |idx: usize| {
+            let local = args.get(idx).and_then(operand_local)?;
+            refs.get(&local)
+                .or_else(|| account_infos.get(&local))
+                .cloned()
+        };
+        if callee.ends_with(TO_ACCOUNT_INFO) {
+            if let Some(field) = arg_field(0) {
+                account_infos.insert(destination.local, field);
+            }
+        } else if callee == ACCOUNTS_CLOSE
+            && let (Some(field), Some(destination)) = (arg_field(0), arg_field(1))
+        {
+            closed.push((field, destination));
+        }
+    }
+    closed
+}
+
+/// Field names of the struct behind the `&self` argument.
+fn self_field_names(body: &Body) -> Option<Vec<String>> {
+    let self_ty = body.locals().get(1)?.ty;
+    let RigidTy::Ref(_, struct_ty, _) = self_ty.kind().rigid()?.clone() else {
+        return None;
+    };
+    let RigidTy::Adt(adt_def, _) = struct_ty.kind().rigid()?.clone() else {
+        return None;
+    };
+    let variant = adt_def.variants_iter().next()?;
+    Some(
+        variant
+            .fields()
+            .into_iter()
+            .map(|field| field.name)
+            .collect(),
+    )
+}
+
+const CONSTRAINT_HAS_ONE: &str = "ConstraintHasOne";
+const CONSTRAINT_ADDRESS: &str = "ConstraintAddress";
+const CONSTRAINT_OWNER: &str = "ConstraintOwner";
+const CONSTRAINT_RAW: &str = "ConstraintRaw";
+const CONSTRAINT_TOKEN_MINT: &str = "ConstraintTokenMint";
+const CONSTRAINT_ASSOCIATED: &str = "ConstraintAssociated";
+const CHECK_ERRORS: [&str; 6] = [
+    CONSTRAINT_HAS_ONE,
+    CONSTRAINT_ADDRESS,
+    CONSTRAINT_OWNER,
+    CONSTRAINT_RAW,
+    CONSTRAINT_TOKEN_MINT,
+    CONSTRAINT_ASSOCIATED,
+];
+
+/// The condition a predecessor of `bb` switches on to reach it, and whether `bb`
+/// is the target taken when the condition is false (`0`).
+fn guard(body: &Body, preds: &[usize], bb: usize) -> Option<(Operand, bool)> {
+    preds.iter().find_map(|&pred| {
+        let TerminatorKind::SwitchInt { discr, targets } = &body.blocks[pred].terminator.kind
+        else {
+            return None;
+        };
+        let on_zero = targets
+            .branches()
+            .any(|(value, target)| value == 0 && target == bb);
+        Some((discr.clone(), on_zero))
+    })
+}
+
+/// The account name of the first `ConstraintSeeds` error reachable from `bb`.
+fn next_seeds_error(body: &Body, bb: usize) -> Option<String> {
+    let mut visited = HashSet::from([bb]);
+    let mut worklist = VecDeque::from([bb]);
+    while let Some(bb) = worklist.pop_front() {
+        for stmt in &body.blocks[bb].statements {
+            if let StatementKind::Assign(
+                _,
+                Rvalue::Aggregate(AggregateKind::Adt(adt_def, idx, ..), _),
+            ) = &stmt.kind
+                && adt_def.name() == ERROR_CODE
+                && adt_def
+                    .variant(*idx)
+                    .is_some_and(|v| v.name() == CONSTRAINT_SEEDS)
+            {
+                return error_account_name(body, bb);
+            }
+        }
+        for succ in body.blocks[bb].terminator.successors() {
+            if visited.insert(succ) {
+                worklist.push_back(succ);
+            }
+        }
+    }
+    None
+}
+
+fn fn_name(body: &Body, func: &Operand) -> Option<String> {
+    let fn_ty = func.ty(body.locals()).ok()?;
+    let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind() else {
+        return None;
+    };
+    Some(fn_def.name())
+}
+
+/// What a local was assigned in `try_accounts`.
+enum Def<'a> {
+    Rvalue(&'a Rvalue),
+    Call(String, &'a [Operand]),
+}
+
+/// Follows expressions in `try_accounts` back to literals, constants,
+/// instruction arguments and fields.
+struct ValueTracer<'a> {
+    body: &'a Body,
+    defs: HashMap<Local, Def<'a>>,
+    /// Locals holding a deserialized field, by field name.
+    fields: HashMap<Local, String>,
+}
+
+impl<'a> ValueTracer<'a> {
+    fn new(body: &'a Body, name: &str) -> Self {
+        let mut defs = HashMap::new();
+        let mut fields = HashMap::new();
+        for block in &body.blocks {
+            for stmt in &block.statements {
+                let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                    continue;
+                };
+                if !lhs.projection.is_empty() {
+                    continue;
+                }
+                defs.insert(lhs.local, Def::Rvalue(rvalue));
+                if let Rvalue::Aggregate(AggregateKind::Adt(adt_def, ..), ops) = rvalue
+                    && short_name(&adt_def.name()) == name
+                    && let Some(variant) = adt_def.variants_iter().next()
+                {
+                    for (op, field) in ops.iter().zip(variant.fields()) {
+                        if let Operand::Copy(place) | Operand::Move(place) = op
+                            && place.projection.is_empty()
+                        {
+                            fields.insert(place.local, field.name);
+                        }
+                    }
+                }
+            }
+            if let TerminatorKind::Call {
+                func,
+                args,
+                destination,
+                ..
+            } = &block.terminator.kind
+                && destination.projection.is_empty()
+                && let Some(callee) = fn_name(body, func)
+            {
+                defs.insert(destination.local, Def::Call(callee, args));
+            }
+        }
+        Self { body, defs, fields }
+    }
+
+    /// The body and captures of the closure `operand` refers to.
+    fn closure(&self, operand: &Operand) -> Option<(Body, &'a [Operand])> {
+        let local = operand_local(operand)?;
+        let Some(Def::Rvalue(Rvalue::Ref(_, _, place))) = self.defs.get(&local) else {
+            return None;
+        };
+        let Some(Def::Rvalue(Rvalue::Aggregate(AggregateKind::Closure(def, _), captures))) =
+            self.defs.get(&place.local)
+        else {
+            return None;
+        };
+        Some((def.body()?, captures))
+    }
+
+    /// The field whose value is `local` after `?` and moves.
+    fn field_built_from(&self, local: Local) -> Option<String> {
+        self.fields.iter().find_map(|(field_local, name)| {
+            let mut current = *field_local;
+            for _ in 0..MAX_TRACE_DEPTH {
+                if current == local {
+                    return Some(name.clone());
+                }
+                current = match self.defs.get(&current)? {
+                    Def::Rvalue(Rvalue::Use(Operand::Copy(place) | Operand::Move(place))) => {
+                        place.local
+                    }
+                    Def::Call(callee, args) if callee.ends_with("::branch") => {
+                        operand_local(args.first()?)?
+                    }
+                    _ => return None,
+                };
+            }
+            None
+        })
+    }
+
+    /// The seeds of a `&[&[u8]]` argument to `find_program_address` or
+    /// `create_program_address`.
+    fn seed_array(&self, operand: &Operand) -> Vec<Value> {
+        let mut local = match operand {
+            Operand::Copy(place) | Operand::Move(place) => place.local,
+            Operand::Constant(_) => return vec![Value::Unknown],
+        };
+        for _ in 0..MAX_TRACE_DEPTH {
+            match self.defs.get(&local) {
+                Some(Def::Rvalue(Rvalue::Aggregate(AggregateKind::Array(_), ops))) => {
+                    return ops.iter().map(|op| self.value(op)).collect();
+                }
+                Some(Def::Rvalue(
+                    Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+                    | Rvalue::Cast(_, Operand::Copy(place) | Operand::Move(place), _)
+                    | Rvalue::Ref(_, _, place),
+                )) if place.projection.is_empty() => local = place.local,
+                // A promoted `&[b"config"]`.
+                Some(Def::Rvalue(
+                    Rvalue::Use(Operand::Constant(_)) | Rvalue::Cast(_, Operand::Constant(_), _),
+                )) => {
+                    return vec![Value::Unknown];
+                }
+                _ => break,
+            }
+        }
+        vec![]
+    }
+
+    /// In a `PartialEq::eq`/`ne` comparison of two keys, the side that is not
+    /// `field` itself (its key or its `AccountInfo::owner`).
+    fn compared_value(&self, condition: &Operand, field: &str) -> Option<Value> {
+        let (_, lhs, rhs) = self.comparison(condition)?;
+        let is_own = |value: &Value| match value {
+            Value::Key(account) | Value::Data(account, _) => account == field,
+            _ => false,
+        };
+        let lhs = self.value(lhs);
+        let rhs = self.value(rhs);
+        if is_own(&lhs) { Some(rhs) } else { Some(lhs) }
+    }
+
+    /// The account whose `mint` is one side of the comparison `condition`.
+    fn mint_owner(&self, condition: &Operand) -> Option<String> {
+        let (_, lhs, rhs) = self.comparison(condition)?;
+        [lhs, rhs]
+            .into_iter()
+            .find_map(|operand| match self.value(operand) {
+                Value::Data(account, field) if field == MINT => Some(account),
+                _ => None,
+            })
+    }
+
+    /// `(op, lhs, rhs)` if `condition` is a comparison, either built in or a
+    /// `PartialEq`/`PartialOrd` call.
+    fn comparison(&self, condition: &Operand) -> Option<(&'static str, &'a Operand, &'a Operand)> {
+        let (Operand::Copy(place) | Operand::Move(place)) = condition else {
+            return None;
+        };
+        if !place.projection.is_empty() {
+            return None;
+        }
+        match self.defs.get(&place.local)? {
+            Def::Rvalue(Rvalue::BinaryOp(op, lhs, rhs)) => Some((bin_op_str(*op)?, lhs, rhs)),
+            Def::Call(callee, args) if args.len() == 2 => {
+                let op = COMPARISON_CALLS
+                    .iter()
+                    .find(|(suffix, _)| callee.ends_with(suffix))?
+                    .1;
+                Some((op, &args[0], &args[1]))
+            }
+            _ => None,
+        }
+    }
+
+    /// A short source-like rendering of a boolean condition.
+    fn describe(&self, condition: &Operand) -> String {
+        if let Some((op, lhs, rhs)) = self.comparison(condition) {
+            return format!(
+                "{} {op} {}",
+                self.describe_operand(lhs),
+                self.describe_operand(rhs)
+            );
+        }
+        if let Operand::Copy(place) | Operand::Move(place) = condition
+            && place.projection.is_empty()
+            && let Some(Def::Rvalue(Rvalue::UnaryOp(UnOp::Not, inner))) =
+                self.defs.get(&place.local)
+        {
+            return format!("!{}", self.describe(inner));
+        }
+        self.describe_operand(condition)
+    }
+
+    fn describe_operand(&self, operand: &Operand) -> String {
+        if let Operand::Constant(constant) = operand
+            && let Ok(ty) = operand.ty(self.body.locals())
+            && matches!(
+                ty.kind().rigid(),
+                Some(RigidTy::Int(_) | RigidTy::Uint(_) | RigidTy::Bool)
+            )
+            && let ConstantKind::Allocated(alloc) = constant.const_.kind()
+            && let Ok(value) = alloc.read_int()
+        {
+            return if matches!(ty.kind().rigid(), Some(RigidTy::Bool)) {
+                (value != 0).to_string()
+            } else {
+                value.to_string()
+            };
+        }
+        self.value(operand).to_string()
+    }
+
+    fn value(&self, operand: &Operand) -> Value {
+        self.value_at(operand, 0).unwrap_or(Value::Unknown)
+    }
+
+    fn value_at(&self, operand: &Operand, depth: usize) -> Option<Value> {
+        if depth > MAX_TRACE_DEPTH {
+            return None;
+        }
+        let place = match operand {
+            Operand::Constant(constant) => return constant_value(&constant.const_),
+            Operand::Copy(place) | Operand::Move(place) => place,
+        };
+        self.place_value(place, depth)
+    }
+
+    fn place_value(&self, place: &Place, depth: usize) -> Option<Value> {
+        match place.projection.as_slice() {
+            [] => {}
+            // `(*_x)`: follow the reference.
+            [ProjectionElem::Deref] => return self.local_value(place.local, depth),
+            // `(_args.i)` of the deserialized `#[instruction(..)]` arguments, or
+            // `((*_x).i)` of an account's data reached through `Deref`.
+            [.., ProjectionElem::Field(field_idx, _)] => {
+                let parent = Place {
+                    local: place.local,
+                    projection: place.projection[..place.projection.len() - 1].to_vec(),
+                };
+                let ty = parent.ty(self.body.locals()).ok()?;
+                let path = field_path(ty, *field_idx);
+                let field = path.rsplit('.').next().unwrap_or(&path).to_string();
+                if let Some(account) = self.account_of(&parent, depth) {
+                    return Some(Value::Data(account, field));
+                }
+                return path
+                    .starts_with(INSTRUCTION_ARGS)
+                    .then_some(Value::Arg(field));
+            }
+            _ => return None,
+        }
+        self.local_value(place.local, depth)
+    }
+
+    fn local_value(&self, local: Local, depth: usize) -> Option<Value> {
+        match self.defs.get(&local)? {
+            Def::Rvalue(Rvalue::Use(operand) | Rvalue::Cast(_, operand, _)) => {
+                self.value_at(operand, depth + 1)
+            }
+            Def::Rvalue(Rvalue::Ref(_, _, place) | Rvalue::CopyForDeref(place)) => {
+                self.place_value(place, depth + 1)
+            }
+            // `[bump]` built for `&[bump][..]`.
+            Def::Rvalue(Rvalue::Aggregate(AggregateKind::Array(_), ops)) if ops.len() == 1 => {
+                self.value_at(&ops[0], depth + 1)
+            }
+            Def::Call(callee, args) if callee.ends_with(KEY) => {
+                let arg = args.first()?;
+                self.account_of_operand(arg, depth).map(Value::Key)
+            }
+            Def::Call(callee, args)
+                if VALUE_CONVERSIONS
+                    .iter()
+                    .any(|suffix| callee.ends_with(suffix)) =>
+            {
+                self.value_at(args.first()?, depth + 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// The field whose deserialized account `place` refers to, looking through
+    /// references, `Deref::deref` and `AccountInfo` views of it.
+    fn account_of(&self, place: &Place, depth: usize) -> Option<String> {
+        if depth > MAX_TRACE_DEPTH {
+            return None;
+        }
+        match place.projection.as_slice() {
+            [] => {}
+            [ProjectionElem::Deref] => {}
+            _ => return None,
+        }
+        // Fields that need a drop, e.g. a bare `AccountInfo`, are moved into the
+        // struct through a temporary.
+        if let Some(field) = self.field_built_from(place.local) {
+            return Some(field);
+        }
+        match self.defs.get(&place.local)? {
+            Def::Rvalue(Rvalue::Use(operand)) => self.account_of_operand(operand, depth + 1),
+            Def::Rvalue(Rvalue::Ref(_, _, place) | Rvalue::CopyForDeref(place)) => {
+                self.account_of(place, depth + 1)
+            }
+            Def::Call(callee, args)
+                if ACCOUNT_VIEWS.iter().any(|suffix| callee.ends_with(suffix)) =>
+            {
+                self.account_of_operand(args.first()?, depth + 1)
+            }
+            _ => None,
+        }
+    }
+
+    fn account_of_operand(&self, operand: &Operand, depth: usize) -> Option<String> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.account_of(place, depth),
+            Operand::Constant(_) => None,
+        }
+    }
+}
+
+/// Comparison calls and the operator they implement.
+const COMPARISON_CALLS: [(&str, &str); 6] = [
+    ("::eq", "=="),
+    ("::ne", "!="),
+    ("::lt", "<"),
+    ("::le", "<="),
+    ("::gt", ">"),
+    ("::ge", ">="),
+];
+
+/// The struct `try_accounts` deserializes `#[instruction(..)]` arguments into.
+const INSTRUCTION_ARGS: &str = "__Args.";
+/// Calls that view an account as its data or its `AccountInfo`.
+const ACCOUNT_VIEWS: [&str; 3] = ["::deref", "::as_ref", "::to_account_info"];
+
+/// The field of an SPL token account holding its mint.
+const MINT: &str = "mint";
+
+/// How far an expression is followed back.
+const MAX_TRACE_DEPTH: usize = 8;
+
+/// A literal (`b"vault"`), a named constant (`VAULT_SEED`), a constant value
+/// such as a `Pubkey`, or a reference to a static such as the program `ID`.
+pub fn constant_value(constant: &MirConst) -> Option<Value> {
+    match constant.kind() {
+        ConstantKind::Allocated(alloc) => match alloc.provenance.ptrs.first() {
+            None => {
+                let bytes = alloc.raw_bytes().ok()?;
+                if is_known_ty(Concept::Pubkey, constant.ty())
+                    && let Ok(id) = ProgramId::try_from(&bytes[..])
+                {
+                    Some(Value::Pubkey(id))
+                } else {
+                    Some(Value::Bytes(bytes))
+                }
+            }
+            Some((_, prov)) => match GlobalAlloc::from(prov.0) {
+                GlobalAlloc::Memory(data) => data.raw_bytes().ok().map(Value::Bytes),
+                GlobalAlloc::Static(def) => {
+                    let bytes = def.eval_initializer().ok().and_then(|a| a.raw_bytes().ok());
+                    Some(Value::Const(short_name(&def.name()), bytes))
+                }
+                _ => None,
+            },
+        },
+        ConstantKind::Unevaluated(unevaluated) if unevaluated.promoted.is_none() => {
+            let name = unevaluated.def.name();
+            Some(Value::Const(short_name(&name), local_const_bytes(&name)))
+        }
+        _ => None,
+    }
+}
+
+/// The bytes a reference constant points to.
+fn pointee_bytes(constant: &MirConst) -> Option<Vec<u8>> {
+    let ConstantKind::Allocated(alloc) = constant.kind() else {
+        return None;
+    };
+    let (_, prov) = alloc.provenance.ptrs.first()?;
+    let GlobalAlloc::Memory(data) = GlobalAlloc::from(prov.0) else {
+        return None;
+    };
+    data.raw_bytes().ok()
+}
+
+/// The bytes of a local `const NAME: &[u8] = b"..";`.
+pub(super) fn local_const_bytes(name: &str) -> Option<Vec<u8>> {
+    let item = rustc_public::all_local_items()
+        .into_iter()
+        .find(|item| matches!(item.kind(), ItemKind::Const) && item.name() == name)?;
+    let body = item.body()?;
+    body.blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|stmt| {
+            if let StatementKind::Assign(_, Rvalue::Use(Operand::Constant(constant))) = &stmt.kind {
+                pointee_bytes(&constant.const_)
+            } else {
+                None
+            }
+        })
+}
+
+/// Follow the error built in block `bb` to the account name it is tagged with.
+fn error_account_name(body: &Body, mut bb: usize) -> Option<String> {
+    for _ in 0..MAX_ERROR_CHAIN {
+        let TerminatorKind::Call {
+            func, args, target, ..
+        } = &body.blocks[bb].terminator.kind
+        else {
+            return None;
+        };
+        if let Ok(fn_ty) = func.ty(body.locals())
+            && let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind()
+            && fn_def.name().ends_with(WITH_ACCOUNT_NAME)
+        {
+            return args.get(1).and_then(const_str);
+        }
+        bb = (*target)?;
+    }
+    None
+}
+
+/// Read a `&str` constant operand.
+pub(crate) fn const_str(operand: &Operand) -> Option<String> {
+    let Operand::Constant(constant) = operand else {
+        return None;
+    };
+    let ConstantKind::Allocated(fat_ptr) = constant.const_.kind() else {
+        return None;
+    };
+    str_at(fat_ptr)
+}
+
+/// Read a `&[&str; N]` constant, e.g. the pieces of a format string.
+pub(crate) fn const_strs(constant: &MirConst) -> Option<Vec<String>> {
+    let ConstantKind::Allocated(ptr) = constant.kind() else {
+        return None;
+    };
+    let (_, prov) = ptr.provenance.ptrs.first()?;
+    let GlobalAlloc::Memory(strs) = GlobalAlloc::from(prov.0) else {
+        return None;
+    };
+    strs_at(&strs)
+}
+
+/// Read the `&str` stored in `fat_ptr`.
+pub(crate) fn str_at(fat_ptr: &Allocation) -> Option<String> {
+    strs_at(fat_ptr)?.into_iter().next()
+}
+
+/// Read the `&str`s stored back to back in `fat_ptrs`, e.g. a `[&str; N]`.
+fn strs_at(fat_ptrs: &Allocation) -> Option<Vec<String>> {
+    (0..fat_ptrs.bytes.len() / 16)
+        .map(|idx| {
+            let offset = idx * 16;
+            let len = fat_ptrs.read_partial_uint(offset + 8..offset + 16).ok()? as usize;
+            if len == 0 {
+                return Some(String::new());
+            }
+            let (_, prov) = fat_ptrs
+                .provenance
+                .ptrs
+                .iter()
+                .find(|(ptr_offset, _)| *ptr_offset == offset)?;
+            let GlobalAlloc::Memory(data) = GlobalAlloc::from(prov.0) else {
+                return None;
+            };
+            let bytes = data.raw_bytes().ok()?;
+            String::from_utf8(bytes.get(..len)?.to_vec()).ok()
+        })
+        .collect()
+}
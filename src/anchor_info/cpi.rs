@@ -0,0 +1,306 @@
+//! The CPIs instruction handlers perform: the program called, the instruction
+//! when the callee names it, and the accounts passed.
+use std::collections::BTreeSet;
+use std::fmt;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{AggregateKind, Body, Operand, Rvalue, StatementKind, TerminatorKind};
+use rustc_public::ty::{RigidTy, TyKind};
+
+use super::access::{Role, local_roles};
+use super::constraint::{Value, constant_value};
+use super::program_id::ProgramId;
+use super::{AnchorAccountKind, handlers_by_accounts};
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::metadata::{Concept, known_types};
+use crate::report::location;
+
+const CPI_CONTEXT: &str = "anchor_lang::context::CpiContext";
+/// `CpiContext` constructors, taking the program and then the accounts.
+const CPI_CONTEXT_NEW: [&str; 2] = ["::new", "::new_with_signer"];
+const INSTRUCTION: &str = "::Instruction";
+const PROGRAM_ID: &str = "program_id";
+
+/// How deep we follow accounts into local helpers.
+const MAX_DEPTH: usize = 4;
+
+/// The program a CPI calls.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CpiTarget {
+    /// A constant program id, e.g. `system_program::ID`.
+    Const(ProgramId),
+    /// The field of the Accounts struct passed as the program, usually a
+    /// `Program<T>`.
+    Field(String),
+    /// Anything we cannot pin down, e.g. the key of an unchecked account.
+    Dynamic,
+}
+
+impl fmt::Display for CpiTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CpiTarget::Const(id) => write!(f, "{id}"),
+            CpiTarget::Field(field) => write!(f, "{field}"),
+            CpiTarget::Dynamic => write!(f, "dynamic"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CpiCall {
+    /// The instruction handler the CPI is performed in, maybe through helpers.
+    pub handler: String,
+    /// `file.rs:line` of the call.
+    pub location: String,
+    pub target: CpiTarget,
+    /// The Anchor `CpiContext` helper called, e.g. `anchor_spl::token::transfer`,
+    /// or the fn building the instruction passed to `invoke`, e.g.
+    /// `system_instruction::transfer`. `None` for instructions built in place.
+    pub instruction: Option<String>,
+    /// Fields of the Accounts struct passed to the call.
+    pub accounts: Vec<String>,
+}
+
+/// Every CPI of every instruction handler, in the order of the Accounts structs
+/// and of the handlers using each.
+pub fn extract_cpi_calls() -> Vec<CpiCall> {
+    let mut calls = vec![];
+    for (_, (adt_def, handlers)) in handlers_by_accounts() {
+        let Some(variant) = adt_def.variants_iter().next() else {
+            continue;
+        };
+        let fields = variant.fields();
+        let programs: BTreeSet<usize> = fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| {
+                matches!(
+                    AnchorAccountKind::from_ty(&field.ty().kind()),
+                    Some(AnchorAccountKind::Program | AnchorAccountKind::Interface(_))
+                )
+            })
+            .map(|(field_idx, _)| field_idx)
+            .collect();
+        for handler in handlers {
+            let Some(body) = handler.body() else {
+                continue;
+            };
+            let sources: Taint<usize> = local_roles(&body, 1, Role::Ctx)
+                .into_iter()
+                .filter_map(|(local, role)| match role {
+                    Role::Field(field_idx) => Some((local, BTreeSet::from([field_idx]))),
+                    _ => None,
+                })
+                .collect();
+            for site in cpi_sites(&body, sources, 0) {
+                let target = match (site.program_id, site.program) {
+                    (Some(id), _) => CpiTarget::Const(id),
+                    (None, Some(field_idx)) => CpiTarget::Field(fields[field_idx].name.clone()),
+                    // The runtime needs the program among the accounts, so a
+                    // single `Program<T>` passed to `invoke` is the one called.
+                    (None, None) => {
+                        let mut passed = site.accounts.intersection(&programs);
+                        match (passed.next(), passed.next(), site.from_context) {
+                            (Some(field_idx), None, false) => {
+                                CpiTarget::Field(fields[*field_idx].name.clone())
+                            }
+                            _ => CpiTarget::Dynamic,
+                        }
+                    }
+                };
+                calls.push(CpiCall {
+                    handler: handler.name(),
+                    location: site.location,
+                    target,
+                    instruction: site.instruction,
+                    accounts: site
+                        .accounts
+                        .iter()
+                        .map(|field_idx| fields[*field_idx].name.clone())
+                        .collect(),
+                });
+            }
+        }
+    }
+    calls
+}
+
+/// A CPI in terms of the fields labelling the locals of one body.
+struct CpiSite {
+    location: String,
+    program_id: Option<ProgramId>,
+    /// The field passed as the program of a `CpiContext`.
+    program: Option<usize>,
+    from_context: bool,
+    instruction: Option<String>,
+    accounts: BTreeSet<usize>,
+}
+
+/// Where an `Instruction` comes from: a builder fn or an aggregate with a
+/// constant program id.
+struct InstructionOrigin {
+    builder: Option<String>,
+    program_id: Option<ProgramId>,
+}
+
+/// The CPIs `body` performs, directly or through local helpers, given the fields
+/// the locals in `sources` hold.
+fn cpi_sites(body: &Body, sources: Taint<usize>, depth: usize) -> Vec<CpiSite> {
+    let taint = propagate(body, sources);
+
+    // `CpiContext`s built in the body, as (program, accounts), and instructions,
+    // each labelling the locals it flows into.
+    let mut contexts = vec![];
+    let mut context_sources = Taint::new();
+    let mut origins = vec![];
+    let mut origin_sources = Taint::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(
+                lhs,
+                Rvalue::Aggregate(AggregateKind::Adt(adt_def, ..), operands),
+            ) = &stmt.kind
+                && adt_def.name().ends_with(INSTRUCTION)
+                && let Some(variant) = adt_def.variants_iter().next()
+                && let Some(idx) = variant
+                    .fields()
+                    .iter()
+                    .position(|field| field.name == PROGRAM_ID)
+            {
+                let program_id = match operands.get(idx) {
+                    Some(Operand::Constant(constant)) => match constant_value(&constant.const_) {
+                        Some(Value::Pubkey(id)) => Some(id),
+                        Some(Value::Bytes(id) | Value::Const(_, Some(id))) => {
+                            ProgramId::try_from(&id[..]).ok()
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                origin_sources.insert(lhs.local, BTreeSet::from([origins.len()]));
+                origins.push(InstructionOrigin {
+                    builder: None,
+                    program_id,
+                });
+            }
+        }
+        let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+        else {
+            continue;
+        };
+        let Some(callee_name) = callee_name(body, func) else {
+            continue;
+        };
+        if callee_name.starts_with(CPI_CONTEXT)
+            && CPI_CONTEXT_NEW
+                .iter()
+                .any(|suffix| callee_name.ends_with(suffix))
+        {
+            let program = labels_of(&taint, args.first().and_then(operand_local));
+            let accounts = labels_of(&taint, args.get(1).and_then(operand_local));
+            context_sources.insert(destination.local, BTreeSet::from([contexts.len()]));
+            contexts.push((program, accounts));
+        } else if destination
+            .ty(body.locals())
+            .is_ok_and(|ty| ty.to_string().ends_with(INSTRUCTION))
+        {
+            origin_sources.insert(destination.local, BTreeSet::from([origins.len()]));
+            origins.push(InstructionOrigin {
+                builder: Some(callee_name),
+                program_id: None,
+            });
+        }
+    }
+    let context_taint = propagate(body, context_sources);
+    let origin_taint = propagate(body, origin_sources);
+
+    let mut sites = vec![];
+    for block in &body.blocks {
+        let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+            continue;
+        };
+        let Ok(fn_ty) = func.ty(body.locals()) else {
+            continue;
+        };
+        let TyKind::RigidTy(RigidTy::FnDef(fn_def, generic_args)) = fn_ty.kind() else {
+            continue;
+        };
+        let name = fn_def.name();
+        let takes_context = args.iter().any(|arg| {
+            arg.ty(body.locals())
+                .is_ok_and(|ty| ty.to_string().starts_with(CPI_CONTEXT))
+        });
+        let location = location(&block.terminator.span);
+        if !fn_def.krate().is_local && known_types().is(Concept::Invoke, &name) {
+            let origins: Vec<&InstructionOrigin> =
+                labels_of(&origin_taint, args.first().and_then(operand_local))
+                    .into_iter()
+                    .map(|origin| &origins[origin])
+                    .collect();
+            let (instruction, program_id) = match origins[..] {
+                [origin] => (origin.builder.clone(), origin.program_id),
+                _ => (None, None),
+            };
+            sites.push(CpiSite {
+                location,
+                program_id,
+                program: None,
+                from_context: false,
+                instruction,
+                accounts: labels_of(&taint, args.get(1).and_then(operand_local)),
+            });
+        } else if takes_context && !name.starts_with(CPI_CONTEXT) {
+            let mut programs = BTreeSet::new();
+            let mut accounts = BTreeSet::new();
+            for context in labels_of(&context_taint, args.iter().filter_map(operand_local)) {
+                programs.extend(contexts[context].0.iter().copied());
+                accounts.extend(contexts[context].1.iter().copied());
+            }
+            let mut programs = programs.into_iter();
+            let program = match (programs.next(), programs.next()) {
+                (Some(field_idx), None) => Some(field_idx),
+                _ => None,
+            };
+            sites.push(CpiSite {
+                location,
+                program_id: None,
+                program,
+                from_context: true,
+                instruction: Some(name),
+                accounts,
+            });
+        } else if fn_def.krate().is_local
+            && depth < MAX_DEPTH
+            && let Ok(helper) = Instance::resolve(fn_def, &generic_args)
+            && let Some(helper_body) = helper.body()
+        {
+            let helper_sources: Taint<usize> = args
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, arg)| {
+                    let labels = labels_of(&taint, operand_local(arg));
+                    (!labels.is_empty()).then_some((idx + 1, labels))
+                })
+                .collect();
+            if !helper_sources.is_empty() {
+                sites.extend(cpi_sites(&helper_body, helper_sources, depth + 1));
+            }
+        }
+    }
+    sites
+}
+
+/// Path of the fn a call operand calls.
+fn callee_name(body: &Body, func: &Operand) -> Option<String> {
+    let fn_ty = func.ty(body.locals()).ok()?;
+    match fn_ty.kind() {
+        TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) => Some(fn_def.name()),
+        _ => None,
+    }
+}
@@ -0,0 +1,169 @@
+//! Recover the error table of a program from its `#[error_code]` enums.
+//!
+//! For each enum, Anchor generates `From<E> for u32` adding the offset (6000
+//! unless `#[error_code(offset = ..)]`) to the discriminant, and a `Display` that
+//! writes the `#[msg(..)]` of each variant, or its `Debug` name without one.
+use std::collections::HashMap;
+
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{BinOp, Body, Operand, Rvalue, StatementKind, TerminatorKind};
+use rustc_public::ty::{AdtDef, ConstantKind, MirConst, RigidTy};
+use rustc_public::{CrateDef, ItemKind};
+
+use super::access::short_name;
+use super::constraint::const_strs;
+
+const ANCHOR_ERROR: &str = "anchor_lang::error::Error";
+const FROM: &str = "std::convert::From";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ErrorCode {
+    /// Short name of the `#[error_code]` enum.
+    pub enum_name: String,
+    pub name: String,
+    pub code: u32,
+    /// The `#[msg(..)]` of the variant, or its name as Anchor reports it when
+    /// there is none.
+    pub msg: String,
+}
+
+/// Every variant of the local `#[error_code]` enums, ordered by code.
+pub fn extract_error_codes() -> Vec<ErrorCode> {
+    let mut codes = vec![];
+    for adt_def in error_enums() {
+        let enum_name = short_name(&adt_def.name());
+        let Some(offset) = code_offset(&adt_def) else {
+            continue;
+        };
+        let messages = messages(&adt_def);
+        for variant in adt_def.variants_iter() {
+            let discr = adt_def.discriminant_for_variant(variant.idx).val;
+            let Some(code) = u32::try_from(discr)
+                .ok()
+                .and_then(|discr| discr.checked_add(offset))
+            else {
+                continue;
+            };
+            let name = variant.name();
+            codes.push(ErrorCode {
+                enum_name: enum_name.clone(),
+                msg: messages
+                    .get(&discr)
+                    .cloned()
+                    .unwrap_or_else(|| name.clone()),
+                name,
+                code,
+            });
+        }
+    }
+    codes.sort_by_key(|error_code| error_code.code);
+    codes
+}
+
+/// The code of the error variant `name`, either `Variant` or `Enum::Variant`.
+// For checkers matching `require!(.., Enum::Variant)`; none does yet.
+#[allow(dead_code)]
+pub fn error_code_for(name: &str) -> Option<u32> {
+    let (enum_name, name) = match name.rsplit_once("::") {
+        Some((enum_name, name)) => (Some(short_name(enum_name)), name),
+        None => (None, name),
+    };
+    extract_error_codes()
+        .into_iter()
+        .find(|error_code| {
+            error_code.name == name
+                && enum_name
+                    .as_ref()
+                    .is_none_or(|enum_name| *enum_name == error_code.enum_name)
+        })
+        .map(|error_code| error_code.code)
+}
+
+/// Local enums with a `From<E> for anchor_lang::error::Error` impl.
+fn error_enums() -> Vec<AdtDef> {
+    let mut enums = vec![];
+    for trait_impl in rustc_public::all_trait_impls() {
+        let trait_ref = trait_impl.trait_impl().value;
+        if trait_ref.def_id.name() != FROM {
+            continue;
+        }
+        let is_anchor_error = matches!(
+            trait_ref.self_ty().kind().rigid(),
+            Some(RigidTy::Adt(adt_def, _)) if adt_def.name() == ANCHOR_ERROR
+        );
+        if !is_anchor_error {
+            continue;
+        }
+        if let Some(source) = trait_ref.args().0.get(1).and_then(|arg| arg.ty())
+            && let Some(RigidTy::Adt(adt_def, _)) = source.kind().rigid()
+            && adt_def.krate().is_local
+            && adt_def.kind().is_enum()
+        {
+            enums.push(*adt_def);
+        }
+    }
+    enums
+}
+
+/// Mono body of the local fn named `name`, with constants evaluated.
+fn local_fn_body(name: &str) -> Option<Body> {
+    rustc_public::all_local_items()
+        .into_iter()
+        .find(|item| matches!(item.kind(), ItemKind::Fn) && item.name() == name)
+        .and_then(|item| Instance::try_from(item).ok())
+        .and_then(|instance| instance.body())
+}
+
+/// The constant added to the discriminant in `From<E> for u32`.
+fn code_offset(adt_def: &AdtDef) -> Option<u32> {
+    let body = local_fn_body(&format!("<impl {FROM}<{}> for u32>::from", adt_def.name()))?;
+    body.blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(
+                _,
+                Rvalue::CheckedBinaryOp(BinOp::Add, _, Operand::Constant(offset))
+                | Rvalue::BinaryOp(BinOp::Add, _, Operand::Constant(offset)),
+            ) => read_u32(&offset.const_),
+            _ => None,
+        })
+}
+
+fn read_u32(constant: &MirConst) -> Option<u32> {
+    match constant.kind() {
+        ConstantKind::Allocated(alloc) => u32::try_from(alloc.read_uint().ok()?).ok(),
+        _ => None,
+    }
+}
+
+/// Discriminant -> message written by the `Display` impl, for the variants with
+/// a `#[msg(..)]`.
+fn messages(adt_def: &AdtDef) -> HashMap<u128, String> {
+    let mut messages = HashMap::new();
+    let Some(body) = local_fn_body(&format!("<{} as std::fmt::Display>::fmt", adt_def.name()))
+    else {
+        return messages;
+    };
+    let Some(TerminatorKind::SwitchInt { targets, .. }) =
+        body.blocks.first().map(|block| &block.terminator.kind)
+    else {
+        return messages;
+    };
+    for (discr, target) in targets.branches() {
+        // `write!(fmt, "msg")` passes the pieces `&["msg"]` to `Arguments::new_const`.
+        let message = body.blocks[target]
+            .statements
+            .iter()
+            .find_map(|stmt| match &stmt.kind {
+                StatementKind::Assign(_, Rvalue::Use(Operand::Constant(pieces))) => {
+                    const_strs(&pieces.const_)?.into_iter().next()
+                }
+                _ => None,
+            });
+        if let Some(message) = message {
+            messages.insert(discr, message);
+        }
+    }
+    messages
+}
@@ -0,0 +1,85 @@
+//! `#[event]` structs, as `emit!` serializes them: the discriminator followed by
+//! the Borsh encoding of the fields.
+use rustc_public::CrateDef;
+use rustc_public::ty::{AdtDef, AdtKind, RigidTy};
+
+use super::discriminator_consts;
+use super::layout::{BorshSize, borsh_size};
+
+const ANCHOR_EVENT: &str = "anchor_lang::Event";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventInfo {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    pub fields: Vec<EventField>,
+    /// Size of the emitted data, discriminator included, or `None` when a field
+    /// has a type we cannot size.
+    pub size: Option<BorshSize>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventField {
+    pub name: String,
+    pub ty: String,
+    pub size: Option<BorshSize>,
+}
+
+/// Every local `#[event]` struct, found through its `anchor_lang::Event` impl.
+pub fn extract_events() -> Vec<EventInfo> {
+    let discriminators = discriminator_consts();
+    event_structs()
+        .into_iter()
+        .map(|adt_def| {
+            let name = adt_def.name();
+            let discriminator = discriminators
+                .iter()
+                .find(|(ty, _)| *ty == name)
+                .map(|(_, discriminator)| discriminator.clone())
+                .unwrap_or_default();
+            let fields: Vec<EventField> = adt_def
+                .variants_iter()
+                .next()
+                .map(|variant| variant.fields())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|field| {
+                    let ty = field.ty();
+                    EventField {
+                        name: field.name,
+                        ty: ty.to_string(),
+                        size: borsh_size(ty),
+                    }
+                })
+                .collect();
+            let size = fields.iter().try_fold(
+                BorshSize::fixed(discriminator.len() as u64),
+                |size, field| Some(size.then(field.size?)),
+            );
+            EventInfo {
+                name,
+                discriminator,
+                fields,
+                size,
+            }
+        })
+        .collect()
+}
+
+/// Local structs implementing `anchor_lang::Event`.
+pub(crate) fn event_structs() -> Vec<AdtDef> {
+    let mut events = vec![];
+    for trait_impl in rustc_public::all_trait_impls() {
+        let trait_ref = trait_impl.trait_impl().value;
+        if trait_ref.def_id.name() != ANCHOR_EVENT {
+            continue;
+        }
+        if let Some(RigidTy::Adt(adt_def, _)) = trait_ref.self_ty().kind().rigid()
+            && adt_def.krate().is_local
+            && adt_def.kind() == AdtKind::Struct
+        {
+            events.push(*adt_def);
+        }
+    }
+    events
+}
@@ -0,0 +1,276 @@
+//! An Anchor-IDL-shaped JSON document of the program, built from what the other
+//! extractors recover, so it can be compared with the IDL `anchor build` writes.
+//!
+//! The layout follows the IDL spec of Anchor 0.30: instructions and accounts
+//! carry their discriminators, and account and event types are defined once in
+//! `types`.
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rustc_public::CrateDef;
+use rustc_public::ty::{
+    AdtDef, AdtKind, FieldDef, FloatTy, GenericArgs, IntTy, RigidTy, Ty, UintTy,
+};
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use super::access::short_name;
+use super::event::event_structs;
+use super::layout::account_types;
+use super::{
+    extract_discriminators, extract_error_codes, extract_events,
+    extract_instruction_discriminators, extract_program_id, find_to_account_metas,
+    instruction_structs, program_handlers, upper_camel_case,
+};
+use crate::metadata::{Concept, known_types};
+
+/// Version of the IDL format, as Anchor 0.30 writes it in `metadata.spec`.
+const IDL_SPEC: &str = "0.1.0";
+const STRING: &str = "std::string::String";
+const VEC: &str = "std::vec::Vec";
+const OPTION: &str = "std::option::Option";
+const BOX: &str = "std::boxed::Box";
+/// Module of the accounts `#[program]` generates for its IDL instructions, which
+/// Anchor leaves out of the IDL.
+const GENERATED: &str = "__private::";
+
+#[derive(Error, Debug)]
+pub enum IdlError {
+    #[error("failed to read {0}: {1}")]
+    Read(String, io::Error),
+    #[error("failed to parse {0}: {1}")]
+    Parse(String, serde_json::Error),
+}
+
+/// The IDL JSON at `path`, e.g. the `target/idl/*.json` of `anchor build`.
+pub fn read_idl(path: &Path) -> Result<Value, IdlError> {
+    let display = path.display().to_string();
+    let json = fs::read_to_string(path).map_err(|err| IdlError::Read(display.clone(), err))?;
+    serde_json::from_str(&json).map_err(|err| IdlError::Parse(display, err))
+}
+
+/// The IDL of the local program, named after the crate, with `version` as its
+/// package version.
+pub fn to_idl_json(version: &str) -> Value {
+    let mut types = TypeDefs::default();
+
+    let instruction_discriminators: BTreeMap<String, Vec<u8>> =
+        extract_instruction_discriminators().into_iter().collect();
+    let instruction_structs = instruction_structs();
+    let metas = find_to_account_metas();
+    let instructions: Vec<Value> = program_handlers()
+        .into_iter()
+        .map(|handler| {
+            let struct_name = upper_camel_case(&handler.name);
+            let fields = handler
+                .accounts_def
+                .variants_iter()
+                .next()
+                .map(|variant| variant.fields())
+                .unwrap_or_default();
+            let accounts: Vec<Value> = fields
+                .iter()
+                .enumerate()
+                .map(|(field_idx, field)| {
+                    let mut account = json!({ "name": field.name });
+                    let meta = metas.iter().find(|meta| {
+                        meta.struct_name == handler.context_struct && meta.field_idx == field_idx
+                    });
+                    // Anchor leaves out the flags that are false.
+                    if meta.is_some_and(|meta| meta.writable) {
+                        account["writable"] = json!(true);
+                    }
+                    if meta.is_some_and(|meta| meta.signer) {
+                        account["signer"] = json!(true);
+                    }
+                    account
+                })
+                .collect();
+            let args: Vec<Value> = instruction_structs
+                .get(&struct_name)
+                .and_then(|adt_def| adt_def.variants_iter().next())
+                .map(|variant| variant.fields())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|field| json!({ "name": field.name, "type": types.idl_type(field.ty()) }))
+                .collect();
+            json!({
+                "name": handler.name,
+                "discriminator": instruction_discriminators
+                    .get(&format!("instruction::{struct_name}"))
+                    .cloned()
+                    .unwrap_or_default(),
+                "accounts": accounts,
+                "args": args,
+            })
+        })
+        .collect();
+
+    let discriminators: BTreeMap<String, Vec<u8>> = extract_discriminators().into_iter().collect();
+    let accounts: Vec<Value> = account_types()
+        .into_iter()
+        .filter(|adt_def| !adt_def.name().starts_with(GENERATED))
+        .filter_map(|adt_def| {
+            let discriminator = discriminators.get(&adt_def.name())?;
+            types.define(adt_def, &GenericArgs(vec![]));
+            Some(json!({ "name": short_name(&adt_def.name()), "discriminator": discriminator }))
+        })
+        .collect();
+
+    let event_defs = event_structs();
+    let events: Vec<Value> = extract_events()
+        .into_iter()
+        .map(|event| {
+            if let Some(adt_def) = event_defs
+                .iter()
+                .find(|adt_def| adt_def.name() == event.name)
+            {
+                types.define(*adt_def, &GenericArgs(vec![]));
+            }
+            json!({ "name": short_name(&event.name), "discriminator": event.discriminator })
+        })
+        .collect();
+
+    let errors: Vec<Value> = extract_error_codes()
+        .into_iter()
+        .map(|error| json!({ "code": error.code, "name": error.name, "msg": error.msg }))
+        .collect();
+
+    json!({
+        "address": extract_program_id().ok().map(|program_id| program_id.to_string()),
+        "metadata": {
+            "name": rustc_public::local_crate().name,
+            "version": version,
+            "spec": IDL_SPEC,
+        },
+        "instructions": instructions,
+        "accounts": accounts,
+        "events": events,
+        "errors": errors,
+        "types": types.defs.into_values().collect::<Vec<_>>(),
+    })
+}
+
+/// Definitions of the local types the IDL refers to, by short name.
+#[derive(Default)]
+struct TypeDefs {
+    defs: BTreeMap<String, Value>,
+}
+
+impl TypeDefs {
+    /// `ty` as an IDL type, e.g. `"u64"`, `{"vec": "pubkey"}` or
+    /// `{"defined": {"name": "Side"}}`, defining the local types it uses.
+    fn idl_type(&mut self, ty: Ty) -> Value {
+        let Some(rigid) = ty.kind().rigid().cloned() else {
+            return json!(ty.to_string());
+        };
+        match rigid {
+            RigidTy::Bool => json!("bool"),
+            RigidTy::Int(int_ty) => json!(match int_ty {
+                IntTy::I8 => "i8",
+                IntTy::I16 => "i16",
+                IntTy::I32 => "i32",
+                IntTy::I64 | IntTy::Isize => "i64",
+                IntTy::I128 => "i128",
+            }),
+            RigidTy::Uint(uint_ty) => json!(match uint_ty {
+                UintTy::U8 => "u8",
+                UintTy::U16 => "u16",
+                UintTy::U32 => "u32",
+                UintTy::U64 | UintTy::Usize => "u64",
+                UintTy::U128 => "u128",
+            }),
+            RigidTy::Float(FloatTy::F32) => json!("f32"),
+            RigidTy::Float(FloatTy::F64) => json!("f64"),
+            RigidTy::Array(elem, len) => match len.eval_target_usize() {
+                Ok(len) => json!({ "array": [self.idl_type(elem), len] }),
+                Err(_) => json!(ty.to_string()),
+            },
+            RigidTy::Adt(adt_def, args) => {
+                let name = adt_def.name();
+                let first = args.0.first().and_then(|arg| arg.ty()).copied();
+                match (name.as_str(), first) {
+                    (STRING, _) => json!("string"),
+                    (VEC, Some(elem))
+                        if matches!(elem.kind().rigid(), Some(RigidTy::Uint(UintTy::U8))) =>
+                    {
+                        json!("bytes")
+                    }
+                    (VEC, Some(elem)) => json!({ "vec": self.idl_type(elem) }),
+                    (OPTION, Some(inner)) => json!({ "option": self.idl_type(inner) }),
+                    (BOX, Some(inner)) => self.idl_type(inner),
+                    _ if known_types().is(Concept::Pubkey, &name) => json!("pubkey"),
+                    _ => {
+                        if adt_def.krate().is_local {
+                            self.define(adt_def, &args);
+                        }
+                        json!({ "defined": { "name": short_name(&name) } })
+                    }
+                }
+            }
+            _ => json!(ty.to_string()),
+        }
+    }
+
+    /// Add the definition of the local struct or enum `adt_def`, and of the
+    /// types of its fields.
+    fn define(&mut self, adt_def: AdtDef, args: &GenericArgs) {
+        let name = short_name(&adt_def.name());
+        if self.defs.contains_key(&name) {
+            return;
+        }
+        // Placeholder so recursive types terminate.
+        self.defs.insert(name.clone(), Value::Null);
+        let ty = match adt_def.kind() {
+            AdtKind::Struct => {
+                let fields = adt_def
+                    .variants_iter()
+                    .next()
+                    .map(|variant| variant.fields())
+                    .unwrap_or_default();
+                json!({ "kind": "struct", "fields": self.fields(fields, args) })
+            }
+            AdtKind::Enum => {
+                let variants: Vec<Value> = adt_def
+                    .variants_iter()
+                    .map(|variant| {
+                        let fields = variant.fields();
+                        if fields.is_empty() {
+                            json!({ "name": variant.name() })
+                        } else {
+                            json!({ "name": variant.name(), "fields": self.fields(fields, args) })
+                        }
+                    })
+                    .collect();
+                json!({ "kind": "enum", "variants": variants })
+            }
+            AdtKind::Union => {
+                self.defs.remove(&name);
+                return;
+            }
+        };
+        self.defs
+            .insert(name.clone(), json!({ "name": name, "type": ty }));
+    }
+
+    /// Named fields as `{name, type}`, tuple fields as their types alone.
+    fn fields(&mut self, fields: Vec<FieldDef>, args: &GenericArgs) -> Value {
+        let tuple = fields
+            .first()
+            .is_some_and(|field| field.name.parse::<usize>().is_ok());
+        let fields: Vec<Value> = fields
+            .into_iter()
+            .map(|field| {
+                let ty = self.idl_type(field.ty_with_args(args));
+                if tuple {
+                    ty
+                } else {
+                    json!({ "name": field.name, "type": ty })
+                }
+            })
+            .collect();
+        json!(fields)
+    }
+}
@@ -0,0 +1,211 @@
+//! Borsh-serialized sizes of types, as Anchor writes accounts and events, and
+//! the resulting layout of account data.
+use rustc_public::CrateDef;
+use rustc_public::ty::{AdtDef, AdtKind, FloatTy, IntTy, RigidTy, Ty, UintTy};
+
+use super::discriminator_consts;
+
+/// Collections Borsh writes as a `u32` length followed by the elements.
+const LENGTH_PREFIXED: [&str; 8] = [
+    "std::string::String",
+    "std::vec::Vec",
+    "std::collections::VecDeque",
+    "std::collections::BTreeMap",
+    "std::collections::BTreeSet",
+    "std::collections::HashMap",
+    "std::collections::HashSet",
+    "std::collections::LinkedList",
+];
+const BOX: &str = "std::boxed::Box";
+/// Deeper types are most likely recursive, e.g. through a `Box`.
+const MAX_DEPTH: usize = 32;
+
+/// Serialized size of a type: exact when `dynamic` is false, otherwise the
+/// smallest size a value can take.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BorshSize {
+    pub size: u64,
+    pub dynamic: bool,
+}
+
+impl BorshSize {
+    pub fn fixed(size: u64) -> Self {
+        Self {
+            size,
+            dynamic: false,
+        }
+    }
+
+    /// `self` followed by `other`.
+    pub fn then(self, other: Self) -> Self {
+        Self {
+            size: self.size + other.size,
+            dynamic: self.dynamic || other.dynamic,
+        }
+    }
+}
+
+/// Borsh size of `ty`, or `None` for types Borsh cannot serialize (references,
+/// fn pointers, ..) or we do not model.
+pub fn borsh_size(ty: Ty) -> Option<BorshSize> {
+    size_at(ty, 0)
+}
+
+fn size_at(ty: Ty, depth: usize) -> Option<BorshSize> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let size = match ty.kind().rigid()? {
+        RigidTy::Bool => BorshSize::fixed(1),
+        RigidTy::Char => BorshSize::fixed(4),
+        RigidTy::Int(int_ty) => BorshSize::fixed(match int_ty {
+            IntTy::I8 => 1,
+            IntTy::I16 => 2,
+            IntTy::I32 => 4,
+            IntTy::I64 | IntTy::Isize => 8,
+            IntTy::I128 => 16,
+        }),
+        RigidTy::Uint(uint_ty) => BorshSize::fixed(match uint_ty {
+            UintTy::U8 => 1,
+            UintTy::U16 => 2,
+            UintTy::U32 => 4,
+            UintTy::U64 | UintTy::Usize => 8,
+            UintTy::U128 => 16,
+        }),
+        RigidTy::Float(float_ty) => BorshSize::fixed(match float_ty {
+            FloatTy::F16 => 2,
+            FloatTy::F32 => 4,
+            FloatTy::F64 => 8,
+            FloatTy::F128 => 16,
+        }),
+        RigidTy::Array(elem, len) => {
+            let elem = size_at(*elem, depth + 1)?;
+            let len = len.eval_target_usize().ok()?;
+            BorshSize {
+                size: elem.size * len,
+                dynamic: elem.dynamic,
+            }
+        }
+        RigidTy::Tuple(elems) => elems.iter().try_fold(BorshSize::fixed(0), |size, elem| {
+            Some(size.then(size_at(*elem, depth + 1)?))
+        })?,
+        RigidTy::Adt(adt_def, args) => {
+            let name = adt_def.name();
+            if LENGTH_PREFIXED.contains(&name.as_str()) {
+                return Some(BorshSize {
+                    size: 4,
+                    dynamic: true,
+                });
+            }
+            if name == BOX {
+                return size_at(*args.0.first()?.ty()?, depth + 1);
+            }
+            let variants: Vec<BorshSize> = adt_def
+                .variants_iter()
+                .map(|variant| {
+                    variant
+                        .fields()
+                        .iter()
+                        .try_fold(BorshSize::fixed(0), |size, field| {
+                            Some(size.then(size_at(field.ty_with_args(args), depth + 1)?))
+                        })
+                })
+                .collect::<Option<_>>()?;
+            match adt_def.kind() {
+                AdtKind::Struct => *variants.first()?,
+                // A `u8` variant index, then the fields of the variant.
+                AdtKind::Enum => {
+                    let min = variants.iter().map(|variant| variant.size).min()?;
+                    let dynamic = variants
+                        .iter()
+                        .any(|variant| variant.dynamic || variant.size != min);
+                    BorshSize {
+                        size: 1 + min,
+                        dynamic,
+                    }
+                }
+                AdtKind::Union => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(size)
+}
+
+const ACCOUNT_DESERIALIZE: &str = "anchor_lang::AccountDeserialize";
+/// Length of the discriminator Anchor derives from the type name.
+const DEFAULT_DISCRIMINATOR_LEN: u64 = 8;
+
+/// Where the fields of an `#[account]` type sit in the account data.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountLayout {
+    pub name: String,
+    /// The discriminator prefixes the fields.
+    pub discriminator_len: u64,
+    pub fields: Vec<LayoutField>,
+    /// Size of the account data, discriminator included, or `None` when a field
+    /// has a type we cannot size.
+    pub size: Option<BorshSize>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LayoutField {
+    pub name: String,
+    pub ty: String,
+    /// Byte offset in the account data. After a dynamically sized field this is
+    /// the smallest offset the field can start at; `None` after a field we
+    /// cannot size.
+    pub offset: Option<u64>,
+    pub size: Option<BorshSize>,
+}
+
+/// Layout of the local account type `symbol`, as named by
+/// `AnchorAccountKind::Account`.
+pub fn account_layout(symbol: &str) -> Option<AccountLayout> {
+    let adt_def = account_types()
+        .into_iter()
+        .find(|adt_def| adt_def.name() == symbol)?;
+    let discriminator_len = discriminator_consts()
+        .into_iter()
+        .find(|(name, _)| name == symbol)
+        .map_or(DEFAULT_DISCRIMINATOR_LEN, |(_, discriminator)| {
+            discriminator.len() as u64
+        });
+    let mut end = Some(BorshSize::fixed(discriminator_len));
+    let mut fields = vec![];
+    for field in adt_def.variants_iter().next()?.fields() {
+        let ty = field.ty();
+        let size = borsh_size(ty);
+        fields.push(LayoutField {
+            name: field.name,
+            ty: ty.to_string(),
+            offset: end.map(|end| end.size),
+            size,
+        });
+        end = end.zip(size).map(|(end, size)| end.then(size));
+    }
+    Some(AccountLayout {
+        name: symbol.to_string(),
+        discriminator_len,
+        fields,
+        size: end,
+    })
+}
+
+/// Local structs Anchor deserializes accounts into, i.e. `#[account]` types.
+pub(super) fn account_types() -> Vec<AdtDef> {
+    let mut types = vec![];
+    for trait_impl in rustc_public::all_trait_impls() {
+        let trait_ref = trait_impl.trait_impl().value;
+        if trait_ref.def_id.name() != ACCOUNT_DESERIALIZE {
+            continue;
+        }
+        if let Some(RigidTy::Adt(adt_def, _)) = trait_ref.self_ty().kind().rigid()
+            && adt_def.krate().is_local
+            && adt_def.kind() == AdtKind::Struct
+        {
+            types.push(*adt_def);
+        }
+    }
+    types
+}
@@ -0,0 +1,218 @@
+//! List what the program logs with `msg!`, for reviewing logs that leak secrets.
+//!
+//! `msg!("text")` calls `sol_log("text")`; with arguments it calls
+//! `sol_log(&format!(..))`, whose `Arguments` are built from the literal pieces
+//! of the format string and one `Argument::new_display::<T>` (or `new_debug`,
+//! ..) per logged value.
+use std::collections::HashMap;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{
+    AggregateKind, Body, Local, Operand, Rvalue, StatementKind, TerminatorKind,
+};
+use rustc_public::ty::{GenericArgs, MirConst, RigidTy, TyKind};
+
+use super::constraint::{const_str, const_strs};
+use crate::analysis::taint::operand_local;
+
+const SOL_LOG: &str = "sol_log";
+/// Module of the code `#[program]` generates, which only logs instruction names.
+const GENERATED: &str = "__private::";
+/// How deep to follow a logged `&str` back to the `Arguments` it was formatted from.
+const MAX_DEPTH: usize = 16;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogMessage {
+    /// The fn calling `sol_log`.
+    pub function: String,
+    /// The logged literal, or the format string with `{}`/`{:?}` in place of the
+    /// arguments. `None` for a string built some other way.
+    pub message: Option<String>,
+    /// Types of the formatted arguments, in order.
+    pub arg_types: Vec<String>,
+}
+
+/// Every `sol_log` in `reachable`, the fns reachable from the entrypoints in
+/// call-graph order, leaving out Anchor's generated dispatch.
+pub fn logged_messages(reachable: &[Instance]) -> Vec<LogMessage> {
+    let mut messages = vec![];
+    for &instance in reachable {
+        if instance.name().starts_with(GENERATED) {
+            continue;
+        }
+        let Some(body) = instance.body() else {
+            continue;
+        };
+        let defs = Defs::new(&body);
+        for block in &body.blocks {
+            let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+                continue;
+            };
+            let Some((name, _)) = fn_def(&body, func) else {
+                continue;
+            };
+            if name.rsplit("::").next() != Some(SOL_LOG) {
+                continue;
+            }
+            let (message, arg_types) = match args.first().and_then(|arg| defs.format(arg, 0)) {
+                Some((message, arg_types)) => (Some(message), arg_types),
+                None => (None, vec![]),
+            };
+            messages.push(LogMessage {
+                function: instance.name(),
+                message,
+                arg_types,
+            });
+        }
+    }
+    messages
+}
+
+/// Name and generic args of the fn `func` calls.
+fn fn_def(body: &Body, func: &Operand) -> Option<(String, GenericArgs)> {
+    let TyKind::RigidTy(RigidTy::FnDef(fn_def, args)) = func.ty(body.locals()).ok()?.kind() else {
+        return None;
+    };
+    Some((fn_def.name(), args))
+}
+
+enum Def<'a> {
+    Assign(&'a Rvalue),
+    Call(&'a Operand, &'a [Operand]),
+}
+
+/// How each local of a mono body is defined, by an assignment or as the
+/// destination of a call.
+struct Defs<'a> {
+    body: &'a Body,
+    defs: HashMap<Local, Def<'a>>,
+}
+
+impl<'a> Defs<'a> {
+    fn new(body: &'a Body) -> Self {
+        let mut defs = HashMap::new();
+        for block in &body.blocks {
+            for stmt in &block.statements {
+                if let StatementKind::Assign(lhs, rvalue) = &stmt.kind
+                    && lhs.projection.is_empty()
+                {
+                    defs.insert(lhs.local, Def::Assign(rvalue));
+                }
+            }
+            if let TerminatorKind::Call {
+                func,
+                args,
+                destination,
+                ..
+            } = &block.terminator.kind
+                && destination.projection.is_empty()
+            {
+                defs.insert(destination.local, Def::Call(func, args));
+            }
+        }
+        Self { body, defs }
+    }
+
+    /// The message and argument types of the `&str` `operand`: a literal, or a
+    /// `String` formatted from `Arguments` and reached through `&`, `deref` and
+    /// `must_use`.
+    fn format(&self, operand: &Operand, depth: usize) -> Option<(String, Vec<String>)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let local = match operand {
+            Operand::Constant(_) => return Some((const_str(operand)?, vec![])),
+            Operand::Copy(place) | Operand::Move(place) => place.local,
+        };
+        match self.defs.get(&local)? {
+            Def::Assign(Rvalue::Use(
+                operand @ (Operand::Copy(_) | Operand::Move(_) | Operand::Constant(_)),
+            )) => self.format(operand, depth + 1),
+            Def::Assign(Rvalue::Ref(_, _, place) | Rvalue::CopyForDeref(place)) => {
+                self.format(&Operand::Copy(place.clone()), depth + 1)
+            }
+            Def::Assign(_) => None,
+            Def::Call(func, args) => {
+                let (name, _) = fn_def(self.body, func)?;
+                let fn_name = name.rsplit("::").next().unwrap_or(&name);
+                if name.contains("Arguments") && fn_name.starts_with("new_") {
+                    self.arguments(args)
+                } else {
+                    self.format(args.first()?, depth + 1)
+                }
+            }
+        }
+    }
+
+    /// Rebuild the format string from the pieces and arguments passed to
+    /// `Arguments::new_const(&pieces)` or `Arguments::new_v1(&pieces, &args)`.
+    fn arguments(&self, args: &[Operand]) -> Option<(String, Vec<String>)> {
+        let pieces = self.constant(args.first()?).and_then(const_strs)?;
+        let formatted: Vec<(String, String)> = match args.get(1) {
+            Some(args) => self.formatted_args(args)?,
+            None => vec![],
+        };
+        let mut message = String::new();
+        for idx in 0..pieces.len().max(formatted.len()) {
+            if let Some(piece) = pieces.get(idx) {
+                message.push_str(piece);
+            }
+            if let Some((placeholder, _)) = formatted.get(idx) {
+                message.push_str(placeholder);
+            }
+        }
+        let arg_types = formatted.into_iter().map(|(_, ty)| ty).collect();
+        Some((message, arg_types))
+    }
+
+    /// Placeholder and type of each `Argument` in the `&[Argument; N]` operand.
+    fn formatted_args(&self, operand: &Operand) -> Option<Vec<(String, String)>> {
+        let Some(Def::Assign(Rvalue::Ref(_, _, array))) = self.defs.get(&operand_local(operand)?)
+        else {
+            return None;
+        };
+        let Some(Def::Assign(Rvalue::Aggregate(AggregateKind::Array(_), elements))) =
+            self.defs.get(&array.local)
+        else {
+            return None;
+        };
+        elements
+            .iter()
+            .map(|element| {
+                let Some(Def::Call(func, _)) = self.defs.get(&operand_local(element)?) else {
+                    return None;
+                };
+                let (name, generic_args) = fn_def(self.body, func)?;
+                let ty = generic_args.0.iter().rev().find_map(|arg| arg.ty())?;
+                Some((placeholder(&name).to_string(), ty.to_string()))
+            })
+            .collect()
+    }
+
+    /// The constant `operand` holds, directly or through one local.
+    fn constant(&self, operand: &'a Operand) -> Option<&'a MirConst> {
+        match operand {
+            Operand::Constant(constant) => Some(&constant.const_),
+            Operand::Copy(place) | Operand::Move(place) => match self.defs.get(&place.local)? {
+                Def::Assign(Rvalue::Use(Operand::Constant(constant))) => Some(&constant.const_),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// The placeholder the `Argument` constructor `name` stands for.
+fn placeholder(name: &str) -> &'static str {
+    match name.rsplit("::").next() {
+        Some("new_debug") => "{:?}",
+        Some("new_lower_hex") => "{:x}",
+        Some("new_upper_hex") => "{:X}",
+        Some("new_binary") => "{:b}",
+        Some("new_octal") => "{:o}",
+        Some("new_lower_exp") => "{:e}",
+        Some("new_upper_exp") => "{:E}",
+        Some("new_pointer") => "{:p}",
+        _ => "{}",
+    }
+}
@@ -1,13 +1,13 @@
-use regex::Regex;
+use rustc_public::CrateDefItems;
 use rustc_public::mir::ProjectionElem;
 use rustc_public::mir::StatementKind::Assign;
 use rustc_public::mir::mono::Instance;
-use rustc_public::mir::{AggregateKind, ConstOperand, Operand, Rvalue, TerminatorKind};
-use rustc_public::ty::{AdtDef, AssocKind, FieldDef, MirConst, RigidTy, Ty, UintTy};
-use rustc_public::{CompilerError, CrateDefItems};
-use rustc_public::{CrateDef, CrateItem, ItemKind, run};
-use std::ops::ControlFlow;
-use std::process::ExitCode;
+use rustc_public::mir::{
+    AggregateKind, BasicBlockIdx, Body, ConstOperand, Local, Operand, Rvalue, Terminator,
+    TerminatorKind,
+};
+use rustc_public::ty::{AdtDef, AssocKind, FieldDef, RigidTy, UintTy};
+use rustc_public::{CrateDef, ItemKind};
 
 use rustc_public::Symbol;
 use rustc_public::mir::StatementKind;
@@ -16,22 +16,55 @@ use rustc_public::ty::Allocation;
 use rustc_public::ty::ConstantKind::Allocated;
 use rustc_public::ty::TyKind;
 use rustc_public::ty::VariantDef;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::analysis::callgraph;
+use crate::analysis::visit::{MirVisitor, walk_body};
+use crate::metadata::{Concept, known_types};
+use access_control::GuardInfo;
+use constraint::AnchorConstraint;
+use remaining::RemainingAccounts;
+
+pub mod access;
+pub mod access_control;
+pub mod account_graph;
+pub mod constants;
+pub mod constraint;
+pub mod cpi;
+pub mod error_code;
+pub mod event;
+pub mod idl;
+pub mod layout;
+pub mod logs;
+pub mod program_id;
+pub mod remaining;
+
+pub use constants::extract_constants;
+pub use cpi::extract_cpi_calls;
+pub use error_code::extract_error_codes;
+pub use event::extract_events;
+pub use idl::{read_idl, to_idl_json};
+pub use logs::logged_messages;
+pub use program_id::extract_program_id;
 
 /// Model an Anchor's account: #[account]
 #[derive(Clone, Debug)]
 pub struct AnchorAccount {
     pub name: String,
     pub kind: AnchorAccountKind,
+    pub constraints: Vec<AnchorConstraint>,
 }
 
 impl AnchorAccount {
-    pub fn from_field_def(field_def: &FieldDef) -> Option<Self> {
+    /// The account a field of an Accounts struct holds, with kind `Unknown` for
+    /// types we do not model so that every field keeps its index.
+    pub fn from_field_def(field_def: &FieldDef) -> Self {
         let kind = field_def.ty().kind();
-        let anchor_account_kind = AnchorAccountKind::from_ty(&kind)?;
-        Some(Self {
+        Self {
             name: field_def.name.clone(),
-            kind: anchor_account_kind,
-        })
+            kind: AnchorAccountKind::from_ty(&kind).unwrap_or(AnchorAccountKind::Unknown),
+            constraints: vec![],
+        }
     }
 }
 
@@ -41,6 +74,16 @@ pub enum AnchorAccountKind {
     Signer,
     Program,
     Sysvar(Symbol),
+    /// An account owned by any of several programs, e.g. Token or Token-2022.
+    InterfaceAccount(Symbol),
+    /// One of several programs, e.g. `TokenInterface`.
+    Interface(Symbol),
+    /// An account owned by the System program, e.g. a fee payer.
+    SystemAccount,
+    /// `UncheckedAccount` or a bare `AccountInfo`, which Anchor does not check.
+    Unchecked,
+    /// A field of a type we do not model, e.g. `AccountLoader` or a boxed account.
+    Unknown,
 }
 
 impl AnchorAccountKind {
@@ -66,11 +109,33 @@ impl AnchorAccountKind {
                     // "system_program", RigidTy(Adt(AdtDef(DefId { id: 460, name: "anchor_lang::prelude::Program" }), GenericArgs([Lifetime(Region { kind: ReEarlyParam(EarlyParamRegion { index: 0, name: "'info" }) }), Type(Ty { id: 131, kind: RigidTy(Adt(AdtDef(DefId { id: 42667, name: "anchor_lang::system_program::System" }), GenericArgs([]))) })])))
                     Some(Self::Program)
                 }
+                "anchor_lang::prelude::InterfaceAccount" => {
+                    // e.g.
+                    // "vault", RigidTy(Adt(AdtDef(DefId { id: .., name: "anchor_lang::prelude::InterfaceAccount" }), GenericArgs([Lifetime(..), Type(Ty { id: .., kind: RigidTy(Adt(AdtDef(DefId { id: .., name: "anchor_spl::token_interface::TokenAccount" }), GenericArgs([]))) })])))
+                    if let RigidTy::Adt(adt_def, _) = generics.0.get(1)?.ty()?.kind().rigid()? {
+                        Some(Self::InterfaceAccount(adt_def.name()))
+                    } else {
+                        None
+                    }
+                }
+                "anchor_lang::prelude::Interface" => {
+                    // e.g.
+                    // "token_program", RigidTy(Adt(AdtDef(DefId { id: .., name: "anchor_lang::prelude::Interface" }), GenericArgs([Lifetime(..), Type(Ty { id: .., kind: RigidTy(Adt(AdtDef(DefId { id: .., name: "anchor_spl::token_interface::TokenInterface" }), GenericArgs([]))) })])))
+                    if let RigidTy::Adt(adt_def, _) = generics.0.get(1)?.ty()?.kind().rigid()? {
+                        Some(Self::Interface(adt_def.name()))
+                    } else {
+                        None
+                    }
+                }
+                "anchor_lang::prelude::SystemAccount" => Some(Self::SystemAccount),
+                "anchor_lang::prelude::UncheckedAccount" | "anchor_lang::prelude::AccountInfo" => {
+                    Some(Self::Unchecked)
+                }
                 "anchor_lang::prelude::Sysvar" => {
                     // e.g.
                     // "rent", RigidTy(Adt(AdtDef(DefId { id: 459, name: "anchor_lang::prelude::Sysvar" }), GenericArgs([Lifetime(Region { kind: ReEarlyParam(EarlyParamRegion { index: 0, name: "'info" }) }), Type(Ty { id: 129, kind: RigidTy(Adt(AdtDef(DefId { id: 579, name: "anchor_lang::prelude::Rent" }), GenericArgs([]))) })])))
                     if let RigidTy::Adt(adt_def, _) = generics.0.get(1)?.ty()?.kind().rigid()? {
-                        Some(Self::Account(adt_def.name()))
+                        Some(Self::Sysvar(adt_def.name()))
                     } else {
                         None
                     }
@@ -81,35 +146,47 @@ impl AnchorAccountKind {
             None
         }
     }
+
+    /// The type the account data is deserialized into: `T` of `Account<T>` and
+    /// `InterfaceAccount<T>`, which hold the same kind of data.
+    pub fn data_type(&self) -> Option<&Symbol> {
+        match self {
+            Self::Account(symbol) | Self::InterfaceAccount(symbol) => Some(symbol),
+            _ => None,
+        }
+    }
 }
 
 /// Model anchors' Accounts: #[derive(Accounts)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AnchorAccounts {
     pub name: String,
+    pub adt_def: AdtDef,
+    /// One entry per field, in declaration order, so indices match field indices.
     pub anchor_accounts: Vec<AnchorAccount>,
 }
 
 pub const ANCHOR_ACCOUNTS: &str = "anchor_lang::Accounts";
 pub const TO_ACCOUNT_METAS: &str = "to_account_metas";
+pub const ACCOUNTS_EXIT: &str = "anchor_lang::AccountsExit";
 
 impl AnchorAccounts {
     pub fn from_variant(variant: VariantDef) -> Option<Self> {
-        let fields = variant.fields();
-        let mut anchor_accounts = Vec::with_capacity(fields.len());
-        for field_def in fields {
-            if let Some(anchor_account) = AnchorAccount::from_field_def(&field_def) {
-                anchor_accounts.push(anchor_account);
-            }
-        }
+        let anchor_accounts = variant
+            .fields()
+            .iter()
+            .map(AnchorAccount::from_field_def)
+            .collect();
         Some(Self {
             name: variant.name(),
+            adt_def: variant.adt_def,
             anchor_accounts,
         })
     }
 }
 
-/// Collect all anchor Accounts defined locally by tracking trait anchor_lang::Accounts
+/// Collect all anchor Accounts defined locally by tracking trait anchor_lang::Accounts,
+/// sorted by name
 pub fn local_anchor_accounts() -> Vec<AnchorAccounts> {
     let mut anchor_accounts_collection = vec![];
     let trait_impls = rustc_public::all_trait_impls();
@@ -130,18 +207,68 @@ pub fn local_anchor_accounts() -> Vec<AnchorAccounts> {
                     && name == "try_accounts"
                     && !has_self
                     && let Some(variant) = adt_def.variants_iter().next()
-                    && let Some(anchor_accounts) = AnchorAccounts::from_variant(variant)
+                    && let Some(mut anchor_accounts) = AnchorAccounts::from_variant(variant)
                 {
+                    let mut pda = constraint::pda_constraints(&anchor_accounts.name);
+                    let mut checks = constraint::check_constraints(&anchor_accounts.name);
+                    let mut lifecycle = constraint::lifecycle_constraints(&anchor_accounts.name);
+                    for anchor_account in &mut anchor_accounts.anchor_accounts {
+                        let constraints = &mut anchor_account.constraints;
+                        constraints.extend(pda.remove(&anchor_account.name).unwrap_or_default());
+                        constraints.extend(checks.remove(&anchor_account.name).unwrap_or_default());
+                        constraints
+                            .extend(lifecycle.remove(&anchor_account.name).unwrap_or_default());
+                    }
                     anchor_accounts_collection.push(anchor_accounts);
                     break; // There can only be one `try_accounts` for one struct
                 }
             }
         }
     }
+    anchor_accounts_collection.sort_by(|a, b| a.name.cmp(&b.name));
     anchor_accounts_collection
 }
 
-pub fn find_to_account_metas() -> Vec<(String, &'static str, usize)> {
+/// An `AccountMeta` the generated client code builds for a field of an Accounts
+/// struct.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountMetaInfo {
+    /// Short name of the Accounts struct.
+    pub struct_name: String,
+    /// The client struct building it, telling a hand-written copy apart from
+    /// the generated one.
+    pub client: AdtDef,
+    /// Index of the field in `client`, the same as in the Accounts struct as
+    /// long as the two agree.
+    pub field_idx: usize,
+    pub writable: bool,
+    pub signer: bool,
+    /// Built by the `__cpi_client_accounts_*` struct used for CPIs into the
+    /// program, rather than the `__client_accounts_*` one used off-chain.
+    pub is_cpi: bool,
+}
+
+/// Module prefixes of the client structs Anchor generates for each Accounts
+/// struct.
+const CLIENT_ACCOUNTS: &str = "__client_accounts";
+const CPI_CLIENT_ACCOUNTS: &str = "__cpi_client_accounts";
+
+/// The metas built by the off-chain client structs.
+pub fn find_to_account_metas() -> Vec<AccountMetaInfo> {
+    extract_account_metas(false)
+}
+
+/// The metas built by the CPI client structs.
+pub fn find_cpi_account_metas() -> Vec<AccountMetaInfo> {
+    extract_account_metas(true)
+}
+
+fn extract_account_metas(is_cpi: bool) -> Vec<AccountMetaInfo> {
+    let module = if is_cpi {
+        CPI_CLIENT_ACCOUNTS
+    } else {
+        CLIENT_ACCOUNTS
+    };
     let mut to_account_metas = vec![];
     let items = rustc_public::all_local_items();
     for item in items {
@@ -149,8 +276,8 @@ pub fn find_to_account_metas() -> Vec<(String, &'static str, usize)> {
         if !name.contains(TO_ACCOUNT_METAS) {
             continue;
         }
-        if !name.contains("__client_accounts") {
-            // can also be "__cpi_client_accounts"
+        // `__cpi_client_accounts` does not contain `__client_accounts`.
+        if !name.contains(module) {
             continue;
         }
         let instance = match Instance::try_from(item) {
@@ -165,72 +292,266 @@ pub fn find_to_account_metas() -> Vec<(String, &'static str, usize)> {
             Some(body) => body,
             None => continue,
         };
-        let first_arg_ty = if let Some(local_decl) = body.local_decl(1)  // first arg ty
+        let (client, struct_name) = if let Some(local_decl) = body.local_decl(1)  // first arg ty
             && let Some(RigidTy::Ref(_, next_ty, _)) = local_decl.ty.kind().rigid()
             && let Some(RigidTy::Adt(adt_def, _)) = next_ty.kind().rigid()
             && let Some(last) = adt_def.name().split(":").last()
         {
-            last.to_owned()
+            (*adt_def, last.to_owned())
         } else {
             continue;
         };
-        for bb in body.blocks {
-            if let TerminatorKind::Call {
-                func,
-                ..
-            } = bb.terminator.kind
+        let mut calls = MetaCalls {
+            fields: self_field_locals(&body),
+            metas: vec![],
+        };
+        walk_body(&body, &mut calls);
+        for (field_idx, writable, signer) in calls.metas {
+            account_metas.push(AccountMetaInfo {
+                struct_name: struct_name.clone(),
+                client,
+                field_idx,
+                writable,
+                signer,
+                is_cpi,
+            });
+        }
+    }
+    account_metas.sort_by(|a, b| (&a.struct_name, a.field_idx).cmp(&(&b.struct_name, b.field_idx)));
+    account_metas
+}
+
+/// The `AccountMeta::new` and `new_readonly` calls of a `to_account_metas` body
+/// on the key of a field of `self`, as `(field index, writable, signer)`.
+struct MetaCalls {
+    /// See `self_field_locals`.
+    fields: HashMap<Local, usize>,
+    metas: Vec<(usize, bool, bool)>,
+}
+
+impl MirVisitor for MetaCalls {
+    fn visit_terminator(&mut self, _: BasicBlockIdx, terminator: &Terminator) {
+        if let TerminatorKind::Call { func, args, .. } = &terminator.kind
             && let Operand::Constant(const_operand) = func
             && let Some(RigidTy::FnDef(fn_def, _)) = const_operand.ty().kind().rigid()
-            && (fn_def.name() == "anchor_lang::prelude::AccountMeta::new" || 
-                fn_def.name() == "anchor_lang::prelude::AccountMeta::new_readonly")
-            && let Some(statement) = bb.statements.last()  // the last statement (right before terminator)
-            // Assign(_7, Use(Copy(((*_1).0: anchor_lang::prelude::Pubkey))))
-            && let StatementKind::Assign(_, Rvalue::Use(Operand::Copy(ref place))) = statement.kind
-            && place.local == 1  // The first arg
-            && let [ProjectionElem::Deref, ProjectionElem::Field(field_idx, _)] = place.projection[..]
+            && let Some(meta) = account_meta_call(&fn_def.name(), args)
+            && let Operand::Copy(key) | Operand::Move(key) = meta.key
+            && let Some(field_idx) = self.fields.get(&key.local)
+        {
+            self.metas
+                .push((*field_idx, meta.writable, meta.signer.unwrap_or(false)));
+        }
+    }
+}
+
+/// An `AccountMeta::new` or `new_readonly` call, in Anchor's generated code or
+/// in a native program building an `Instruction` by hand.
+pub struct AccountMetaCall<'a> {
+    pub key: &'a Operand,
+    pub writable: bool,
+    /// The `is_signer` argument, when it is a constant rather than e.g. the
+    /// `is_signer` of an account passed on.
+    pub signer: Option<bool>,
+}
+
+/// The `AccountMeta` built by a call to `fn_name` with `args`, if it is
+/// `AccountMeta::new(key, is_signer)` or `new_readonly`.
+pub fn account_meta_call<'a>(fn_name: &str, args: &'a [Operand]) -> Option<AccountMetaCall<'a>> {
+    let (ty, ctor) = fn_name.rsplit_once("::")?;
+    if !known_types().is(Concept::AccountMeta, ty) {
+        return None;
+    }
+    let writable = match ctor {
+        "new" => true,
+        "new_readonly" => false,
+        _ => return None,
+    };
+    let [key, signer] = args else {
+        return None;
+    };
+    Some(AccountMetaCall {
+        key,
+        writable,
+        signer: const_bool(signer),
+    })
+}
+
+/// Locals of a `to_account_metas` body holding a field of `self`, or its key:
+/// the client struct copies the `Pubkey` field, e.g.
+/// `_7 = ((*_1).0: anchor_lang::prelude::Pubkey)`, while the CPI struct calls
+/// `Key::key(&(*_1).0)` on its `AccountInfo` field. An optional field is
+/// unwrapped first, with `if let Some(key) = &self.key`.
+fn self_field_locals(body: &Body) -> HashMap<Local, usize> {
+    let mut fields = HashMap::new();
+    let reads: Vec<_> = body
+        .blocks
+        .iter()
+        .flat_map(|bb| &bb.statements)
+        .filter_map(|statement| match &statement.kind {
+            StatementKind::Assign(
+                lhs,
+                Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+                | Rvalue::Ref(_, _, place)
+                | Rvalue::CopyForDeref(place),
+            ) => Some((lhs.local, place)),
+            _ => None,
+        })
+        .collect();
+    for (lhs, place) in &reads {
+        if place.local == 1
+            && let [ProjectionElem::Deref, ProjectionElem::Field(field_idx, _)] =
+                place.projection[..]
+        {
+            fields.insert(*lhs, field_idx);
+        }
+    }
+    // `&(((*_5) as Some).0: Pubkey)` and its copies.
+    loop {
+        let known = fields.len();
+        for (lhs, place) in &reads {
+            if let Some(field_idx) = fields.get(&place.local).copied()
+                && place.projection.iter().all(|elem| {
+                    matches!(
+                        elem,
+                        ProjectionElem::Deref
+                            | ProjectionElem::Downcast(_)
+                            | ProjectionElem::Field(0, _)
+                    )
+                })
             {
-                if fn_def.name() == "anchor_lang::prelude::AccountMeta::new" {
-                    account_metas.push((first_arg_ty.clone(), "mut", field_idx));
-                } else {
-                    // new_readonly
-                    account_metas.push((first_arg_ty.clone(), "immu", field_idx));
-                }
+                fields.entry(*lhs).or_insert(field_idx);
             }
         }
+        if fields.len() == known {
+            break;
+        }
     }
-    account_metas
+    for bb in &body.blocks {
+        if let TerminatorKind::Call {
+            args, destination, ..
+        } = &bb.terminator.kind
+            && let Some(Operand::Copy(arg) | Operand::Move(arg)) = args.first()
+            && let Some(field_idx) = fields.get(&arg.local).copied()
+        {
+            fields.insert(destination.local, field_idx);
+        }
+    }
+    fields
 }
 
-pub fn extract_program_id() -> Option<Vec<u8>> {
-    let mut program_id = None;
-    for item in rustc_public::all_local_items() {
-        if !matches!(item.kind(), ItemKind::Static) {
-            continue;
+/// The value of a `bool` constant operand.
+fn const_bool(operand: &Operand) -> Option<bool> {
+    let Operand::Constant(constant) = operand else {
+        return None;
+    };
+    match constant.const_.kind() {
+        Allocated(alloc) => alloc.read_bool().ok(),
+        _ => None,
+    }
+}
+
+/// Discriminators of the local `#[account]` types, by type name.
+pub fn extract_discriminators() -> Vec<(String, Vec<u8>)> {
+    let events: Vec<String> = event::event_structs()
+        .iter()
+        .map(|adt_def| adt_def.name())
+        .collect();
+    discriminator_consts()
+        .into_iter()
+        .filter(|(name, _)| !name.starts_with(INSTRUCTION_NAMESPACE) && !events.contains(name))
+        .collect()
+}
+
+/// Discriminators of the instructions, keyed by the generated struct, e.g.
+/// `instruction::Stake`. The first bytes of an instruction's data select its
+/// handler.
+pub fn extract_instruction_discriminators() -> Vec<(String, Vec<u8>)> {
+    discriminator_consts()
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(INSTRUCTION_NAMESPACE))
+        .collect()
+}
+
+/// Lowercase hex without separators, e.g. `f19a6d0411b16dbc`.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Module of the structs `#[program]` generates for instruction arguments.
+const INSTRUCTION_NAMESPACE: &str = "instruction::";
+
+const ANCHOR_DISCRIMINATOR: &str = "anchor_lang::Discriminator";
+
+/// `T` in the path `<T as trait_path>::assoc`. `T` may itself contain generic
+/// args and qualified paths, so the ` as ` is the one at the outermost `<`.
+fn qualified_self_ty<'a>(path: &'a str, trait_path: &str, assoc: &str) -> Option<&'a str> {
+    let inner = path.strip_prefix('<')?;
+    let mut depth = 0usize;
+    let mut prev = None;
+    for (idx, c) in inner.char_indices() {
+        match c {
+            '<' => depth += 1,
+            // The `>` of a `fn() -> T` in the type is not a bracket.
+            '>' if prev == Some('-') => {}
+            '>' => depth = depth.checked_sub(1)?,
+            ' ' if depth == 0 && inner[idx..].starts_with(" as ") => {
+                let rest = inner[idx + " as ".len()..].strip_prefix(trait_path)?;
+                let rest = rest.strip_prefix(">::")?;
+                return (rest == assoc).then_some(&inner[..idx]);
+            }
+            _ => {}
         }
+        prev = Some(c);
+    }
+    None
+}
 
-        if item.name() != "ID" {
+/// Every local `<T as anchor_lang::Discriminator>::DISCRIMINATOR`, with `T`, sorted
+/// by `T`.
+fn discriminator_consts() -> Vec<(String, Vec<u8>)> {
+    let mut discriminators = vec![];
+    for item in rustc_public::all_local_items() {
+        if !matches!(item.kind(), ItemKind::Const) {
             continue;
         }
 
-        let body = match item.body() {
+        let item_name = item.name();
+
+        let name = match qualified_self_ty(&item_name, ANCHOR_DISCRIMINATOR, "DISCRIMINATOR") {
+            Some(self_ty) => self_ty.to_owned(),
+            None => continue,
+        };
+
+        // The mono body has constants evaluated, including the promoted array
+        // behind the `&[u8]` discriminator of Anchor >= 0.30.
+        let body = match Instance::try_from(item)
+            .ok()
+            .and_then(|instance| instance.body())
+        {
             Some(b) => b,
             None => continue,
         };
 
-        // look at the first block's statements
         for stmt in &body.blocks[0].statements {
             let (_, rvalue) = match &stmt.kind {
                 Assign(place, rvalue) => (place, rvalue),
                 _ => continue,
             };
 
+            if let Rvalue::Use(Operand::Constant(ConstOperand { const_, .. })) = rvalue
+                && let Some(constraint::Value::Bytes(id)) = constraint::constant_value(const_)
+            {
+                discriminators.push((name, id));
+                break;
+            }
+
             // array of u8 check
             let (ty, operands) = match rvalue {
                 Rvalue::Aggregate(AggregateKind::Array(ty), operands) => (ty, operands),
                 _ => continue,
             };
 
-            let RigidTy::Uint(UintTy::U8) = ty.kind().rigid()? else {
+            let RigidTy::Uint(UintTy::U8) = ty.kind().rigid().unwrap() else {
                 continue;
             };
 
@@ -245,75 +566,217 @@ pub fn extract_program_id() -> Option<Vec<u8>> {
                 }
             }
 
-            program_id = Some(id);
-            return program_id;
+            discriminators.push((name, id));
+            break;
         }
     }
-    program_id
+    discriminators.sort();
+    discriminators
 }
 
-pub fn extract_discriminators() -> Vec<(String, Vec<u8>)> {
-    let re = Regex::new(r"<(.+?)\s+as\s+anchor_lang::Discriminator>").unwrap();
-    let mut account_discriminators = vec![];
+/// Find the local `<name as trait_path<..>>::fn_name` generated by `#[derive(Accounts)]`,
+/// e.g. `<Stake<'info> as anchor_lang::Accounts<'info, StakeBumps>>::try_accounts`.
+pub fn accounts_impl_fn(name: &str, trait_path: &str, fn_name: &str) -> Option<Instance> {
+    let trait_part = format!(" as {trait_path}<");
+    let fn_part = format!(">::{fn_name}");
     for item in rustc_public::all_local_items() {
-        if !matches!(item.kind(), ItemKind::Const) {
+        let item_name = item.name();
+        let Some((self_ty, rest)) = item_name.split_once(&trait_part) else {
+            continue;
+        };
+        if !rest.ends_with(&fn_part) {
             continue;
         }
-
-        let item_name = item.name();
-
-        if !item_name.ends_with("::DISCRIMINATOR") {
+        let self_ty = self_ty.trim_start_matches('<');
+        let self_path = self_ty.split('<').next().unwrap_or(self_ty);
+        if access::short_name(self_path) != name {
             continue;
         }
+        if let Ok(instance) = Instance::try_from(item) {
+            return Some(instance);
+        }
+    }
+    None
+}
 
-        if item_name.starts_with("<instruction::") {
+const CONTEXT: &str = "anchor_lang::context::Context";
+
+/// Find instruction handlers, i.e. local fns taking `Context<S>` as the first
+/// argument, paired with the definition of `S`.
+pub fn context_handlers() -> Vec<(AdtDef, Instance)> {
+    let mut handlers = vec![];
+    for item in rustc_public::all_local_items() {
+        if !matches!(item.kind(), ItemKind::Fn) || item.requires_monomorphization() {
             continue;
         }
-
-        let account_name = if let Some(caps) = re.captures(&item_name) {
-            let account = &caps[1];
-            account.to_owned()
-        } else {
+        let Ok(instance) = Instance::try_from(item) else {
             continue;
         };
+        if let Some(accounts_def) = context_accounts(instance) {
+            handlers.push((accounts_def, instance));
+        }
+    }
+    handlers
+}
 
-        let body = match item.body() {
-            Some(b) => b,
-            None => continue,
-        };
+/// The `T` of a fn whose first argument is `Context<'_, '_, '_, '_, T>`.
+fn context_accounts(instance: Instance) -> Option<AdtDef> {
+    let body = instance.body()?;
+    let local_decl = body.local_decl(1)?;
+    if let Some(RigidTy::Adt(adt_def, generic_args)) = local_decl.ty.kind().rigid()
+        && adt_def.name() == CONTEXT
+        // Skip the lifetimes.
+        && let Some(accounts_ty) = generic_args.0.iter().find_map(|arg| arg.ty())
+        && let Some(RigidTy::Adt(accounts_def, _)) = accounts_ty.kind().rigid()
+    {
+        Some(*accounts_def)
+    } else {
+        None
+    }
+}
 
-        for stmt in &body.blocks[0].statements {
-            let (_, rvalue) = match &stmt.kind {
-                Assign(place, rvalue) => (place, rvalue),
-                _ => continue,
-            };
+/// An instruction of the `#[program]` module and the user-written fn handling it.
+#[derive(Clone, Debug)]
+pub struct HandlerInfo {
+    /// Instruction name, i.e. the name of the handler fn.
+    pub name: String,
+    pub instance: Instance,
+    /// Short name of the `T` in the handler's `Context<T>`.
+    pub context_struct: String,
+    /// Name and type of each instruction argument, from the fields of the
+    /// generated `instruction::<Name>` struct.
+    pub args: Vec<(String, String)>,
+    /// The `T` in the handler's `Context<T>`.
+    pub accounts_def: AdtDef,
+    /// Guards run before the body, e.g. by `#[access_control(is_admin(&ctx))]`.
+    pub access_controls: Vec<GuardInfo>,
+    /// The accounts it reads beyond those of `accounts_def`.
+    pub remaining_accounts: RemainingAccounts,
+}
 
-            // array of u8 check
-            let (ty, operands) = match rvalue {
-                Rvalue::Aggregate(AggregateKind::Array(ty), operands) => (ty, operands),
-                _ => continue,
-            };
+const INSTRUCTION_DATA: &str = "anchor_lang::InstructionData";
 
-            let RigidTy::Uint(UintTy::U8) = ty.kind().rigid().unwrap() else {
-                continue;
-            };
+/// The `instruction::<Name>` structs `#[program]` generates to (de)serialize
+/// instruction arguments, by short name.
+fn instruction_structs() -> HashMap<String, AdtDef> {
+    let mut structs = HashMap::new();
+    for trait_impl in rustc_public::all_trait_impls() {
+        let trait_impl = trait_impl.trait_impl().value;
+        if trait_impl.def_id.name() != INSTRUCTION_DATA {
+            continue;
+        }
+        if let Some(RigidTy::Adt(adt_def, _)) = trait_impl.self_ty().kind().rigid()
+            && adt_def.krate().is_local
+        {
+            structs.insert(access::short_name(&adt_def.name()), *adt_def);
+        }
+    }
+    structs
+}
 
-            let mut id = Vec::with_capacity(operands.len());
-            for operand in operands {
-                if let Operand::Constant(ConstOperand { const_, .. }) = operand
-                    && let Allocated(Allocation { bytes, .. }) = const_.kind()
-                {
-                    for byte in bytes.iter().flatten() {
-                        id.push(*byte);
-                    }
-                }
-            }
+/// `read_checked` -> `ReadChecked`, as Anchor names instruction structs.
+fn upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
 
-            account_discriminators.push((account_name, id));
-            break;
+/// Resolve each instruction the program dispatches to its handler, in
+/// declaration order. A handler without accounts still takes a `Context` of an
+/// empty struct.
+pub fn program_handlers() -> Vec<HandlerInfo> {
+    let instruction_structs = instruction_structs();
+    let mut handlers = vec![];
+    for (name, instance) in instruction_handlers() {
+        let Some(accounts_def) = context_accounts(instance) else {
+            continue;
+        };
+        let args = instruction_structs
+            .get(&upper_camel_case(&name))
+            .and_then(|adt_def| adt_def.variants_iter().next())
+            .map(|variant| {
+                variant
+                    .fields()
+                    .into_iter()
+                    .map(|field| (field.name.clone(), field.ty().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        handlers.push(HandlerInfo {
+            name,
+            instance,
+            context_struct: access::short_name(&accounts_def.name()),
+            args,
+            accounts_def,
+            access_controls: access_control::handler_guards(instance),
+            remaining_accounts: instance
+                .body()
+                .map(|body| remaining::remaining_accounts_use(&body))
+                .unwrap_or_default(),
+        });
+    }
+    handlers
+}
+
+/// Group instruction handlers by the short name of their Accounts struct. Several
+/// handlers may share one struct.
+pub fn handlers_by_accounts() -> BTreeMap<String, (AdtDef, Vec<Instance>)> {
+    let mut structs: BTreeMap<String, (AdtDef, Vec<Instance>)> = BTreeMap::new();
+    for (adt_def, handler) in context_handlers() {
+        structs
+            .entry(access::short_name(&adt_def.name()))
+            .or_insert_with(|| (adt_def, vec![]))
+            .1
+            .push(handler);
+    }
+    structs
+}
+
+/// Module of the per-instruction wrappers generated by `#[program]`. Each
+/// deserializes the instruction and calls the user-written handler.
+const GLOBAL_DISPATCH: &str = "__private::__global::";
+
+/// Find the user-written instruction handlers that Anchor dispatches to, paired
+/// with the instruction name, in declaration order.
+///
+/// For each `__private::__global::<ix>` wrapper the handler is the local,
+/// non-trait fn called `<ix>` that the wrapper calls.
+pub fn instruction_handlers() -> Vec<(String, Instance)> {
+    let mut handlers = vec![];
+    for item in rustc_public::all_local_items() {
+        let item_name = item.name();
+        let Some(ix_name) = item_name.strip_prefix(GLOBAL_DISPATCH) else {
+            continue;
+        };
+        if ix_name.contains("::") || !matches!(item.kind(), ItemKind::Fn) {
+            continue;
+        }
+        let Ok(wrapper) = Instance::try_from(item) else {
+            continue;
+        };
+        let Some(body) = wrapper.body() else {
+            continue;
+        };
+        let handler = (0..body.blocks.len())
+            .filter_map(|block| callgraph::callee(&body, block))
+            .find(|callee| {
+                let name = callee.name();
+                callee.def.krate().is_local
+                    && !name.starts_with('<')
+                    && name.rsplit("::").next() == Some(ix_name)
+            });
+        if let Some(handler) = handler {
+            handlers.push((ix_name.to_string(), handler));
         }
     }
-    account_discriminators
+    handlers
 }
 
 const ENTRY: &str = "entry";
@@ -338,3 +801,58 @@ pub fn entry_instance() -> Option<Instance> {
     }
     entry_fn
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discriminator_self_ty(path: &str) -> Option<&str> {
+        qualified_self_ty(path, ANCHOR_DISCRIMINATOR, "DISCRIMINATOR")
+    }
+
+    #[test]
+    fn test_qualified_self_ty() {
+        assert_eq!(
+            discriminator_self_ty("<Vault as anchor_lang::Discriminator>::DISCRIMINATOR"),
+            Some("Vault")
+        );
+        assert_eq!(
+            discriminator_self_ty("<Wrapper<Vault> as anchor_lang::Discriminator>::DISCRIMINATOR"),
+            Some("Wrapper<Vault>")
+        );
+        assert_eq!(
+            discriminator_self_ty(
+                "<state::Pair<Vault, Box<[u8]>> as anchor_lang::Discriminator>::DISCRIMINATOR"
+            ),
+            Some("state::Pair<Vault, Box<[u8]>>")
+        );
+        assert_eq!(
+            discriminator_self_ty(
+                "<instruction::Stake as anchor_lang::Discriminator>::DISCRIMINATOR"
+            ),
+            Some("instruction::Stake")
+        );
+        // A qualified path in the generic args has its own ` as `.
+        assert_eq!(
+            discriminator_self_ty(
+                "<Wrapper<<Vault as Owner>::Key> as anchor_lang::Discriminator>::DISCRIMINATOR"
+            ),
+            Some("Wrapper<<Vault as Owner>::Key>")
+        );
+        assert_eq!(
+            discriminator_self_ty(
+                "<Handler<fn() -> u8> as anchor_lang::Discriminator>::DISCRIMINATOR"
+            ),
+            Some("Handler<fn() -> u8>")
+        );
+        assert_eq!(
+            discriminator_self_ty("<Vault as anchor_lang::Owner>::DISCRIMINATOR"),
+            None
+        );
+        assert_eq!(
+            discriminator_self_ty("<Vault as anchor_lang::Discriminator>::discriminator"),
+            None
+        );
+        assert_eq!(discriminator_self_ty("Vault::DISCRIMINATOR"), None);
+    }
+}
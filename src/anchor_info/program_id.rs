@@ -0,0 +1,459 @@
+//! Program ids and other `Pubkey`s, shown in base58 as explorers and
+//! `declare_id!` write them.
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use rustc_public::mir::alloc::GlobalAlloc;
+use rustc_public::mir::mono::{Instance, StaticDef};
+use rustc_public::mir::{
+    AggregateKind, BasicBlockIdx, Body, Operand, Place, Rvalue, StatementKind, Terminator,
+    TerminatorKind,
+};
+use rustc_public::ty::{ConstantKind, MirConst, RigidTy, Ty, TyKind, UintTy};
+use rustc_public::{CrateDef, CrateItem, ItemKind};
+use thiserror::Error;
+
+use crate::analysis::types::is_known_ty;
+use crate::analysis::visit::{MirVisitor, walk_body};
+use crate::metadata::Concept;
+
+pub const PUBKEY_LEN: usize = 32;
+
+/// Names `declare_id!` (`ID`, and `ID_CONST` since Anchor 0.30) and hand-written
+/// programs give the program id.
+const ID_NAMES: [&str; 3] = ["ID", "ID_CONST", "PROGRAM_ID"];
+/// Fns `declare_id!` generates next to the id, reading the static it declares.
+const ID_FNS: [&str; 2] = ["check_id", "id"];
+/// `Pubkey::from_str_const`, decoding a base58 literal at compile time.
+const FROM_STR_CONST: &str = "::from_str_const";
+
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum ProgramIdError {
+    #[error("no program id declared")]
+    Missing,
+    #[error("malformed program id: {0} bytes instead of {PUBKEY_LEN}")]
+    Length(usize),
+    #[error("malformed program id `{0}`: not base58")]
+    Base58(String),
+    #[error("malformed program id `{0}`: not hex")]
+    Hex(String),
+    #[error("conflicting program ids: {}", list_candidates(.0))]
+    Ambiguous(Vec<IdCandidate>),
+}
+
+/// A 32-byte public key, usually the id of a program.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ProgramId(pub [u8; PUBKEY_LEN]);
+
+/// A static or const that may hold the program id.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IdCandidate {
+    /// Item path, e.g. `my_program::ID`.
+    pub path: String,
+    pub id: ProgramId,
+    /// Whether the `check_id`/`id()` fns generated by `declare_id!` read it.
+    pub referenced: bool,
+}
+
+impl TryFrom<&[u8]> for ProgramId {
+    type Error = ProgramIdError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bytes
+            .try_into()
+            .map(ProgramId)
+            .map_err(|_| ProgramIdError::Length(bytes.len()))
+    }
+}
+
+/// Parse a program id in base58, as `declare_id!` takes it, or as hex digits
+/// after `0x`.
+impl FromStr for ProgramId {
+    type Err = ProgramIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = match s.strip_prefix("0x") {
+            Some(hex) => parse_hex(hex).ok_or_else(|| ProgramIdError::Hex(s.to_string()))?,
+            None => bs58::decode(s)
+                .into_vec()
+                .map_err(|_| ProgramIdError::Base58(s.to_string()))?,
+        };
+        ProgramId::try_from(&bytes[..])
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).ok())
+        .collect()
+}
+
+impl fmt::Display for ProgramId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", bs58::encode(self.0).into_string())
+    }
+}
+
+/// The base58 string, quoted, so that constraints printed with `{:?}` stay
+/// readable.
+impl fmt::Debug for ProgramId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+fn list_candidates(candidates: &[IdCandidate]) -> String {
+    candidates
+        .iter()
+        .map(|candidate| format!("`{}` = {}", candidate.path, candidate.id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The id the program declares. Candidates are the local statics and consts
+/// of type `Pubkey` or `[u8; 32]` that the fns `declare_id!` generates read, or
+/// else that are named like a program id, e.g. an `ID` re-exported from a
+/// constants module. Candidates disagreeing on the id are returned in the error.
+pub fn extract_program_id() -> Result<ProgramId, ProgramIdError> {
+    let referenced = id_fn_statics();
+    let mut candidates = vec![];
+    for item in rustc_public::all_local_items() {
+        if !matches!(item.kind(), ItemKind::Static | ItemKind::Const) || !is_pubkey(item.ty()) {
+            continue;
+        }
+        let path = item.name();
+        let is_referenced = referenced.contains(&path);
+        let name = path.rsplit("::").next().unwrap_or(&path);
+        if !is_referenced && !ID_NAMES.contains(&name) {
+            continue;
+        }
+        let Some(bytes) = item_bytes(item) else {
+            continue;
+        };
+        candidates.push(IdCandidate {
+            id: ProgramId::try_from(&bytes[..])?,
+            path,
+            referenced: is_referenced,
+        });
+    }
+    choose_program_id(candidates)
+}
+
+/// The id of the candidates `declare_id!` fns read if any, else of all of them,
+/// as long as they agree.
+fn choose_program_id(mut candidates: Vec<IdCandidate>) -> Result<ProgramId, ProgramIdError> {
+    if candidates.iter().any(|candidate| candidate.referenced) {
+        candidates.retain(|candidate| candidate.referenced);
+    }
+    let ids: BTreeSet<ProgramId> = candidates.iter().map(|candidate| candidate.id).collect();
+    let mut ids = ids.into_iter();
+    match (ids.next(), ids.next()) {
+        (None, _) => Err(ProgramIdError::Missing),
+        (Some(id), None) => Ok(id),
+        (Some(_), Some(_)) => {
+            candidates.sort_by(|a, b| a.path.cmp(&b.path));
+            Err(ProgramIdError::Ambiguous(candidates))
+        }
+    }
+}
+
+/// Whether `ty` is a `Pubkey`, or the `[u8; 32]` Pinocchio aliases it to.
+pub(crate) fn is_pubkey(ty: Ty) -> bool {
+    match ty.kind().rigid() {
+        Some(RigidTy::Array(elem_ty, len)) => {
+            matches!(elem_ty.kind().rigid(), Some(RigidTy::Uint(UintTy::U8)))
+                && len.eval_target_usize() == Ok(PUBKEY_LEN as u64)
+        }
+        Some(RigidTy::Adt(..)) => is_known_ty(Concept::Pubkey, ty),
+        _ => false,
+    }
+}
+
+/// Whether `constant` is `id`: its bytes, the bytes it points to, or a local
+/// const or static holding them, e.g. the `ID` of `declare_id!`.
+pub(crate) fn is_id_const(constant: &MirConst, id: ProgramId) -> bool {
+    let bytes = match constant.kind() {
+        ConstantKind::Allocated(alloc) => match alloc.provenance.ptrs.first() {
+            None => alloc.raw_bytes().ok(),
+            Some((_, prov)) => match GlobalAlloc::from(prov.0) {
+                GlobalAlloc::Memory(data) => data.raw_bytes().ok(),
+                GlobalAlloc::Static(def) => def
+                    .eval_initializer()
+                    .ok()
+                    .and_then(|alloc| alloc.raw_bytes().ok()),
+                _ => None,
+            },
+        },
+        ConstantKind::Unevaluated(unevaluated) if unevaluated.promoted.is_none() => {
+            let name = unevaluated.def.name();
+            rustc_public::all_local_items()
+                .into_iter()
+                .find(|item| item.name() == name)
+                .and_then(item_bytes)
+        }
+        _ => None,
+    };
+    bytes.is_some_and(|bytes| bytes == id.0)
+}
+
+/// Whether `name` is a local fn `declare_id!` generates, `check_id` or `id`.
+pub(crate) fn is_id_fn(name: &str) -> bool {
+    ID_FNS.contains(&name.rsplit("::").next().unwrap_or(name))
+}
+
+/// Paths of the statics the local `check_id` and `id` fns read.
+fn id_fn_statics() -> HashSet<String> {
+    let mut statics = StaticRefs::default();
+    for item in rustc_public::all_local_items() {
+        let path = item.name();
+        let name = path.rsplit("::").next().unwrap_or(&path);
+        if matches!(item.kind(), ItemKind::Fn)
+            && ID_FNS.contains(&name)
+            && let Some(body) = item.body()
+        {
+            walk_body(&body, &mut statics);
+        }
+    }
+    statics.paths
+}
+
+#[derive(Default)]
+struct StaticRefs {
+    paths: HashSet<String>,
+}
+
+impl MirVisitor for StaticRefs {
+    fn visit_operand(&mut self, _: BasicBlockIdx, operand: &Operand) {
+        if let Operand::Constant(constant) = operand
+            && let ConstantKind::Allocated(alloc) = constant.const_.kind()
+        {
+            for (_, prov) in &alloc.provenance.ptrs {
+                if let GlobalAlloc::Static(def) = GlobalAlloc::from(prov.0) {
+                    self.paths.insert(def.name());
+                }
+            }
+        }
+    }
+}
+
+/// The bytes a static holds, or a const evaluates to. Consts are evaluated by
+/// the compiler, so that ids built by `const fn`s from other consts, e.g.
+/// `Pubkey::new_from_array(ID_BYTES)` or a `pubkey!` aliased by another const,
+/// are recovered; their bodies are read when they cannot be.
+pub(super) fn item_bytes(item: CrateItem) -> Option<Vec<u8>> {
+    if let Ok(def) = StaticDef::try_from(item) {
+        return def.eval_initializer().ok()?.raw_bytes().ok();
+    }
+    const_eval_bytes(item).or_else(|| body_bytes(&item.body()?))
+}
+
+/// The bytes the const `item` evaluates to, unless they hold a pointer.
+fn const_eval_bytes(item: CrateItem) -> Option<Vec<u8>> {
+    if !matches!(item.kind(), ItemKind::Const) {
+        return None;
+    }
+    let alloc = Instance::try_from(item)
+        .ok()?
+        .try_const_eval(item.ty())
+        .ok()?;
+    if !alloc.provenance.ptrs.is_empty() {
+        return None;
+    }
+    alloc.raw_bytes().ok()
+}
+
+/// The 32 bytes a const body builds, from an array of `u8`s (as in
+/// `Pubkey::new_from_array([..])`), a constant, or the base58 string
+/// `Pubkey::from_str_const` decodes (as the `declare_id!` of `solana-program`
+/// expands to), in whichever block.
+fn body_bytes(body: &Body) -> Option<Vec<u8>> {
+    let mut id = IdBytes { body, bytes: None };
+    walk_body(body, &mut id);
+    id.bytes
+}
+
+/// The first 32 bytes `body_bytes` finds in a const body.
+struct IdBytes<'a> {
+    body: &'a Body,
+    bytes: Option<Vec<u8>>,
+}
+
+impl MirVisitor for IdBytes<'_> {
+    fn visit_terminator(&mut self, _: BasicBlockIdx, terminator: &Terminator) {
+        if self.bytes.is_none()
+            && let TerminatorKind::Call { func, .. } = &terminator.kind
+            && let Ok(fn_ty) = func.ty(self.body.locals())
+            && let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind()
+            && fn_def.name().ends_with(FROM_STR_CONST)
+        {
+            self.bytes = body_str_id(self.body).map(|id| id.0.to_vec());
+        }
+    }
+
+    fn visit_rvalue(&mut self, _: BasicBlockIdx, _: &Place, rvalue: &Rvalue) {
+        if self.bytes.is_some() {
+            return;
+        }
+        let bytes = match rvalue {
+            Rvalue::Aggregate(AggregateKind::Array(ty), operands)
+                if matches!(ty.kind().rigid(), Some(RigidTy::Uint(UintTy::U8))) =>
+            {
+                operands.iter().map(const_u8).collect::<Option<Vec<u8>>>()
+            }
+            Rvalue::Use(Operand::Constant(constant)) => match constant.const_.kind() {
+                ConstantKind::Allocated(alloc) if alloc.provenance.ptrs.is_empty() => {
+                    alloc.raw_bytes().ok()
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        self.bytes = bytes.filter(|bytes| bytes.len() == PUBKEY_LEN);
+    }
+}
+
+/// The program id a `&str` constant of `body` spells in base58.
+fn body_str_id(body: &Body) -> Option<ProgramId> {
+    body.blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(_, Rvalue::Use(Operand::Constant(constant))) => {
+                String::from_utf8(str_bytes(&constant.const_)?)
+                    .ok()?
+                    .parse()
+                    .ok()
+            }
+            _ => None,
+        })
+}
+
+/// The bytes of a `&str` constant.
+fn str_bytes(constant: &MirConst) -> Option<Vec<u8>> {
+    let ConstantKind::Allocated(alloc) = constant.kind() else {
+        return None;
+    };
+    let (_, prov) = alloc.provenance.ptrs.first()?;
+    match GlobalAlloc::from(prov.0) {
+        GlobalAlloc::Memory(data) => data.raw_bytes().ok(),
+        _ => None,
+    }
+}
+
+fn const_u8(operand: &Operand) -> Option<u8> {
+    let Operand::Constant(constant) = operand else {
+        return None;
+    };
+    match constant.const_.kind() {
+        ConstantKind::Allocated(alloc) => alloc.read_uint().ok()?.try_into().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(path: &str, byte: u8, referenced: bool) -> IdCandidate {
+        IdCandidate {
+            path: path.to_string(),
+            id: ProgramId([byte; PUBKEY_LEN]),
+            referenced,
+        }
+    }
+
+    #[test]
+    fn test_base58() {
+        assert_eq!(
+            ProgramId([0; PUBKEY_LEN]).to_string(),
+            "11111111111111111111111111111111"
+        );
+        let token = [
+            6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28, 180,
+            133, 237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+        ];
+        assert_eq!(
+            ProgramId(token).to_string(),
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        );
+    }
+
+    #[test]
+    fn test_length() {
+        assert_eq!(
+            ProgramId::try_from(&[1u8; 31][..]),
+            Err(ProgramIdError::Length(31))
+        );
+        assert_eq!(
+            ProgramId::try_from(&[1u8; 32][..]),
+            Ok(ProgramId([1; PUBKEY_LEN]))
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let token: ProgramId = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            token.to_string(),
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        );
+        assert_eq!(
+            format!("0x{}", "01".repeat(PUBKEY_LEN)).parse(),
+            Ok(ProgramId([1; PUBKEY_LEN]))
+        );
+        assert_eq!(
+            "Tokenkeg0".parse::<ProgramId>(),
+            Err(ProgramIdError::Base58("Tokenkeg0".to_string()))
+        );
+        assert_eq!(
+            "0x0g".parse::<ProgramId>(),
+            Err(ProgramIdError::Hex("0x0g".to_string()))
+        );
+        assert_eq!("1111".parse::<ProgramId>(), Err(ProgramIdError::Length(4)));
+    }
+
+    #[test]
+    fn test_choose_program_id() {
+        assert_eq!(choose_program_id(vec![]), Err(ProgramIdError::Missing));
+        // `ID` and `ID_CONST` agree.
+        assert_eq!(
+            choose_program_id(vec![
+                candidate("p::ID", 1, true),
+                candidate("p::ID_CONST", 1, false),
+            ]),
+            Ok(ProgramId([1; PUBKEY_LEN]))
+        );
+        // What `check_id` reads wins over another program's id in a module.
+        assert_eq!(
+            choose_program_id(vec![
+                candidate("p::other::ID", 2, false),
+                candidate("p::ID", 1, true),
+            ]),
+            Ok(ProgramId([1; PUBKEY_LEN]))
+        );
+        let err = choose_program_id(vec![
+            candidate("p::legacy::ID", 2, false),
+            candidate("p::ID", 1, false),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ProgramIdError::Ambiguous(vec![
+                candidate("p::ID", 1, false),
+                candidate("p::legacy::ID", 2, false),
+            ])
+        );
+        assert_eq!(
+            err.to_string(),
+            "conflicting program ids: `p::ID` = 4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi, \
+             `p::legacy::ID` = 8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! How handlers use `ctx.remaining_accounts`, the accounts a client passes
+//! after those of the Accounts struct, which Anchor neither deserializes nor
+//! checks.
+//!
+//! A `Vec<T>` field of an Accounts struct is no catch-all: Anchor's `Vec<T>`
+//! impl of `Accounts` takes a single `T`, so it is a fixed account like the
+//! others.
+use std::collections::BTreeSet;
+use std::fmt;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::{
+    AggregateKind, Body, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind,
+};
+use rustc_public::ty::{RigidTy, Span, TyKind};
+
+use crate::analysis::condition::{Definitions, Origin, Side, field_path};
+use crate::analysis::taint::{Taint, operand_local, propagate};
+
+const REMAINING_ACCOUNTS: &str = "Context.remaining_accounts";
+pub const SLICE_LEN: &str = "::len";
+const SLICE_GET: &str = "::get";
+const INDEX: &str = "std::ops::Index";
+
+/// The remaining accounts a handler reads.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RemainingAccounts {
+    /// Whether the handler touches `ctx.remaining_accounts` at all.
+    pub used: bool,
+    /// How many leading accounts it reads at constant positions, e.g. 2 for
+    /// `remaining_accounts[1]` or `remaining_accounts[2..]`.
+    pub fixed: usize,
+    /// Whether it also takes any number of them: iterates over them, indexes
+    /// them with values computed at runtime, or hands them to another fn.
+    pub open_ended: bool,
+}
+
+impl fmt::Display for RemainingAccounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.fixed, self.open_ended) {
+            (0, false) => write!(f, "length only"),
+            (0, true) => write!(f, "any number"),
+            (fixed, false) => write!(f, "{fixed}"),
+            (fixed, true) => write!(f, "{fixed} or more"),
+        }
+    }
+}
+
+/// A read of the remaining accounts at constant positions.
+pub struct ConstAccess {
+    pub block: usize,
+    pub span: Span,
+    /// The access written out, e.g. `[1]` or `[2..]`.
+    pub access: String,
+    /// How many accounts it needs.
+    pub min_len: usize,
+}
+
+/// What the handler `body` reads of its remaining accounts.
+pub fn remaining_accounts_use(body: &Body) -> RemainingAccounts {
+    let defs = Definitions::new(body);
+    let slices = remaining_slices(body, &defs);
+    if slices.is_empty() {
+        return RemainingAccounts::default();
+    }
+    let mut remaining = RemainingAccounts {
+        used: true,
+        fixed: 0,
+        open_ended: false,
+    };
+    for access in const_accesses(body, &slices, &defs) {
+        remaining.fixed = remaining.fixed.max(access.min_len);
+    }
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(_, rvalue) = &stmt.kind
+                && let Some(place) = read_place(rvalue)
+                && slices.contains_key(&place.local)
+                && matches!(
+                    place.projection[..],
+                    [ProjectionElem::Deref, ProjectionElem::Index(_), ..]
+                )
+                && slice_index(place, &slices, &defs).is_none()
+            {
+                remaining.open_ended = true;
+            }
+        }
+        let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+            continue;
+        };
+        if !args.iter().any(|arg| {
+            is_slice(body, arg)
+                && operand_local(arg).is_some_and(|local| slices.contains_key(&local))
+        }) {
+            continue;
+        }
+        let name = callee_name(body, func).unwrap_or_default();
+        let const_arg = args.get(1).and_then(|arg| const_usize(arg, &defs));
+        if name.ends_with(SLICE_LEN) {
+            continue;
+        } else if name.ends_with(SLICE_GET)
+            && let Some(index) = const_arg
+        {
+            remaining.fixed = remaining.fixed.max(index + 1);
+        } else if !(name.contains(INDEX) && const_range_local(args, body, &defs).is_some()) {
+            remaining.open_ended = true;
+        }
+    }
+    remaining
+}
+
+/// Locals holding the remaining accounts, a part of them or one of them.
+pub fn remaining_slices(body: &Body, defs: &Definitions) -> Taint<()> {
+    let mut sources = Taint::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(lhs, rvalue) = &stmt.kind
+                && let Some(place) = read_place(rvalue)
+                && defs
+                    .field_parent(place)
+                    .is_some_and(|(ty, field_idx)| field_path(ty, field_idx) == REMAINING_ACCOUNTS)
+            {
+                sources.insert(lhs.local, BTreeSet::from([()]));
+            }
+        }
+    }
+    if sources.is_empty() {
+        return sources;
+    }
+    propagate(body, sources)
+}
+
+/// Constant indexing and slicing of the remaining accounts, `slices`, in
+/// `body`: `[i]`, and `[a..b]` and its other range forms.
+pub fn const_accesses(body: &Body, slices: &Taint<()>, defs: &Definitions) -> Vec<ConstAccess> {
+    let mut accesses = vec![];
+    for (bb, block) in body.blocks.iter().enumerate() {
+        for stmt in &block.statements {
+            let StatementKind::Assign(_, rvalue) = &stmt.kind else {
+                continue;
+            };
+            let Some(place) = read_place(rvalue) else {
+                continue;
+            };
+            if let Some(index) = slice_index(place, slices, defs) {
+                accesses.push(ConstAccess {
+                    block: bb,
+                    span: stmt.span,
+                    access: format!("[{index}]"),
+                    min_len: index + 1,
+                });
+            }
+        }
+        if let TerminatorKind::Call { func, args, .. } = &block.terminator.kind
+            && callee_name(body, func).is_some_and(|name| name.contains(INDEX))
+            && args
+                .first()
+                .and_then(operand_local)
+                .is_some_and(|local| slices.contains_key(&local))
+            && let Some((range, min_len)) = const_range_local(args, body, defs)
+        {
+            accesses.push(ConstAccess {
+                block: bb,
+                span: block.terminator.span,
+                access: format!("[{range}]"),
+                min_len,
+            });
+        }
+    }
+    accesses
+}
+
+/// The place an rvalue reads, if it reads one directly.
+pub fn read_place(rvalue: &Rvalue) -> Option<&Place> {
+    match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+        | Rvalue::Ref(_, _, place)
+        | Rvalue::AddressOf(_, place)
+        | Rvalue::CopyForDeref(place) => Some(place),
+        _ => None,
+    }
+}
+
+pub fn callee_name(body: &Body, func: &Operand) -> Option<String> {
+    let fn_ty = func.ty(body.locals()).ok()?;
+    match fn_ty.kind() {
+        TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) => Some(fn_def.name()),
+        _ => None,
+    }
+}
+
+/// The constant index of `(*slice)[i]` into a local in `slices`.
+fn slice_index(place: &Place, slices: &Taint<()>, defs: &Definitions) -> Option<usize> {
+    if !slices.contains_key(&place.local) {
+        return None;
+    }
+    let [ProjectionElem::Deref, ProjectionElem::Index(index), ..] = &place.projection[..] else {
+        return None;
+    };
+    const_usize(
+        &Operand::Copy(Place {
+            local: *index,
+            projection: vec![],
+        }),
+        defs,
+    )
+}
+
+fn const_usize(operand: &Operand, defs: &Definitions) -> Option<usize> {
+    match defs.origin(&Side::Operand(operand.clone()))? {
+        Origin::Const(value) => value.try_into().ok(),
+        Origin::Field { .. } => None,
+    }
+}
+
+/// The constant range passed as the second argument of an `Index::index` call,
+/// written out, with the length a slice needs for it.
+fn const_range_local(args: &[Operand], body: &Body, defs: &Definitions) -> Option<(String, usize)> {
+    let range = operand_local(args.get(1)?)?;
+    body.blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(
+                lhs,
+                Rvalue::Aggregate(AggregateKind::Adt(adt_def, ..), operands),
+            ) if lhs.local == range => const_range(&adt_def.name(), operands, defs),
+            _ => None,
+        })
+}
+
+/// A range with constant bounds built as `adt` from `operands`, written out,
+/// with the length a slice needs for it.
+fn const_range(adt: &str, operands: &[Operand], defs: &Definitions) -> Option<(String, usize)> {
+    let bounds: Vec<usize> = operands
+        .iter()
+        .map(|operand| const_usize(operand, defs))
+        .collect::<Option<_>>()?;
+    match (adt, &bounds[..]) {
+        ("std::ops::Range", [start, end]) => Some((format!("{start}..{end}"), *start.max(end))),
+        ("std::ops::RangeFrom", [start]) => Some((format!("{start}.."), *start)),
+        ("std::ops::RangeTo", [end]) => Some((format!("..{end}"), *end)),
+        ("std::ops::RangeToInclusive", [end]) => Some((format!("..={end}"), end + 1)),
+        _ => None,
+    }
+}
+
+/// Whether `operand` is a slice or a reference to one.
+fn is_slice(body: &Body, operand: &Operand) -> bool {
+    let Ok(ty) = operand.ty(body.locals()) else {
+        return false;
+    };
+    let ty = match ty.kind().rigid() {
+        Some(RigidTy::Ref(_, ty, _)) => *ty,
+        _ => ty,
+    };
+    matches!(ty.kind().rigid(), Some(RigidTy::Slice(_)))
+}
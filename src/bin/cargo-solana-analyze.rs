@@ -0,0 +1,314 @@
+//! `cargo solana-analyze`: run `cargo check` with the analyzer as the rustc of
+//! the workspace members and print one report for the programs analyzed.
+//!
+//! Each analyzed crate writes its `AnalysisReport` into a temporary directory
+//! named by `SOLANA_ANALYZER_REPORT_DIR`; the reports are then combined as
+//! `solana-program-analyzer --combine` does.
+// The library links the compiler when built with its `driver` feature.
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use solana_program_analyzer::aggregate::{
+    AnalysisReport, DENIED_EXIT_CODE, REPORT_DIR_ENV, ReportError, SEVERITIES, combine,
+};
+use solana_program_analyzer::metadata::{
+    SolanaMetadataError, discover_workspace_programs, find_workspace_root, parse_toml_in_crate_path,
+};
+use thiserror::Error;
+
+/// The subcommand name cargo passes first when run as `cargo solana-analyze`.
+const SUBCOMMAND: &str = "solana-analyze";
+/// The analyzer binary, installed next to this one.
+const ANALYZER: &str = "solana-program-analyzer";
+/// rustup sets this while building us; the analyzer links the compiler of that
+/// toolchain, so the checked crates must be compiled by it too.
+const TOOLCHAIN: Option<&str> = option_env!("RUSTUP_TOOLCHAIN");
+
+#[derive(Error, Debug)]
+enum AnalyzeError {
+    #[error("missing value after {0}")]
+    MissingValue(String),
+    #[error("unexpected argument `{0}`")]
+    UnexpectedArgument(String),
+    #[error("unknown format `{0}`, expected `text` or `json`")]
+    Format(String),
+    #[error("unknown severity `{0}`, expected `info`, `warning` or `error`")]
+    Severity(String),
+    #[error("the analyzer is not installed next to {0}")]
+    AnalyzerNotFound(PathBuf),
+    #[error("failed to locate this executable: {0}")]
+    CurrentExe(io::Error),
+    #[error("failed to read the manifest of {0}: {1}")]
+    Metadata(PathBuf, SolanaMetadataError),
+    #[error("failed to prepare {0}: {1}")]
+    ReportDir(PathBuf, io::Error),
+    #[error("failed to run cargo: {0}")]
+    Cargo(io::Error),
+    #[error("cargo check failed")]
+    CheckFailed,
+    #[error(transparent)]
+    Report(#[from] ReportError),
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+/// What we were asked for on the command line.
+#[derive(Debug, Default)]
+struct Args {
+    packages: Vec<String>,
+    manifest_path: Option<PathBuf>,
+    format: Format,
+    /// Exit with `DENIED_EXIT_CODE` when a finding is at or above this
+    /// severity, one of `SEVERITIES`.
+    deny: Option<String>,
+    /// Flags passed on to `cargo check` as they are, e.g. `--release`.
+    cargo_args: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, AnalyzeError> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter().peekable();
+        if args.peek().map(String::as_str) == Some(SUBCOMMAND) {
+            args.next();
+        }
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| args.next().ok_or(AnalyzeError::MissingValue(flag.into()));
+            match arg.as_str() {
+                "-p" | "--package" => parsed.packages.push(value(&arg)?),
+                "--manifest-path" => parsed.manifest_path = Some(value(&arg)?.into()),
+                "--format" => {
+                    parsed.format = match value(&arg)?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        format => return Err(AnalyzeError::Format(format.to_string())),
+                    }
+                }
+                "--deny" => {
+                    let level = value(&arg)?;
+                    if !SEVERITIES.contains(&level.as_str()) {
+                        return Err(AnalyzeError::Severity(level));
+                    }
+                    parsed.deny = Some(level);
+                }
+                "-F" | "--features" => {
+                    let features = value(&arg)?;
+                    parsed
+                        .cargo_args
+                        .extend(["--features".to_string(), features]);
+                }
+                "--release"
+                | "--all-targets"
+                | "--all-features"
+                | "--no-default-features"
+                | "--offline"
+                | "--locked"
+                | "--frozen" => parsed.cargo_args.push(arg),
+                _ => match arg.strip_prefix("--features=") {
+                    Some(features) => parsed
+                        .cargo_args
+                        .extend(["--features".to_string(), features.to_string()]),
+                    None => return Err(AnalyzeError::UnexpectedArgument(arg)),
+                },
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+fn main() -> ExitCode {
+    match Args::parse(std::env::args().skip(1)).and_then(|args| analyze(&args)) {
+        Ok((output, denied)) => {
+            print!("{output}");
+            if denied {
+                ExitCode::from(DENIED_EXIT_CODE)
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Check the packages `args` selects with the analyzer and return the combined
+/// report in the requested format, and whether a finding reaches the `--deny`
+/// severity.
+fn analyze(args: &Args) -> Result<(String, bool), AnalyzeError> {
+    let exe = std::env::current_exe().map_err(AnalyzeError::CurrentExe)?;
+    let analyzer = exe.with_file_name(format!("{ANALYZER}{}", std::env::consts::EXE_SUFFIX));
+    if !analyzer.is_file() {
+        return Err(AnalyzeError::AnalyzerNotFound(exe));
+    }
+    let report_dir = report_dir()?;
+    let result = check(args, &analyzer, &report_dir).and_then(|()| collect(&report_dir));
+    let _ = fs::remove_dir_all(&report_dir);
+    let combined = combine(result?);
+    let denied = args.deny.as_ref().is_some_and(|level| {
+        combined
+            .reports
+            .iter()
+            .any(|report| report.has_findings_at(level))
+    });
+    let output = match args.format {
+        Format::Text => combined.to_string(),
+        Format::Json => {
+            serde_json::to_string_pretty(&combined).expect("reports serialize to JSON") + "\n"
+        }
+    };
+    Ok((output, denied))
+}
+
+/// A fresh directory for the reports of this run.
+fn report_dir() -> Result<PathBuf, AnalyzeError> {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    let dir = std::env::temp_dir().join(format!("solana-analyze-{}-{nonce}", std::process::id()));
+    fs::create_dir_all(&dir).map_err(|err| AnalyzeError::ReportDir(dir.clone(), err))?;
+    Ok(dir)
+}
+
+/// Run `cargo check` with the analyzer as the workspace wrapper. The packages
+/// to analyze are cleaned first, as cargo would otherwise reuse their cached
+/// metadata and never run the analyzer on them. What cargo and the analyzer
+/// print goes to stderr, leaving stdout to the combined report.
+fn check(args: &Args, analyzer: &Path, report_dir: &Path) -> Result<(), AnalyzeError> {
+    let packages = if args.packages.is_empty() {
+        selected_programs(args.manifest_path.as_deref())?
+    } else {
+        args.packages.clone()
+    };
+    let cargo = |subcommand: &str| {
+        let mut cargo = Command::new("cargo");
+        if let Some(toolchain) = TOOLCHAIN {
+            cargo.arg(format!("+{toolchain}"));
+        }
+        cargo.arg(subcommand);
+        if let Some(manifest_path) = &args.manifest_path {
+            cargo.arg("--manifest-path").arg(manifest_path);
+        }
+        // A caching wrapper would replay the output instead of running us.
+        cargo.env_remove("RUSTC_WRAPPER").env_remove("RUSTC");
+        cargo
+    };
+
+    if !packages.is_empty() {
+        let mut clean = cargo("clean");
+        for package in &packages {
+            clean.args(["-p", package]);
+        }
+        if args.cargo_args.iter().any(|arg| arg == "--release") {
+            clean.arg("--release");
+        }
+        clean
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(AnalyzeError::Cargo)?;
+    }
+
+    let mut check = cargo("check");
+    for package in &args.packages {
+        check.args(["-p", package]);
+    }
+    check
+        .args(&args.cargo_args)
+        .env("RUSTC_WORKSPACE_WRAPPER", analyzer)
+        .env(REPORT_DIR_ENV, report_dir)
+        .stdout(io::stderr());
+    if check.status().map_err(AnalyzeError::Cargo)?.success() {
+        Ok(())
+    } else {
+        Err(AnalyzeError::CheckFailed)
+    }
+}
+
+/// The program packages `cargo check` picks without `-p`: the package of the
+/// manifest, or the programs of a virtual workspace.
+fn selected_programs(manifest_path: Option<&Path>) -> Result<Vec<String>, AnalyzeError> {
+    let dir = match manifest_path {
+        Some(path) if path.file_name().is_some_and(|name| name == "Cargo.toml") => {
+            path.parent().unwrap_or(Path::new(".")).to_path_buf()
+        }
+        Some(path) => path.to_path_buf(),
+        None => PathBuf::from("."),
+    };
+    if let Ok((name, _)) = parse_toml_in_crate_path(&dir.to_string_lossy()) {
+        return Ok(vec![name]);
+    }
+    let root = find_workspace_root(&dir);
+    let programs =
+        discover_workspace_programs(&root).map_err(|err| AnalyzeError::Metadata(root, err))?;
+    Ok(programs.into_iter().map(|program| program.name).collect())
+}
+
+/// The reports the analyzed crates wrote into `report_dir`, by crate name.
+fn collect(report_dir: &Path) -> Result<Vec<AnalysisReport>, AnalyzeError> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(report_dir)
+        .map_err(|err| AnalyzeError::ReportDir(report_dir.to_path_buf(), err))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    Ok(paths
+        .iter()
+        .map(|path| AnalysisReport::read(path))
+        .collect::<Result<_, _>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse(&[
+            "solana-analyze",
+            "-p",
+            "vault",
+            "--format",
+            "json",
+            "--features=devnet",
+            "--deny",
+            "error",
+            "--release",
+            "--all-targets",
+        ]);
+        assert_eq!(args.packages, ["vault"]);
+        assert_eq!(args.format, Format::Json);
+        assert_eq!(args.deny.as_deref(), Some("error"));
+        assert_eq!(
+            args.cargo_args,
+            ["--features", "devnet", "--release", "--all-targets"]
+        );
+        assert!(matches!(
+            Args::parse(["--format".to_string(), "xml".to_string()]),
+            Err(AnalyzeError::Format(_))
+        ));
+        assert!(matches!(
+            Args::parse(["--deny".to_string(), "high".to_string()]),
+            Err(AnalyzeError::Severity(_))
+        ));
+        assert!(matches!(
+            Args::parse(["--layouts".to_string()]),
+            Err(AnalyzeError::UnexpectedArgument(_))
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::anchor_info::AnchorAccountKind;
+use crate::anchor_info::access::short_name;
+use crate::anchor_info::constraint::{AnchorConstraint, Value};
+use crate::report::Finding;
+
+const CHECKER: &str = "conflicting_account_roles";
+
+/// Report accounts an Accounts struct requires to be both a signer and a
+/// mutable program data account: an `Account<T>` marked `signer`, or one
+/// pinned with `address = <signer>.key()` to a `Signer` field. A data account
+/// is created from a keypair at most once, with `init`, and a signer is
+/// normally a wallet the program does not own, so such an instruction is
+/// rarely what was meant and often cannot be called at all.
+///
+/// A mutable `Signer` alone is not a conflict: a payer is both.
+pub struct ConflictingAccountRoles;
+
+impl Checker for ConflictingAccountRoles {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Accounts required to be both a signer and a mutable program data account"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let metas = ctx.to_account_metas();
+        let mut findings = vec![];
+        for anchor_accounts in ctx.anchor_accounts() {
+            let name = &anchor_accounts.name;
+            let fields = &anchor_accounts.anchor_accounts;
+            let meta = |idx: usize| {
+                metas
+                    .iter()
+                    .find(|meta| !meta.is_cpi && meta.struct_name == *name && meta.field_idx == idx)
+            };
+            let signs = |idx: usize| {
+                fields[idx].kind == AnchorAccountKind::Signer
+                    || meta(idx).is_some_and(|meta| meta.signer)
+            };
+            // The type of the data account at `idx`, if it is writable.
+            let mutable_data = |idx: usize| {
+                fields[idx]
+                    .kind
+                    .data_type()
+                    .filter(|_| meta(idx).is_some_and(|meta| meta.writable))
+            };
+
+            for (idx, account) in fields.iter().enumerate() {
+                // A keypair account signs the transaction creating it.
+                let created = account
+                    .constraints
+                    .iter()
+                    .any(|constraint| matches!(constraint, AnchorConstraint::Init { .. }));
+                if let Some(ty) = mutable_data(idx)
+                    && signs(idx)
+                    && !created
+                {
+                    let message = format!(
+                        "account `{}` in `{name}` is a mutable `{}` account that must also sign; accounts holding program data are not signers once created",
+                        account.name,
+                        short_name(ty)
+                    );
+                    findings.push(
+                        Finding::warning(CHECKER, message)
+                            .at(&anchor_accounts.adt_def)
+                            .key(&account.name),
+                    );
+                }
+
+                for constraint in &account.constraints {
+                    let AnchorConstraint::Address(Value::Key(other)) = constraint else {
+                        continue;
+                    };
+                    let Some(other_idx) = fields.iter().position(|field| field.name == *other)
+                    else {
+                        continue;
+                    };
+                    let (data, signer) = if signs(other_idx) {
+                        (idx, other_idx)
+                    } else if signs(idx) {
+                        (other_idx, idx)
+                    } else {
+                        continue;
+                    };
+                    let Some(ty) = mutable_data(data) else {
+                        continue;
+                    };
+                    let message = format!(
+                        "account `{}` in `{name}` must be the same account as `{}` (`address = {other}.key()`), making it both a signer and a mutable `{}` account",
+                        account.name,
+                        other,
+                        short_name(ty)
+                    );
+                    findings.push(
+                        Finding::warning(CHECKER, message)
+                            .at(&anchor_accounts.adt_def)
+                            .key(&fields[data].name)
+                            .key(&fields[signer].name),
+                    );
+                }
+            }
+        }
+        findings
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rustc_public::mir::{Body, TerminatorKind};
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::anchor_info::access::{Role, handler_field_accesses, local_roles, short_name};
+use crate::anchor_info::constraint::AnchorConstraint;
+use crate::anchor_info::remaining::callee_name;
+use crate::anchor_info::{AnchorAccountKind, AnchorAccounts};
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "authority_substitution";
+
+/// Report handlers that compare a signer against a value read from one
+/// `Account<T>`, e.g. `require_keys_eq!(source.authority, owner.key())`, and
+/// then write a sibling `Account<T>` of the same `T` whose authority nothing
+/// checks. A client passes its own vault to pass the check and someone else's
+/// as the one written.
+///
+/// An account counts as checked by a comparison against a signer in the
+/// handler itself, or by a `has_one` or `constraint` Anchor checks before it.
+pub struct AuthoritySubstitution;
+
+impl Checker for AuthoritySubstitution {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Writes to an account other than the one whose authority was checked"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for (name, (_, handlers)) in ctx.handlers_by_accounts() {
+            let Some(anchor_accounts) = ctx
+                .anchor_accounts()
+                .iter()
+                .find(|anchor_accounts| &anchor_accounts.name == name)
+            else {
+                continue;
+            };
+            for &handler in handlers {
+                let Some(body) = ctx.body(handler) else {
+                    continue;
+                };
+                let checked = signer_checks(&body, anchor_accounts);
+                if checked.is_empty() {
+                    continue;
+                }
+                let writes = handler_field_accesses(handler).writes;
+                for (target_idx, target) in anchor_accounts.anchor_accounts.iter().enumerate() {
+                    let AnchorAccountKind::Account(data_type) = &target.kind else {
+                        continue;
+                    };
+                    if !writes.contains(&target_idx)
+                        || checked.contains_key(&target_idx)
+                        || target.constraints.iter().any(|constraint| {
+                            matches!(
+                                constraint,
+                                AnchorConstraint::HasOne(_) | AnchorConstraint::Raw(_)
+                            )
+                        })
+                    {
+                        continue;
+                    }
+                    for (&source_idx, signers) in &checked {
+                        let source = &anchor_accounts.anchor_accounts[source_idx];
+                        if source.kind != target.kind {
+                            continue;
+                        }
+                        let signers: Vec<String> = signers
+                            .iter()
+                            .map(|&idx| format!("`{}`", anchor_accounts.anchor_accounts[idx].name))
+                            .collect();
+                        let message = format!(
+                            "`{}` checks {} against `{}.{}` but writes `{}`, another `{}` whose authority it never checks",
+                            handler.name(),
+                            signers.join(", "),
+                            name,
+                            source.name,
+                            target.name,
+                            short_name(data_type)
+                        );
+                        findings.push(
+                            Finding::error(CHECKER, message)
+                                .in_fn(handler)
+                                .key(&source.name)
+                                .key(&target.name),
+                        );
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// The `Account<T>` fields of `anchor_accounts` that `body`, a handler, compares
+/// with `==` or `!=` against a value computed from a `Signer` field, with the
+/// signers compared against.
+fn signer_checks(
+    body: &Body,
+    anchor_accounts: &AnchorAccounts,
+) -> BTreeMap<usize, BTreeSet<usize>> {
+    let kind = |field_idx: usize| &anchor_accounts.anchor_accounts[field_idx].kind;
+    let sources: Taint<usize> = local_roles(body, 1, Role::Ctx)
+        .into_iter()
+        .filter_map(|(local, role)| match role {
+            Role::Field(field_idx) if field_idx < anchor_accounts.anchor_accounts.len() => {
+                Some((local, BTreeSet::from([field_idx])))
+            }
+            _ => None,
+        })
+        .collect();
+    if sources.is_empty() {
+        return BTreeMap::new();
+    }
+    let taint = propagate(body, sources);
+
+    let mut checked: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    for block in &body.blocks {
+        let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+            continue;
+        };
+        if !callee_name(body, func).is_some_and(|name| {
+            name.contains("PartialEq") && (name.ends_with("::eq") || name.ends_with("::ne"))
+        }) {
+            continue;
+        }
+        let [lhs, rhs] = &args[..] else {
+            continue;
+        };
+        let lhs = labels_of(&taint, operand_local(lhs));
+        let rhs = labels_of(&taint, operand_local(rhs));
+        for (accounts, signers) in [(&lhs, &rhs), (&rhs, &lhs)] {
+            let signers: BTreeSet<usize> = signers
+                .iter()
+                .copied()
+                .filter(|&idx| *kind(idx) == AnchorAccountKind::Signer)
+                .collect();
+            if signers.is_empty() {
+                continue;
+            }
+            for &idx in accounts {
+                if matches!(kind(idx), AnchorAccountKind::Account(_)) {
+                    checked.entry(idx).or_default().extend(&signers);
+                }
+            }
+        }
+    }
+    checked
+}
@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rustc_public::ty::AdtDef;
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::anchor_info::constraint::{AnchorConstraint, Value};
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "missing_bump_persistence";
+/// Instructions deriving the same PDA before the lookup is worth storing.
+const MIN_INSTRUCTIONS: usize = 2;
+
+/// Suggest storing the bump of PDAs that several instructions derive with a
+/// plain `bump`, e.g. `seeds = [b"vault", owner.key().as_ref()], bump` in
+/// both `deposit` and `withdraw`, while none passes a stored one with
+/// `bump = vault.bump`. Anchor then calls `find_program_address`, which tries
+/// bumps from 255 down, on every call; storing the canonical bump once and
+/// checking it with `create_program_address` costs a single hash.
+///
+/// PDAs are told apart by their seeds, with `const`s read as their bytes;
+/// seeds rustc promoted to a constant as a whole are not visible and left out.
+pub struct MissingBumpPersistence;
+
+/// The derivations of one seed list across the Accounts structs.
+#[derive(Default)]
+struct Derivations {
+    /// `Struct.field` of each field derived with a plain `bump`.
+    canonical: BTreeSet<String>,
+    /// The instructions whose Accounts struct derives it with a plain `bump`.
+    instructions: BTreeSet<String>,
+    /// Whether a field passes a stored bump with `bump = ..`.
+    stored: bool,
+    structs: Vec<AdtDef>,
+}
+
+impl Checker for MissingBumpPersistence {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "PDAs re-derived with find_program_address by several instructions instead of a stored bump"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut instructions: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for handler in ctx.handlers() {
+            instructions
+                .entry(&handler.context_struct)
+                .or_default()
+                .push(&handler.name);
+        }
+
+        let mut pdas: Vec<(Vec<Value>, Derivations)> = vec![];
+        for anchor_accounts in ctx.anchor_accounts() {
+            for account in &anchor_accounts.anchor_accounts {
+                let seeds = account
+                    .constraints
+                    .iter()
+                    .find_map(|constraint| match constraint {
+                        AnchorConstraint::Seeds(seeds) => Some(seeds),
+                        _ => None,
+                    });
+                let Some(seeds) =
+                    seeds.filter(|seeds| !seeds.is_empty() && !seeds.contains(&Value::Unknown))
+                else {
+                    continue;
+                };
+                let seeds: Vec<Value> = seeds.iter().map(normalized).collect();
+                let idx = match pdas.iter().position(|(known, _)| *known == seeds) {
+                    Some(idx) => idx,
+                    None => {
+                        pdas.push((seeds, Derivations::default()));
+                        pdas.len() - 1
+                    }
+                };
+                let derivations = &mut pdas[idx].1;
+                for constraint in &account.constraints {
+                    match constraint {
+                        AnchorConstraint::CanonicalBump => {
+                            derivations
+                                .canonical
+                                .insert(format!("{}.{}", anchor_accounts.name, account.name));
+                            derivations.instructions.extend(
+                                instructions
+                                    .get(anchor_accounts.name.as_str())
+                                    .into_iter()
+                                    .flatten()
+                                    .map(|name| name.to_string()),
+                            );
+                        }
+                        AnchorConstraint::Bump(_) => derivations.stored = true,
+                        _ => continue,
+                    }
+                    if !derivations.structs.contains(&anchor_accounts.adt_def) {
+                        derivations.structs.push(anchor_accounts.adt_def);
+                    }
+                }
+            }
+        }
+
+        let mut findings = vec![];
+        for (seeds, derivations) in pdas {
+            if derivations.stored || derivations.instructions.len() < MIN_INSTRUCTIONS {
+                continue;
+            }
+            let seeds: Vec<String> = seeds.iter().map(Value::to_string).collect();
+            let list = |names: &BTreeSet<String>| {
+                names
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let message = format!(
+                "PDA with seeds [{}] is derived with `find_program_address` by instructions {} ({}); store its bump in the account and check it with `bump = <account>.bump`",
+                seeds.join(", "),
+                list(&derivations.instructions),
+                list(&derivations.canonical)
+            );
+            let mut finding = Finding::info(CHECKER, message);
+            for adt_def in &derivations.structs {
+                finding = finding.at(adt_def);
+            }
+            findings.push(finding.key(&seeds.join(", ")));
+        }
+        findings
+    }
+}
+
+/// `seed` with a `const` whose bytes are known as those bytes, as rustc
+/// evaluates some `const`s in place and leaves others.
+fn normalized(seed: &Value) -> Value {
+    match seed {
+        Value::Const(_, Some(bytes)) => Value::Bytes(bytes.clone()),
+        seed => seed.clone(),
+    }
+}
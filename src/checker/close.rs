@@ -0,0 +1,82 @@
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::anchor_info::constraint::{
+    AnchorConstraint, constraint_error_codes, declared_mut_fields,
+};
+use crate::anchor_info::{ANCHOR_ACCOUNTS, AnchorAccountKind, accounts_impl_fn};
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "bad_close_destination";
+
+/// Report `close = destination` constraints whose destination cannot take the
+/// lamports of the closed account.
+///
+/// `close` moves the lamports to the destination, then zeroes the account, so
+/// closing an account into itself burns its lamports. A destination that is not
+/// declared `mut`, or a program or sysvar, which the runtime never lets an
+/// instruction write, makes every close fail.
+pub struct CloseDestination;
+
+impl Checker for CloseDestination {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "close = destination constraints whose destination cannot take the lamports"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for anchor_accounts in ctx.anchor_accounts() {
+            let name = &anchor_accounts.name;
+            // Without `try_accounts` we know nothing of the declared mutability.
+            if accounts_impl_fn(name, ANCHOR_ACCOUNTS, "try_accounts").is_none() {
+                continue;
+            }
+            let declared_mut = declared_mut_fields(anchor_accounts, &constraint_error_codes(name));
+            let fields = &anchor_accounts.anchor_accounts;
+            for account in fields {
+                for constraint in &account.constraints {
+                    let AnchorConstraint::Close { destination } = constraint else {
+                        continue;
+                    };
+                    let finding = if destination == &account.name {
+                        let message = format!(
+                            "account `{}` in `{name}` is closed into itself, which burns its lamports",
+                            account.name
+                        );
+                        Finding::error(CHECKER, message)
+                    } else {
+                        let Some((field_idx, target)) = fields
+                            .iter()
+                            .enumerate()
+                            .find(|(_, field)| &field.name == destination)
+                        else {
+                            continue;
+                        };
+                        let reason = match target.kind {
+                            AnchorAccountKind::Program | AnchorAccountKind::Interface(_) => {
+                                "is a program"
+                            }
+                            AnchorAccountKind::Sysvar(_) => "is a sysvar",
+                            _ if !declared_mut.contains(&field_idx) => "is not declared mut",
+                            _ => continue,
+                        };
+                        let message = format!(
+                            "account `{}` in `{name}` is closed into `{destination}`, which {reason} and cannot receive its lamports",
+                            account.name
+                        );
+                        Finding::error(CHECKER, message)
+                    };
+                    findings.push(finding.at(&anchor_accounts.adt_def).key(&account.name));
+                }
+            }
+        }
+        findings
+    }
+}
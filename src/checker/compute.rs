@@ -0,0 +1,181 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+
+use super::{AnalysisContext, Checker};
+use crate::analysis::callgraph::callee;
+use crate::analysis::loops::{innermost_loop, natural_loops};
+use crate::report::Finding;
+
+const CHECKER: &str = "compute_heavy_loop";
+
+/// A syscall-backed fn and the fixed part of its compute cost under the default
+/// compute budget. Hashes also charge per input byte, which is not modeled.
+struct SyscallCost {
+    /// Last path segment of the module the fn is reached through. Both the
+    /// `solana_program` re-export and the defining crate are listed.
+    modules: &'static [&'static str],
+    name: &'static str,
+    cost: u64,
+}
+
+const SHA256: &[&str] = &["hash", "solana_sha256_hasher"];
+const KECCAK: &[&str] = &["keccak", "solana_keccak_hasher"];
+const BLAKE3: &[&str] = &["blake3", "solana_blake3_hasher"];
+const SECP256K1: &[&str] = &["secp256k1_recover", "solana_secp256k1_recover"];
+const EDWARDS: &[&str] = &["edwards"];
+const RISTRETTO: &[&str] = &["ristretto"];
+
+#[rustfmt::skip]
+const SYSCALL_COSTS: &[SyscallCost] = &[
+    SyscallCost { modules: SHA256, name: "hash", cost: 85 },
+    SyscallCost { modules: SHA256, name: "hashv", cost: 85 },
+    SyscallCost { modules: KECCAK, name: "hash", cost: 85 },
+    SyscallCost { modules: KECCAK, name: "hashv", cost: 85 },
+    SyscallCost { modules: BLAKE3, name: "hash", cost: 85 },
+    SyscallCost { modules: BLAKE3, name: "hashv", cost: 85 },
+    SyscallCost { modules: SECP256K1, name: "secp256k1_recover", cost: 25_000 },
+    SyscallCost { modules: EDWARDS, name: "validate_edwards", cost: 159 },
+    SyscallCost { modules: EDWARDS, name: "add_edwards", cost: 473 },
+    SyscallCost { modules: EDWARDS, name: "subtract_edwards", cost: 475 },
+    SyscallCost { modules: EDWARDS, name: "multiply_edwards", cost: 2_177 },
+    SyscallCost { modules: EDWARDS, name: "multiscalar_multiply_edwards", cost: 2_273 },
+    SyscallCost { modules: RISTRETTO, name: "validate_ristretto", cost: 169 },
+    SyscallCost { modules: RISTRETTO, name: "add_ristretto", cost: 521 },
+    SyscallCost { modules: RISTRETTO, name: "subtract_ristretto", cost: 519 },
+    SyscallCost { modules: RISTRETTO, name: "multiply_ristretto", cost: 2_208 },
+    SyscallCost { modules: RISTRETTO, name: "multiscalar_multiply_ristretto", cost: 2_303 },
+];
+
+/// `module::fn` and its cost if `instance` is one of `SYSCALL_COSTS`.
+fn syscall_cost(instance: &Instance) -> Option<(String, u64)> {
+    let name = instance.name();
+    let mut segments = name.rsplit("::");
+    let fn_name = segments.next()?;
+    let module = segments.next()?;
+    SYSCALL_COSTS
+        .iter()
+        .find(|entry| entry.name == fn_name && entry.modules.contains(&module))
+        .map(|entry| (format!("{module}::{fn_name}"), entry.cost))
+}
+
+/// Syscalls a local fn performs, directly or through other local fns, each
+/// counted once.
+#[derive(Clone, Default)]
+struct Summary {
+    syscalls: BTreeSet<String>,
+    cost: u64,
+}
+
+struct Summaries {
+    summaries: HashMap<Instance, Summary>,
+}
+
+impl Summaries {
+    fn get(&mut self, instance: Instance) -> Summary {
+        if let Some(summary) = self.summaries.get(&instance) {
+            return summary.clone();
+        }
+        // Recursive calls see an empty summary.
+        self.summaries.insert(instance, Summary::default());
+        let mut summary = Summary::default();
+        if let Some(body) = instance.body() {
+            for block in 0..body.blocks.len() {
+                let Some(callee) = callee(&body, block) else {
+                    continue;
+                };
+                if let Some((syscall, cost)) = syscall_cost(&callee) {
+                    summary.syscalls.insert(syscall);
+                    summary.cost += cost;
+                } else if callee.def.krate().is_local {
+                    let inner = self.get(callee);
+                    summary.syscalls.extend(inner.syscalls);
+                    summary.cost += inner.cost;
+                }
+            }
+        }
+        self.summaries.insert(instance, summary.clone());
+        summary
+    }
+}
+
+/// Report hashing and curve syscalls inside natural loops of fns reachable from
+/// the entrypoint, either called directly in the loop or through a local helper,
+/// with the summed fixed cost of the syscalls in one iteration. Loops costing
+/// less than `[compute] min_loop_cu` of the config are left out.
+pub struct ComputeHeavyLoop;
+
+impl Checker for ComputeHeavyLoop {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Hashing and curve syscalls inside loops, with their cost per iteration"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        let mut summaries = Summaries {
+            summaries: HashMap::new(),
+        };
+        for &instance in ctx.reachable_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            let loops = natural_loops(&body, ctx.fixpoints());
+            if loops.is_empty() {
+                continue;
+            }
+            // Loop head -> (call block, description, cost) of heavy calls in its body.
+            let mut heavy_calls: BTreeMap<usize, Vec<(usize, String, u64)>> = BTreeMap::new();
+            for block in 0..body.blocks.len() {
+                let Some(natural_loop) = innermost_loop(&loops, block) else {
+                    continue;
+                };
+                let Some(callee) = callee(&body, block) else {
+                    continue;
+                };
+                let call = if let Some((syscall, cost)) = syscall_cost(&callee) {
+                    (block, format!("`{syscall}`"), cost)
+                } else if callee.def.krate().is_local {
+                    let summary = summaries.get(callee);
+                    if summary.syscalls.is_empty() {
+                        continue;
+                    }
+                    let syscalls: Vec<_> =
+                        summary.syscalls.iter().map(|s| format!("`{s}`")).collect();
+                    let via = format!("{} via `{}`", syscalls.join(", "), callee.name());
+                    (block, via, summary.cost)
+                } else {
+                    continue;
+                };
+                heavy_calls.entry(natural_loop.head).or_default().push(call);
+            }
+
+            for (head, calls) in heavy_calls {
+                let cost: u64 = calls.iter().map(|(_, _, cost)| cost).sum();
+                if cost < ctx.config.compute.min_loop_cu {
+                    continue;
+                }
+                let calls: Vec<_> = calls
+                    .iter()
+                    .map(|(block, call, _)| format!("{call} (bb{block})"))
+                    .collect();
+                let message = format!(
+                    "loop at bb{head} in `{}` calls {} each iteration, ~{cost} CU per iteration",
+                    instance.name(),
+                    calls.join(", ")
+                );
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .in_fn(instance)
+                        .key(&format!("bb{head}")),
+                );
+            }
+        }
+
+        findings
+    }
+}
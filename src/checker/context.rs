@@ -0,0 +1,464 @@
+//! State shared by the checkers of a run. Each part is extracted the first time
+//! a checker asks for it, and kept for the others.
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::Body;
+use rustc_public::mir::mono::Instance;
+use rustc_public::ty::{AdtDef, FnDef};
+use serde_json::Value;
+use tracing::info_span;
+
+use crate::analysis::callgraph::{call_graph, local_instances, reachable_local_instances};
+use crate::analysis::dataflow::interproc::{ArgumentFlows, FlowSummary, summarize};
+use crate::analysis::entry::{EntryKind, EntryPoint, find_entrypoints};
+use crate::analysis::graph::{DirectedGraph, Fixpoints};
+use crate::anchor_info::cpi::CpiCall;
+use crate::anchor_info::program_id::{ProgramId, ProgramIdError};
+use crate::anchor_info::{
+    AccountMetaInfo, AnchorAccounts, HandlerInfo, extract_cpi_calls, extract_discriminators,
+    extract_instruction_discriminators, extract_program_id, find_cpi_account_metas,
+    find_to_account_metas, handlers_by_accounts, local_anchor_accounts, program_handlers,
+};
+use crate::config::Config;
+use crate::metadata::{Concept, known_types};
+use crate::native_info::{
+    NativeAccountsModel, NativeInstruction, PackLayout, native_accounts, native_instructions,
+    pack_layouts,
+};
+use crate::report::timings::{PassKind, PassTiming};
+
+/// Print the `ContextStats` of the run to stderr when set.
+pub const STATS_ENV: &str = "SOLANA_ANALYZER_STATS";
+
+pub struct AnalysisContext<'a> {
+    pub config: &'a Config,
+    call_graph: OnceCell<DirectedGraph<Instance>>,
+    flow_summaries: OnceCell<HashMap<Instance, FlowSummary>>,
+    instances: OnceCell<Vec<Instance>>,
+    local_instances: OnceCell<Vec<Instance>>,
+    entrypoints: OnceCell<Vec<EntryPoint>>,
+    reachable_instances: OnceCell<Vec<Instance>>,
+    anchor_accounts: OnceCell<Vec<AnchorAccounts>>,
+    to_account_metas: OnceCell<Vec<AccountMetaInfo>>,
+    cpi_account_metas: OnceCell<Vec<AccountMetaInfo>>,
+    handlers: OnceCell<Vec<HandlerInfo>>,
+    native_instructions: OnceCell<Vec<NativeInstruction>>,
+    native_accounts: OnceCell<Vec<NativeAccountsModel>>,
+    pack_layouts: OnceCell<Vec<PackLayout>>,
+    handlers_by_accounts: OnceCell<BTreeMap<String, (AdtDef, Vec<Instance>)>>,
+    cpi_calls: OnceCell<Vec<CpiCall>>,
+    program_id: OnceCell<Result<ProgramId, ProgramIdError>>,
+    discriminators: OnceCell<Vec<(String, Vec<u8>)>>,
+    instruction_discriminators: OnceCell<Vec<(String, Vec<u8>)>>,
+    bodies: RefCell<HashMap<Instance, Rc<Body>>>,
+    fixpoints: Fixpoints,
+    idl_comparison: Option<(Value, Value)>,
+    stats: ContextStats,
+}
+
+/// How often each part of an `AnalysisContext` was asked for, and what it
+/// took to extract it the one time it was.
+#[derive(Debug, Default)]
+pub struct ContextStats {
+    /// Part -> (requests, time spent extracting it, items extracted).
+    parts: RefCell<BTreeMap<&'static str, (usize, Duration, usize)>>,
+    bodies_built: Cell<usize>,
+    bodies_reused: Cell<usize>,
+}
+
+impl ContextStats {
+    fn request(&self, part: &'static str) {
+        self.parts.borrow_mut().entry(part).or_default().0 += 1;
+    }
+
+    fn extracted(&self, part: &'static str, took: Duration, items: usize) {
+        let mut parts = self.parts.borrow_mut();
+        let entry = parts.entry(part).or_default();
+        entry.1 = took;
+        entry.2 = items;
+    }
+
+    /// The extraction of each part, by name. The time of a part includes that
+    /// of the parts it asked for first.
+    pub fn timings(&self) -> Vec<PassTiming> {
+        self.parts
+            .borrow()
+            .iter()
+            .map(|(part, (_, took, items))| {
+                PassTiming::new(*part, PassKind::Extraction, *took, *items)
+            })
+            .collect()
+    }
+}
+
+/// One line per part extracted, then the bodies built and reused.
+impl fmt::Display for ContextStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (part, (requests, took, _)) in self.parts.borrow().iter() {
+            writeln!(
+                f,
+                "context {part}: extracted once in {took:?}, requested {requests} times"
+            )?;
+        }
+        writeln!(
+            f,
+            "context bodies: {} built, {} reused",
+            self.bodies_built.get(),
+            self.bodies_reused.get()
+        )
+    }
+}
+
+/// An instruction handler, as the reports group findings by.
+#[derive(Clone, Debug)]
+pub struct InstructionHandler {
+    pub name: String,
+    pub instance: Instance,
+    /// Path of the Accounts struct of an Anchor handler.
+    pub accounts: Option<String>,
+}
+
+impl<'a> AnalysisContext<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self {
+            config,
+            call_graph: OnceCell::new(),
+            flow_summaries: OnceCell::new(),
+            instances: OnceCell::new(),
+            local_instances: OnceCell::new(),
+            entrypoints: OnceCell::new(),
+            reachable_instances: OnceCell::new(),
+            anchor_accounts: OnceCell::new(),
+            to_account_metas: OnceCell::new(),
+            cpi_account_metas: OnceCell::new(),
+            handlers: OnceCell::new(),
+            native_instructions: OnceCell::new(),
+            native_accounts: OnceCell::new(),
+            pack_layouts: OnceCell::new(),
+            handlers_by_accounts: OnceCell::new(),
+            cpi_calls: OnceCell::new(),
+            program_id: OnceCell::new(),
+            discriminators: OnceCell::new(),
+            instruction_discriminators: OnceCell::new(),
+            bodies: RefCell::new(HashMap::new()),
+            fixpoints: Fixpoints::new(config.analysis.max_fixpoint_iterations),
+            idl_comparison: None,
+            stats: ContextStats::default(),
+        }
+    }
+
+    /// What the parts of the context cost and how much they were reused.
+    pub fn stats(&self) -> &ContextStats {
+        &self.stats
+    }
+
+    /// The dominator fixpoints of the run, stopped after `max_fixpoint_iterations`
+    /// sweeps of the `[analysis]` config.
+    pub fn fixpoints(&self) -> &Fixpoints {
+        &self.fixpoints
+    }
+
+    /// Compare `published`, the IDL given with `--compare-idl`, with `program`,
+    /// the IDL built from the source, in this context's runs.
+    pub fn with_idl_comparison(mut self, published: Value, program: Value) -> Self {
+        self.idl_comparison = Some((published, program));
+        self
+    }
+
+    /// The published IDL and the one built from the source, when the run
+    /// compares them.
+    pub fn idl_comparison(&self) -> Option<(&Value, &Value)> {
+        self.idl_comparison
+            .as_ref()
+            .map(|(published, program)| (published, program))
+    }
+
+    /// The content of `cell`, extracted with `init` on the first request.
+    fn cached<'s, T: Extracted>(
+        &'s self,
+        part: &'static str,
+        cell: &'s OnceCell<T>,
+        init: impl FnOnce() -> T,
+    ) -> &'s T {
+        self.stats.request(part);
+        cell.get_or_init(|| {
+            let _span = info_span!("extract", part).entered();
+            let start = Instant::now();
+            let value = init();
+            self.stats.extracted(part, start.elapsed(), value.items());
+            value
+        })
+    }
+
+    /// The calls between the local fns and every instance they call,
+    /// transitively. The bodies walked are kept for the checkers.
+    pub fn call_graph(&self) -> &DirectedGraph<Instance> {
+        self.cached("call_graph", &self.call_graph, || {
+            call_graph(|instance| self.body(instance))
+        })
+    }
+
+    /// Where the arguments of each local fn flow, into its return value and
+    /// into CPIs, through the local fns it calls.
+    pub fn flow_summaries(&self) -> &HashMap<Instance, FlowSummary> {
+        self.cached("flow_summaries", &self.flow_summaries, || {
+            let analysis = ArgumentFlows {
+                // Calls into other crates pass the taint of every argument on.
+                body: |instance: Instance| {
+                    if instance.def.krate().is_local {
+                        self.body(instance)
+                    } else {
+                        None
+                    }
+                },
+                is_sink: |fn_def: FnDef| {
+                    !fn_def.krate().is_local && known_types().is(Concept::Invoke, &fn_def.name())
+                },
+            };
+            summarize(self.call_graph(), &analysis)
+        })
+    }
+
+    /// The local fns and every instance they call, transitively, by name.
+    pub fn instances(&self) -> &[Instance] {
+        self.cached("instances", &self.instances, || {
+            let mut instances: Vec<_> = self.call_graph().nodes().copied().collect();
+            instances.sort_by_cached_key(|instance| instance.name());
+            instances
+        })
+        .as_slice()
+    }
+
+    /// The local fns that need no monomorphization.
+    pub fn local_instances(&self) -> &[Instance] {
+        self.cached("local_instances", &self.local_instances, local_instances)
+            .as_slice()
+    }
+
+    /// The entrypoints of the program, Anchor's or native ones.
+    pub fn entrypoints(&self) -> &[EntryPoint] {
+        self.cached("entrypoints", &self.entrypoints, find_entrypoints)
+            .as_slice()
+    }
+
+    /// The local instances the program entrypoints reach, each entrypoint
+    /// before the fns it calls; empty without an entrypoint.
+    pub fn reachable_instances(&self) -> &[Instance] {
+        self.cached("reachable_instances", &self.reachable_instances, || {
+            let mut reachable = vec![];
+            for entry in self.entrypoints() {
+                for instance in reachable_local_instances(entry.instance) {
+                    if !reachable.contains(&instance) {
+                        reachable.push(instance);
+                    }
+                }
+            }
+            reachable
+        })
+        .as_slice()
+    }
+
+    pub fn anchor_accounts(&self) -> &[AnchorAccounts] {
+        self.cached(
+            "anchor_accounts",
+            &self.anchor_accounts,
+            local_anchor_accounts,
+        )
+        .as_slice()
+    }
+
+    /// The `AccountMeta`s the off-chain clients of the Accounts structs build.
+    pub fn to_account_metas(&self) -> &[AccountMetaInfo] {
+        self.cached(
+            "to_account_metas",
+            &self.to_account_metas,
+            find_to_account_metas,
+        )
+        .as_slice()
+    }
+
+    /// The `AccountMeta`s the CPI clients of the Accounts structs build.
+    pub fn cpi_account_metas(&self) -> &[AccountMetaInfo] {
+        self.cached(
+            "cpi_account_metas",
+            &self.cpi_account_metas,
+            find_cpi_account_metas,
+        )
+        .as_slice()
+    }
+
+    /// The instruction handlers, in declaration order.
+    pub fn handlers(&self) -> &[HandlerInfo] {
+        self.cached("handlers", &self.handlers, program_handlers)
+            .as_slice()
+    }
+
+    /// The instructions of a native program, by tag; empty for Anchor programs.
+    pub fn native_instructions(&self) -> &[NativeInstruction] {
+        self.cached("native_instructions", &self.native_instructions, || {
+            if self
+                .entrypoints()
+                .iter()
+                .any(|entry| entry.kind == EntryKind::Native)
+            {
+                native_instructions(self.reachable_instances(), &self.fixpoints)
+            } else {
+                vec![]
+            }
+        })
+        .as_slice()
+    }
+
+    /// The instruction handlers by name: those of the `#[program]` module, or
+    /// the fns the dispatch of a native program calls.
+    pub fn instruction_handlers(&self) -> Vec<InstructionHandler> {
+        if !self.handlers().is_empty() {
+            return self
+                .handlers()
+                .iter()
+                .map(|handler| InstructionHandler {
+                    name: handler.name.clone(),
+                    instance: handler.instance,
+                    accounts: Some(handler.accounts_def.name()),
+                })
+                .collect();
+        }
+        let mut handlers: Vec<InstructionHandler> = vec![];
+        for instruction in self.native_instructions() {
+            if !handlers
+                .iter()
+                .any(|handler| handler.instance == instruction.handler)
+            {
+                handlers.push(InstructionHandler {
+                    name: instruction.handler.name(),
+                    instance: instruction.handler,
+                    accounts: None,
+                });
+            }
+        }
+        handlers
+    }
+
+    /// The accounts of each native handler, in tag order. A program without a
+    /// dispatch is modeled from its entrypoints.
+    pub fn native_accounts(&self) -> &[NativeAccountsModel] {
+        self.cached("native_accounts", &self.native_accounts, || {
+            let mut handlers: Vec<Instance> = vec![];
+            for instruction in self.native_instructions() {
+                if !handlers.contains(&instruction.handler) {
+                    handlers.push(instruction.handler);
+                }
+            }
+            if handlers.is_empty() {
+                handlers = self
+                    .entrypoints()
+                    .iter()
+                    .filter(|entry| entry.kind == EntryKind::Native)
+                    .map(|entry| entry.instance)
+                    .collect();
+            }
+            handlers.into_iter().map(native_accounts).collect()
+        })
+        .as_slice()
+    }
+
+    /// The local types laid out with `Pack`, by name.
+    pub fn pack_layouts(&self) -> &[PackLayout] {
+        self.cached("pack_layouts", &self.pack_layouts, pack_layouts)
+            .as_slice()
+    }
+
+    /// The handlers by the short name of their Accounts struct.
+    pub fn handlers_by_accounts(&self) -> &BTreeMap<String, (AdtDef, Vec<Instance>)> {
+        self.cached(
+            "handlers_by_accounts",
+            &self.handlers_by_accounts,
+            handlers_by_accounts,
+        )
+    }
+
+    /// The CPIs of the instruction handlers.
+    pub fn cpi_calls(&self) -> &[CpiCall] {
+        self.cached("cpi_calls", &self.cpi_calls, extract_cpi_calls)
+            .as_slice()
+    }
+
+    pub fn program_id(&self) -> &Result<ProgramId, ProgramIdError> {
+        self.cached("program_id", &self.program_id, extract_program_id)
+    }
+
+    /// The discriminators of the account types.
+    pub fn discriminators(&self) -> &[(String, Vec<u8>)] {
+        self.cached(
+            "discriminators",
+            &self.discriminators,
+            extract_discriminators,
+        )
+        .as_slice()
+    }
+
+    pub fn instruction_discriminators(&self) -> &[(String, Vec<u8>)] {
+        self.cached(
+            "instruction_discriminators",
+            &self.instruction_discriminators,
+            extract_instruction_discriminators,
+        )
+        .as_slice()
+    }
+
+    /// The body of `instance`, built on first use and shared by later callers.
+    pub fn body(&self, instance: Instance) -> Option<Rc<Body>> {
+        if let Some(body) = self.bodies.borrow().get(&instance) {
+            self.stats
+                .bodies_reused
+                .set(self.stats.bodies_reused.get() + 1);
+            return Some(body.clone());
+        }
+        let body = Rc::new(instance.body()?);
+        self.stats
+            .bodies_built
+            .set(self.stats.bodies_built.get() + 1);
+        self.bodies.borrow_mut().insert(instance, body.clone());
+        Some(body)
+    }
+}
+
+/// A part of the context, counted for `--analyzer-timings`.
+trait Extracted {
+    fn items(&self) -> usize;
+}
+
+impl<T> Extracted for Vec<T> {
+    fn items(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Extracted for HashMap<K, V> {
+    fn items(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Extracted for BTreeMap<K, V> {
+    fn items(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, E> Extracted for Result<T, E> {
+    fn items(&self) -> usize {
+        usize::from(self.is_ok())
+    }
+}
+
+impl Extracted for DirectedGraph<Instance> {
+    fn items(&self) -> usize {
+        self.nodes().count()
+    }
+}
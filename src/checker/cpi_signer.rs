@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+
+use rustc_public::mir::TerminatorKind;
+
+use super::Checker;
+use super::context::AnalysisContext;
+use super::signer_seeds::{is_signed_invoke, seed_lists};
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate_through_pointers};
+use crate::anchor_info::account_meta_call;
+use crate::anchor_info::remaining::callee_name;
+use crate::metadata::{Concept, known_types};
+use crate::native_info::accounts::account_positions;
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "cpi_signer_propagation";
+
+/// Report CPIs whose hand-built `AccountMeta`s disagree with the signer seeds
+/// passed along: `invoke_signed` with seeds but no account marked `is_signer`,
+/// so the PDA signature is dropped and the callee rejects it, or an account
+/// marked `is_signer` without seeds when the handler never checks that it
+/// signed, leaving the callee to enforce a signature the program took on trust.
+/// Only the metas built in the fn making the CPI are traced; an `Instruction`
+/// from a helper such as `system_instruction::transfer` is skipped.
+pub struct CpiSignerPropagation;
+
+impl Checker for CpiSignerPropagation {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "CPI account metas whose is_signer flags disagree with the signer seeds"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for &instance in ctx.reachable_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            // Each meta, labeled by index, with its key and constant signer flag.
+            let mut metas = vec![];
+            let mut sources: Taint<usize> = Taint::new();
+            for block in &body.blocks {
+                if let TerminatorKind::Call {
+                    func,
+                    args,
+                    destination,
+                    ..
+                } = &block.terminator.kind
+                    && let Some(callee) = callee_name(&body, func)
+                    && let Some(meta) = account_meta_call(&callee, args)
+                {
+                    sources.insert(destination.local, BTreeSet::from([metas.len()]));
+                    metas.push((operand_local(meta.key), meta.signer));
+                }
+            }
+            if metas.is_empty() {
+                continue;
+            }
+            let taint = propagate_through_pointers(&body, sources);
+            let positions = account_positions(&body);
+            let accounts = ctx
+                .native_accounts()
+                .iter()
+                .find(|model| model.handler == instance)
+                .map_or(&[][..], |model| model.accounts.as_slice());
+            for (bb, block) in body.blocks.iter().enumerate() {
+                let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+                    continue;
+                };
+                let Some(callee) = callee_name(&body, func) else {
+                    continue;
+                };
+                if !known_types().is(Concept::Invoke, &callee) {
+                    continue;
+                }
+                let Some(instruction) = args.first().and_then(operand_local) else {
+                    continue;
+                };
+                let passed = labels_of(&taint, [instruction]);
+                if passed.is_empty() {
+                    continue;
+                }
+                let seeded = is_signed_invoke(&callee)
+                    && args
+                        .last()
+                        .and_then(operand_local)
+                        .is_some_and(|seeds| !seed_lists(&body, seeds).is_empty());
+                let callee = callee.rsplit("::").next().unwrap_or(&callee);
+                let at = location(&block.terminator.span);
+                if seeded {
+                    if passed.iter().all(|&meta| metas[meta].1 == Some(false)) {
+                        let message = format!(
+                            "`{}` calls `{callee}` at {at} with signer seeds, but marks no account of the instruction as signer, so the PDA does not sign",
+                            instance.name()
+                        );
+                        findings.push(
+                            Finding::warning(CHECKER, message)
+                                .in_fn(instance)
+                                .span(&block.terminator.span)
+                                .key(&format!("bb{bb}")),
+                        );
+                    }
+                    continue;
+                }
+                for &meta in &passed {
+                    let (Some(key), Some(true)) = metas[meta] else {
+                        continue;
+                    };
+                    for position in labels_of(&positions, [key]) {
+                        let Some(account) = accounts.get(position) else {
+                            continue;
+                        };
+                        if account.signer_checked {
+                            continue;
+                        }
+                        let name = account
+                            .name
+                            .as_ref()
+                            .map_or(String::new(), |name| format!(" `{name}`"));
+                        let message = format!(
+                            "`{}` calls `{callee}` at {at} marking account #{position}{name} as signer without signer seeds, and never checks that it signed",
+                            instance.name()
+                        );
+                        findings.push(
+                            Finding::warning(CHECKER, message)
+                                .in_fn(instance)
+                                .span(&block.terminator.span)
+                                .key(&format!("bb{bb}"))
+                                .key(&position.to_string()),
+                        );
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
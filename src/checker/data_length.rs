@@ -0,0 +1,182 @@
+use std::collections::BTreeSet;
+
+use rustc_public::mir::{Body, StatementKind, TerminatorKind};
+use rustc_public::ty::Span;
+
+use super::Checker;
+use super::context::AnalysisContext;
+use super::remaining_accounts::{length_checks, slice_lens};
+use crate::analysis::condition::{Definitions, field_path};
+use crate::analysis::graph::Fixpoints;
+use crate::analysis::loops::predecessors;
+use crate::analysis::taint::{Taint, operand_local, propagate};
+use crate::anchor_info::remaining::{callee_name, const_accesses, read_place};
+use crate::native_info::accounts::DESERIALIZE_FNS;
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "unchecked_data_length";
+const DATA: &str = "AccountInfo.data";
+/// `AccountInfo::try_borrow_data` and `try_borrow_mut_data`.
+const BORROW_DATA: [&str; 2] = ["::try_borrow_data", "::try_borrow_mut_data"];
+/// `AccountInfo::data_len`, whose result checks the data of its account.
+const DATA_LEN: &str = "::data_len";
+/// `Result::unwrap` and `Result::expect`.
+const UNWRAP_FNS: [&str; 2] = ["::unwrap", "::expect"];
+
+/// Report account data read at constant offsets, e.g. `data.borrow()[0..8]`, and
+/// account data decoded with `try_from_slice` and the like whose result is
+/// unwrapped, when no comparison on the length of the data dominates the read.
+/// Anyone can pass an account with shorter data, which makes the instruction
+/// panic instead of failing with an error.
+///
+/// Any length comparison counts as a check, whatever its bound, as for
+/// `unchecked_remaining_accounts`; decoding with `?` is left alone.
+pub struct UncheckedDataLength;
+
+impl Checker for UncheckedDataLength {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Account data read at a fixed offset with no length check before it"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for &instance in ctx.local_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            for (block, span, read) in unchecked_reads(&body, ctx.fixpoints()) {
+                let message = format!(
+                    "account data is {read} at {} in `{}` without checking its length",
+                    location(&span),
+                    instance.name()
+                );
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .in_fn(instance)
+                        .key(&format!("bb{block}"))
+                        .key(&read),
+                );
+            }
+        }
+        findings
+    }
+}
+
+/// Reads of account data in `body` that no length comparison dominates, as
+/// the block of the read and what it does, e.g. "sliced as `[0..8]`".
+fn unchecked_reads(body: &Body, fixpoints: &Fixpoints) -> Vec<(usize, Span, String)> {
+    let defs = Definitions::new(body);
+    let data = account_data(body, &defs);
+    if data.is_empty() {
+        return vec![];
+    }
+
+    let mut reads: Vec<(usize, Span, String)> = const_accesses(body, &data, &defs)
+        .into_iter()
+        .map(|access| {
+            let read = if access.access.contains("..") {
+                format!("sliced as `{}`", access.access)
+            } else {
+                format!("indexed as `{}`", access.access)
+            };
+            (access.block, access.span, read)
+        })
+        .collect();
+    // Values decoded from the data, by the fn decoding them.
+    let mut decoded: Taint<&str> = Taint::new();
+    for block in &body.blocks {
+        if let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+            && let Some(name) = callee_name(body, func)
+            && let Some(fn_name) = DESERIALIZE_FNS
+                .iter()
+                .find(|fn_name| name.rsplit("::").next() == Some(**fn_name))
+            && args
+                .iter()
+                .filter_map(operand_local)
+                .any(|local| data.contains_key(&local))
+        {
+            decoded.insert(destination.local, BTreeSet::from([*fn_name]));
+        }
+    }
+    for (bb, block) in body.blocks.iter().enumerate() {
+        if let TerminatorKind::Call { func, args, .. } = &block.terminator.kind
+            && callee_name(body, func)
+                .is_some_and(|name| UNWRAP_FNS.iter().any(|suffix| name.ends_with(suffix)))
+            && let Some(fn_names) = args
+                .first()
+                .and_then(operand_local)
+                .and_then(|local| decoded.get(&local))
+        {
+            for fn_name in fn_names {
+                reads.push((
+                    bb,
+                    block.terminator.span,
+                    format!("decoded with `{fn_name}` and unwrapped"),
+                ));
+            }
+        }
+    }
+    if reads.is_empty() {
+        return vec![];
+    }
+
+    let mut lens = slice_lens(body, &data);
+    for block in &body.blocks {
+        if let TerminatorKind::Call {
+            func, destination, ..
+        } = &block.terminator.kind
+            && callee_name(body, func).is_some_and(|name| name.ends_with(DATA_LEN))
+        {
+            lens.insert(destination.local);
+        }
+    }
+    let checks = length_checks(body, &defs, &lens);
+    let dominators = fixpoints.dominators(body.blocks.len(), &predecessors(body));
+    reads
+        .into_iter()
+        .filter(|(block, _, _)| {
+            !checks
+                .iter()
+                .any(|check| dominators.dominates(check, block))
+        })
+        .collect()
+}
+
+/// Locals holding the data of an account, borrowed from its `data` field or
+/// with `try_borrow_data`, and the values computed from it.
+fn account_data(body: &Body, defs: &Definitions) -> Taint<()> {
+    let mut sources = Taint::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(lhs, rvalue) = &stmt.kind
+                && let Some(place) = read_place(rvalue)
+                && defs
+                    .field_parent(place)
+                    .is_some_and(|(ty, field_idx)| field_path(ty, field_idx) == DATA)
+            {
+                sources.insert(lhs.local, BTreeSet::from([()]));
+            }
+        }
+        if let TerminatorKind::Call {
+            func, destination, ..
+        } = &block.terminator.kind
+            && callee_name(body, func)
+                .is_some_and(|name| BORROW_DATA.iter().any(|suffix| name.ends_with(suffix)))
+        {
+            sources.insert(destination.local, BTreeSet::from([()]));
+        }
+    }
+    if sources.is_empty() {
+        return sources;
+    }
+    propagate(body, sources)
+}
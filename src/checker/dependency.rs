@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::config::Config;
+use crate::metadata::{
+    AdvisoryDb, AdvisorySeverity, detect_vulnerable_dep, parse_toml_in_crate_path,
+};
+use crate::report::{Finding, SourceLocation};
+
+const CHECKER: &str = "vulnerable_dependency";
+
+/// Report the dependencies of the program whose version requirement allows a
+/// version an advisory affects: the advisories shipped with the analyzer,
+/// unless `[advisories] builtin = false`, and those of the files listed in
+/// `[advisories] files` of the config. Findings point at the dependency in
+/// `Cargo.toml`.
+pub struct VulnerableDependency;
+
+impl Checker for VulnerableDependency {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Dependencies allowing versions with a known advisory"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let Some(root) = std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from) else {
+            return vec![];
+        };
+        let Ok((_, deps)) = parse_toml_in_crate_path(&root.to_string_lossy()) else {
+            return vec![];
+        };
+        let db = advisories(ctx.config, &root);
+        let manifest = root.join("Cargo.toml");
+        let text = fs::read_to_string(&manifest).unwrap_or_default();
+
+        let mut findings = vec![];
+        for dep in detect_vulnerable_dep(&deps, &db) {
+            let advisory = dep.advisory;
+            let message = format!(
+                "dependency `{}` = \"{}\" allows versions affected by {} ({}): {}",
+                dep.name, dep.version, advisory.id, advisory.affected, advisory.title
+            );
+            let finding = match advisory.severity {
+                AdvisorySeverity::Warning => Finding::warning(CHECKER, message),
+                AdvisorySeverity::Error => Finding::error(CHECKER, message),
+            };
+            findings.push(finding.located(SourceLocation {
+                file: manifest.to_string_lossy().into_owned(),
+                line: dependency_line(&text, &dep.name).unwrap_or(1),
+                column: 1,
+            }));
+        }
+        findings
+    }
+}
+
+/// The builtin advisories and those of the files of the config, relative to
+/// `root`; a file that is absent or broken is left out.
+fn advisories(config: &Config, root: &Path) -> AdvisoryDb {
+    let mut db = if config.advisories.builtin {
+        AdvisoryDb::builtin()
+    } else {
+        AdvisoryDb::default()
+    };
+    for file in &config.advisories.files {
+        match AdvisoryDb::load(&root.join(file)) {
+            Ok(advisories) => db.extend(advisories),
+            Err(err) => eprintln!("{err}, ignoring it"),
+        }
+    }
+    db
+}
+
+/// The line, from 1, declaring `name` under `[dependencies]` of the manifest
+/// `text`, either as a key or as a `[dependencies.name]` table.
+fn dependency_line(text: &str, name: &str) -> Option<usize> {
+    let mut in_dependencies = false;
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            if line == format!("[dependencies.{name}]") {
+                return Some(idx + 1);
+            }
+            in_dependencies = line == "[dependencies]";
+            continue;
+        }
+        if in_dependencies
+            && let Some(rest) = line.strip_prefix(name)
+            && rest.trim_start().starts_with(['=', '.'])
+        {
+            return Some(idx + 1);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_line() {
+        let text = "[package]\nname = \"spl-token\"\n\n[dependencies]\nspl-token-2022 = \"1\"\n\
+                    spl-token = { version = \"3.1\" }\n\n[dev-dependencies]\nborsh = \"1\"\n\n\
+                    [dependencies.borsh]\nversion = \"0.10\"\n";
+        assert_eq!(dependency_line(text, "spl-token"), Some(6));
+        assert_eq!(dependency_line(text, "borsh"), Some(11));
+        assert_eq!(dependency_line(text, "anchor-lang"), None);
+    }
+}
@@ -0,0 +1,77 @@
+use super::{AnalysisContext, Checker};
+use crate::anchor_info::hex;
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "discriminator_collision";
+
+/// Report account types, or instructions, whose discriminators collide. Anchor
+/// only compares the first `len` bytes of the data with a discriminator, so one
+/// that is a prefix of another (possible with custom `discriminator = ..`
+/// overrides) lets an account of one type pass as the other.
+pub struct DiscriminatorCollision;
+
+impl Checker for DiscriminatorCollision {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Account or instruction discriminators that are a prefix of another"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for (kind, discriminators) in [
+            ("account types", ctx.discriminators()),
+            ("instructions", ctx.instruction_discriminators()),
+        ] {
+            for (first, second) in collisions(discriminators) {
+                let (first_name, first_id) = &discriminators[first];
+                let (second_name, second_id) = &discriminators[second];
+                let message = format!(
+                    "{kind} `{first_name}` ({}) and `{second_name}` ({}) have colliding discriminators",
+                    hex(first_id),
+                    hex(second_id)
+                );
+                findings.push(Finding::error(CHECKER, message));
+            }
+        }
+
+        findings
+    }
+}
+
+/// Pairs of indices into `discriminators` where one is a prefix of the other,
+/// including equal ones.
+fn collisions(discriminators: &[(String, Vec<u8>)]) -> Vec<(usize, usize)> {
+    let mut pairs = vec![];
+    for (first, (_, first_id)) in discriminators.iter().enumerate() {
+        for (second, (_, second_id)) in discriminators.iter().enumerate().skip(first + 1) {
+            if first_id.starts_with(second_id) || second_id.starts_with(first_id) {
+                pairs.push((first, second));
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collisions() {
+        let discriminators = [
+            ("Vault".to_string(), vec![1, 2, 3, 4, 5, 6, 7, 8]),
+            ("Config".to_string(), vec![8, 7, 6, 5, 4, 3, 2, 1]),
+            ("Legacy".to_string(), vec![1]),
+            ("Copy".to_string(), vec![8, 7, 6, 5, 4, 3, 2, 1]),
+        ];
+        assert_eq!(collisions(&discriminators), [(0, 2), (1, 3)]);
+        assert!(collisions(&discriminators[..2]).is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use rustc_public::CrateDef;
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "duplicate_mutable_account";
+
+/// Module of the Accounts structs `#[program]` generates for its IDL
+/// instructions. `IdlSetBuffer` takes two writable `IdlAccount`s by design.
+const GENERATED_IDL: &str = "__private::__idl::";
+
+/// Report two writable fields of an Accounts struct holding the same account
+/// type. Nothing stops a client from passing the same account for both, and
+/// the handler then writes one copy over the other.
+pub struct DuplicateMutableAccount;
+
+impl Checker for DuplicateMutableAccount {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Two writable fields of an Accounts struct holding the same account type"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        // The off-chain and the CPI client of a struct normally agree, so each
+        // pair is reported once.
+        let metas_by_side = [ctx.to_account_metas(), ctx.cpi_account_metas()];
+        let mut reported = HashSet::new();
+        let mut findings = vec![];
+
+        for anchor_accounts in ctx.anchor_accounts() {
+            if anchor_accounts.adt_def.name().starts_with(GENERATED_IDL) {
+                continue;
+            }
+            for metas in metas_by_side {
+                let writable: Vec<_> = anchor_accounts
+                    .anchor_accounts
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, anchor_account)| {
+                        let writable = metas
+                            .iter()
+                            .find(|meta| {
+                                meta.struct_name == anchor_accounts.name && meta.field_idx == idx
+                            })
+                            .is_some_and(|meta| meta.writable);
+                        (anchor_account, writable)
+                    })
+                    .collect();
+
+                for (i, (a, a_writable)) in writable.iter().enumerate() {
+                    for (j, (b, b_writable)) in writable.iter().enumerate().skip(i + 1) {
+                        if !a_writable || !b_writable {
+                            continue;
+                        }
+                        match (a.kind.data_type(), b.kind.data_type()) {
+                            (Some(a_struct), Some(b_struct))
+                                if a_struct == b_struct
+                                    && reported.insert((anchor_accounts.name.clone(), i, j)) =>
+                            {
+                                let message = format!(
+                                    "two mutable accounts of the same type in the same Context `{}`: `{}` and `{}` ({})",
+                                    anchor_accounts.name, a.name, b.name, a_struct
+                                );
+                                let mut finding = Finding::error(CHECKER, message)
+                                    .at(&anchor_accounts.adt_def)
+                                    .key(&a.name)
+                                    .key(&b.name);
+                                // The handlers taking the struct, by its short name.
+                                if let Some((_, handlers)) =
+                                    ctx.handlers_by_accounts().get(&anchor_accounts.name)
+                                {
+                                    for handler in handlers {
+                                        finding = finding.related(&handler.def.span());
+                                    }
+                                }
+                                findings.push(finding);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
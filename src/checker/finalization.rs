@@ -0,0 +1,167 @@
+use std::collections::{BTreeSet, HashSet};
+
+use rustc_public::mir::{Body, TerminatorKind};
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::graph::{DirectedGraph, Fixpoints};
+use crate::anchor_info::remaining::callee_name;
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "skipped_finalization";
+
+/// Report fns calling one of the `[finalization] fns` of the config, e.g. a
+/// check of the state's invariants at the end of a handler, that can also
+/// return without calling it: no call post-dominates the start of the fn, and
+/// an early `return` or `?` leaves it out. Each path is reported at the branch
+/// where it leaves every call behind, e.g. the condition of the `if` returning
+/// early.
+pub struct SkippedFinalization;
+
+impl Checker for SkippedFinalization {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Returns that skip the configured finalization call"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let finalizers = &ctx.config.finalization.fns;
+        if finalizers.is_empty() {
+            return vec![];
+        }
+        let mut findings = vec![];
+        for &instance in ctx.reachable_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            let is_finalizer = |name: &str| {
+                finalizers
+                    .iter()
+                    .any(|fin| name == fin || name.ends_with(&format!("::{fin}")))
+            };
+            if is_finalizer(&instance.name()) {
+                continue;
+            }
+            let calls: BTreeSet<usize> = body
+                .blocks
+                .iter()
+                .enumerate()
+                .filter(|(_, block)| {
+                    matches!(
+                        &block.terminator.kind,
+                        TerminatorKind::Call { func, .. }
+                            if callee_name(&body, func).is_some_and(|name| is_finalizer(&name))
+                    )
+                })
+                .map(|(bb, _)| bb)
+                .collect();
+            if calls.is_empty() {
+                continue;
+            }
+            let bypasses = bypasses(&body, &calls, ctx.fixpoints());
+            if bypasses.is_empty() {
+                continue;
+            }
+            let first_call = calls.first().map(|bb| &body.blocks[*bb].terminator.kind);
+            let finalizer = match first_call {
+                Some(TerminatorKind::Call { func, .. }) => callee_name(&body, func),
+                _ => None,
+            }
+            .unwrap_or_default();
+            let at: BTreeSet<String> = bypasses
+                .iter()
+                .map(|bb| location(&body.blocks[*bb].terminator.span))
+                .collect();
+            let at: Vec<String> = at.into_iter().collect();
+            let message = format!(
+                "`{}` can return without calling `{finalizer}`, branching away from it at {}",
+                instance.name(),
+                at.join(", ")
+            );
+            findings.push(Finding::warning(CHECKER, message).in_fn(instance));
+        }
+        findings
+    }
+}
+
+/// The branches of `body` where a path from the start to a `return` leaves the
+/// blocks that can still reach one of `calls` without having gone through one.
+/// Paths that never return, e.g. unwinding, are left out.
+fn bypasses(body: &Body, calls: &BTreeSet<usize>, fixpoints: &Fixpoints) -> Vec<usize> {
+    let successors = |bb: usize| body.blocks[bb].terminator.successors();
+
+    // Blocks reaching a `return`, and the graph of the paths through them.
+    let returning = backward_closure(body, |bb| {
+        matches!(body.blocks[bb].terminator.kind, TerminatorKind::Return)
+    });
+    if !returning.contains(&0) {
+        return vec![];
+    }
+    let mut graph = DirectedGraph::new();
+    for &bb in &returning {
+        graph.add_node(bb);
+    }
+    for &bb in &returning {
+        for succ in successors(bb) {
+            if returning.contains(&succ) {
+                graph.add_edge(bb, succ);
+            }
+        }
+    }
+    let post_dominators = fixpoints.post_dominators(&graph);
+    if calls
+        .iter()
+        .any(|call| returning.contains(call) && post_dominators.is_post_dominated_by(&0, call))
+    {
+        return vec![];
+    }
+
+    // Walk from the start without going through a call; the paths that bypass
+    // them branch from a block that can still reach one to a block that cannot.
+    let reaching_call = backward_closure(body, |bb| calls.contains(&bb));
+    let mut bypasses = BTreeSet::new();
+    let mut visited = HashSet::from([0]);
+    let mut stack = vec![0];
+    while let Some(bb) = stack.pop() {
+        if calls.contains(&bb) {
+            continue;
+        }
+        for succ in successors(bb) {
+            if !returning.contains(&succ) {
+                continue;
+            }
+            if reaching_call.contains(&bb) && !reaching_call.contains(&succ) {
+                bypasses.insert(bb);
+            }
+            if visited.insert(succ) {
+                stack.push(succ);
+            }
+        }
+    }
+    bypasses.into_iter().collect()
+}
+
+/// The blocks of `body` from which some path leads to a block in `targets`,
+/// those included.
+fn backward_closure(body: &Body, targets: impl Fn(usize) -> bool) -> HashSet<usize> {
+    let mut reached: HashSet<usize> = (0..body.blocks.len()).filter(|bb| targets(*bb)).collect();
+    loop {
+        let known = reached.len();
+        for (bb, block) in body.blocks.iter().enumerate() {
+            if block
+                .terminator
+                .successors()
+                .iter()
+                .any(|succ| reached.contains(succ))
+            {
+                reached.insert(bb);
+            }
+        }
+        if reached.len() == known {
+            return reached;
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use super::{AnalysisContext, Checker};
+use crate::report::Finding;
+
+const CHECKER: &str = "float_round";
+const F32_ROUND: &str = "f32::<impl f32>::round";
+const F64_ROUND: &str = "f64::<impl f64>::round";
+
+/// Report instances of `f32::round` and `f64::round`.
+pub struct FloatRound;
+
+impl Checker for FloatRound {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Calls to f32::round or f64::round"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for instance in ctx.instances() {
+            let name = instance.name();
+            if name.contains(F32_ROUND) || name.contains(F64_ROUND) {
+                let message = format!("Contains f32::round or f64::round: {}", name);
+                findings.push(Finding::warning(CHECKER, message).in_fn(*instance));
+            }
+        }
+        findings
+    }
+}
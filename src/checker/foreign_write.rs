@@ -0,0 +1,170 @@
+use std::collections::BTreeSet;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{Body, TerminatorKind};
+use rustc_public::ty::{RigidTy, Ty, TyKind};
+
+use super::{AnalysisContext, Checker};
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::anchor_info::AnchorAccountKind;
+use crate::anchor_info::access::{Role, local_roles};
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "foreign_account_write";
+/// `AccountInfo` methods changing the data or the owner of the account.
+const DATA_WRITES: [&str; 3] = ["::try_borrow_mut_data", "::realloc", "::assign"];
+/// `RefCell::borrow_mut` and `try_borrow_mut`, on the `data` field of an
+/// `AccountInfo` when the cell holds `&mut [u8]`.
+const BORROW_MUT: [&str; 2] = ["::borrow_mut", "::try_borrow_mut"];
+const DATA_CELL: &str = "std::cell::RefCell<&mut [u8]>";
+
+/// How deep we follow an account into local helpers.
+const MAX_DEPTH: usize = 4;
+
+/// Report handlers writing the data of an account that another program owns:
+/// programs, sysvars, system accounts and `Account<T>` of a type from another
+/// crate, e.g. a token account. Only the owner may change the data, so the
+/// write fails at runtime; a CPI to the owner is the way to change it.
+pub struct ForeignAccountWrite;
+
+impl Checker for ForeignAccountWrite {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Writes to the data of an account another program owns"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for (name, (adt_def, handlers)) in ctx.handlers_by_accounts() {
+            let Some(variant) = adt_def.variants_iter().next() else {
+                continue;
+            };
+            let fields = variant.fields();
+            let foreign: BTreeSet<usize> = fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| is_foreign_owned(field.ty()))
+                .map(|(field_idx, _)| field_idx)
+                .collect();
+            if foreign.is_empty() {
+                continue;
+            }
+            for &handler in handlers {
+                let Some(body) = ctx.body(handler) else {
+                    continue;
+                };
+                let sources: Taint<usize> = local_roles(&body, 1, Role::Ctx)
+                    .into_iter()
+                    .filter_map(|(local, role)| match role {
+                        Role::Field(field_idx) if foreign.contains(&field_idx) => {
+                            Some((local, BTreeSet::from([field_idx])))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                for field_idx in written_fields(&body, sources, 0) {
+                    let message = format!(
+                        "account `{}` in `{name}` is owned by another program but `{}` writes its data",
+                        fields[field_idx].name,
+                        handler.name()
+                    );
+                    findings.push(
+                        Finding::error(CHECKER, message)
+                            .in_fn(handler)
+                            .key(&fields[field_idx].name),
+                    );
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+/// Whether an account field of type `ty` is owned by a program other than the
+/// one being analyzed.
+fn is_foreign_owned(ty: Ty) -> bool {
+    match AnchorAccountKind::from_ty(&ty.kind()) {
+        Some(
+            AnchorAccountKind::Program
+            | AnchorAccountKind::Interface(_)
+            | AnchorAccountKind::Sysvar(_)
+            | AnchorAccountKind::SystemAccount,
+        ) => true,
+        // The owner of `Account<T>` is the one `T` names, ours for local types.
+        Some(AnchorAccountKind::Account(_) | AnchorAccountKind::InterfaceAccount(_)) => {
+            let Some(RigidTy::Adt(_, generic_args)) = ty.kind().rigid().cloned() else {
+                return false;
+            };
+            generic_args
+                .0
+                .get(1)
+                .and_then(|arg| arg.ty().cloned())
+                .and_then(|data_ty| match data_ty.kind().rigid() {
+                    Some(RigidTy::Adt(data_def, _)) => Some(!data_def.krate().is_local),
+                    _ => None,
+                })
+                .unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// Labels of the `sources` whose data `body` writes, directly or through local
+/// helpers it passes them to.
+fn written_fields(body: &Body, sources: Taint<usize>, depth: usize) -> BTreeSet<usize> {
+    let taint = propagate(body, sources);
+    let mut written = BTreeSet::new();
+    for block in &body.blocks {
+        let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+            continue;
+        };
+        let Ok(fn_ty) = func.ty(body.locals()) else {
+            continue;
+        };
+        let TyKind::RigidTy(RigidTy::FnDef(fn_def, generic_args)) = fn_ty.kind() else {
+            continue;
+        };
+        let callee_name = fn_def.name();
+        let Some(receiver) = args.first() else {
+            continue;
+        };
+        let writes_data = DATA_WRITES
+            .iter()
+            .any(|suffix| callee_name.ends_with(suffix))
+            || (BORROW_MUT
+                .iter()
+                .any(|suffix| callee_name.ends_with(suffix))
+                && receiver
+                    .ty(body.locals())
+                    .is_ok_and(|ty| ty.to_string().contains(DATA_CELL)));
+        if writes_data {
+            written.extend(labels_of(&taint, operand_local(receiver)));
+        } else if fn_def.krate().is_local
+            && depth < MAX_DEPTH
+            && let Ok(helper) = Instance::resolve(fn_def, &generic_args)
+            && let Some(helper_body) = helper.body()
+        {
+            let helper_sources: Taint<usize> = args
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, arg)| {
+                    let labels = labels_of(&taint, operand_local(arg));
+                    (!labels.is_empty()).then_some((idx + 1, labels))
+                })
+                .collect();
+            if !helper_sources.is_empty() {
+                written.extend(written_fields(&helper_body, helper_sources, depth + 1));
+            }
+        }
+    }
+    written
+}
@@ -0,0 +1,296 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::{AnalysisContext, Checker};
+use crate::anchor_info::hex;
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "idl_drift";
+
+/// Report where the IDL the program was published with, given with
+/// `--compare-idl`, no longer matches the IDL built from its source: a
+/// different program id, instructions, account types, events or errors only
+/// one of them has, discriminators and error codes that changed, and
+/// instruction accounts whose `writable` or `signer` flags changed. Clients built from a stale IDL send
+/// instructions the program rejects, or misread its accounts and errors.
+///
+/// Changes breaking clients are errors, others warnings. IDLs from before
+/// Anchor 0.30, with camelCase names, `isMut` and `isSigner` and no
+/// discriminators, are compared as far as they go.
+pub struct IdlDrift;
+
+impl Checker for IdlDrift {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Differences between the IDL given with --compare-idl and the program"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let Some((idl, program)) = ctx.idl_comparison() else {
+            return vec![];
+        };
+        idl_differences(idl, program)
+    }
+}
+
+fn idl_differences(idl: &Value, program: &Value) -> Vec<Finding> {
+    let mut findings = vec![];
+    let idl_address = idl["address"]
+        .as_str()
+        .or(idl["metadata"]["address"].as_str());
+    if let (Some(idl_address), Some(address)) = (idl_address, program["address"].as_str())
+        && idl_address != address
+    {
+        let message =
+            format!("program id is {idl_address} in the IDL but {address} in the program");
+        findings.push(Finding::error(CHECKER, message));
+    }
+
+    let instructions = matching(
+        |name| format!("instruction `{name}`"),
+        by_name(&idl["instructions"], true),
+        by_name(&program["instructions"], true),
+        &mut findings,
+    );
+    for (name, idl_instruction, instruction) in instructions {
+        compare_discriminators(
+            &format!("instruction `{name}`"),
+            idl_instruction,
+            instruction,
+            &mut findings,
+        );
+        let accounts = matching(
+            |account| format!("account `{name}.{account}`"),
+            by_name(&idl_instruction["accounts"], true),
+            by_name(&instruction["accounts"], true),
+            &mut findings,
+        );
+        for (account, idl_account, program_account) in accounts {
+            // Composite accounts have no flags of their own.
+            if !idl_account["accounts"].is_null() || !program_account["accounts"].is_null() {
+                continue;
+            }
+            for (flag, keys) in [
+                ("writable", ["writable", "isMut"]),
+                ("a signer", ["signer", "isSigner"]),
+            ] {
+                let in_idl = keys.iter().any(|key| idl_account[key] == true);
+                let in_program = keys.iter().any(|key| program_account[key] == true);
+                let (yes, no) = match (in_idl, in_program) {
+                    (true, false) => ("IDL", "program"),
+                    (false, true) => ("program", "IDL"),
+                    _ => continue,
+                };
+                let message =
+                    format!("`{name}.{account}` is {flag} in the {yes} but not in the {no}");
+                findings.push(Finding::error(CHECKER, message));
+            }
+        }
+    }
+
+    for (kind, key) in [("account type", "accounts"), ("event", "events")] {
+        let items = matching(
+            |name| format!("{kind} `{name}`"),
+            by_name(&idl[key], false),
+            by_name(&program[key], false),
+            &mut findings,
+        );
+        for (name, idl_item, item) in items {
+            compare_discriminators(&format!("{kind} `{name}`"), idl_item, item, &mut findings);
+        }
+    }
+
+    let errors = matching(
+        |name| format!("error `{name}`"),
+        by_name(&idl["errors"], false),
+        by_name(&program["errors"], false),
+        &mut findings,
+    );
+    for (name, idl_error, error) in errors {
+        if idl_error["code"] != error["code"] {
+            let message = format!(
+                "error `{name}` has code {} in the IDL but {} in the program",
+                idl_error["code"], error["code"]
+            );
+            findings.push(Finding::error(CHECKER, message));
+        }
+        // Anchor leaves out the message of variants without `#[msg(..)]`.
+        if let Some(idl_msg) = idl_error["msg"].as_str()
+            && Some(idl_msg) != error["msg"].as_str()
+        {
+            let message = format!(
+                "error `{name}` has message {idl_msg:?} in the IDL but {} in the program",
+                error["msg"]
+            );
+            findings.push(Finding::warning(CHECKER, message));
+        }
+    }
+    findings
+}
+
+/// The entries of the array `items` by name, in snake_case if `snake`, so that
+/// the camelCase names of older IDLs match.
+fn by_name(items: &Value, snake: bool) -> BTreeMap<String, &Value> {
+    items
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let name = item["name"].as_str()?;
+            let name = if snake {
+                snake_case(name)
+            } else {
+                name.to_string()
+            };
+            Some((name, item))
+        })
+        .collect()
+}
+
+/// Report the entries only one side has, as `describe` names them, and return
+/// those both have.
+fn matching<'a>(
+    describe: impl Fn(&str) -> String,
+    idl: BTreeMap<String, &'a Value>,
+    mut program: BTreeMap<String, &'a Value>,
+    findings: &mut Vec<Finding>,
+) -> Vec<(String, &'a Value, &'a Value)> {
+    let mut both = vec![];
+    for (name, idl_item) in idl {
+        match program.remove(&name) {
+            Some(item) => both.push((name, idl_item, item)),
+            None => {
+                let message = format!("{} of the IDL is not in the program", describe(&name));
+                findings.push(Finding::warning(CHECKER, message));
+            }
+        }
+    }
+    for name in program.into_keys() {
+        let message = format!("{} of the program is not in the IDL", describe(&name));
+        findings.push(Finding::warning(CHECKER, message));
+    }
+    both
+}
+
+fn compare_discriminators(what: &str, idl_item: &Value, item: &Value, findings: &mut Vec<Finding>) {
+    if let (Some(idl_discriminator), Some(discriminator)) =
+        (discriminator(idl_item), discriminator(item))
+        && idl_discriminator != discriminator
+    {
+        let message = format!(
+            "discriminator of {what} is {} in the IDL but {} in the program",
+            hex(&idl_discriminator),
+            hex(&discriminator)
+        );
+        findings.push(Finding::error(CHECKER, message));
+    }
+}
+
+fn discriminator(item: &Value) -> Option<Vec<u8>> {
+    item["discriminator"]
+        .as_array()?
+        .iter()
+        .map(|byte| byte.as_u64()?.try_into().ok())
+        .collect()
+}
+
+/// `initializeVault` as `initialize_vault`; snake_case names are unchanged.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (idx, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if idx > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn messages(idl: Value, program: Value) -> Vec<String> {
+        let mut messages: Vec<_> = idl_differences(&idl, &program)
+            .into_iter()
+            .map(|finding| format!("{}: {}", finding.severity, finding.message))
+            .collect();
+        messages.sort();
+        messages
+    }
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("initializeVault"), "initialize_vault");
+        assert_eq!(snake_case("initialize_vault"), "initialize_vault");
+        assert_eq!(snake_case("deposit"), "deposit");
+    }
+
+    #[test]
+    fn test_idl_differences() {
+        let program = json!({
+            "address": "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS",
+            "instructions": [{
+                "name": "set_authority",
+                "discriminator": [1, 2],
+                "accounts": [{ "name": "config", "writable": true }, { "name": "authority", "signer": true }],
+            }],
+            "errors": [{ "code": 6000, "name": "Locked", "msg": "Vault is locked" }],
+        });
+        assert!(messages(program.clone(), program.clone()).is_empty());
+
+        // An IDL from before Anchor 0.30 agreeing with the program.
+        let legacy = json!({
+            "metadata": { "address": "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS" },
+            "instructions": [{
+                "name": "setAuthority",
+                "accounts": [
+                    { "name": "config", "isMut": true, "isSigner": false },
+                    { "name": "authority", "isMut": false, "isSigner": true },
+                ],
+            }],
+            "errors": [{ "code": 6000, "name": "Locked", "msg": "Vault is locked" }],
+        });
+        assert!(messages(legacy, program.clone()).is_empty());
+
+        let stale = json!({
+            "address": "11111111111111111111111111111111",
+            "instructions": [
+                {
+                    "name": "set_authority",
+                    "discriminator": [1, 3],
+                    "accounts": [{ "name": "config" }, { "name": "authority", "signer": true }, { "name": "admin" }],
+                },
+                { "name": "close", "accounts": [] },
+            ],
+            "errors": [{ "code": 6001, "name": "Locked", "msg": "Locked" }, { "code": 6002, "name": "Paused" }],
+        });
+        assert_eq!(
+            messages(stale, program),
+            [
+                "error: `set_authority.config` is writable in the program but not in the IDL",
+                "error: discriminator of instruction `set_authority` is 0103 in the IDL but 0102 in the program",
+                "error: error `Locked` has code 6001 in the IDL but 6000 in the program",
+                "error: program id is 11111111111111111111111111111111 in the IDL but Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS in the program",
+                "warning: account `set_authority.admin` of the IDL is not in the program",
+                "warning: error `Locked` has message \"Locked\" in the IDL but \"Vault is locked\" in the program",
+                "warning: error `Paused` of the IDL is not in the program",
+                "warning: instruction `close` of the IDL is not in the program",
+            ]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use rustc_public::CrateDef;
+use rustc_public::mir::{Body, TerminatorKind};
+use rustc_public::ty::{RigidTy, Span, TyKind};
+
+use super::{AnalysisContext, Checker};
+use crate::analysis::datadep::used_locals;
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "ignored_check";
+/// Comparison methods of `PartialEq` and `PartialOrd`.
+const COMPARISONS: [&str; 6] = ["eq", "ne", "lt", "le", "gt", "ge"];
+const RESULT: &str = "std::result::Result";
+/// Code `#[program]` and the derives generate.
+const GENERATED: [&str; 2] = ["__private::", " as anchor_lang::"];
+
+/// Report comparisons and fallible calls whose outcome is dropped, e.g.
+/// `let _ = a.key() == b.key();` or `let _ = validate(amount);`: the check runs
+/// but nothing branches on it or propagates its error.
+///
+/// Comparisons of integers and other primitives are plain MIR operations that
+/// the compiler already removes when unused, so only comparisons through
+/// `PartialEq`/`PartialOrd` calls, such as on `Pubkey`, are left to find.
+pub struct IgnoredCheck;
+
+impl Checker for IgnoredCheck {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Comparisons and fallible calls whose outcome is dropped"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for &instance in ctx.local_instances() {
+            let name = instance.name();
+            if GENERATED.iter().any(|generated| name.contains(generated)) {
+                continue;
+            }
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            for (block, span, check) in ignored_checks(&body) {
+                let message = format!(
+                    "result of {check} at {} in `{name}` is never used",
+                    location(&span)
+                );
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .in_fn(instance)
+                        .key(&format!("bb{block}")),
+                );
+            }
+        }
+
+        findings
+    }
+}
+
+/// Calls in `body` returning a comparison or a `Result` nothing reads, with
+/// their block and a description of each.
+fn ignored_checks(body: &Body) -> Vec<(usize, Span, String)> {
+    let used = used_locals(body);
+    let mut checks = vec![];
+    for (bb, block) in body.blocks.iter().enumerate() {
+        let TerminatorKind::Call {
+            func, destination, ..
+        } = &block.terminator.kind
+        else {
+            continue;
+        };
+        if !destination.projection.is_empty() || used.contains(&destination.local) {
+            continue;
+        }
+        let Ok(fn_ty) = func.ty(body.locals()) else {
+            continue;
+        };
+        let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind() else {
+            continue;
+        };
+        let callee_name = fn_def.name();
+        let fn_name = callee_name.rsplit("::").next().unwrap_or(&callee_name);
+        let is_comparison = (callee_name.contains("PartialEq")
+            || callee_name.contains("PartialOrd"))
+            && COMPARISONS.contains(&fn_name);
+        let returns_result = destination
+            .ty(body.locals())
+            .is_ok_and(|ty| ty.to_string().starts_with(RESULT));
+        if is_comparison {
+            checks.push((bb, block.terminator.span, format!("`{fn_name}`")));
+        } else if returns_result {
+            checks.push((bb, block.terminator.span, format!("`{fn_name}()`")));
+        }
+    }
+    checks
+}
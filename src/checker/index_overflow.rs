@@ -0,0 +1,202 @@
+use std::collections::{BTreeSet, HashMap};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::{
+    BasicBlockIdx, BinOp, Body, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind,
+    TerminatorKind,
+};
+use rustc_public::ty::{RigidTy, Span, TyKind};
+
+use super::{AnalysisContext, Checker};
+use crate::analysis::reaching::{StmtIdx, backward_slice, location_span};
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "index_overflow";
+/// `Index::index` and `IndexMut::index_mut`, as called for `Vec` and slices.
+const INDEX_FNS: [&str; 2] = ["index", "index_mut"];
+
+/// Report indices computed with `+`, `-` or `*` on instruction arguments. The
+/// arithmetic panics with overflow checks on and silently wraps without them,
+/// both before any bounds check on the index can run.
+pub struct IndexOverflow;
+
+impl Checker for IndexOverflow {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Indices computed with overflowing arithmetic on instruction arguments"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for handler in ctx.handlers() {
+            let Some(body) = ctx.body(handler.instance) else {
+                continue;
+            };
+            // `_1` is the `Context`, the instruction arguments follow in order.
+            let args: Taint<usize> = (0..handler.args.len())
+                .map(|arg_idx| (arg_idx + 2, BTreeSet::from([arg_idx])))
+                .collect();
+            let args = propagate(&body, args);
+            for site in overflowing_indices(&body, &args) {
+                let arg_names: Vec<String> = site
+                    .labels
+                    .iter()
+                    .filter_map(|arg_idx| handler.args.get(*arg_idx))
+                    .map(|(name, _)| format!("`{name}`"))
+                    .collect();
+                let message = format!(
+                    "index at {} in `{}` is computed with arithmetic on {} that can overflow",
+                    location(&site.span),
+                    handler.instance.name(),
+                    arg_names.join(", ")
+                );
+                let (block, stmt) = site.location;
+                let mut finding = Finding::warning(CHECKER, message)
+                    .in_fn(handler.instance)
+                    .key(&format!("bb{block}[{stmt}]"));
+                let lines = slice_lines(&body, site.location, site.index);
+                if !lines.is_empty() {
+                    finding = finding.note(format!("computed at {}", lines.join(", ")));
+                }
+                findings.push(finding);
+            }
+        }
+
+        findings
+    }
+}
+
+/// An indexing site whose index can overflow.
+struct IndexSite {
+    location: (BasicBlockIdx, StmtIdx),
+    span: Span,
+    /// The local holding the index.
+    index: Local,
+    /// The arguments the overflowing arithmetic reads.
+    labels: BTreeSet<usize>,
+}
+
+/// Indexing sites of `body` whose index flows from `+`, `-` or `*` on locals
+/// tainted by `args`, with the labels of the arithmetic's operands.
+fn overflowing_indices(body: &Body, args: &Taint<usize>) -> Vec<IndexSite> {
+    let mut arithmetic: Taint<usize> = HashMap::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(
+                lhs,
+                Rvalue::BinaryOp(op, left, right) | Rvalue::CheckedBinaryOp(op, left, right),
+            ) = &stmt.kind
+                && is_overflowing(*op)
+            {
+                let labels = labels_of(args, [left, right].into_iter().filter_map(operand_local));
+                if !labels.is_empty() {
+                    arithmetic.entry(lhs.local).or_default().extend(labels);
+                }
+            }
+        }
+    }
+    if arithmetic.is_empty() {
+        return vec![];
+    }
+    let arithmetic = propagate(body, arithmetic);
+
+    let mut sites = vec![];
+    for (bb, block) in body.blocks.iter().enumerate() {
+        for (stmt_idx, stmt) in block.statements.iter().enumerate() {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            let Some(index) = std::iter::once(lhs)
+                .chain(rvalue_place(rvalue))
+                .flat_map(index_locals)
+                .find(|local| arithmetic.contains_key(local))
+            else {
+                continue;
+            };
+            sites.push(IndexSite {
+                location: (bb, stmt_idx),
+                span: stmt.span,
+                index,
+                labels: labels_of(&arithmetic, [index]),
+            });
+        }
+        let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+            continue;
+        };
+        if is_index_call(body, func)
+            && let Some(index) = args.get(1).and_then(operand_local)
+            && let Some(labels) = arithmetic.get(&index)
+        {
+            sites.push(IndexSite {
+                location: (bb, block.statements.len()),
+                span: block.terminator.span,
+                index,
+                labels: labels.clone(),
+            });
+        }
+    }
+    sites
+}
+
+/// The source lines the value of `index` at `site` is computed on, in order.
+fn slice_lines(body: &Body, site: (BasicBlockIdx, StmtIdx), index: Local) -> Vec<String> {
+    let mut spans: Vec<Span> = backward_slice(body, site, index)
+        .into_iter()
+        .map(|def| location_span(body, def))
+        .collect();
+    spans.sort_by_key(|span| (span.get_filename(), span.get_lines().start_line));
+    let mut lines: Vec<String> = spans.iter().map(location).collect();
+    lines.dedup();
+    lines
+}
+
+fn is_overflowing(op: BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::Add
+            | BinOp::AddUnchecked
+            | BinOp::Sub
+            | BinOp::SubUnchecked
+            | BinOp::Mul
+            | BinOp::MulUnchecked
+    )
+}
+
+/// The place an rvalue reads, if it reads one directly.
+fn rvalue_place(rvalue: &Rvalue) -> Option<&Place> {
+    match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+        | Rvalue::Ref(_, _, place)
+        | Rvalue::AddressOf(_, place)
+        | Rvalue::CopyForDeref(place) => Some(place),
+        _ => None,
+    }
+}
+
+/// Locals used as an index in the projection of `place`, e.g. `i` in `(*_5).0[i]`.
+fn index_locals(place: &Place) -> impl Iterator<Item = Local> + '_ {
+    place.projection.iter().filter_map(|elem| match elem {
+        ProjectionElem::Index(local) => Some(*local),
+        _ => None,
+    })
+}
+
+/// Whether `func` is `Index::index` or `IndexMut::index_mut`.
+fn is_index_call(body: &Body, func: &Operand) -> bool {
+    let Ok(fn_ty) = func.ty(body.locals()) else {
+        return false;
+    };
+    let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind() else {
+        return false;
+    };
+    let name = fn_def.name();
+    name.contains("std::ops::Index")
+        && name
+            .rsplit("::")
+            .next()
+            .is_some_and(|fn_name| INDEX_FNS.contains(&fn_name))
+}
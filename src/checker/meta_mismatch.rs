@@ -0,0 +1,74 @@
+use rustc_public::CrateDef;
+
+use super::{AnalysisContext, Checker};
+use crate::anchor_info::constraint::{constraint_error_codes, declared_mut_fields};
+use crate::anchor_info::{ANCHOR_ACCOUNTS, accounts_impl_fn};
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "account_meta_mismatch";
+
+/// Report fields whose client `AccountMeta` disagrees with the mutability the
+/// Accounts struct declares. A `mut` field sent readonly makes every call fail
+/// the `mut` check (or the write, for `init` and `close`); a readonly field sent
+/// writable locks the account for nothing.
+///
+/// The clients `#[derive(Accounts)]` generates always agree with the struct, so
+/// this catches hand-written or stale copies of them.
+pub struct AccountMetaMismatch;
+
+impl Checker for AccountMetaMismatch {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Client AccountMetas disagreeing with the mutability the Accounts struct declares"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        let metas: Vec<_> = ctx
+            .to_account_metas()
+            .iter()
+            .chain(ctx.cpi_account_metas())
+            .collect();
+        for anchor_accounts in ctx.anchor_accounts() {
+            let name = &anchor_accounts.name;
+            // Without `try_accounts` we know nothing of the declared mutability.
+            if accounts_impl_fn(name, ANCHOR_ACCOUNTS, "try_accounts").is_none() {
+                continue;
+            }
+            let codes = constraint_error_codes(name);
+            let declared_mut = declared_mut_fields(anchor_accounts, &codes);
+            for meta in metas.iter().filter(|meta| &meta.struct_name == name) {
+                let Some(account) = anchor_accounts.anchor_accounts.get(meta.field_idx) else {
+                    continue;
+                };
+                let client = if meta.is_cpi { "CPI client" } else { "client" };
+                let message = match (declared_mut.contains(&meta.field_idx), meta.writable) {
+                    (true, false) => format!(
+                        "account `{}` in `{name}` is declared mut but the {client} sends it readonly",
+                        account.name
+                    ),
+                    (false, true) => format!(
+                        "account `{}` in `{name}` is not declared mut but the {client} sends it writable",
+                        account.name
+                    ),
+                    _ => continue,
+                };
+                findings.push(
+                    Finding::error(CHECKER, message)
+                        .at(&anchor_accounts.adt_def)
+                        .key(&account.name)
+                        .key(&meta.client.name()),
+                );
+            }
+        }
+
+        findings
+    }
+}
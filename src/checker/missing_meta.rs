@@ -0,0 +1,119 @@
+use std::collections::{BTreeSet, HashSet};
+
+use rustc_public::CrateDef;
+use rustc_public::ty::{AdtDef, RigidTy};
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::anchor_info::AnchorAccounts;
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "missing_account_meta";
+
+/// Report fields of an Accounts struct that a client of it never builds an
+/// `AccountMeta` for, and metas a client builds for fields past the end of the
+/// struct. The client then sends fewer or more accounts than the instruction
+/// reads, shifting every later account into the wrong field.
+///
+/// Each client struct is compared on its own, so a stale hand-written copy is
+/// reported even when the generated one is complete. Nested Accounts structs
+/// add the metas of their own client and are left out.
+pub struct MissingAccountMeta;
+
+impl Checker for MissingAccountMeta {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Clients building too few or too many AccountMetas for an Accounts struct"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let nested: HashSet<AdtDef> = ctx
+            .anchor_accounts()
+            .iter()
+            .map(|anchor_accounts| anchor_accounts.adt_def)
+            .collect();
+        let mut findings = vec![];
+        for anchor_accounts in ctx.anchor_accounts() {
+            let name = &anchor_accounts.name;
+            // Field indices sent by each client of the struct, in the order the
+            // metas are found.
+            let mut clients: Vec<(bool, AdtDef, BTreeSet<usize>)> = vec![];
+            for meta in ctx
+                .to_account_metas()
+                .iter()
+                .chain(ctx.cpi_account_metas())
+                .filter(|meta| &meta.struct_name == name)
+            {
+                match clients
+                    .iter_mut()
+                    .find(|(is_cpi, client, _)| (*is_cpi, *client) == (meta.is_cpi, meta.client))
+                {
+                    Some((_, _, sent)) => {
+                        sent.insert(meta.field_idx);
+                    }
+                    None => {
+                        clients.push((meta.is_cpi, meta.client, BTreeSet::from([meta.field_idx])))
+                    }
+                }
+            }
+            let fields = &anchor_accounts.anchor_accounts;
+            for (is_cpi, client, sent) in clients {
+                let side = if is_cpi { "CPI client" } else { "client" };
+                for (field_idx, account) in fields.iter().enumerate() {
+                    if sent.contains(&field_idx)
+                        || nested_field(anchor_accounts, field_idx)
+                            .is_some_and(|adt_def| nested.contains(&adt_def))
+                    {
+                        continue;
+                    }
+                    let message = format!(
+                        "account `{}` in `{name}` has no meta in the {side} `{}`",
+                        account.name,
+                        client.name()
+                    );
+                    findings.push(
+                        Finding::error(CHECKER, message)
+                            .at(&anchor_accounts.adt_def)
+                            .key(&account.name)
+                            .key(&client.name()),
+                    );
+                }
+                for field_idx in sent.range(fields.len()..) {
+                    let field = client
+                        .variants_iter()
+                        .next()
+                        .and_then(|variant| variant.fields().get(*field_idx).cloned())
+                        .map_or_else(|| format!("#{field_idx}"), |field| field.name);
+                    let message = format!(
+                        "the {side} `{}` sends a meta for `{field}`, which `{name}` does not have",
+                        client.name()
+                    );
+                    findings.push(
+                        Finding::error(CHECKER, message)
+                            .at(&anchor_accounts.adt_def)
+                            .key(&field)
+                            .key(&client.name()),
+                    );
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// The struct held by field `field_idx` of `anchor_accounts`, if any.
+fn nested_field(anchor_accounts: &AnchorAccounts, field_idx: usize) -> Option<AdtDef> {
+    let variant = anchor_accounts.adt_def.variants_iter().next()?;
+    let field = variant.fields().into_iter().nth(field_idx)?;
+    match field.ty().kind().rigid()? {
+        RigidTy::Adt(adt_def, _) => Some(*adt_def),
+        _ => None,
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::{BTreeSet, HashSet};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::{
+    AggregateKind, Body, BorrowKind, Local, Rvalue, StatementKind, TerminatorKind,
+    VarDebugInfoContents,
+};
+use rustc_public::ty::Span;
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::condition::{Definitions, field_path};
+use crate::analysis::entry::EntryKind;
+use crate::analysis::graph::{DirectedGraph, Fixpoints, PostDominators};
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::anchor_info::remaining::{callee_name, read_place};
+use crate::native_info::accounts::decoded_type;
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "missing_writeback";
+const DATA: &str = "AccountInfo.data";
+/// `AccountInfo::try_borrow_data` and `try_borrow_mut_data`.
+const BORROW_DATA: [&str; 2] = ["::try_borrow_data", "::try_borrow_mut_data"];
+/// Borsh fns decoding account data into a value.
+const DESERIALIZE_FNS: [&str; 2] = ["::try_from_slice", "::deserialize"];
+/// `BorshSerialize::serialize`, taking the value and the writer.
+const SERIALIZE: &str = "::serialize";
+/// `FromResidual::from_residual`, the error path of `?`.
+const FROM_RESIDUAL: &str = "::from_residual";
+const RESULT: &str = "std::result::Result";
+const ERR: &str = "Err";
+
+/// Report native fns that decode account data with Borsh, change the decoded
+/// value, and do not serialize it back into the same account's data on every
+/// path returning `Ok`. Unlike Anchor, which writes `Account<T>` back in
+/// `exit`, a native program only changes the copy, and the instruction
+/// succeeds without doing anything.
+///
+/// Paths returning an error are left out, as the runtime discards the changes
+/// of a failed instruction anyway. Write-backs in helpers the fn calls are not
+/// followed.
+pub struct MissingWriteback;
+
+impl Checker for MissingWriteback {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Deserialized account data changed but never serialized back"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        if !ctx
+            .entrypoints()
+            .iter()
+            .any(|entry| entry.kind == EntryKind::Native)
+        {
+            return vec![];
+        }
+        let mut findings = vec![];
+        for &instance in ctx.reachable_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            for (block, span, ty, account) in missing_writebacks(&body, ctx.fixpoints()) {
+                let message = format!(
+                    "`{}` changes the `{ty}` decoded from the data of `{account}` at {} without serializing it back on every path",
+                    instance.name(),
+                    location(&span)
+                );
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .in_fn(instance)
+                        .key(&format!("bb{block}"))
+                        .key(&account),
+                );
+            }
+        }
+        findings
+    }
+}
+
+/// The decodings of account data in `body` whose value is changed but not
+/// written back on every `Ok` path after the change, as the block of the
+/// decoding call, its span, the decoded type and the account.
+fn missing_writebacks(body: &Body, fixpoints: &Fixpoints) -> Vec<(usize, Span, String, String)> {
+    let data = account_data(body);
+    if data.is_empty() {
+        return vec![];
+    }
+    // Decoded values, by the decoding call's block.
+    let mut decoded: Taint<usize> = Taint::new();
+    let mut decodings = vec![];
+    for (bb, block) in body.blocks.iter().enumerate() {
+        if let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+            && callee_name(body, func)
+                .is_some_and(|name| DESERIALIZE_FNS.iter().any(|suffix| name.ends_with(suffix)))
+            && let accounts = labels_of(&data, args.iter().filter_map(operand_local))
+            && !accounts.is_empty()
+        {
+            decoded.insert(destination.local, BTreeSet::from([bb]));
+            decodings.push((bb, accounts));
+        }
+    }
+    if decodings.is_empty() {
+        return vec![];
+    }
+    let decoded = propagate(body, decoded);
+
+    // Blocks changing each decoded value, and writing it back into each account.
+    let mut changes: Vec<(usize, usize)> = vec![];
+    let mut writebacks: Vec<(usize, usize, Local)> = vec![];
+    for (bb, block) in body.blocks.iter().enumerate() {
+        for stmt in &block.statements {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            let mut changed = BTreeSet::new();
+            if !lhs.projection.is_empty() {
+                changed.extend(labels_of(&decoded, [lhs.local]));
+            }
+            if let Rvalue::Ref(_, BorrowKind::Mut { .. }, place) = rvalue {
+                changed.extend(labels_of(&decoded, [place.local]));
+            }
+            changes.extend(changed.into_iter().map(|decoding| (decoding, bb)));
+        }
+        if let TerminatorKind::Call { func, args, .. } = &block.terminator.kind
+            && callee_name(body, func).is_some_and(|name| name.ends_with(SERIALIZE))
+            && let [value, writer, ..] = &args[..]
+        {
+            for decoding in labels_of(&decoded, operand_local(value)) {
+                for account in labels_of(&data, operand_local(writer)) {
+                    writebacks.push((decoding, bb, account));
+                }
+            }
+        }
+    }
+
+    let post_dominators = ok_post_dominators(body, fixpoints);
+    let mut missing = vec![];
+    for (decoding, accounts) in decodings {
+        for account in accounts {
+            let unsaved = changes
+                .iter()
+                .filter(|(changed, _)| *changed == decoding)
+                .any(|(_, bb)| {
+                    post_dominators.is_reachable(bb)
+                        && !writebacks.iter().any(|(saved, writeback, saved_account)| {
+                            *saved == decoding
+                                && *saved_account == account
+                                && post_dominators.is_post_dominated_by(bb, writeback)
+                        })
+                });
+            if !unsaved {
+                continue;
+            }
+            let TerminatorKind::Call { destination, .. } = &body.blocks[decoding].terminator.kind
+            else {
+                continue;
+            };
+            let ty = destination
+                .ty(body.locals())
+                .ok()
+                .and_then(decoded_type)
+                .unwrap_or_else(|| "value".to_string());
+            missing.push((
+                decoding,
+                body.blocks[decoding].terminator.span,
+                ty,
+                local_name(body, account),
+            ));
+        }
+    }
+    missing
+}
+
+/// Locals holding account data, labeled with the local of the `&AccountInfo`
+/// it was borrowed from.
+fn account_data(body: &Body) -> Taint<Local> {
+    let defs = Definitions::new(body);
+    let mut sources: Taint<Local> = Taint::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(lhs, rvalue) = &stmt.kind
+                && let Some(place) = read_place(rvalue)
+                && defs
+                    .field_parent(place)
+                    .is_some_and(|(ty, field_idx)| field_path(ty, field_idx) == DATA)
+            {
+                sources.insert(lhs.local, BTreeSet::from([place.local]));
+            }
+        }
+        if let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+            && callee_name(body, func)
+                .is_some_and(|name| BORROW_DATA.iter().any(|suffix| name.ends_with(suffix)))
+            && let Some(account) = args.first().and_then(operand_local)
+        {
+            sources.insert(destination.local, BTreeSet::from([account]));
+        }
+    }
+    if sources.is_empty() {
+        return sources;
+    }
+    propagate(body, sources)
+}
+
+/// Post-dominators of the blocks of `body` over the paths that return `Ok`:
+/// the blocks setting the return value to an error, with `?` or
+/// `return Err(..)`, and the blocks that never return, e.g. unwinding, are
+/// left out.
+fn ok_post_dominators(body: &Body, fixpoints: &Fixpoints) -> PostDominators<usize> {
+    let errors: HashSet<usize> = body
+        .blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block)| {
+            let returns_err = block.statements.iter().any(|stmt| {
+                matches!(
+                    &stmt.kind,
+                    StatementKind::Assign(lhs, Rvalue::Aggregate(AggregateKind::Adt(adt_def, variant, ..), _))
+                        if lhs.local == 0
+                            && lhs.projection.is_empty()
+                            && adt_def.name() == RESULT
+                            && adt_def.variant(*variant).is_some_and(|variant| variant.name() == ERR)
+                )
+            });
+            let from_residual = matches!(
+                &block.terminator.kind,
+                TerminatorKind::Call { func, destination, .. }
+                    if destination.local == 0
+                        && callee_name(body, func).is_some_and(|name| name.ends_with(FROM_RESIDUAL))
+            );
+            returns_err || from_residual
+        })
+        .map(|(bb, _)| bb)
+        .collect();
+
+    // Blocks reaching a `return` without going through an error block.
+    let mut returning: HashSet<usize> = body
+        .blocks
+        .iter()
+        .enumerate()
+        .filter(|(bb, block)| {
+            matches!(block.terminator.kind, TerminatorKind::Return) && !errors.contains(bb)
+        })
+        .map(|(bb, _)| bb)
+        .collect();
+    loop {
+        let known = returning.len();
+        for (bb, block) in body.blocks.iter().enumerate() {
+            if !errors.contains(&bb)
+                && block
+                    .terminator
+                    .successors()
+                    .iter()
+                    .any(|succ| returning.contains(succ))
+            {
+                returning.insert(bb);
+            }
+        }
+        if returning.len() == known {
+            break;
+        }
+    }
+
+    let mut graph = DirectedGraph::new();
+    for &bb in &returning {
+        graph.add_node(bb);
+    }
+    for &bb in &returning {
+        for succ in body.blocks[bb].terminator.successors() {
+            if returning.contains(&succ) {
+                graph.add_edge(bb, succ);
+            }
+        }
+    }
+    fixpoints.post_dominators(&graph)
+}
+
+/// The variable `local` is bound to, or `_n`.
+fn local_name(body: &Body, local: Local) -> String {
+    body.var_debug_info
+        .iter()
+        .find_map(|info| match &info.value {
+            VarDebugInfoContents::Place(place)
+                if place.local == local && place.projection.is_empty() =>
+            {
+                Some(info.name.clone())
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| format!("_{local}"))
+}
@@ -1,65 +1,359 @@
-use crate::{analysis::callgraph, anchor_info::{find_to_account_metas, local_anchor_accounts, AnchorAccountKind}};
-
-pub fn detect_duplicate_mutable_account() {
-    let res = find_to_account_metas();
-    // println!("{:?}", res);
-
-    let anchor_accounts_collection = local_anchor_accounts();
-    // println!("{:?}", anchor_accounts_collection);
-    for anchor_accounts in anchor_accounts_collection {
-        // println!("{}", anchor_accounts.name);
-        let mut muts = vec![];
-        for (name, mutability, field_idx) in res.iter() {
-            if name == &anchor_accounts.name {
-                muts.push((field_idx, mutability));
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
+
+use thiserror::Error;
+use tracing::info_span;
+
+use crate::config::Config;
+use crate::report::timings::{PassKind, PassTiming};
+use crate::report::{Finding, Level, Reporter, Severity};
+
+mod account_roles;
+mod authority_substitution;
+mod bump;
+mod close;
+mod compute;
+mod context;
+mod cpi_signer;
+mod data_length;
+mod dependency;
+mod discriminator;
+mod duplicate_mutable;
+mod finalization;
+mod float_round;
+mod foreign_write;
+mod idl_drift;
+mod ignored_check;
+mod index_overflow;
+mod meta_mismatch;
+mod missing_meta;
+mod missing_writeback;
+mod native_duplicate;
+mod native_program_id;
+mod oracle;
+mod over_permissioned;
+mod payer;
+mod reload;
+mod remaining_accounts;
+mod seeds;
+mod signer_seeds;
+mod singleton;
+mod stack;
+mod sysvar;
+mod timestamp;
+mod token_mint;
+mod token_owner;
+mod unchecked_unpack;
+mod user_seeds;
+
+pub use account_roles::ConflictingAccountRoles;
+pub use authority_substitution::AuthoritySubstitution;
+pub use bump::MissingBumpPersistence;
+pub use close::CloseDestination;
+pub use compute::ComputeHeavyLoop;
+pub use context::{AnalysisContext, InstructionHandler, STATS_ENV};
+pub use cpi_signer::CpiSignerPropagation;
+pub use data_length::UncheckedDataLength;
+pub use dependency::VulnerableDependency;
+pub use discriminator::DiscriminatorCollision;
+pub use duplicate_mutable::DuplicateMutableAccount;
+pub use finalization::SkippedFinalization;
+pub use float_round::FloatRound;
+pub use foreign_write::ForeignAccountWrite;
+pub use idl_drift::IdlDrift;
+pub use ignored_check::IgnoredCheck;
+pub use index_overflow::IndexOverflow;
+pub use meta_mismatch::AccountMetaMismatch;
+pub use missing_meta::MissingAccountMeta;
+pub use missing_writeback::MissingWriteback;
+pub use native_duplicate::NativeDuplicateMutableAccount;
+pub use native_program_id::HardcodedProgramId;
+pub use oracle::UnpinnedOracleAccount;
+pub use over_permissioned::OverPermissionedAccount;
+pub use payer::NonSignerPayer;
+pub use reload::StaleReadAfterCpi;
+pub use remaining_accounts::UncheckedRemainingAccounts;
+pub use seeds::NearMissSeed;
+pub use signer_seeds::UserSignerSeeds;
+pub use singleton::UnpinnedSingletonAccount;
+pub use stack::LargeStackFrame;
+pub use sysvar::UncheckedSysvarAccount;
+pub use timestamp::FragileTimestampCheck;
+pub use token_mint::UncheckedTokenMint;
+pub use token_owner::UncheckedTokenOwner;
+pub use unchecked_unpack::UncheckedUnpack;
+pub use user_seeds::UserControlledSeeds;
+
+/// A checker run over the state shared by all checkers of a run.
+pub trait Checker {
+    /// Name the findings, `#[cfx::allow(..)]` and the config levels use.
+    fn id(&self) -> &'static str;
+    /// One line on what the checker reports.
+    fn description(&self) -> &'static str;
+    /// The severity of its findings, unless the config sets another level.
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding>;
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    #[error("a checker with id `{0}` is already registered")]
+    DuplicateId(&'static str),
+    #[error("no checker has id `{0}`")]
+    UnknownId(String),
+}
+
+/// The checkers of a run by id, so they run and are listed in the same order
+/// whatever order they were registered in.
+#[derive(Default)]
+pub struct CheckerRegistry {
+    checkers: BTreeMap<&'static str, Box<dyn Checker>>,
+    disabled: BTreeSet<&'static str>,
+}
+
+impl CheckerRegistry {
+    /// The checkers of the analyzer.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        let checkers: [Box<dyn Checker>; 36] = [
+            Box::new(DuplicateMutableAccount),
+            Box::new(CloseDestination),
+            Box::new(UserControlledSeeds),
+            Box::new(UncheckedDataLength),
+            Box::new(UncheckedUnpack),
+            Box::new(AuthoritySubstitution),
+            Box::new(HardcodedProgramId),
+            Box::new(MissingWriteback),
+            Box::new(MissingAccountMeta),
+            Box::new(NativeDuplicateMutableAccount),
+            Box::new(SkippedFinalization),
+            Box::new(LargeStackFrame),
+            Box::new(NonSignerPayer),
+            Box::new(UserSignerSeeds),
+            Box::new(VulnerableDependency),
+            Box::new(UncheckedSysvarAccount),
+            Box::new(MissingBumpPersistence),
+            Box::new(ConflictingAccountRoles),
+            Box::new(UncheckedTokenMint),
+            Box::new(CpiSignerPropagation),
+            Box::new(FloatRound),
+            Box::new(OverPermissionedAccount),
+            Box::new(UnpinnedOracleAccount),
+            Box::new(FragileTimestampCheck),
+            Box::new(UnpinnedSingletonAccount),
+            Box::new(ComputeHeavyLoop),
+            Box::new(StaleReadAfterCpi),
+            Box::new(UncheckedTokenOwner),
+            Box::new(DiscriminatorCollision),
+            Box::new(IndexOverflow),
+            Box::new(ForeignAccountWrite),
+            Box::new(IgnoredCheck),
+            Box::new(AccountMetaMismatch),
+            Box::new(NearMissSeed),
+            Box::new(UncheckedRemainingAccounts),
+            Box::new(IdlDrift),
+        ];
+        for checker in checkers {
+            registry
+                .register(checker)
+                .expect("builtin checker ids are unique");
+        }
+        registry
+    }
+
+    /// The builtin checkers, without those `config` turns off.
+    pub fn configured(config: &Config) -> Self {
+        let mut registry = Self::builtin();
+        for (id, level) in &config.checkers {
+            if *level == Level::Off {
+                // Ids of no checker, like `fixpoint_limit`, are left to the `Reporter`.
+                registry.set_enabled(id, false).ok();
             }
         }
-        let mut final_res = vec![];
-        for (idx, anchor_account) in anchor_accounts.anchor_accounts.iter().enumerate() {
-            // println!("- {idx}: {:?}", &anchor_account);
-            let mut mu = None;
-            for (field_idx, mutability) in muts.iter() {
-                if *field_idx == &idx {
-                    mu = Some(*mutability);
-                    break;
-                }
+        registry
+    }
+
+    /// Turn off the registered checkers `selection` leaves out.
+    pub fn select(&mut self, selection: &CheckerSelection) {
+        for id in self.checkers.keys() {
+            if !selection.is_selected(id) {
+                self.disabled.insert(id);
             }
-            // println!("- {idx}: {:?} {:?}", mu, &anchor_account);
-            final_res.push((anchor_account, mu));
         }
+    }
+
+    /// Add `checker`, unless another checker already has its id.
+    pub fn register(&mut self, checker: Box<dyn Checker>) -> Result<(), RegistryError> {
+        let id = checker.id();
+        if self.checkers.contains_key(id) {
+            return Err(RegistryError::DuplicateId(id));
+        }
+        self.checkers.insert(id, checker);
+        Ok(())
+    }
+
+    /// The registered checkers, by id.
+    pub fn checkers(&self) -> impl Iterator<Item = &dyn Checker> {
+        self.checkers.values().map(|checker| checker.as_ref())
+    }
+
+    /// Turn the checker with id `id` on or off for this registry's runs.
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> Result<(), RegistryError> {
+        let Some((&id, _)) = self.checkers.get_key_value(id) else {
+            return Err(RegistryError::UnknownId(id.to_string()));
+        };
+        if enabled {
+            self.disabled.remove(id);
+        } else {
+            self.disabled.insert(id);
+        }
+        Ok(())
+    }
+
+    /// Whether the checker with id `id` runs.
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.checkers.contains_key(id) && !self.disabled.contains(id)
+    }
 
-        let len = final_res.len();
-        for i in 0..len {
-            for j in i + 1..len {
-                if final_res[i].1 == Some(&"mut") && final_res[j].1 == Some(&"mut") {
-                    match (final_res[i].0.kind.clone(), final_res[j].0.kind.clone()) {
-                        (
-                            AnchorAccountKind::Account(i_struct),
-                            AnchorAccountKind::Account(j_struct),
-                        ) if i_struct == j_struct => {
-                            println!(
-                                "Find error: two mutable accounts of the same type in the same Context: {:?} {:?}",
-                                final_res[i], final_res[j]
-                            );
-                        }
-                        _ => {}
-                    }
-                }
+    /// Run every enabled checker, handing their findings to `reporter`, and
+    /// return what each took with how many findings it had. The parts of `ctx`
+    /// a checker is the first to ask for count in its time.
+    pub fn run(&self, ctx: &AnalysisContext, reporter: &Reporter) -> Vec<PassTiming> {
+        let mut timings = vec![];
+        for (id, checker) in &self.checkers {
+            if !self.is_enabled(id) {
+                continue;
+            }
+            let _span = info_span!("checker", id).entered();
+            let start = Instant::now();
+            let findings = checker.run(ctx);
+            timings.push(PassTiming::new(
+                *id,
+                PassKind::Checker,
+                start.elapsed(),
+                findings.len(),
+            ));
+            for finding in findings {
+                reporter.report(finding);
             }
         }
+        timings
     }
 }
 
-const F32_ROUND: &'static str = "f32::<impl f32>::round";
-const F64_ROUND: &'static str = "f64::<impl f64>::round";
+/// The checkers picked on the command line: those of `only`, or all of them
+/// when it is `None`, without those of `skip`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CheckerSelection {
+    pub only: Option<BTreeSet<String>>,
+    pub skip: BTreeSet<String>,
+}
+
+impl CheckerSelection {
+    pub fn is_selected(&self, id: &str) -> bool {
+        self.only.as_ref().is_none_or(|only| only.contains(id)) && !self.skip.contains(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Named(&'static str);
 
-pub fn detect_float_round_fn() {
-    let instances = callgraph::compute_instances();
-    for instance in instances {
-        let name = instance.name();
-        println!("{name}");
-        if name.contains(F32_ROUND) || name.contains(F64_ROUND) {
-            println!("Contains f32::round or f64::round: {}", name);
+    impl Checker for Named {
+        fn id(&self) -> &'static str {
+            self.0
+        }
+
+        fn description(&self) -> &'static str {
+            "test checker"
+        }
+
+        fn run(&self, _: &AnalysisContext) -> Vec<Finding> {
+            vec![]
         }
     }
-}
\ No newline at end of file
+
+    fn ids(registry: &CheckerRegistry) -> Vec<&'static str> {
+        registry.checkers().map(|checker| checker.id()).collect()
+    }
+
+    #[test]
+    fn test_duplicate_id_rejected() {
+        let mut registry = CheckerRegistry::default();
+        assert_eq!(registry.register(Box::new(Named("seeds"))), Ok(()));
+        assert_eq!(
+            registry.register(Box::new(Named("seeds"))),
+            Err(RegistryError::DuplicateId("seeds"))
+        );
+        assert_eq!(ids(&registry), ["seeds"]);
+    }
+
+    #[test]
+    fn test_order_by_id() {
+        let mut forward = CheckerRegistry::default();
+        let mut backward = CheckerRegistry::default();
+        for id in ["b", "c", "a"] {
+            forward.register(Box::new(Named(id))).unwrap();
+        }
+        for id in ["a", "c", "b"] {
+            backward.register(Box::new(Named(id))).unwrap();
+        }
+        assert_eq!(ids(&forward), ["a", "b", "c"]);
+        assert_eq!(ids(&forward), ids(&backward));
+
+        let builtin = ids(&CheckerRegistry::builtin());
+        assert_eq!(builtin.len(), 36);
+        assert!(builtin.is_sorted());
+    }
+
+    #[test]
+    fn test_enable_by_id() {
+        let mut registry = CheckerRegistry::builtin();
+        assert!(registry.is_enabled("duplicate_mutable_account"));
+        registry
+            .set_enabled("duplicate_mutable_account", false)
+            .unwrap();
+        assert!(!registry.is_enabled("duplicate_mutable_account"));
+        assert!(registry.is_enabled("unchecked_unpack"));
+        registry
+            .set_enabled("duplicate_mutable_account", true)
+            .unwrap();
+        assert!(registry.is_enabled("duplicate_mutable_account"));
+        assert_eq!(
+            registry.set_enabled("no_such_checker", false),
+            Err(RegistryError::UnknownId("no_such_checker".to_string()))
+        );
+        assert!(!registry.is_enabled("no_such_checker"));
+    }
+
+    #[test]
+    fn test_select() {
+        let mut registry = CheckerRegistry::builtin();
+        let selection = CheckerSelection {
+            only: Some(BTreeSet::from([
+                "unchecked_unpack".to_string(),
+                "missing_writeback".to_string(),
+            ])),
+            skip: BTreeSet::from(["missing_writeback".to_string()]),
+        };
+        registry.select(&selection);
+        assert!(registry.is_enabled("unchecked_unpack"));
+        assert!(!registry.is_enabled("missing_writeback"));
+        assert!(!registry.is_enabled("duplicate_mutable_account"));
+        assert!(CheckerSelection::default().is_selected("no_such_checker"));
+    }
+
+    #[test]
+    fn test_configured_levels() {
+        let config: Config =
+            toml::from_str("[checkers]\nunchecked_unpack = \"off\"\nfixpoint_limit = \"off\"\n")
+                .unwrap();
+        let registry = CheckerRegistry::configured(&config);
+        assert!(!registry.is_enabled("unchecked_unpack"));
+        assert!(registry.is_enabled("missing_writeback"));
+    }
+}
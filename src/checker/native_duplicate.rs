@@ -0,0 +1,89 @@
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::keys::key_comparisons;
+use crate::analysis::loops::predecessors;
+use crate::native_info::accounts::{NativeAccount, account_positions};
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "native_duplicate_mutable_account";
+
+/// Report native handlers writing two of their accounts whose data they
+/// deserialize into the same type, when no comparison of the two keys
+/// dominates every write. As with `duplicate_mutable_account` for Anchor, a
+/// client can pass the same account twice, and the last write overwrites the
+/// first, e.g. crediting points that were never debited.
+pub struct NativeDuplicateMutableAccount;
+
+impl Checker for NativeDuplicateMutableAccount {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Native handlers writing two same-type accounts without comparing their keys"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for model in ctx.native_accounts() {
+            let writable: Vec<(&NativeAccount, &String)> = model
+                .accounts
+                .iter()
+                .filter(|account| account.writable())
+                .filter_map(|account| Some((account, account.deserialized_as.as_ref()?)))
+                .collect();
+            if writable.len() < 2 {
+                continue;
+            }
+            let Some(body) = ctx.body(model.handler) else {
+                continue;
+            };
+            let comparisons = key_comparisons(&body, &account_positions(&body));
+            let dominators = ctx
+                .fixpoints()
+                .dominators(body.blocks.len(), &predecessors(&body));
+            for (i, (a, a_ty)) in writable.iter().enumerate() {
+                for (b, b_ty) in writable.iter().skip(i + 1) {
+                    if a_ty != b_ty {
+                        continue;
+                    }
+                    let checked = comparisons.iter().any(|comparison| {
+                        comparison.between(&a.position, &b.position)
+                            && a.writes
+                                .iter()
+                                .chain(&b.writes)
+                                .all(|write| dominators.dominates(&comparison.block, write))
+                    });
+                    if checked {
+                        continue;
+                    }
+                    let message = format!(
+                        "`{}` writes accounts {} and {}, both deserialized as `{a_ty}`, without checking that their keys differ",
+                        model.handler.name(),
+                        describe(a),
+                        describe(b)
+                    );
+                    findings.push(
+                        Finding::error(CHECKER, message)
+                            .in_fn(model.handler)
+                            .key(&a.position.to_string())
+                            .key(&b.position.to_string()),
+                    );
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// `#0 `from``, or `#0` for an account bound to no variable.
+fn describe(account: &NativeAccount) -> String {
+    match &account.name {
+        Some(name) => format!("#{} `{name}`", account.position),
+        None => format!("#{}", account.position),
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::BTreeSet;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{Body, Operand, Rvalue, StatementKind, TerminatorKind};
+use rustc_public::ty::Span;
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::callgraph::callee;
+use crate::analysis::condition::{Definitions, field_path};
+use crate::analysis::entry::EntryKind;
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate, rvalue_locals};
+use crate::anchor_info::program_id::{ProgramId, is_id_const, is_id_fn};
+use crate::anchor_info::remaining::{callee_name, read_place};
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "hardcoded_program_id";
+const OWNER: &str = "AccountInfo.owner";
+/// The `program_id` argument of the fn `entrypoint!` is given.
+const PROGRAM_ID_ARG: usize = 1;
+/// What goes wrong, the end of both messages.
+const HAZARD: &str = "redeployed under another id, the program rejects its own accounts and trusts those the old id owns";
+
+/// Report native programs that declare their id with `declare_id!` and check
+/// account owners against that constant instead of the `program_id` the
+/// runtime passes to the entrypoint, or never use the `program_id` at all. The
+/// constant is fixed at build time, so a program deployed at another address
+/// still checks for the old one.
+pub struct HardcodedProgramId;
+
+impl Checker for HardcodedProgramId {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Owner checks against the declared id instead of the runtime's program_id"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let Ok(id) = *ctx.program_id() else {
+            return vec![];
+        };
+        let native: Vec<Instance> = ctx
+            .entrypoints()
+            .iter()
+            .filter(|entry| entry.kind == EntryKind::Native)
+            .map(|entry| entry.instance)
+            .collect();
+        if native.is_empty() {
+            return vec![];
+        }
+        let mut findings = vec![];
+        for entry in native {
+            if let Some(body) = ctx.body(entry)
+                && !uses_local(&body, PROGRAM_ID_ARG)
+            {
+                let message = format!(
+                    "`{}` never uses its `program_id` argument, while the program declares its id as {id}; {HAZARD}",
+                    entry.name()
+                );
+                findings.push(Finding::warning(CHECKER, message).in_fn(entry));
+            }
+        }
+        for &instance in ctx.reachable_instances() {
+            if is_id_fn(&instance.name()) {
+                continue;
+            }
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            for (block, span) in owner_checks_against_id(&body, id) {
+                let message = format!(
+                    "`{}` compares an account owner with the declared program id at {} instead of the `program_id` passed to the entrypoint; {HAZARD}",
+                    instance.name(),
+                    location(&span)
+                );
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .in_fn(instance)
+                        .key(&format!("bb{block}")),
+                );
+            }
+        }
+        findings
+    }
+}
+
+/// Whether `body` reads `local` in an assignment or a call.
+fn uses_local(body: &Body, local: usize) -> bool {
+    body.blocks.iter().any(|block| {
+        let in_statement = block.statements.iter().any(|stmt| match &stmt.kind {
+            StatementKind::Assign(_, rvalue) => rvalue_locals(rvalue).contains(&local),
+            _ => false,
+        });
+        let in_call = match &block.terminator.kind {
+            TerminatorKind::Call { args, .. } => args
+                .iter()
+                .filter_map(operand_local)
+                .any(|arg| arg == local),
+            _ => false,
+        };
+        in_statement || in_call
+    })
+}
+
+/// Blocks and spans of the calls of `body` comparing an account's `owner` with `id`:
+/// `PartialEq::eq` and `ne` on the owner and the declared constant or the
+/// result of its `id()`, and `check_id(owner)`.
+fn owner_checks_against_id(body: &Body, id: ProgramId) -> Vec<(usize, Span)> {
+    let defs = Definitions::new(body);
+    let mut owners: Taint<()> = Taint::new();
+    let mut ids: Taint<()> = Taint::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            if let Rvalue::Use(Operand::Constant(constant)) = rvalue
+                && is_id_const(&constant.const_, id)
+            {
+                ids.insert(lhs.local, BTreeSet::from([()]));
+            } else if let Some(place) = read_place(rvalue)
+                && defs
+                    .field_parent(place)
+                    .is_some_and(|(ty, field_idx)| field_path(ty, field_idx) == OWNER)
+            {
+                owners.insert(lhs.local, BTreeSet::from([()]));
+            }
+        }
+    }
+    for (bb, block) in body.blocks.iter().enumerate() {
+        if let TerminatorKind::Call { destination, .. } = &block.terminator.kind
+            && let Some(instance) = local_callee(body, bb)
+            && instance.name().rsplit("::").next() == Some("id")
+        {
+            ids.insert(destination.local, BTreeSet::from([()]));
+        }
+    }
+    if owners.is_empty() {
+        return vec![];
+    }
+    let owners = propagate(body, owners);
+    let ids = propagate(body, ids);
+
+    let mut checks = vec![];
+    for (bb, block) in body.blocks.iter().enumerate() {
+        let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+            continue;
+        };
+        let has_owner = !labels_of(&owners, args.iter().filter_map(operand_local)).is_empty();
+        if !has_owner {
+            continue;
+        }
+        let compares_id = callee_name(body, func).is_some_and(|name| {
+            name.contains("PartialEq") && (name.ends_with("::eq") || name.ends_with("::ne"))
+        }) && args.iter().any(|arg| match arg {
+            Operand::Constant(constant) => is_id_const(&constant.const_, id),
+            _ => !labels_of(&ids, operand_local(arg)).is_empty(),
+        });
+        let checks_id = local_callee(body, bb)
+            .is_some_and(|instance| instance.name().rsplit("::").next() == Some("check_id"));
+        if compares_id || checks_id {
+            checks.push((bb, block.terminator.span));
+        }
+    }
+    checks
+}
+
+/// The local fn block `bb` of `body` calls, if any.
+fn local_callee(body: &Body, bb: usize) -> Option<Instance> {
+    callee(body, bb).filter(|instance| instance.def.krate().is_local)
+}
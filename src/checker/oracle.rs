@@ -0,0 +1,92 @@
+use super::{AnalysisContext, Checker};
+use crate::anchor_info::access_control::guarded_field_accesses;
+use crate::anchor_info::constraint::constraint_error_codes;
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "unpinned_oracle_account";
+
+const CONSTRAINT_ADDRESS: &str = "ConstraintAddress";
+const CONSTRAINT_OWNER: &str = "ConstraintOwner";
+
+/// Report oracle accounts (by type name, see `[oracle] types` in the config) that are
+/// pinned neither by an `address =`/`owner =` constraint nor by a key comparison in
+/// a handler or its `access_control` guards, so any account with the right layout
+/// could be passed as the feed.
+pub struct UnpinnedOracleAccount;
+
+impl Checker for UnpinnedOracleAccount {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Oracle accounts pinned by neither an address or owner constraint nor a key check"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for (name, (adt_def, handlers)) in ctx.handlers_by_accounts() {
+            let Some(variant) = adt_def.variants_iter().next() else {
+                continue;
+            };
+            let mut constraints = None;
+            let mut accesses = None;
+            for (field_idx, field) in variant.fields().iter().enumerate() {
+                let ty = field.ty().to_string();
+                let Some(oracle_ty) = ctx
+                    .config
+                    .oracle
+                    .types
+                    .iter()
+                    .find(|oracle_ty| ty_mentions(&ty, oracle_ty))
+                else {
+                    continue;
+                };
+
+                let codes = constraints
+                    .get_or_insert_with(|| constraint_error_codes(name))
+                    .get(&field.name);
+                let has_address = codes.is_some_and(|codes| codes.contains(CONSTRAINT_ADDRESS));
+                let has_owner = codes.is_some_and(|codes| codes.contains(CONSTRAINT_OWNER));
+                if has_address || has_owner {
+                    continue;
+                }
+                let accesses = accesses.get_or_insert_with(|| {
+                    handlers
+                        .iter()
+                        .map(|handler| guarded_field_accesses(*handler))
+                        .collect::<Vec<_>>()
+                });
+                // A struct no handler takes has no key check to rely on.
+                let key_checked = !accesses.is_empty()
+                    && accesses
+                        .iter()
+                        .all(|access| access.key_checks.contains(&field_idx));
+                if key_checked {
+                    continue;
+                }
+                let message = format!(
+                    "oracle account `{}` ({oracle_ty}) in `{name}` is missing an `address` or `owner` constraint",
+                    field.name
+                );
+                findings.push(
+                    Finding::error(CHECKER, message)
+                        .at(adt_def)
+                        .key(&field.name),
+                );
+            }
+        }
+
+        findings
+    }
+}
+
+/// Whether `oracle_ty` appears as a whole path segment in the printed type `ty`.
+pub(super) fn ty_mentions(ty: &str, oracle_ty: &str) -> bool {
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|segment| segment == oracle_ty)
+}
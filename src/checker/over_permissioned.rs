@@ -0,0 +1,67 @@
+use super::{AnalysisContext, Checker};
+use crate::anchor_info::access::{FieldAccesses, framework_written_fields, handler_field_accesses};
+use crate::report::Finding;
+
+const CHECKER: &str = "over_permissioned_account";
+
+/// Report accounts requested as `mut` that no handler using the Accounts struct
+/// ever writes. Accounts written by Anchor itself (`init`, its payer, `close`) are
+/// not reported.
+pub struct OverPermissionedAccount;
+
+impl Checker for OverPermissionedAccount {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Accounts requested as mut that no handler writes"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        let metas = ctx.to_account_metas();
+
+        for (name, (adt_def, handlers)) in ctx.handlers_by_accounts() {
+            let Some(variant) = adt_def.variants_iter().next() else {
+                continue;
+            };
+            let fields = variant.fields();
+            // An account only needs to be written by one of the handlers to justify `mut`.
+            let accesses: Vec<FieldAccesses> = handlers
+                .iter()
+                .copied()
+                .map(handler_field_accesses)
+                .collect();
+            let mut framework_written = None;
+            for meta in metas {
+                if meta.struct_name != *name || !meta.writable {
+                    continue;
+                }
+                let field_idx = meta.field_idx;
+                if accesses.iter().any(|access| access.may_write(field_idx)) {
+                    continue;
+                }
+                if framework_written
+                    .get_or_insert_with(|| framework_written_fields(name))
+                    .contains(&field_idx)
+                {
+                    continue;
+                }
+                let field_name = fields
+                    .get(field_idx)
+                    .map(|field| field.name.clone())
+                    .unwrap_or_else(|| field_idx.to_string());
+                let message =
+                    format!("account `{field_name}` in `{name}` is marked mut but never written");
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .at(adt_def)
+                        .key(&field_name),
+                );
+            }
+        }
+
+        findings
+    }
+}
@@ -0,0 +1,70 @@
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::anchor_info::AnchorAccountKind;
+use crate::anchor_info::access::short_name;
+use crate::anchor_info::constraint::AnchorConstraint;
+use crate::report::Finding;
+
+const CHECKER: &str = "non_signer_payer";
+
+/// Report `init` constraints whose `payer` is not a `Signer`.
+///
+/// `init` funds the new account with `system_program::create_account` from the
+/// payer, which the runtime only allows when the payer signed the transaction.
+/// Anchor does not check that for an `AccountInfo`, an `UncheckedAccount` or a
+/// `SystemAccount`, so the instruction either fails at the CPI or draws the
+/// lamports from whichever account signed and was passed in its place.
+pub struct NonSignerPayer;
+
+impl Checker for NonSignerPayer {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "init constraints whose payer is not a Signer"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for anchor_accounts in ctx.anchor_accounts() {
+            let name = &anchor_accounts.name;
+            let fields = &anchor_accounts.anchor_accounts;
+            for account in fields {
+                for constraint in &account.constraints {
+                    let AnchorConstraint::Init { payer, .. } = constraint else {
+                        continue;
+                    };
+                    let Some(payer_account) = fields.iter().find(|field| &field.name == payer)
+                    else {
+                        continue;
+                    };
+                    let kind = match &payer_account.kind {
+                        // A boxed `Signer` or a type we do not model may sign.
+                        AnchorAccountKind::Signer | AnchorAccountKind::Unknown => continue,
+                        AnchorAccountKind::Unchecked => "an unchecked account".to_string(),
+                        AnchorAccountKind::SystemAccount => "a `SystemAccount`".to_string(),
+                        AnchorAccountKind::Program | AnchorAccountKind::Interface(_) => {
+                            "a program".to_string()
+                        }
+                        AnchorAccountKind::Sysvar(_) => "a sysvar".to_string(),
+                        AnchorAccountKind::Account(ty)
+                        | AnchorAccountKind::InterfaceAccount(ty) => {
+                            format!("an account of type `{}`", short_name(ty))
+                        }
+                    };
+                    let message = format!(
+                        "account `{}` in `{name}` is initialized with payer `{payer}`, which is {kind} rather than a `Signer`",
+                        account.name
+                    );
+                    findings.push(
+                        Finding::warning(CHECKER, message)
+                            .at(&anchor_accounts.adt_def)
+                            .key(&account.name),
+                    );
+                }
+            }
+        }
+        findings
+    }
+}
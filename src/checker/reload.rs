@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{Body, Operand, TerminatorKind};
+use rustc_public::ty::{FnDef, RigidTy, TyKind};
+
+use super::{AnalysisContext, Checker};
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::anchor_info::AnchorAccountKind;
+use crate::anchor_info::access::{Role, field_ref, local_roles};
+use crate::metadata::{Concept, known_types};
+use crate::report::Finding;
+
+const CHECKER: &str = "stale_read_after_cpi";
+
+/// Anchor CPI helpers (`anchor_spl::token::transfer`, generated `cpi` modules)
+/// take the accounts of the call wrapped in a `CpiContext`.
+const CPI_CONTEXT: &str = "anchor_lang::context::CpiContext";
+/// Calls that read the deserialized data of an `Account<T>`.
+const DATA_ACCESS: [&str; 2] = ["::deref", "::deref_mut"];
+const RELOAD: &str = "::reload";
+
+/// How deep we look into local helpers for a CPI.
+const MAX_DEPTH: usize = 4;
+
+/// Report reads of an `Account<T>` after a CPI that was passed the account,
+/// unless `reload()` is called on it in between. The handler still sees the data
+/// deserialized on entry, which the CPI may have changed.
+pub struct StaleReadAfterCpi;
+
+impl Checker for StaleReadAfterCpi {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Account data read after a CPI that was passed the account, without reload()"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        let mut cpi_helpers = HashMap::new();
+        for (name, (adt_def, handlers)) in ctx.handlers_by_accounts() {
+            let Some(variant) = adt_def.variants_iter().next() else {
+                continue;
+            };
+            let fields = variant.fields();
+            let deserialized: HashSet<usize> = fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| {
+                    matches!(
+                        AnchorAccountKind::from_ty(&field.ty().kind()),
+                        Some(AnchorAccountKind::Account(_))
+                    )
+                })
+                .map(|(field_idx, _)| field_idx)
+                .collect();
+            if deserialized.is_empty() {
+                continue;
+            }
+            for &handler in handlers {
+                let Some(body) = ctx.body(handler) else {
+                    continue;
+                };
+                for (field_idx, read, cpi) in stale_reads(&body, &deserialized, &mut cpi_helpers) {
+                    let message = format!(
+                        "account `{}` in `{name}` is read at bb{read} in `{}` after a CPI at bb{cpi} without `reload()`",
+                        fields[field_idx].name,
+                        handler.name()
+                    );
+                    findings.push(
+                        Finding::warning(CHECKER, message)
+                            .in_fn(handler)
+                            .key(&fields[field_idx].name)
+                            .key(&format!("bb{read}")),
+                    );
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+/// `(field, read block, CPI block)` for every read of a field in `deserialized`
+/// reachable from a CPI involving the field without passing a reload of it.
+fn stale_reads(
+    body: &Body,
+    deserialized: &HashSet<usize>,
+    cpi_helpers: &mut HashMap<Instance, bool>,
+) -> Vec<(usize, usize, usize)> {
+    let roles = local_roles(body, 1, Role::Ctx);
+    let sources: Taint<usize> = roles
+        .iter()
+        .filter_map(|(local, role)| match role {
+            Role::Field(field_idx) if deserialized.contains(field_idx) => {
+                Some((*local, BTreeSet::from([*field_idx])))
+            }
+            _ => None,
+        })
+        .collect();
+    let taint = propagate(body, sources);
+
+    // Blocks ending in a CPI, with the fields it was passed.
+    let mut cpis = vec![];
+    // Field -> blocks ending in a read of its data, or in a reload.
+    let mut reads: HashMap<usize, HashSet<usize>> = HashMap::new();
+    let mut reloads: HashMap<usize, HashSet<usize>> = HashMap::new();
+    for (block, data) in body.blocks.iter().enumerate() {
+        let TerminatorKind::Call { func, args, .. } = &data.terminator.kind else {
+            continue;
+        };
+        let Ok(fn_ty) = func.ty(body.locals()) else {
+            continue;
+        };
+        let TyKind::RigidTy(RigidTy::FnDef(fn_def, generic_args)) = fn_ty.kind() else {
+            continue;
+        };
+        let is_call_cpi = is_cpi(body, fn_def, args)
+            || (fn_def.krate().is_local
+                && Instance::resolve(fn_def, &generic_args)
+                    .is_ok_and(|helper| calls_cpi(helper, 0, cpi_helpers)));
+        if is_call_cpi {
+            let fields = labels_of(&taint, args.iter().filter_map(operand_local));
+            cpis.push((block, fields));
+            continue;
+        }
+        let callee_name = fn_def.name();
+        let Some(field_idx) = args.first().and_then(|arg| match arg {
+            Operand::Copy(place) | Operand::Move(place) => field_ref(place, &roles),
+            Operand::Constant(_) => None,
+        }) else {
+            continue;
+        };
+        if DATA_ACCESS
+            .iter()
+            .any(|suffix| callee_name.ends_with(suffix))
+        {
+            reads.entry(field_idx).or_default().insert(block);
+        } else if callee_name.ends_with(RELOAD) {
+            reloads.entry(field_idx).or_default().insert(block);
+        }
+    }
+
+    // (field, read) -> the first CPI it is stale after.
+    let mut stale = BTreeMap::new();
+    let no_reloads = HashSet::new();
+    for (cpi, fields) in cpis {
+        for field_idx in fields {
+            let Some(field_reads) = reads.get(&field_idx) else {
+                continue;
+            };
+            let field_reloads = reloads.get(&field_idx).unwrap_or(&no_reloads);
+            let mut seen = HashSet::new();
+            let mut worklist = body.blocks[cpi].terminator.successors();
+            while let Some(block) = worklist.pop() {
+                if !seen.insert(block) {
+                    continue;
+                }
+                if field_reads.contains(&block) {
+                    stale.entry((field_idx, block)).or_insert(cpi);
+                }
+                if !field_reloads.contains(&block) {
+                    worklist.extend(body.blocks[block].terminator.successors());
+                }
+            }
+        }
+    }
+    stale
+        .into_iter()
+        .map(|((field_idx, read), cpi)| (field_idx, read, cpi))
+        .collect()
+}
+
+/// Whether a call to `fn_def` with `args` is a CPI.
+fn is_cpi(body: &Body, fn_def: FnDef, args: &[Operand]) -> bool {
+    if fn_def.krate().is_local {
+        return false;
+    }
+    known_types().is(Concept::Invoke, &fn_def.name())
+        || args.iter().any(|arg| {
+            arg.ty(body.locals())
+                .is_ok_and(|ty| ty.to_string().starts_with(CPI_CONTEXT))
+        })
+}
+
+/// Whether the local fn `instance` performs a CPI, directly or through other
+/// local fns.
+fn calls_cpi(instance: Instance, depth: usize, cache: &mut HashMap<Instance, bool>) -> bool {
+    if let Some(&cached) = cache.get(&instance) {
+        return cached;
+    }
+    // Recursive calls are assumed not to perform a CPI.
+    cache.insert(instance, false);
+    let mut found = false;
+    if let Some(body) = instance.body() {
+        for block in &body.blocks {
+            let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+                continue;
+            };
+            let Ok(fn_ty) = func.ty(body.locals()) else {
+                continue;
+            };
+            let TyKind::RigidTy(RigidTy::FnDef(fn_def, generic_args)) = fn_ty.kind() else {
+                continue;
+            };
+            if is_cpi(&body, fn_def, args)
+                || (depth < MAX_DEPTH
+                    && fn_def.krate().is_local
+                    && Instance::resolve(fn_def, &generic_args)
+                        .is_ok_and(|callee| calls_cpi(callee, depth + 1, cache)))
+            {
+                found = true;
+                break;
+            }
+        }
+    }
+    cache.insert(instance, found);
+    found
+}
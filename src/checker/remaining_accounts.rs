@@ -0,0 +1,153 @@
+use std::collections::{BTreeSet, HashSet};
+
+use rustc_public::mir::{AssertMessage, Body, Local, Rvalue, StatementKind, TerminatorKind, UnOp};
+use rustc_public::ty::Span;
+
+use super::{AnalysisContext, Checker};
+use crate::analysis::condition::{Definitions, Side, comparisons};
+use crate::analysis::graph::Fixpoints;
+use crate::analysis::loops::predecessors;
+use crate::analysis::taint::{Taint, operand_local};
+use crate::anchor_info::remaining::{SLICE_LEN, callee_name, const_accesses, remaining_slices};
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "unchecked_remaining_accounts";
+
+/// Report `ctx.remaining_accounts[i]` and `ctx.remaining_accounts[i..]` with a
+/// constant `i` when no comparison on `remaining_accounts.len()` dominates the
+/// access. A client passing fewer accounts makes the instruction panic instead
+/// of failing with an error the client can act on.
+///
+/// Any length comparison counts as a check, whatever its bound; accesses with
+/// an index computed at runtime, and `get(i)`, are left alone.
+pub struct UncheckedRemainingAccounts;
+
+impl Checker for UncheckedRemainingAccounts {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "remaining_accounts indexed with no check of their number"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for handler in ctx.handlers() {
+            let Some(body) = ctx.body(handler.instance) else {
+                continue;
+            };
+            let accesses = unchecked_accesses(&body, ctx.fixpoints());
+            for (block, span, access, min_len) in accesses {
+                let message = format!(
+                    "`remaining_accounts{access}` at {} in `{}` assumes at least {min_len} remaining accounts without checking their number",
+                    location(&span),
+                    handler.instance.name()
+                );
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .in_fn(handler.instance)
+                        .key(&format!("bb{block}"))
+                        .key(&access),
+                );
+            }
+        }
+
+        findings
+    }
+}
+
+/// Constant indexing and slicing of `remaining_accounts` in `body` that no
+/// length comparison dominates, as the block of the access, the access written
+/// out (`[1]`, `[2..]`) and the number of accounts it needs.
+fn unchecked_accesses(body: &Body, fixpoints: &Fixpoints) -> Vec<(usize, Span, String, usize)> {
+    let defs = Definitions::new(body);
+    let slices = remaining_slices(body, &defs);
+    if slices.is_empty() {
+        return vec![];
+    }
+
+    let lens = slice_lens(body, &slices);
+    let checks = length_checks(body, &defs, &lens);
+    let dominators = fixpoints.dominators(body.blocks.len(), &predecessors(body));
+    let is_checked = |block: usize| {
+        checks
+            .iter()
+            .any(|check| dominators.dominates(check, &block))
+    };
+
+    const_accesses(body, &slices, &defs)
+        .into_iter()
+        .filter(|access| !is_checked(access.block))
+        .map(|access| (access.block, access.span, access.access, access.min_len))
+        .collect()
+}
+
+/// Locals holding the length of a local in `slices`.
+pub(super) fn slice_lens(body: &Body, slices: &Taint<()>) -> HashSet<Local> {
+    let mut lens = HashSet::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            match rvalue {
+                Rvalue::UnaryOp(UnOp::PtrMetadata, operand)
+                    if operand_local(operand).is_some_and(|local| slices.contains_key(&local)) =>
+                {
+                    lens.insert(lhs.local);
+                }
+                Rvalue::Len(place) if slices.contains_key(&place.local) => {
+                    lens.insert(lhs.local);
+                }
+                _ => {}
+            }
+        }
+        if let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+            && callee_name(body, func).is_some_and(|name| name.ends_with(SLICE_LEN))
+            && args
+                .first()
+                .and_then(operand_local)
+                .is_some_and(|local| slices.contains_key(&local))
+        {
+            lens.insert(destination.local);
+        }
+    }
+    lens
+}
+
+/// Blocks comparing one of `lens`, leaving out the bounds checks rustc inserts
+/// before each indexing, which only decide between the access and a panic.
+pub(super) fn length_checks(
+    body: &Body,
+    defs: &Definitions,
+    lens: &HashSet<Local>,
+) -> BTreeSet<usize> {
+    comparisons(body)
+        .into_iter()
+        .filter(|comparison| {
+            !matches!(
+                body.blocks[comparison.block].terminator.kind,
+                TerminatorKind::Assert {
+                    msg: AssertMessage::BoundsCheck { .. },
+                    ..
+                }
+            )
+        })
+        .filter(|comparison| {
+            [&comparison.lhs, &comparison.rhs].into_iter().any(|side| {
+                let Side::Operand(operand) = side else {
+                    return false;
+                };
+                defs.source_place(operand)
+                    .is_some_and(|place| place.projection.is_empty() && lens.contains(&place.local))
+            })
+        })
+        .map(|comparison| comparison.block)
+        .collect()
+}
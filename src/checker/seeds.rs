@@ -0,0 +1,126 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{AnalysisContext, Checker};
+use crate::anchor_info::constants::{ConstantSource, extract_constants};
+use crate::anchor_info::constraint::Value;
+use crate::report::Finding;
+
+const CHECKER: &str = "near_miss_seed";
+/// Seeds shorter than this are usually tags where one byte is the whole point.
+const MIN_SEED_LEN: usize = 3;
+
+/// Report byte string seeds of different Accounts structs that differ in a
+/// single byte, e.g. `b"vault"` and `b"vaulr"`. The handlers then derive
+/// different PDAs for what is meant to be the same account, and whatever one of
+/// them creates the other never finds.
+///
+/// Seeds differing in a digit, like `b"tier1"` and `b"tier2"`, are numbered on
+/// purpose and left alone.
+pub struct NearMissSeed;
+
+impl Checker for NearMissSeed {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Byte string seeds of different Accounts structs differing in a single byte"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        // Each seed value with the fields using it, by Accounts struct.
+        let mut uses: BTreeMap<Vec<u8>, BTreeMap<String, BTreeSet<String>>> = BTreeMap::new();
+        for constant in extract_constants() {
+            let ConstantSource::Seed {
+                accounts, field, ..
+            } = &constant.source
+            else {
+                continue;
+            };
+            let Some(bytes) = constant.bytes().filter(|bytes| bytes.len() >= MIN_SEED_LEN) else {
+                continue;
+            };
+            uses.entry(bytes.to_vec())
+                .or_default()
+                .entry(accounts.clone())
+                .or_default()
+                .insert(field.clone());
+        }
+        let adt_defs: BTreeMap<_, _> = ctx
+            .anchor_accounts()
+            .iter()
+            .map(|anchor_accounts| (&anchor_accounts.name, anchor_accounts.adt_def))
+            .collect();
+
+        let seeds: Vec<_> = uses.iter().collect();
+        for (i, (a, a_uses)) in seeds.iter().enumerate() {
+            for (b, b_uses) in &seeds[i + 1..] {
+                // Two seeds used together in one struct are told apart on purpose.
+                if !near_miss(a, b) || a_uses.keys().any(|accounts| b_uses.contains_key(accounts)) {
+                    continue;
+                }
+                let message = format!(
+                    "seeds {} in {} and {} in {} differ by a single byte",
+                    Value::Bytes(a.to_vec()),
+                    list_uses(a_uses),
+                    Value::Bytes(b.to_vec()),
+                    list_uses(b_uses)
+                );
+                let mut finding = Finding::warning(CHECKER, message);
+                for accounts in a_uses.keys().chain(b_uses.keys()) {
+                    if let Some(adt_def) = adt_defs.get(accounts) {
+                        finding = finding.at(adt_def);
+                    }
+                }
+                findings.push(
+                    finding
+                        .key(&Value::Bytes(a.to_vec()).to_string())
+                        .key(&Value::Bytes(b.to_vec()).to_string()),
+                );
+            }
+        }
+
+        findings
+    }
+}
+
+/// Whether `a` and `b` have the same length and differ in exactly one byte that
+/// is not a digit in both.
+fn near_miss(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diffs = a.iter().zip(b).filter(|(x, y)| x != y);
+    match (diffs.next(), diffs.next()) {
+        (Some((x, y)), None) => !(x.is_ascii_digit() && y.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+fn list_uses(uses: &BTreeMap<String, BTreeSet<String>>) -> String {
+    uses.iter()
+        .flat_map(|(accounts, fields)| {
+            fields
+                .iter()
+                .map(move |field| format!("`{accounts}.{field}`"))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_near_miss() {
+        assert!(near_miss(b"vault", b"vaulr"));
+        assert!(near_miss(b"config", b"cOnfig"));
+        assert!(!near_miss(b"vault", b"vault"));
+        assert!(!near_miss(b"vault", b"vaults"));
+        assert!(!near_miss(b"vault", b"valve"));
+        assert!(!near_miss(b"tier1", b"tier2"));
+        assert!(near_miss(b"tier1", b"tierl"));
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::{BTreeSet, HashSet};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::{
+    AggregateKind, Body, Local, Operand, Rvalue, StatementKind, TerminatorKind,
+};
+use rustc_public::ty::{RigidTy, Ty, TyKind, UintTy};
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate, rvalue_locals};
+use crate::analysis::types::is_known_ty;
+use crate::anchor_info::remaining::callee_name;
+use crate::metadata::{Concept, known_types};
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "user_signer_seeds";
+
+/// Where the bytes of a seed come from.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Source {
+    /// An argument of the fn holding instruction data, e.g. `data: &[u8]` or
+    /// `id: u64`.
+    Arg,
+    /// A `Pubkey`, e.g. `owner.key.as_ref()`.
+    Key,
+    /// A byte string or `str` constant, e.g. `b"vault"`.
+    Const,
+}
+
+/// Report `invoke_signed` calls signing with a seed list made only of
+/// instruction arguments, e.g. `&[&[user_seed_bytes]]`. Without a constant
+/// namespace or an account key among the seeds, the caller picks the PDA the
+/// program signs for, and can have it sign for a PDA of another kind or
+/// another user.
+pub struct UserSignerSeeds;
+
+impl Checker for UserSignerSeeds {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "invoke_signed seeds made only of instruction arguments"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for &instance in ctx.reachable_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            let mut taint = None;
+            for (bb, block) in body.blocks.iter().enumerate() {
+                let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+                    continue;
+                };
+                let Some(callee) = callee_name(&body, func) else {
+                    continue;
+                };
+                if !is_signed_invoke(&callee) {
+                    continue;
+                }
+                let Some(seeds) = args.last().and_then(operand_local) else {
+                    continue;
+                };
+                let taint = taint.get_or_insert_with(|| propagate(&body, sources(&body)));
+                let user_only = seed_lists(&body, seeds).into_iter().any(|seeds| {
+                    let sources = seed_sources(taint, seeds);
+                    sources.contains(&Source::Arg)
+                        && !sources.contains(&Source::Key)
+                        && !sources.contains(&Source::Const)
+                });
+                if !user_only {
+                    continue;
+                }
+                let callee = callee.rsplit("::").next().unwrap_or(&callee);
+                let message = format!(
+                    "`{}` calls `{callee}` at {} with signer seeds made only of instruction arguments, without a constant or an account key",
+                    instance.name(),
+                    location(&block.terminator.span)
+                );
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .in_fn(instance)
+                        .span(&block.terminator.span)
+                        .key(&format!("bb{bb}")),
+                );
+            }
+        }
+        findings
+    }
+}
+
+/// `invoke_signed` and its variants, which take the signer seeds last.
+pub(super) fn is_signed_invoke(name: &str) -> bool {
+    known_types().is(Concept::Invoke, name) && name.contains("signed")
+}
+
+/// The arguments holding instruction data, the `Pubkey`s, and the locals
+/// assigned a byte string or `str` constant.
+fn sources(body: &Body) -> Taint<Source> {
+    let mut taint = Taint::new();
+    for (local, decl) in body.local_decls() {
+        let source = if is_known_ty(Concept::Pubkey, decl.ty) {
+            Source::Key
+        } else if (1..=body.arg_locals().len()).contains(&local) && is_instruction_data(decl.ty) {
+            Source::Arg
+        } else {
+            continue;
+        };
+        taint.insert(local, BTreeSet::from([source]));
+    }
+    for stmt in body.blocks.iter().flat_map(|block| &block.statements) {
+        if let StatementKind::Assign(
+            lhs,
+            Rvalue::Use(Operand::Constant(constant))
+            | Rvalue::Cast(_, Operand::Constant(constant), _),
+        ) = &stmt.kind
+            && is_byte_string(constant.ty())
+        {
+            taint.entry(lhs.local).or_default().insert(Source::Const);
+        }
+    }
+    taint
+}
+
+/// The operands of the `[&[u8]]` seed lists the signer seeds `seeds` are built
+/// from.
+pub(super) fn seed_lists(body: &Body, seeds: Local) -> Vec<&[Operand]> {
+    let mut reached = HashSet::from([seeds]);
+    let mut lists = vec![];
+    loop {
+        let known = reached.len();
+        for stmt in body.blocks.iter().flat_map(|block| &block.statements) {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            if !reached.contains(&lhs.local) {
+                continue;
+            }
+            if let Rvalue::Aggregate(AggregateKind::Array(ty), operands) = rvalue
+                && is_byte_slice_ref(*ty)
+            {
+                if !lists.contains(&operands.as_slice()) {
+                    lists.push(operands.as_slice());
+                }
+                continue;
+            }
+            reached.extend(rvalue_locals(rvalue));
+        }
+        if reached.len() == known {
+            return lists;
+        }
+    }
+}
+
+fn seed_sources(taint: &Taint<Source>, seeds: &[Operand]) -> BTreeSet<Source> {
+    let mut sources = labels_of(taint, seeds.iter().filter_map(operand_local));
+    if seeds
+        .iter()
+        .any(|seed| matches!(seed, Operand::Constant(constant) if is_byte_string(constant.ty())))
+    {
+        sources.insert(Source::Const);
+    }
+    sources
+}
+
+/// Whether `ty` is a type instruction data is decoded into: integers, `bool`,
+/// bytes and strings, or a reference to one.
+fn is_instruction_data(ty: Ty) -> bool {
+    match ty.kind().rigid() {
+        Some(RigidTy::Ref(_, inner, _)) => is_instruction_data(*inner),
+        Some(RigidTy::Bool | RigidTy::Int(_) | RigidTy::Uint(_) | RigidTy::Str) => true,
+        Some(RigidTy::Slice(elem) | RigidTy::Array(elem, _)) => is_instruction_data(*elem),
+        Some(RigidTy::Adt(adt_def, _)) => {
+            matches!(
+                adt_def.name().as_str(),
+                "std::vec::Vec" | "std::string::String"
+            )
+        }
+        _ => false,
+    }
+}
+
+/// `&[u8]`, the type of one seed.
+fn is_byte_slice_ref(ty: Ty) -> bool {
+    match ty.kind() {
+        TyKind::RigidTy(RigidTy::Ref(_, inner, _)) => matches!(
+            inner.kind().rigid(),
+            Some(RigidTy::Slice(elem)) if matches!(elem.kind().rigid(), Some(RigidTy::Uint(UintTy::U8)))
+        ),
+        _ => false,
+    }
+}
+
+/// A reference to a `str` or to bytes, as the constant `b"vault"` is.
+fn is_byte_string(ty: Ty) -> bool {
+    let TyKind::RigidTy(RigidTy::Ref(_, inner, _)) = ty.kind() else {
+        return false;
+    };
+    match inner.kind().rigid() {
+        Some(RigidTy::Str) => true,
+        Some(RigidTy::Slice(elem) | RigidTy::Array(elem, _)) => {
+            matches!(elem.kind().rigid(), Some(RigidTy::Uint(UintTy::U8)))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,90 @@
+use super::oracle::ty_mentions;
+use super::{AnalysisContext, Checker};
+use crate::anchor_info::access_control::guarded_field_accesses;
+use crate::anchor_info::constraint::constraint_error_codes;
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "unpinned_singleton_account";
+/// `try_accounts` errors showing the key of the account is fixed.
+const KEY_CONSTRAINTS: [&str; 2] = ["ConstraintAddress", "ConstraintSeeds"];
+
+/// Report accounts of a singleton type (by type name, see `[singleton] types` in
+/// the config) whose key is fixed neither by an `address =` or `seeds`
+/// constraint nor by a key comparison in every handler or its `access_control`
+/// guards. Anyone can create another account of the type, e.g. a config with
+/// themselves as admin, and pass it instead.
+///
+/// Nothing in the type tells whether a program keeps one or many accounts of
+/// it, so only the configured types are checked.
+pub struct UnpinnedSingletonAccount;
+
+impl Checker for UnpinnedSingletonAccount {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Singleton accounts whose key no constraint or key check fixes"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        if ctx.config.singleton.types.is_empty() {
+            return findings;
+        }
+        for (name, (adt_def, handlers)) in ctx.handlers_by_accounts() {
+            let Some(variant) = adt_def.variants_iter().next() else {
+                continue;
+            };
+            let mut constraints = None;
+            let mut accesses = None;
+            for (field_idx, field) in variant.fields().iter().enumerate() {
+                let ty = field.ty().to_string();
+                let Some(singleton_ty) = ctx
+                    .config
+                    .singleton
+                    .types
+                    .iter()
+                    .find(|singleton_ty| ty_mentions(&ty, singleton_ty))
+                else {
+                    continue;
+                };
+
+                let key_constrained = constraints
+                    .get_or_insert_with(|| constraint_error_codes(name))
+                    .get(&field.name)
+                    .is_some_and(|codes| KEY_CONSTRAINTS.iter().any(|code| codes.contains(*code)));
+                if key_constrained {
+                    continue;
+                }
+                let key_checked = accesses
+                    .get_or_insert_with(|| {
+                        handlers
+                            .iter()
+                            .map(|handler| guarded_field_accesses(*handler))
+                            .collect::<Vec<_>>()
+                    })
+                    .iter()
+                    .all(|access| access.key_checks.contains(&field_idx));
+                if key_checked {
+                    continue;
+                }
+                let message = format!(
+                    "singleton account `{}` ({singleton_ty}) in `{name}` is missing an `address` or `seeds` constraint",
+                    field.name
+                );
+                findings.push(
+                    Finding::error(CHECKER, message)
+                        .at(adt_def)
+                        .key(&field.name),
+                );
+            }
+        }
+
+        findings
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::BTreeSet;
+
+use rustc_public::mir::{Body, VarDebugInfoContents};
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::report::Finding;
+
+const CHECKER: &str = "large_stack_frame";
+
+/// Report fns whose variables take more bytes than `[stack] max_frame_bytes`
+/// of the config, by default the 4 KiB of an SBF stack frame; the program
+/// then fails at runtime on an access violation. Only the variables the
+/// source declares are counted, not the temporaries the compiler adds, so the
+/// frame of a fn reported is at least this large.
+pub struct LargeStackFrame;
+
+impl Checker for LargeStackFrame {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Fns whose variables exceed the stack frame size"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let max = ctx.config.stack.max_frame_bytes;
+        let mut findings = vec![];
+        for &instance in ctx.reachable_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            let size = variables_size(&body);
+            if size > max {
+                let message = format!(
+                    "`{}` declares {size} bytes of variables, more than the {max} bytes of a stack frame",
+                    instance.name()
+                );
+                findings.push(Finding::warning(CHECKER, message).in_fn(instance));
+            }
+        }
+        findings
+    }
+}
+
+/// The bytes taken by the variables of `body`, each counted once.
+fn variables_size(body: &Body) -> usize {
+    let variables: BTreeSet<usize> = body
+        .var_debug_info
+        .iter()
+        .filter_map(|info| match &info.value {
+            VarDebugInfoContents::Place(place) if place.projection.is_empty() => Some(place.local),
+            _ => None,
+        })
+        .collect();
+    variables
+        .into_iter()
+        .filter_map(|local| body.locals()[local].ty.layout().ok())
+        .map(|layout| layout.shape().size.bytes())
+        .sum()
+}
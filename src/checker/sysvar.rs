@@ -0,0 +1,97 @@
+use rustc_public::mir::TerminatorKind;
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::keys::id_checks;
+use crate::analysis::loops::predecessors;
+use crate::analysis::taint::{labels_of, operand_local};
+use crate::anchor_info::remaining::callee_name;
+use crate::native_info::accounts::{account_positions, decoded_type};
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "unchecked_sysvar_account";
+
+/// `Sysvar::from_account_info`, as called on any sysvar type.
+const FROM_ACCOUNT_INFO: &str = "Sysvar::from_account_info";
+
+/// Report native handlers reading a sysvar with `Sysvar::from_account_info`,
+/// e.g. `Clock::from_account_info(clock_info)`, from an account whose key no
+/// comparison with an id, or `check_id` call, dominates the read. The client
+/// picks the account passed in place of the sysvar; recent SDKs reject the
+/// wrong one inside `from_account_info`, but an explicit check keeps the
+/// requirement visible and does not depend on the SDK version. Anchor's
+/// `Sysvar<T>` accounts are checked by the framework.
+pub struct UncheckedSysvarAccount;
+
+impl Checker for UncheckedSysvarAccount {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Sysvars read from an account whose key is not checked against the sysvar id"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for model in ctx.native_accounts() {
+            let Some(body) = ctx.body(model.handler) else {
+                continue;
+            };
+            let positions = account_positions(&body);
+            let mut guards = None;
+            for (bb, block) in body.blocks.iter().enumerate() {
+                let TerminatorKind::Call {
+                    func,
+                    args,
+                    destination,
+                    ..
+                } = &block.terminator.kind
+                else {
+                    continue;
+                };
+                if !callee_name(&body, func).is_some_and(|name| name.ends_with(FROM_ACCOUNT_INFO)) {
+                    continue;
+                }
+                let read = labels_of(&positions, args.first().and_then(operand_local));
+                if read.is_empty() {
+                    continue;
+                }
+                let (checks, dominators) = guards.get_or_insert_with(|| {
+                    (
+                        id_checks(&body, &positions),
+                        ctx.fixpoints()
+                            .dominators(body.blocks.len(), &predecessors(&body)),
+                    )
+                });
+                for position in read {
+                    let checked = checks.iter().any(|check| {
+                        check.account == position && dominators.dominates(&check.block, &bb)
+                    });
+                    if checked {
+                        continue;
+                    }
+                    let account = match model.accounts.get(position).and_then(|a| a.name.as_ref()) {
+                        Some(name) => format!("#{position} `{name}`"),
+                        None => format!("#{position}"),
+                    };
+                    let sysvar = decoded_type(body.locals()[destination.local].ty)
+                        .unwrap_or_else(|| "Sysvar".to_string());
+                    let message = format!(
+                        "`{}` reads `{sysvar}` from account {account} at {} without first checking its key against the sysvar id",
+                        model.handler.name(),
+                        location(&block.terminator.span)
+                    );
+                    findings.push(
+                        Finding::warning(CHECKER, message)
+                            .in_fn(model.handler)
+                            .span(&block.terminator.span)
+                            .key(&format!("bb{bb}"))
+                            .key(&position.to_string()),
+                    );
+                }
+            }
+        }
+        findings
+    }
+}
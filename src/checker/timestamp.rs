@@ -0,0 +1,164 @@
+use rustc_public::mir::{BinOp, Place};
+
+use super::{AnalysisContext, Checker};
+use crate::analysis::condition::{Comparison, Definitions, Origin, Side, comparisons, field_path};
+use crate::analysis::loops::predecessors;
+use crate::analysis::types::is_known_ty;
+use crate::metadata::Concept;
+use crate::report::Finding;
+
+const UNIX_TIMESTAMP: &str = "unix_timestamp";
+const CHECKER: &str = "fragile_timestamp_check";
+
+/// Report logic gated on `Clock::unix_timestamp` that validator clock drift can
+/// break: exact (in)equality against a constant or stored value, and windows
+/// narrower than `[timestamp] min_window_secs`. A window is two bounds on the
+/// same read of the timestamp where one check runs only after the other.
+pub struct FragileTimestampCheck;
+
+impl Checker for FragileTimestampCheck {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "unix_timestamp compared exactly or accepted within a narrow window"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for &instance in ctx.local_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            let defs = Definitions::new(&body);
+            // The place the timestamp was read from, if `side` is one.
+            let timestamp = |side: &Side| -> Option<Place> {
+                let Side::Operand(operand) = side else {
+                    return None;
+                };
+                let place = defs.source_place(operand)?;
+                let (ty, field_idx) = defs.field_parent(&place)?;
+                (is_known_ty(Concept::Clock, ty)
+                    && field_path(ty, field_idx).ends_with(&format!(".{UNIX_TIMESTAMP}")))
+                .then_some(place)
+            };
+
+            let mut lower = vec![];
+            let mut upper = vec![];
+            for comparison in comparisons(&body) {
+                // Normalize to `timestamp op bound`.
+                let (op, read, bound) = if let Some(read) = timestamp(&comparison.lhs) {
+                    (comparison.op, read, &comparison.rhs)
+                } else if let Some(read) = timestamp(&comparison.rhs) {
+                    (flip(comparison.op), read, &comparison.lhs)
+                } else {
+                    continue;
+                };
+                let Some(bound) = defs.origin(bound) else {
+                    continue;
+                };
+                match op {
+                    BinOp::Eq | BinOp::Ne => findings.push(
+                        equality_finding(&instance.name(), &comparison, &bound).in_fn(instance),
+                    ),
+                    // Normalize bounds to an inclusive lower and an exclusive upper end.
+                    BinOp::Gt => lower.push((read, comparison.block, shift(bound, 1))),
+                    BinOp::Ge => lower.push((read, comparison.block, bound)),
+                    BinOp::Lt => upper.push((read, comparison.block, bound)),
+                    BinOp::Le => upper.push((read, comparison.block, shift(bound, 1))),
+                    _ => {}
+                }
+            }
+
+            if lower.is_empty() || upper.is_empty() {
+                continue;
+            }
+            // Bounds in sibling branches never apply together.
+            let dominators = ctx
+                .fixpoints()
+                .dominators(body.blocks.len(), &predecessors(&body));
+            let chained =
+                |a: usize, b: usize| dominators.dominates(&a, &b) || dominators.dominates(&b, &a);
+
+            // The accepted side of each bound depends on the branch taken, so compare
+            // the distance between bounds rather than assume an order.
+            for (low_read, low_block, low) in &lower {
+                for (high_read, high_block, high) in &upper {
+                    if low_read != high_read || !chained(*low_block, *high_block) {
+                        continue;
+                    }
+                    if let Some(width) = window(low, high)
+                        && width < i128::from(ctx.config.timestamp.min_window_secs)
+                    {
+                        let (start, end) = if width_signed(low, high) >= 0 {
+                            (low, high)
+                        } else {
+                            (high, low)
+                        };
+                        let message = format!(
+                            "`{}` accepts `unix_timestamp` only within [{start}, {end}), a {width}s window",
+                            instance.name()
+                        );
+                        findings.push(
+                            Finding::warning(CHECKER, message)
+                                .in_fn(instance)
+                                .key(&format!("bb{low_block}"))
+                                .key(&format!("bb{high_block}")),
+                        );
+                    }
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+fn equality_finding(fn_name: &str, comparison: &Comparison, bound: &Origin) -> Finding {
+    let message = format!(
+        "`{fn_name}` compares `unix_timestamp` {} {bound} (bb{}), which clock drift can skip",
+        comparison.op_str(),
+        comparison.block
+    );
+    Finding::warning(CHECKER, message).key(&format!("bb{}", comparison.block))
+}
+
+fn flip(op: BinOp) -> BinOp {
+    match op {
+        BinOp::Lt => BinOp::Gt,
+        BinOp::Le => BinOp::Ge,
+        BinOp::Gt => BinOp::Lt,
+        BinOp::Ge => BinOp::Le,
+        op => op,
+    }
+}
+
+fn shift(origin: Origin, by: i128) -> Origin {
+    match origin {
+        Origin::Const(value) => Origin::Const(value + by),
+        Origin::Field { path, offset } => Origin::Field {
+            path,
+            offset: offset + by,
+        },
+    }
+}
+
+/// `high - low` when both bounds are constants or offsets of the same field.
+fn width_signed(low: &Origin, high: &Origin) -> i128 {
+    match (low, high) {
+        (Origin::Const(low), Origin::Const(high)) => high - low,
+        (Origin::Field { offset: low, .. }, Origin::Field { offset: high, .. }) => high - low,
+        _ => 0,
+    }
+}
+
+fn window(low: &Origin, high: &Origin) -> Option<i128> {
+    match (low, high) {
+        (Origin::Const(_), Origin::Const(_)) => Some(width_signed(low, high).abs()),
+        (Origin::Field { path: a, .. }, Origin::Field { path: b, .. }) if a == b => {
+            Some(width_signed(low, high).abs())
+        }
+        _ => None,
+    }
+}
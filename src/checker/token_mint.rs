@@ -0,0 +1,211 @@
+use std::collections::{BTreeSet, HashMap};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::{
+    Body, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind,
+};
+use rustc_public::ty::{RigidTy, TyKind};
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::condition::Definitions;
+use crate::analysis::internal::coercion::underlying_adt;
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::anchor_info::access::{Role, local_roles};
+use crate::anchor_info::constraint::AnchorConstraint;
+use crate::report::Finding;
+
+const CHECKER: &str = "unchecked_token_mint";
+/// `CpiContext` helpers moving tokens without naming their mint.
+/// `transfer_checked` passes the mint, which the Token program compares with
+/// both accounts.
+const TRANSFERS: [&str; 2] = [
+    "anchor_spl::token::transfer",
+    "anchor_spl::token_2022::transfer",
+];
+const TOKEN_ACCOUNT: &str = "TokenAccount";
+/// The deserialized state an Anchor `TokenAccount` derefs to, named through
+/// the `anchor_spl` re-exports.
+const TOKEN_ACCOUNT_STATES: [&str; 2] = [
+    "spl_token::state::Account",
+    "spl_token_2022::state::Account",
+];
+const MINT: &str = "mint";
+const PARTIAL_EQ: &str = "PartialEq";
+const EQ_FNS: [&str; 2] = ["eq", "ne"];
+
+/// Report token transfers between accounts none of whose mint is checked,
+/// either with a `token::mint`/`associated_token::mint` constraint or by
+/// comparing its `mint` in the handler. The Token program only requires the
+/// source and destination to share a mint, so a program expecting, say, USDC
+/// in its vault accepts any worthless token an attacker mints. Checking one of
+/// the accounts is enough, and an account the instruction creates with `init`
+/// gets the mint its constraints give.
+pub struct UncheckedTokenMint;
+
+impl Checker for UncheckedTokenMint {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Token transfers between accounts whose mint is never validated"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for cpi in ctx.cpi_calls() {
+            if !cpi
+                .instruction
+                .as_deref()
+                .is_some_and(|instruction| TRANSFERS.contains(&instruction))
+            {
+                continue;
+            }
+            let Some((name, handler)) =
+                ctx.handlers_by_accounts()
+                    .iter()
+                    .find_map(|(name, (_, handlers))| {
+                        handlers
+                            .iter()
+                            .find(|handler| handler.name() == cpi.handler)
+                            .map(|handler| (name, *handler))
+                    })
+            else {
+                continue;
+            };
+            let Some(anchor_accounts) = ctx
+                .anchor_accounts()
+                .iter()
+                .find(|anchor_accounts| anchor_accounts.name == *name)
+            else {
+                continue;
+            };
+            let fields = &anchor_accounts.anchor_accounts;
+            let token_accounts: Vec<usize> = fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| {
+                    cpi.accounts.contains(&field.name)
+                        && field
+                            .kind
+                            .data_type()
+                            .is_some_and(|ty| ty.ends_with(TOKEN_ACCOUNT))
+                })
+                .map(|(idx, _)| idx)
+                .collect();
+            if token_accounts.is_empty() {
+                continue;
+            }
+            let constrained = token_accounts.iter().any(|&idx| {
+                fields[idx].constraints.iter().any(|constraint| {
+                    matches!(
+                        constraint,
+                        AnchorConstraint::TokenMint(_) | AnchorConstraint::Init { .. }
+                    )
+                })
+            });
+            if constrained {
+                continue;
+            }
+            let compared = ctx
+                .body(handler)
+                .map(|body| compared_mints(&body, &token_accounts))
+                .unwrap_or_default();
+            if !compared.is_empty() {
+                continue;
+            }
+            let names: Vec<String> = token_accounts
+                .iter()
+                .map(|&idx| format!("`{}`", fields[idx].name))
+                .collect();
+            let message = format!(
+                "`{}` in `{}` moves tokens between {} without checking their mint; add a `token::mint` constraint to one of them or compare its `mint` with the expected one",
+                cpi.instruction.as_deref().unwrap_or_default(),
+                handler.name(),
+                names.join(" and ")
+            );
+            let mut finding = Finding::warning(CHECKER, message).in_fn(handler);
+            for &idx in &token_accounts {
+                finding = finding.key(&fields[idx].name);
+            }
+            findings.push(finding);
+        }
+        findings
+    }
+}
+
+/// The fields among `token_accounts` whose `mint` `body` compares with
+/// `PartialEq`, e.g. in `require_keys_eq!`.
+fn compared_mints(body: &Body, token_accounts: &[usize]) -> BTreeSet<usize> {
+    let sources: Taint<usize> = local_roles(body, 1, Role::Ctx)
+        .into_iter()
+        .filter_map(|(local, role)| match role {
+            Role::Field(field_idx) if token_accounts.contains(&field_idx) => {
+                Some((local, BTreeSet::from([field_idx])))
+            }
+            _ => None,
+        })
+        .collect();
+    let taint = propagate(body, sources);
+
+    let defs = Definitions::new(body);
+    let mut mints: Taint<usize> = HashMap::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(
+                lhs,
+                Rvalue::CopyForDeref(place)
+                | Rvalue::Ref(_, _, place)
+                | Rvalue::Use(Operand::Copy(place) | Operand::Move(place)),
+            ) = &stmt.kind
+                && is_mint_field(&defs, body, place)
+                && let Some(labels) = taint.get(&place.local)
+            {
+                mints.entry(lhs.local).or_default().extend(labels);
+            }
+        }
+    }
+    let mints = propagate(body, mints);
+
+    let mut compared = BTreeSet::new();
+    for block in &body.blocks {
+        let TerminatorKind::Call { func, args, .. } = &block.terminator.kind else {
+            continue;
+        };
+        let Ok(fn_ty) = func.ty(body.locals()) else {
+            continue;
+        };
+        let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind() else {
+            continue;
+        };
+        let callee_name = fn_def.name();
+        let fn_name = callee_name.rsplit("::").next().unwrap_or(&callee_name);
+        if callee_name.contains(PARTIAL_EQ) && EQ_FNS.contains(&fn_name) {
+            compared.extend(labels_of(&mints, args.iter().filter_map(operand_local)));
+        }
+    }
+    compared
+}
+
+/// Whether `place` is the `mint` of a deserialized SPL token account.
+fn is_mint_field(defs: &Definitions, body: &Body, place: &Place) -> bool {
+    let Some((ProjectionElem::Field(field_idx, _), parent)) = place.projection.split_last() else {
+        return false;
+    };
+    let parent = Place {
+        local: place.local,
+        projection: parent.to_vec(),
+    };
+    underlying_adt(defs, body, &parent).is_some_and(|(adt_def, _)| {
+        let name = adt_def.name();
+        TOKEN_ACCOUNT_STATES
+            .iter()
+            .any(|state| name.ends_with(state))
+            && adt_def
+                .variants_iter()
+                .next()
+                .and_then(|variant| variant.fields().into_iter().nth(*field_idx))
+                .is_some_and(|field| field.name == MINT)
+    })
+}
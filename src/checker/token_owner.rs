@@ -0,0 +1,242 @@
+use std::collections::{BTreeSet, HashMap};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::{
+    Body, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind,
+};
+use rustc_public::ty::{RigidTy, TyKind};
+
+use super::{AnalysisContext, Checker};
+use crate::analysis::condition::Definitions;
+use crate::analysis::internal::coercion::underlying_adt;
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::anchor_info::access::{Role, local_roles};
+use crate::anchor_info::constraint::{AnchorConstraint, Value, check_constraints, constant_value};
+use crate::anchor_info::program_id::ProgramId;
+use crate::metadata::{Concept, known_types};
+use crate::report::{Finding, Severity};
+
+const CHECKER: &str = "unchecked_token_owner";
+
+/// Fields holding an account nobody has validated.
+const RAW_ACCOUNTS: [&str; 2] = [
+    "anchor_lang::prelude::UncheckedAccount",
+    "anchor_lang::prelude::AccountInfo",
+];
+/// Types of a deserialized SPL token account.
+const TOKEN_ACCOUNTS: [&str; 2] = ["TokenAccount", "spl_token::state::Account"];
+/// `AccountDeserialize` and `Pack` fns reading an account from its data.
+const DESERIALIZE_FNS: [&str; 5] = [
+    "try_deserialize",
+    "try_deserialize_unchecked",
+    "unpack",
+    "unpack_unchecked",
+    "unpack_from_slice",
+];
+/// Field index of `owner` in `AccountInfo`.
+const ACCOUNT_INFO_OWNER: usize = 3;
+const PARTIAL_EQ: &str = "PartialEq";
+const EQ_FNS: [&str; 2] = ["eq", "ne"];
+
+/// `TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA` and
+/// `TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb` (Token-2022).
+const TOKEN_PROGRAM_IDS: [ProgramId; 2] = [
+    ProgramId([
+        6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28, 180, 133,
+        237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+    ]),
+    ProgramId([
+        6, 221, 246, 225, 238, 117, 143, 222, 24, 66, 93, 188, 228, 108, 205, 218, 182, 26, 252,
+        77, 131, 185, 13, 39, 254, 189, 249, 40, 216, 161, 139, 252,
+    ]),
+];
+
+/// Report `UncheckedAccount`/`AccountInfo` fields a handler deserializes as an
+/// SPL token account without checking that the account is owned by the Token
+/// program, either with an `owner =` constraint or by comparing its `owner` in
+/// the handler. Anyone can create an account with the same layout under another
+/// program.
+pub struct UncheckedTokenOwner;
+
+impl Checker for UncheckedTokenOwner {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "Raw accounts deserialized as token accounts without checking their owner"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for (name, (adt_def, handlers)) in ctx.handlers_by_accounts() {
+            let Some(variant) = adt_def.variants_iter().next() else {
+                continue;
+            };
+            let fields = variant.fields();
+            let raw: Vec<usize> = fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| {
+                    let ty = field.ty().to_string();
+                    RAW_ACCOUNTS.iter().any(|raw_ty| ty.starts_with(raw_ty))
+                })
+                .map(|(field_idx, _)| field_idx)
+                .collect();
+            if raw.is_empty() {
+                continue;
+            }
+            let constraints = check_constraints(name);
+            let constrained = |field_idx: usize| {
+                constraints
+                    .get(&fields[field_idx].name)
+                    .is_some_and(|constraints| {
+                        constraints.iter().any(|constraint| match constraint {
+                            AnchorConstraint::Owner(value) => is_token_program(value),
+                            _ => false,
+                        })
+                    })
+            };
+            for &handler in handlers {
+                let Some(body) = ctx.body(handler) else {
+                    continue;
+                };
+                for field_idx in unchecked_token_reads(&body, &raw) {
+                    if constrained(field_idx) {
+                        continue;
+                    }
+                    let message = format!(
+                        "`{}` in `{name}` is deserialized as a token account in `{}` without checking that its owner is the Token program",
+                        fields[field_idx].name,
+                        handler.name()
+                    );
+                    findings.push(
+                        Finding::error(CHECKER, message)
+                            .in_fn(handler)
+                            .key(&fields[field_idx].name),
+                    );
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+/// Fields in `raw` whose data `body` deserializes as a token account, unless the
+/// body compares the field's `owner` with a Token program id.
+fn unchecked_token_reads(body: &Body, raw: &[usize]) -> BTreeSet<usize> {
+    let sources: Taint<usize> = local_roles(body, 1, Role::Ctx)
+        .into_iter()
+        .filter_map(|(local, role)| match role {
+            Role::Field(field_idx) if raw.contains(&field_idx) => {
+                Some((local, BTreeSet::from([field_idx])))
+            }
+            _ => None,
+        })
+        .collect();
+    let taint = propagate(body, sources);
+
+    // Locals holding `&Pubkey` constants, and the `owner` of a tracked field.
+    let defs = Definitions::new(body);
+    let mut constants = HashMap::new();
+    let mut owners: Taint<usize> = HashMap::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            match rvalue {
+                Rvalue::Use(Operand::Constant(constant)) => {
+                    constants.insert(lhs.local, constant.const_.clone());
+                }
+                Rvalue::CopyForDeref(place)
+                | Rvalue::Ref(_, _, place)
+                | Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+                    if is_owner_field(&defs, body, place) =>
+                {
+                    if let Some(labels) = taint.get(&place.local) {
+                        owners.entry(lhs.local).or_default().extend(labels);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let owners = propagate(body, owners);
+    let is_token_id = |operand: &Operand| {
+        let constant = match operand {
+            Operand::Constant(constant) => Some(&constant.const_),
+            Operand::Copy(place) | Operand::Move(place) => constants.get(&place.local),
+        };
+        constant
+            .and_then(constant_value)
+            .is_some_and(|value| is_token_program(&value))
+    };
+
+    let mut reads = BTreeSet::new();
+    let mut checked = BTreeSet::new();
+    for block in &body.blocks {
+        let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+        else {
+            continue;
+        };
+        let Ok(fn_ty) = func.ty(body.locals()) else {
+            continue;
+        };
+        let TyKind::RigidTy(RigidTy::FnDef(fn_def, _)) = fn_ty.kind() else {
+            continue;
+        };
+        let callee_name = fn_def.name();
+        let fn_name = callee_name.rsplit("::").next().unwrap_or(&callee_name);
+        if callee_name.contains(PARTIAL_EQ) && EQ_FNS.contains(&fn_name) {
+            if args.iter().any(is_token_id) {
+                checked.extend(labels_of(&owners, args.iter().filter_map(operand_local)));
+            }
+            continue;
+        }
+        let returns_token_account = destination.ty(body.locals()).is_ok_and(|ty| {
+            let ty = ty.to_string();
+            TOKEN_ACCOUNTS.iter().any(|token_ty| ty.contains(token_ty))
+        });
+        if DESERIALIZE_FNS.contains(&fn_name) && returns_token_account {
+            reads.extend(labels_of(&taint, args.iter().filter_map(operand_local)));
+        }
+    }
+    reads.difference(&checked).copied().collect()
+}
+
+/// Whether `place` is the `owner` of an `AccountInfo`, held by value or reached
+/// through a reference, a `Box`, or a coercion of either.
+fn is_owner_field(defs: &Definitions, body: &Body, place: &Place) -> bool {
+    let Some((ProjectionElem::Field(ACCOUNT_INFO_OWNER, _), parent)) =
+        place.projection.split_last()
+    else {
+        return false;
+    };
+    let parent = Place {
+        local: place.local,
+        projection: parent.to_vec(),
+    };
+    underlying_adt(defs, body, &parent)
+        .is_some_and(|(adt_def, _)| known_types().is(Concept::AccountInfo, &adt_def.name()))
+}
+
+fn is_token_program(value: &Value) -> bool {
+    match value {
+        Value::Pubkey(id) => TOKEN_PROGRAM_IDS.contains(id),
+        Value::Bytes(bytes) | Value::Const(_, Some(bytes)) => {
+            ProgramId::try_from(&bytes[..]).is_ok_and(|id| TOKEN_PROGRAM_IDS.contains(&id))
+        }
+        _ => false,
+    }
+}
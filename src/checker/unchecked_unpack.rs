@@ -0,0 +1,143 @@
+use std::collections::BTreeSet;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::{Body, StatementKind, TerminatorKind};
+use rustc_public::ty::{AdtDef, RigidTy, Span};
+
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::analysis::condition::Definitions;
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::anchor_info::remaining::{callee_name, read_place};
+use crate::native_info::pack::UNPACK_UNCHECKED;
+use crate::report::{Finding, location};
+
+const CHECKER: &str = "unchecked_unpack";
+/// `IsInitialized::is_initialized`, and the field it usually reads.
+const IS_INITIALIZED: &str = "is_initialized";
+
+/// Report `Pack::unpack_unchecked` results whose fields are read while nothing
+/// calls `is_initialized()` on them. Unlike `unpack`, `unpack_unchecked` also
+/// decodes an account nobody initialized, e.g. one an attacker created with
+/// zeroed data, so its fields cannot be trusted without that check. The
+/// unchecked form belongs in initializing handlers, which reject accounts that
+/// are already initialized instead.
+pub struct UncheckedUnpack;
+
+impl Checker for UncheckedUnpack {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "unpack_unchecked results read without an is_initialized() check"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let packed: Vec<AdtDef> = ctx
+            .pack_layouts()
+            .iter()
+            .map(|layout| layout.adt_def)
+            .collect();
+        let mut findings = vec![];
+        for &instance in ctx.local_instances() {
+            let Some(body) = ctx.body(instance) else {
+                continue;
+            };
+            for (block, span, adt_def) in trusted_unpacks(&body, &packed) {
+                let message = format!(
+                    "`{}` decodes `{}` with `unpack_unchecked` at {} and reads its fields without checking `is_initialized`",
+                    instance.name(),
+                    adt_def.trimmed_name(),
+                    location(&span)
+                );
+                findings.push(
+                    Finding::warning(CHECKER, message)
+                        .in_fn(instance)
+                        .key(&format!("bb{block}")),
+                );
+            }
+        }
+        findings
+    }
+}
+
+/// The `unpack_unchecked` calls in `body` decoding one of `packed` whose fields
+/// are read, while no `is_initialized` check is made on the result, with the
+/// block of the call.
+fn trusted_unpacks(body: &Body, packed: &[AdtDef]) -> Vec<(usize, Span, AdtDef)> {
+    // Each call labels its result with its block.
+    let mut sources: Taint<usize> = Taint::new();
+    let mut unpacks = vec![];
+    for (bb, block) in body.blocks.iter().enumerate() {
+        if let TerminatorKind::Call {
+            func, destination, ..
+        } = &block.terminator.kind
+            && callee_name(body, func)
+                .is_some_and(|name| name.rsplit("::").next() == Some(UNPACK_UNCHECKED))
+            && let Some(adt_def) = packed
+                .iter()
+                .find(|adt_def| unpacked_type(body, destination.local) == Some(**adt_def))
+        {
+            sources.insert(destination.local, BTreeSet::from([bb]));
+            unpacks.push((bb, block.terminator.span, *adt_def));
+        }
+    }
+    if unpacks.is_empty() {
+        return vec![];
+    }
+    let taint = propagate(body, sources);
+    let defs = Definitions::new(body);
+
+    let mut read: BTreeSet<usize> = BTreeSet::new();
+    let mut checked: BTreeSet<usize> = BTreeSet::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(_, rvalue) = &stmt.kind
+                && let Some(place) = read_place(rvalue)
+                && let Some(labels) = taint.get(&place.local)
+                && let Some((ty, field_idx)) = defs.field_parent(place)
+                && let Some(RigidTy::Adt(adt_def, _)) = ty.kind().rigid()
+                && packed.contains(adt_def)
+            {
+                let is_flag = adt_def
+                    .variants_iter()
+                    .next()
+                    .and_then(|variant| {
+                        variant
+                            .fields()
+                            .get(field_idx)
+                            .map(|field| field.name.clone())
+                    })
+                    .is_some_and(|name| name == IS_INITIALIZED);
+                if is_flag {
+                    checked.extend(labels);
+                } else {
+                    read.extend(labels);
+                }
+            }
+        }
+        if let TerminatorKind::Call { func, args, .. } = &block.terminator.kind
+            && callee_name(body, func)
+                .is_some_and(|name| name.rsplit("::").next() == Some(IS_INITIALIZED))
+        {
+            checked.extend(labels_of(&taint, args.iter().filter_map(operand_local)));
+        }
+    }
+    unpacks
+        .into_iter()
+        .filter(|(bb, _, _)| read.contains(bb) && !checked.contains(bb))
+        .collect()
+}
+
+/// The struct in the `Result` the `unpack_unchecked` call assigning `local`
+/// returns.
+fn unpacked_type(body: &Body, local: usize) -> Option<AdtDef> {
+    let RigidTy::Adt(_, args) = body.locals()[local].ty.kind().rigid()?.clone() else {
+        return None;
+    };
+    match args.0.first()?.ty()?.kind().rigid()? {
+        RigidTy::Adt(adt_def, _) => Some(*adt_def),
+        _ => None,
+    }
+}
@@ -0,0 +1,52 @@
+use super::Checker;
+use super::context::AnalysisContext;
+use crate::anchor_info::constraint::{AnchorConstraint, Value};
+use crate::report::Finding;
+
+const CHECKER: &str = "user_controlled_seeds";
+
+/// Report PDAs whose `seeds = [..]` are all instruction arguments, e.g.
+/// `seeds = [&id.to_le_bytes()]`. With no constant namespace and no account
+/// key among the seeds, every user picking the same argument derives the same
+/// address, so one user can take or block the account another one wants, and
+/// PDAs of different kinds can collide.
+pub struct UserControlledSeeds;
+
+impl Checker for UserControlledSeeds {
+    fn id(&self) -> &'static str {
+        CHECKER
+    }
+
+    fn description(&self) -> &'static str {
+        "PDA seeds made only of instruction arguments"
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = vec![];
+        for anchor_accounts in ctx.anchor_accounts() {
+            for account in &anchor_accounts.anchor_accounts {
+                for constraint in &account.constraints {
+                    let AnchorConstraint::Seeds(seeds) = constraint else {
+                        continue;
+                    };
+                    if seeds.is_empty() || !seeds.iter().all(|seed| matches!(seed, Value::Arg(_))) {
+                        continue;
+                    }
+                    let seeds: Vec<String> = seeds.iter().map(Value::to_string).collect();
+                    let message = format!(
+                        "PDA `{}.{}` is seeded only with instruction arguments [{}], without a constant or an account key",
+                        anchor_accounts.name,
+                        account.name,
+                        seeds.join(", ")
+                    );
+                    findings.push(
+                        Finding::warning(CHECKER, message)
+                            .at(&anchor_accounts.adt_def)
+                            .key(&account.name),
+                    );
+                }
+            }
+        }
+        findings
+    }
+}
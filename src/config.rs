@@ -0,0 +1,464 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use toml::Table;
+
+use crate::analysis::graph::DEFAULT_FIXPOINT_LIMIT;
+use crate::report::Level;
+
+/// Name of the analyzer config file looked up in the analyzed program's root.
+pub const CONFIG_FILE: &str = "solana-analyzer.toml";
+
+/// Project-level file mapping checker names to a level, e.g.
+/// `compute_heavy_loop = "off"`. Overrides the `[checkers]` table of `CONFIG_FILE`.
+pub const LEVELS_FILE: &str = "cfx.toml";
+
+/// Account types of the common price oracles, matched by type name.
+const DEFAULT_ORACLE_TYPES: [&str; 2] = ["PriceUpdateV2", "AggregatorAccountData"];
+
+/// Validator clocks may drift by tens of seconds from wall time.
+const DEFAULT_MIN_TIMESTAMP_WINDOW: i64 = 60;
+
+/// The stack frame of an SBF program holds 4 KiB.
+const DEFAULT_MAX_FRAME_BYTES: usize = 4096;
+
+/// The table of `Config` whose keys are checker ids rather than settings.
+const CHECKERS_TABLE: &str = "checkers";
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("failed to read {0}")]
+    Read(PathBuf),
+    #[error("failed to parse {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("failed to parse the config override `{0}`: {1}")]
+    Override(String, toml::de::Error),
+    #[error("invalid config: {0}")]
+    Invalid(toml::de::Error),
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Checker name -> level; `off` disables the checker.
+    pub checkers: BTreeMap<String, Level>,
+    /// Files, relative to the program root, whose findings are dropped, e.g.
+    /// `src/generated/**`; `*` matches within a path segment, `**` any number
+    /// of segments.
+    pub exclude: Vec<String>,
+    pub oracle: OracleConfig,
+    pub timestamp: TimestampConfig,
+    pub singleton: SingletonConfig,
+    pub finalization: FinalizationConfig,
+    pub stack: StackConfig,
+    pub compute: ComputeConfig,
+    pub analysis: AnalysisConfig,
+    pub advisories: AdvisoriesConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AdvisoriesConfig {
+    /// Whether the advisories shipped with the analyzer are checked.
+    pub builtin: bool,
+    /// Advisory files, TOML or JSON, relative to the program root; see
+    /// `AdvisoryDb::load` for their format.
+    pub files: Vec<String>,
+}
+
+impl Default for AdvisoriesConfig {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            files: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    /// Sweeps the dominator and post-dominator fixpoints take at most; a fn
+    /// whose CFG needs more is analyzed with what was found so far.
+    pub max_fixpoint_iterations: usize,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            max_fixpoint_iterations: DEFAULT_FIXPOINT_LIMIT,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OracleConfig {
+    /// Account types holding oracle data; fields of these types must be pinned by
+    /// address or owner.
+    pub types: Vec<String>,
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        Self {
+            types: DEFAULT_ORACLE_TYPES
+                .iter()
+                .map(|ty| ty.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SingletonConfig {
+    /// Account types of which the program keeps a single instance, such as its
+    /// global config; fields of these types must be pinned by address or seeds.
+    pub types: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FinalizationConfig {
+    /// Fns, by path or by name, that a fn calling them must call on every path
+    /// to its return, such as a check of the state's invariants.
+    pub fns: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ComputeConfig {
+    /// Loops whose syscalls cost fewer CU per iteration than this are not
+    /// reported.
+    pub min_loop_cu: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StackConfig {
+    /// Fns whose variables take more bytes than this are reported.
+    pub max_frame_bytes: usize,
+}
+
+impl Default for StackConfig {
+    fn default() -> Self {
+        Self {
+            max_frame_bytes: DEFAULT_MAX_FRAME_BYTES,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TimestampConfig {
+    /// Windows on `Clock::unix_timestamp` narrower than this many seconds are reported.
+    pub min_window_secs: i64,
+}
+
+impl Default for TimestampConfig {
+    fn default() -> Self {
+        Self {
+            min_window_secs: DEFAULT_MIN_TIMESTAMP_WINDOW,
+        }
+    }
+}
+
+impl Config {
+    /// Load `solana-analyzer.toml` and `cfx.toml` from the root of the crate being
+    /// compiled (cargo sets `CARGO_MANIFEST_DIR`), then apply `overrides`, the
+    /// `--config` flags of the command line, each a TOML key and value such as
+    /// `stack.max_frame_bytes = 8192`. Later sources override earlier ones; a
+    /// file that is absent or broken, or an override that does not parse, is
+    /// left out.
+    pub fn load(overrides: &[String]) -> Self {
+        let root = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let mut sources = vec![];
+        let path = root.join(CONFIG_FILE);
+        if path.exists() {
+            match parse_file(&path) {
+                Ok(table) => sources.push((CONFIG_FILE.to_string(), table)),
+                Err(err) => eprintln!("{err}, ignoring it"),
+            }
+        }
+        let path = root.join(LEVELS_FILE);
+        if path.exists() {
+            match parse_file(&path) {
+                Ok(levels) => {
+                    let table = Table::from_iter([(CHECKERS_TABLE.to_string(), levels.into())]);
+                    sources.push((LEVELS_FILE.to_string(), table));
+                }
+                Err(err) => eprintln!("{err}, ignoring it"),
+            }
+        }
+        for text in overrides {
+            match toml::from_str(text) {
+                Ok(table) => sources.push((format!("--config `{text}`"), table)),
+                Err(err) => eprintln!("{}, ignoring it", ConfigError::Override(text.clone(), err)),
+            }
+        }
+        match Self::from_sources(sources) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("{err}, using the default config");
+                Self::default()
+            }
+        }
+    }
+
+    /// The config of `sources`, each named for the warnings about the keys it
+    /// sets that are not settings, merged in order.
+    fn from_sources(sources: Vec<(String, Table)>) -> Result<Self, ConfigError> {
+        let mut merged = Table::new();
+        for (name, table) in sources {
+            for key in unknown_keys(&table) {
+                eprintln!("warning: unknown key `{key}` in {name}, ignoring it");
+            }
+            merge(&mut merged, table);
+        }
+        Config::deserialize(toml::Value::Table(merged)).map_err(ConfigError::Invalid)
+    }
+
+    /// Whether the findings in `file` are dropped, for a path relative to
+    /// `root`, the program root, or to the working directory, as rustc gives
+    /// the paths of spans.
+    pub fn is_excluded(&self, file: &str, root: &Path) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+        let path = Path::new(file);
+        let absolute = match std::env::current_dir() {
+            Ok(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        };
+        let relative = absolute.strip_prefix(root).unwrap_or(path);
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        self.exclude
+            .iter()
+            .any(|pattern| glob_matches(pattern, &relative))
+    }
+}
+
+/// Copy the keys of `table` into `into`, merging the tables both have.
+fn merge(into: &mut Table, table: Table) {
+    for (key, value) in table {
+        match (into.get_mut(&key), value) {
+            (Some(toml::Value::Table(into)), toml::Value::Table(table)) => merge(into, table),
+            (_, value) => {
+                into.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The dotted paths of the keys of `table` that are not settings of `Config`.
+fn unknown_keys(table: &Table) -> Vec<String> {
+    let toml::Value::Table(known) =
+        toml::Value::try_from(Config::default()).expect("the config serializes to TOML")
+    else {
+        unreachable!("the config is a table");
+    };
+    let mut unknown = vec![];
+    collect_unknown_keys(table, &known, "", &mut unknown);
+    unknown
+}
+
+fn collect_unknown_keys(table: &Table, known: &Table, prefix: &str, unknown: &mut Vec<String>) {
+    for (key, value) in table {
+        let path = format!("{prefix}{key}");
+        match (known.get(key), value) {
+            (None, _) => unknown.push(path),
+            (Some(toml::Value::Table(known)), toml::Value::Table(table))
+                if prefix.is_empty() && key != CHECKERS_TABLE =>
+            {
+                collect_unknown_keys(table, known, &format!("{path}."), unknown);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether `path`, with `/` between its segments, matches the glob `pattern`.
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|seg| !seg.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|seg| !seg.is_empty()).collect();
+    segments_match(&pattern, &path)
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((seg, rest)) => path.split_first().is_some_and(|(name, path_rest)| {
+            segment_matches(seg.as_bytes(), name.as_bytes()) && segments_match(rest, path_rest)
+        }),
+    }
+}
+
+/// Whether `name` matches `pattern`, where `*` is any run of characters and
+/// `?` any one.
+fn segment_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| segment_matches(rest, &name[skip..])),
+        Some((&c, rest)) => name.split_first().is_some_and(|(&n, name_rest)| {
+            (c == b'?' || c == n) && segment_matches(rest, name_rest)
+        }),
+    }
+}
+
+fn parse_file(path: &Path) -> Result<Table, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|_| ConfigError::Read(path.to_path_buf()))?;
+    toml::from_str(&content).map_err(|err| ConfigError::Parse(path.to_path_buf(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_oracle_types() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(
+            config.oracle.types,
+            ["PriceUpdateV2", "AggregatorAccountData"]
+        );
+    }
+
+    #[test]
+    fn test_oracle_types_override() {
+        let config: Config = toml::from_str("[oracle]\ntypes = [\"PriceFeed\"]\n").unwrap();
+        assert_eq!(config.oracle.types, ["PriceFeed"]);
+        assert_eq!(config.timestamp.min_window_secs, 60);
+    }
+
+    #[test]
+    fn test_timestamp_window_override() {
+        let config: Config = toml::from_str("[timestamp]\nmin_window_secs = 5\n").unwrap();
+        assert_eq!(config.timestamp.min_window_secs, 5);
+        assert_eq!(config.oracle.types.len(), 2);
+    }
+
+    #[test]
+    fn test_finalization_fns() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.finalization.fns.is_empty());
+        let config: Config =
+            toml::from_str("[finalization]\nfns = [\"check_invariants\"]\n").unwrap();
+        assert_eq!(config.finalization.fns, ["check_invariants"]);
+    }
+
+    #[test]
+    fn test_fixpoint_limit() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.analysis.max_fixpoint_iterations, 10_000);
+        let config: Config = toml::from_str("[analysis]\nmax_fixpoint_iterations = 50\n").unwrap();
+        assert_eq!(config.analysis.max_fixpoint_iterations, 50);
+    }
+
+    #[test]
+    fn test_singleton_types() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.singleton.types.is_empty());
+        let config: Config =
+            toml::from_str("[singleton]\ntypes = [\"Config\", \"State\"]\n").unwrap();
+        assert_eq!(config.singleton.types, ["Config", "State"]);
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.stack.max_frame_bytes, 4096);
+        assert_eq!(config.compute.min_loop_cu, 0);
+        let config: Config =
+            toml::from_str("[stack]\nmax_frame_bytes = 1024\n[compute]\nmin_loop_cu = 500\n")
+                .unwrap();
+        assert_eq!(config.stack.max_frame_bytes, 1024);
+        assert_eq!(config.compute.min_loop_cu, 500);
+    }
+
+    #[test]
+    fn test_advisories() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.advisories.builtin);
+        assert!(config.advisories.files.is_empty());
+        let config: Config =
+            toml::from_str("[advisories]\nbuiltin = false\nfiles = [\"advisories.json\"]\n")
+                .unwrap();
+        assert!(!config.advisories.builtin);
+        assert_eq!(config.advisories.files, ["advisories.json"]);
+    }
+
+    fn source(name: &str, text: &str) -> (String, Table) {
+        (name.to_string(), toml::from_str(text).unwrap())
+    }
+
+    #[test]
+    fn test_precedence() {
+        let config = Config::from_sources(vec![
+            source(
+                CONFIG_FILE,
+                "[checkers]\nduplicate_mutable_account = \"off\"\nfloat_round = \"error\"\n\
+                 [stack]\nmax_frame_bytes = 1024\n[timestamp]\nmin_window_secs = 30\n",
+            ),
+            source(LEVELS_FILE, "[checkers]\nfloat_round = \"warning\"\n"),
+            source("--config", "stack.max_frame_bytes = 8192"),
+            source("--config", "checkers.duplicate_mutable_account = \"error\""),
+        ])
+        .unwrap();
+        assert_eq!(config.checkers["duplicate_mutable_account"], Level::Error);
+        assert_eq!(config.checkers["float_round"], Level::Warning);
+        assert_eq!(config.stack.max_frame_bytes, 8192);
+        // Settings no later source sets are kept.
+        assert_eq!(config.timestamp.min_window_secs, 30);
+        assert_eq!(config.oracle.types.len(), 2);
+
+        let err = Config::from_sources(vec![source("--config", "stack.max_frame_bytes = \"big\"")]);
+        assert!(matches!(err, Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let table: Table = toml::from_str(
+            "exclude = []\nthreshold = 1\n[checkers]\nnot_a_checker = \"off\"\n\
+             [stack]\nmax_frame_bytes = 1\nmax_depth = 2\n[loops]\nmax = 3\n",
+        )
+        .unwrap();
+        assert_eq!(
+            unknown_keys(&table),
+            ["loops", "stack.max_depth", "threshold"]
+        );
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("src/generated/**", "src/generated/mod.rs"));
+        assert!(glob_matches("src/generated/**", "src/generated/a/b.rs"));
+        assert!(!glob_matches("src/generated/**", "src/lib.rs"));
+        assert!(glob_matches(
+            "**/*_generated.rs",
+            "src/ix/accounts_generated.rs"
+        ));
+        assert!(glob_matches("src/?ib.rs", "src/lib.rs"));
+        assert!(!glob_matches("src/*.rs", "src/generated/mod.rs"));
+    }
+
+    #[test]
+    fn test_checker_levels() {
+        let config: Config = toml::from_str(
+            "[checkers]\ncompute_heavy_loop = \"off\"\nstale_read_after_cpi = \"error\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.checkers["compute_heavy_loop"], Level::Off);
+        assert_eq!(config.checkers["stale_read_after_cpi"], Level::Error);
+
+        let levels: BTreeMap<String, Level> =
+            toml::from_str("fragile_timestamp_check = \"warning\"\n").unwrap();
+        assert_eq!(levels["fragile_timestamp_check"], Level::Warning);
+        assert!(toml::from_str::<BTreeMap<String, Level>>("x = \"info\"\n").is_err());
+    }
+}
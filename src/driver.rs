@@ -0,0 +1,1154 @@
+//! The analysis run: the `rustc_public` driver compiling the crate, and what is
+//! extracted and checked once it has been analyzed.
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use rustc_public::mir::Body;
+use rustc_public::{CompilerError, CrateDef, run};
+use thiserror::Error;
+
+use crate::aggregate::{REPORT_DIR_ENV, workspace_callee};
+use crate::analysis::callgraph::local_instances;
+use crate::analysis::cfg::cfg_dot;
+use crate::analysis::graph::Fixpoints;
+use crate::analysis::loops::predecessors;
+use crate::anchor_info::account_graph::AccountGraph;
+use crate::anchor_info::constants::ConstantSource;
+use crate::anchor_info::cpi::{CpiCall, CpiTarget};
+use crate::anchor_info::layout::{BorshSize, account_layout};
+use crate::anchor_info::program_id::{ProgramId, ProgramIdError};
+use crate::anchor_info::{
+    AnchorAccountKind, extract_constants, extract_error_codes, extract_events, hex,
+    logged_messages, read_idl, to_idl_json,
+};
+use crate::checker::{AnalysisContext, CheckerRegistry, CheckerSelection, STATS_ENV};
+use crate::config::{CONFIG_FILE, Config};
+use crate::metadata::{
+    ProgramType, WorkspaceProgram, check_program_type, discover_workspace_programs,
+    find_workspace_root, parse_package_version, parse_toml_in_crate_path,
+};
+use crate::report::baseline::Baseline;
+use crate::report::json::{
+    AnalysisReport, ModelSummary, ReportAccounts, ReportArg, ReportCpi, ReportFinding,
+    ReportHandler, ReportInstruction, ReportNativeInstruction, ReportTarget,
+};
+use crate::report::sarif::SarifLog;
+use crate::report::text::TextReport;
+use crate::report::timings::TimingTable;
+use crate::report::{Finding, Reporter, Severity, group_by_handler};
+use crate::target::{TARGET_CRATE_FLAG, is_analyzed, target};
+
+/// Register the `cfx` tool so analyzed crates can carry `#[cfx::allow(..)]`.
+const CRATE_ATTRS: [&str; 2] = [
+    "-Zcrate-attr=feature(register_tool)",
+    "-Zcrate-attr=register_tool(cfx)",
+];
+
+/// Print the data layout of every account type.
+const LAYOUTS_FLAG: &str = "--layouts";
+/// Print the CFG of the fns with the given name as DOT instead of analyzing.
+const DUMP_CFG_FLAG: &str = "--dump-cfg";
+/// Write the `AnalysisReport` of the crate as JSON to the given path.
+const REPORT_FLAG: &str = "--report";
+/// Write an Anchor-IDL-shaped JSON of the extracted program model to the given
+/// path.
+const EMIT_IDL_FLAG: &str = "--emit-idl";
+/// Report where the IDL JSON at the given path no longer matches the program.
+const COMPARE_IDL_FLAG: &str = "--compare-idl";
+/// Fail the analysis unless the crate declares the given program id, in base58
+/// or as `0x` and hex digits.
+const EXPECT_PROGRAM_ID_FLAG: &str = "--expect-program-id";
+/// Override a setting of `solana-analyzer.toml` with a TOML key and value,
+/// e.g. `stack.max_frame_bytes = 8192`.
+const CONFIG_FLAG: &str = "--config";
+/// Analyzer flags given as one arg, e.g. `--analyzer-skip=float_round`, so they
+/// cannot be taken for rustc's.
+const ANALYZER_FLAG_PREFIX: &str = "--analyzer-";
+/// Run only the checkers with the given comma-separated ids.
+const CHECKERS_FLAG: &str = "--analyzer-checkers=";
+/// Do not run the checkers with the given comma-separated ids.
+const SKIP_FLAG: &str = "--analyzer-skip=";
+/// Print the report as `text`, the default, as `json` or as `sarif`.
+const FORMAT_FLAG: &str = "--analyzer-format=";
+/// Write the JSON or SARIF report to the given path rather than to stdout.
+const OUTPUT_FLAG: &str = "--analyzer-output=";
+/// Write the graph of which instructions use which account types to the given
+/// path, as JSON if it ends in `.json` and as DOT otherwise.
+const ACCOUNT_GRAPH_FLAG: &str = "--analyzer-account-graph=";
+/// Once the crate is compiled, fail with `DENIED_EXIT_CODE` if a finding is at
+/// or above the given severity, `info`, `warning` or `error`.
+const DENY_FLAG: &str = "--analyzer-deny=";
+/// Only print the findings not in the `AnalysisReport` JSON at the given path,
+/// e.g. one written by `--report` before a change, and those fixed since.
+const DIFF_FLAG: &str = "--analyzer-diff=";
+/// Mark the findings whose fingerprint is in the baseline file at the given
+/// path, if there is one, so that `--analyzer-deny` only fails on new findings.
+const BASELINE_FILE_FLAG: &str = "--analyzer-baseline=";
+/// Rewrite the `--analyzer-baseline` file with the findings of this run.
+const UPDATE_BASELINE_FLAG: &str = "--analyzer-update-baseline";
+/// Print what each extraction pass and checker took, and add it to the JSON
+/// report.
+const TIMINGS_FLAG: &str = "--analyzer-timings";
+/// Print where the arguments of each reachable fn flow, into its return value
+/// and into CPIs.
+const FLOWS_FLAG: &str = "--analyzer-flows";
+/// Print the id, description and severity of each checker instead of compiling.
+pub const LIST_CHECKERS_FLAG: &str = "--analyzer-list-checkers";
+/// Analyzer flags, separated by whitespace, applied before those of the args;
+/// for runs as `RUSTC_WRAPPER`, where the args are cargo's.
+pub const FLAGS_ENV: &str = "SOLANA_ANALYZER_FLAGS";
+/// Package version in the IDL when the manifest does not give one.
+const UNKNOWN_VERSION: &str = "0.0.0";
+
+#[derive(Error, Debug)]
+pub enum AnalysisError {
+    /// The crate was analyzed, but failed a gate such as `--expect-program-id`.
+    #[error("`{}` failed the analysis", .0.crate_name)]
+    Rejected(Box<AnalysisReport>),
+    /// A finding reached the severity of `--analyzer-deny`; the crate compiled.
+    #[error("`{}` has findings at or above the `--analyzer-deny` severity", .0.crate_name)]
+    Denied(Box<AnalysisReport>),
+    #[error("the crate failed to compile")]
+    Failed,
+    /// rustc stopped before analysis, e.g. when asked for `--version`.
+    #[error("the compiler did not reach the analysis")]
+    Skipped,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum OptionsError {
+    #[error(transparent)]
+    ProgramId(#[from] ProgramIdError),
+    #[error("unknown analyzer flag `{0}`")]
+    UnknownFlag(String),
+    #[error("unknown output format `{0}`, expected `text`, `json` or `sarif`")]
+    UnknownFormat(String),
+    #[error("unknown severity `{0}`, expected `info`, `warning` or `error`")]
+    UnknownSeverity(String),
+    #[error("`{UPDATE_BASELINE_FLAG}` needs `{BASELINE_FILE_FLAG}<path>`")]
+    UpdateBaselineWithoutFile,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// What is extracted and found, line by line, as it is analyzed.
+    #[default]
+    Text,
+    /// The `AnalysisReport` once the analysis is done.
+    Json,
+    /// A SARIF 2.1.0 log of the findings, for code scanning.
+    Sarif,
+}
+
+impl FromStr for OutputFormat {
+    type Err = OptionsError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            _ => Err(OptionsError::UnknownFormat(format.to_string())),
+        }
+    }
+}
+
+/// A run of the analyzer: the args rustc would get, and the analyzer flags
+/// taken out of them.
+#[derive(Debug, Default)]
+pub struct AnalyzerOptions {
+    pub rustc_args: Vec<String>,
+    pub layouts: bool,
+    pub dump_cfg: Option<String>,
+    pub report: Option<String>,
+    /// The earlier report of `--analyzer-diff`.
+    pub diff: Option<String>,
+    pub emit_idl: Option<String>,
+    pub compare_idl: Option<String>,
+    pub expect_program_id: Option<ProgramId>,
+    pub target_crate: Option<String>,
+    /// The `--config` overrides, in the order given.
+    pub config: Vec<String>,
+    /// The checkers of `--analyzer-checkers` and `--analyzer-skip`.
+    pub checkers: CheckerSelection,
+    pub list_checkers: bool,
+    pub format: OutputFormat,
+    /// Where `--analyzer-format=json` or `sarif` writes the report, stdout
+    /// when `None`.
+    pub output: Option<String>,
+    pub account_graph: Option<String>,
+    /// The severity of `--analyzer-deny`.
+    pub deny: Option<Severity>,
+    pub baseline_file: Option<String>,
+    pub update_baseline: bool,
+    pub timings: bool,
+    pub flows: bool,
+}
+
+impl AnalyzerOptions {
+    /// Split `args`, given to the analyzer in place of rustc, into the rustc
+    /// args and the analyzer flags, after applying those of `FLAGS_ENV`.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, OptionsError> {
+        let env_flags = std::env::var(FLAGS_ENV).unwrap_or_default();
+        Self::parse(args, env_flags.split_whitespace())
+    }
+
+    fn parse<'f>(
+        args: impl IntoIterator<Item = String>,
+        env_flags: impl IntoIterator<Item = &'f str>,
+    ) -> Result<Self, OptionsError> {
+        let mut options = AnalyzerOptions::default();
+        for flag in env_flags {
+            options.analyzer_flag(flag)?;
+        }
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                LAYOUTS_FLAG => options.layouts = true,
+                DUMP_CFG_FLAG => options.dump_cfg = args.next(),
+                REPORT_FLAG => options.report = args.next(),
+                EMIT_IDL_FLAG => options.emit_idl = args.next(),
+                COMPARE_IDL_FLAG => options.compare_idl = args.next(),
+                TARGET_CRATE_FLAG => options.target_crate = args.next(),
+                CONFIG_FLAG => options.config.extend(args.next()),
+                EXPECT_PROGRAM_ID_FLAG => {
+                    options.expect_program_id = args.next().map(|id| id.parse()).transpose()?;
+                }
+                flag if flag.starts_with(ANALYZER_FLAG_PREFIX) => options.analyzer_flag(flag)?,
+                _ => options.rustc_args.push(arg),
+            }
+        }
+        if options.update_baseline && options.baseline_file.is_none() {
+            return Err(OptionsError::UpdateBaselineWithoutFile);
+        }
+        Ok(options)
+    }
+
+    /// Apply one `--analyzer-` flag.
+    fn analyzer_flag(&mut self, flag: &str) -> Result<(), OptionsError> {
+        let ids = |list: &str| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        if let Some(list) = flag.strip_prefix(CHECKERS_FLAG) {
+            self.checkers.only.get_or_insert_default().extend(ids(list));
+        } else if let Some(list) = flag.strip_prefix(SKIP_FLAG) {
+            self.checkers.skip.extend(ids(list));
+        } else if flag == LIST_CHECKERS_FLAG {
+            self.list_checkers = true;
+        } else if let Some(format) = flag.strip_prefix(FORMAT_FLAG) {
+            self.format = format.parse()?;
+        } else if let Some(path) = flag.strip_prefix(OUTPUT_FLAG) {
+            self.output = Some(path.to_string());
+        } else if let Some(level) = flag.strip_prefix(DENY_FLAG) {
+            self.deny = Some(match level {
+                "info" => Severity::Info,
+                "warning" => Severity::Warning,
+                "error" => Severity::Error,
+                _ => return Err(OptionsError::UnknownSeverity(level.to_string())),
+            });
+        } else if let Some(path) = flag.strip_prefix(ACCOUNT_GRAPH_FLAG) {
+            self.account_graph = Some(path.to_string());
+        } else if let Some(path) = flag.strip_prefix(DIFF_FLAG) {
+            self.diff = Some(path.to_string());
+        } else if let Some(path) = flag.strip_prefix(BASELINE_FILE_FLAG) {
+            self.baseline_file = Some(path.to_string());
+        } else if flag == UPDATE_BASELINE_FLAG {
+            self.update_baseline = true;
+        } else if flag == TIMINGS_FLAG {
+            self.timings = true;
+        } else if flag == FLOWS_FLAG {
+            self.flows = true;
+        } else {
+            return Err(OptionsError::UnknownFlag(flag.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Whether the crate the rustc args compile is analyzed, rather than only
+    /// compiled.
+    pub fn is_analyzed(&self) -> bool {
+        is_analyzed(
+            &self.rustc_args,
+            target(self.target_crate.clone()).as_deref(),
+        )
+    }
+}
+
+/// Compile the crate of `options` with the analysis as the callback of the
+/// `rustc_public` driver, printing what is extracted and found on the way,
+/// and return its report. The report is an `AnalysisError::Denied` when a
+/// finding reaches the severity of `--analyzer-deny`, for the caller to exit
+/// with `DENIED_EXIT_CODE`.
+pub fn run_analysis(options: AnalyzerOptions) -> Result<AnalysisReport, AnalysisError> {
+    let mut rustc_args = options.rustc_args.clone();
+    rustc_args.extend(CRATE_ATTRS.map(String::from));
+    let deny = options.deny;
+    match run!(&rustc_args, || analyze(options)) {
+        Ok(report) if deny.is_some_and(|level| report.has_findings_at(&level.to_string())) => {
+            Err(AnalysisError::Denied(Box::new(report)))
+        }
+        Ok(report) => Ok(report),
+        Err(CompilerError::Interrupted(report)) => Err(AnalysisError::Rejected(Box::new(report))),
+        Err(CompilerError::Failed) => Err(AnalysisError::Failed),
+        Err(CompilerError::Skipped) => Err(AnalysisError::Skipped),
+    }
+}
+
+/// The id, description and default severity of each built-in checker, by id.
+pub fn checker_descriptions() -> Vec<(&'static str, &'static str, Severity)> {
+    CheckerRegistry::builtin()
+        .checkers()
+        .map(|checker| (checker.id(), checker.description(), checker.severity()))
+        .collect()
+}
+
+/// Extract the program model of the crate, print it with the findings of the
+/// checkers, and build its report. The report is also written out with
+/// `--report`, or when `cargo solana-analyze` collects it. Breaks when the
+/// crate fails a gate.
+fn analyze(options: AnalyzerOptions) -> ControlFlow<AnalysisReport, AnalysisReport> {
+    let start = Instant::now();
+    let _capture = (options.format != OutputFormat::Text && options.output.is_none())
+        .then(OutputCapture::start);
+    println!("Analyzing");
+    let local_crate = rustc_public::local_crate();
+    println!("crate: {}", local_crate.name);
+    let mut siblings = workspace_programs();
+    siblings.retain(|program| program.name != local_crate.name);
+    let config = Config::load(&options.config);
+    if let Some(name) = &options.dump_cfg {
+        dump_cfg(
+            name,
+            &Fixpoints::new(config.analysis.max_fixpoint_iterations),
+        );
+        return ControlFlow::Continue(empty_report(local_crate.name, siblings));
+    }
+    let mut reporter = Reporter::new(&config);
+    reporter.select(options.checkers.clone());
+    // Without the findings to compare with, a CI gate would pass or fail on
+    // all of them, so the run fails instead.
+    if let Some(path) = &options.diff {
+        match AnalysisReport::read(Path::new(path)) {
+            Ok(previous) => reporter.diff_against(previous.findings),
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ControlFlow::Break(empty_report(local_crate.name, siblings));
+            }
+        }
+    }
+    if let Some(path) = &options.baseline_file {
+        match Baseline::read(Path::new(path)) {
+            Ok(Some(baseline)) => reporter.demote(baseline),
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ControlFlow::Break(empty_report(local_crate.name, siblings));
+            }
+        }
+    }
+    let mut ctx = AnalysisContext::new(&config);
+    if let Some(path) = &options.compare_idl {
+        match read_idl(Path::new(path)) {
+            Ok(idl) => ctx = ctx.with_idl_comparison(idl, to_idl_json(&package_version())),
+            Err(err) => eprintln!("error: {err}"),
+        }
+    }
+
+    let program_id = match ctx.program_id() {
+        Ok(program_id) => {
+            println!("program id: {program_id}");
+            Some(*program_id)
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            None
+        }
+    };
+    let mut failed = false;
+    if let Some(expected) = options.expect_program_id {
+        let mismatch = match ctx.program_id() {
+            Ok(program_id) if *program_id == expected => None,
+            Ok(program_id) => Some(format!(
+                "program id {program_id} does not match the expected {expected}"
+            )),
+            Err(err) => Some(format!(
+                "cannot compare the program id with the expected {expected}: {err}"
+            )),
+        };
+        if let Some(message) = mismatch {
+            failed = reporter.report(Finding::error("expect_program_id", message));
+        }
+    }
+
+    for (account, discriminator) in ctx.discriminators() {
+        println!("account {account} discriminator: {}", hex(discriminator));
+    }
+    for (instruction, discriminator) in ctx.instruction_discriminators() {
+        println!("{instruction} discriminator: {}", hex(discriminator));
+    }
+    for constant in extract_constants() {
+        match constant.source {
+            ConstantSource::Item => println!("constant {constant}"),
+            ConstantSource::Seed { .. } => println!("seed {constant}"),
+        }
+    }
+    for error_code in extract_error_codes() {
+        println!(
+            "error {} {}::{}: {}",
+            error_code.code, error_code.enum_name, error_code.name, error_code.msg
+        );
+    }
+
+    for handler in ctx.handlers() {
+        let params: Vec<_> = std::iter::once(format!("Context<{}>", handler.context_struct))
+            .chain(
+                handler
+                    .args
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}")),
+            )
+            .collect();
+        println!(
+            "instruction {}: {}({})",
+            handler.name,
+            handler.instance.name(),
+            params.join(", ")
+        );
+        let fields = handler
+            .accounts_def
+            .variants_iter()
+            .next()
+            .map(|variant| variant.fields())
+            .unwrap_or_default();
+        let field_names = |field_idxs: &BTreeSet<usize>| -> String {
+            field_idxs
+                .iter()
+                .filter_map(|field_idx| fields.get(*field_idx).map(|field| field.name.clone()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        if handler.remaining_accounts.used {
+            println!(
+                "remaining accounts {}: {}",
+                handler.name, handler.remaining_accounts
+            );
+        }
+        for guard in &handler.access_controls {
+            println!(
+                "access control {} at {}: {} reads [{}], checks key of [{}]",
+                handler.name,
+                guard.location,
+                guard.instance.name(),
+                field_names(&guard.reads),
+                field_names(&guard.key_checks)
+            );
+        }
+    }
+
+    for instruction in ctx.native_instructions() {
+        let variant = instruction
+            .variant
+            .as_ref()
+            .map_or(String::new(), |variant| format!(" {variant}"));
+        println!(
+            "native instruction {}{variant}: {}",
+            instruction.tag,
+            instruction.handler.name()
+        );
+    }
+
+    for model in ctx.native_accounts() {
+        for account in &model.accounts {
+            println!(
+                "native account {} #{} {}: {account}",
+                model.handler.name(),
+                account.position,
+                account.name.as_deref().unwrap_or("_")
+            );
+        }
+    }
+
+    for event in extract_events() {
+        println!(
+            "event {} discriminator: {}, {}",
+            event.name,
+            hex(&event.discriminator),
+            size_str(event.size)
+        );
+        for field in &event.fields {
+            let dynamic = if field.size.is_some_and(|size| size.dynamic) {
+                " (dynamic)"
+            } else {
+                ""
+            };
+            println!("event {}.{}: {}{dynamic}", event.name, field.name, field.ty);
+        }
+    }
+
+    let program_id_str = program_id.map(|program_id| program_id.to_string());
+    let mut cpi_calls = vec![];
+    for cpi in ctx.cpi_calls().iter().cloned() {
+        println!(
+            "cpi at {} in {}: {} on {} with [{}]",
+            cpi.location,
+            cpi.handler,
+            cpi.instruction.as_deref().unwrap_or("<instruction>"),
+            cpi.target,
+            cpi.accounts.join(", ")
+        );
+        let cpi = report_cpi(cpi, program_id_str.as_deref(), &siblings);
+        if let Some(callee) = &cpi.workspace_callee {
+            println!(
+                "cpi at {} in {} into workspace program {callee}",
+                cpi.location, cpi.handler
+            );
+        }
+        cpi_calls.push(cpi);
+    }
+
+    for log in logged_messages(ctx.reachable_instances()) {
+        let message = log
+            .message
+            .map_or("<dynamic>".to_string(), |msg| format!("{msg:?}"));
+        println!(
+            "log in {}: {message} ({})",
+            log.function,
+            log.arg_types.join(", ")
+        );
+    }
+
+    if options.flows {
+        print_flows(&ctx);
+    }
+
+    if options.layouts {
+        print_layouts(&ctx);
+    }
+
+    for anchor_accounts in ctx.anchor_accounts() {
+        for anchor_account in &anchor_accounts.anchor_accounts {
+            if !anchor_account.constraints.is_empty() {
+                println!(
+                    "{}.{}: {:?}",
+                    anchor_accounts.name, anchor_account.name, anchor_account.constraints
+                );
+            }
+        }
+    }
+    print_account_metas(&ctx);
+
+    for entry in ctx.entrypoints() {
+        println!("entrypoint {}: {}", entry.kind, entry.instance.name());
+    }
+
+    let mut registry = CheckerRegistry::configured(&config);
+    registry.select(&options.checkers);
+    let checker_timings = registry.run(&ctx, &reporter);
+    let partial = ctx.fixpoints().partial();
+    if partial > 0 {
+        reporter.report(Finding::warning(
+            "fixpoint_limit",
+            format!(
+                "{partial} dominator computations stopped after {} sweeps, the findings \
+                 relying on them may be incomplete; raise `max_fixpoint_iterations` under \
+                 `[analysis]` in {CONFIG_FILE}",
+                ctx.fixpoints().limit()
+            ),
+        ));
+    }
+    let diffs = reporter.diffs();
+    let findings = reporter.finish();
+    let handlers = ctx.instruction_handlers();
+    if !diffs {
+        println!(
+            "{}",
+            TextReport::new(
+                local_crate.name.clone(),
+                program_id_str.clone(),
+                &findings,
+                &handlers,
+                start.elapsed(),
+            )
+        );
+    }
+    if options.update_baseline
+        && let Some(path) = &options.baseline_file
+    {
+        let baseline = Baseline::new(&findings);
+        match baseline.write(Path::new(path)) {
+            Ok(()) => println!(
+                "baseline: wrote {} fingerprints to {path}",
+                baseline.entries.len()
+            ),
+            Err(err) => eprintln!("error: {err}"),
+        }
+    }
+    if std::env::var_os(STATS_ENV).is_some() {
+        eprint!("{}", ctx.stats());
+    }
+    let timings = options.timings.then(|| {
+        let mut timings = ctx.stats().timings();
+        timings.extend(checker_timings);
+        println!("{}", TimingTable(&timings));
+        timings
+    });
+
+    // `cargo solana-analyze` collects the report of each crate from a directory.
+    let report_path = options.report.as_ref().map(PathBuf::from).or_else(|| {
+        std::env::var_os(REPORT_DIR_ENV)
+            .map(|dir| Path::new(&dir).join(format!("{}.json", local_crate.name)))
+    });
+    let report = AnalysisReport {
+        crate_name: local_crate.name.clone(),
+        program_id: program_id_str,
+        program_type: program_type(),
+        cpi_calls,
+        findings: findings.iter().map(ReportFinding::from).collect(),
+        handlers: group_by_handler(&findings, &handlers)
+            .into_iter()
+            .map(|(handler, findings)| ReportHandler {
+                handler,
+                findings: findings.into_iter().map(ReportFinding::from).collect(),
+            })
+            .collect(),
+        model: model_summary(&ctx),
+        workspace_programs: siblings,
+        timings,
+        ..Default::default()
+    };
+    if let Some(path) = &report_path
+        && let Err(err) = report.write(path)
+    {
+        eprintln!("error: {err}");
+    }
+    if let Some(path) = &options.emit_idl {
+        emit_idl(path);
+    }
+    if let Some(path) = &options.account_graph {
+        emit_account_graph(&ctx, path);
+    }
+    let output = match options.format {
+        OutputFormat::Text => None,
+        OutputFormat::Json => Some(report.to_json()),
+        OutputFormat::Sarif => {
+            Some(SarifLog::new(&findings, &checker_descriptions(), &workspace_root()).to_json())
+        }
+    };
+    if let Some(output) = output {
+        match &options.output {
+            Some(path) => {
+                if let Err(err) = std::fs::write(path, output) {
+                    eprintln!("error: failed to write {path}: {err}");
+                }
+            }
+            // Past the `OutputCapture`, which only takes `print!`s.
+            None => {
+                writeln!(std::io::stdout().lock(), "{output}").ok();
+            }
+        }
+    }
+
+    if failed {
+        return ControlFlow::Break(report);
+    }
+    ControlFlow::Continue(report)
+}
+
+/// The report of a crate that was not analyzed.
+fn empty_report(crate_name: String, workspace_programs: Vec<WorkspaceProgram>) -> AnalysisReport {
+    AnalysisReport {
+        crate_name,
+        workspace_programs,
+        ..Default::default()
+    }
+}
+
+/// What the context extracted, for the report.
+fn model_summary(ctx: &AnalysisContext) -> ModelSummary {
+    ModelSummary {
+        instructions: ctx
+            .handlers()
+            .iter()
+            .map(|handler| ReportInstruction {
+                name: handler.name.clone(),
+                handler: handler.instance.name(),
+                accounts: handler.context_struct.clone(),
+                args: handler
+                    .args
+                    .iter()
+                    .map(|(name, ty)| ReportArg {
+                        name: name.clone(),
+                        ty: ty.clone(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        native_instructions: ctx
+            .native_instructions()
+            .iter()
+            .map(|instruction| ReportNativeInstruction {
+                tag: instruction.tag,
+                variant: instruction.variant.clone(),
+                handler: instruction.handler.name(),
+            })
+            .collect(),
+        accounts: ctx
+            .anchor_accounts()
+            .iter()
+            .map(|accounts| ReportAccounts {
+                name: accounts.name.clone(),
+                fields: accounts
+                    .anchor_accounts
+                    .iter()
+                    .map(|account| account.name.clone())
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+/// `cpi` for the report, resolved against the other programs of the workspace.
+fn report_cpi(cpi: CpiCall, program_id: Option<&str>, siblings: &[WorkspaceProgram]) -> ReportCpi {
+    let mut cpi = ReportCpi {
+        handler: cpi.handler,
+        location: cpi.location,
+        target: match cpi.target {
+            CpiTarget::Const(id) => ReportTarget::Const(id.to_string()),
+            CpiTarget::Field(field) => ReportTarget::Field(field),
+            CpiTarget::Dynamic => ReportTarget::Dynamic,
+        },
+        instruction: cpi.instruction,
+        accounts: cpi.accounts,
+        workspace_callee: None,
+    };
+    cpi.workspace_callee =
+        workspace_callee(&cpi, program_id, siblings).map(|program| program.name.clone());
+    cpi
+}
+
+/// The programs of the workspace of the crate cargo compiles, found from its
+/// manifest directory.
+fn workspace_programs() -> Vec<WorkspaceProgram> {
+    let Ok(dir) = std::env::var("CARGO_MANIFEST_DIR") else {
+        return vec![];
+    };
+    match discover_workspace_programs(&find_workspace_root(Path::new(&dir))) {
+        Ok(programs) => programs,
+        Err(err) => {
+            eprintln!("error: {err}");
+            vec![]
+        }
+    }
+}
+
+/// Write the account graph of the program to `path`.
+fn emit_account_graph(ctx: &AnalysisContext, path: &str) {
+    let graph = AccountGraph::new(
+        ctx.handlers(),
+        ctx.anchor_accounts(),
+        ctx.to_account_metas(),
+    );
+    let graph = if path.ends_with(".json") {
+        graph.to_json()
+    } else {
+        graph.to_dot()
+    };
+    if let Err(err) = std::fs::write(path, graph) {
+        eprintln!("error: failed to write {path}: {err}");
+    }
+}
+
+/// The root of the workspace of the crate cargo compiles, which the paths of
+/// its spans are relative to; the current directory outside of cargo.
+fn workspace_root() -> PathBuf {
+    match std::env::var("CARGO_MANIFEST_DIR") {
+        Ok(dir) => find_workspace_root(Path::new(&dir)),
+        Err(_) => std::env::current_dir().unwrap_or_default(),
+    }
+}
+
+/// Write the IDL of the crate to `path`.
+fn emit_idl(path: &str) {
+    let idl = serde_json::to_string_pretty(&to_idl_json(&package_version()))
+        .expect("IDLs serialize to JSON");
+    if let Err(err) = std::fs::write(path, idl) {
+        eprintln!("error: failed to write {path}: {err}");
+    }
+}
+
+/// What the analysis prints while the JSON or SARIF report goes to stdout, written to
+/// stderr when dropped so stdout holds only the report.
+struct OutputCapture(Arc<Mutex<Vec<u8>>>);
+
+impl OutputCapture {
+    fn start() -> Self {
+        let captured = Arc::new(Mutex::new(vec![]));
+        std::io::set_output_capture(Some(Arc::clone(&captured)));
+        Self(captured)
+    }
+}
+
+impl Drop for OutputCapture {
+    fn drop(&mut self) {
+        std::io::set_output_capture(None);
+        let captured = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        std::io::stderr().write_all(&captured).ok();
+    }
+}
+
+/// The framework of the crate being compiled, by the dependencies of its
+/// manifest.
+fn program_type() -> Option<ProgramType> {
+    let dir = std::env::var("CARGO_MANIFEST_DIR").ok()?;
+    let (_, deps) = parse_toml_in_crate_path(&dir).ok()?;
+    Some(check_program_type(&deps))
+}
+
+/// Package version of the crate, read from the manifest cargo compiles it for.
+fn package_version() -> String {
+    std::env::var("CARGO_MANIFEST_DIR")
+        .ok()
+        .and_then(|dir| parse_package_version(&dir).ok().flatten())
+        .unwrap_or_else(|| UNKNOWN_VERSION.to_string())
+}
+
+/// Print the flow summary of each reachable fn with any flow.
+fn print_flows(ctx: &AnalysisContext) {
+    for instance in ctx.reachable_instances() {
+        let Some(summary) = ctx.flow_summaries().get(instance) else {
+            continue;
+        };
+        let mut flows: Vec<_> = summary
+            .to_return
+            .iter()
+            .map(|arg| format!("arg {arg} -> return"))
+            .collect();
+        for (arg, sinks) in &summary.to_sinks {
+            flows.extend(sinks.iter().map(|sink| format!("arg {arg} -> {sink}")));
+        }
+        if !flows.is_empty() {
+            println!("flow {}: {}", instance.name(), flows.join(", "));
+        }
+    }
+}
+
+/// Print the CFG of each local fn whose path is or ends with `name`, followed
+/// by its dominators.
+fn dump_cfg(name: &str, fixpoints: &Fixpoints) {
+    let suffix = format!("::{name}");
+    let mut found = false;
+    for instance in local_instances() {
+        let fn_name = instance.name();
+        if fn_name != name && !fn_name.ends_with(&suffix) {
+            continue;
+        }
+        if let Some(body) = instance.body() {
+            print!("{}", cfg_dot(&fn_name, &body));
+            print!("{}", dominator_comments(&body, fixpoints));
+            found = true;
+        }
+    }
+    if !found {
+        eprintln!("error: no fn named `{name}` to dump");
+    }
+}
+
+/// Print the flags of the `AccountMeta`s the client structs build for each
+/// field, e.g. `meta Deposit.payer: writable, signer`.
+fn print_account_metas(ctx: &AnalysisContext) {
+    for meta in ctx.to_account_metas().iter().chain(ctx.cpi_account_metas()) {
+        let Some(field) = ctx
+            .anchor_accounts()
+            .iter()
+            .find(|anchor_accounts| anchor_accounts.name == meta.struct_name)
+            .and_then(|anchor_accounts| anchor_accounts.anchor_accounts.get(meta.field_idx))
+        else {
+            continue;
+        };
+        let client = if meta.is_cpi { "cpi meta" } else { "meta" };
+        let access = if meta.writable {
+            "writable"
+        } else {
+            "readonly"
+        };
+        let signer = if meta.signer { ", signer" } else { "" };
+        println!(
+            "{client} {}.{}: {access}{signer}",
+            meta.struct_name, field.name
+        );
+    }
+}
+
+/// Print the layout of each local account type an Accounts struct refers to.
+fn print_layouts(ctx: &AnalysisContext) {
+    let mut symbols = vec![];
+    for anchor_accounts in ctx.anchor_accounts() {
+        for anchor_account in &anchor_accounts.anchor_accounts {
+            if let AnchorAccountKind::Account(symbol) = &anchor_account.kind
+                && !symbols.contains(symbol)
+            {
+                symbols.push(symbol.clone());
+            }
+        }
+    }
+    for layout in symbols.iter().filter_map(|symbol| account_layout(symbol)) {
+        println!(
+            "layout {}: {}, discriminator {} bytes",
+            layout.name,
+            size_str(layout.size),
+            layout.discriminator_len
+        );
+        // Offsets after a dynamic field are the smallest possible ones.
+        let mut after_dynamic = false;
+        for field in &layout.fields {
+            let offset = match field.offset {
+                Some(offset) if after_dynamic => format!("{offset}+"),
+                Some(offset) => offset.to_string(),
+                None => "?".to_string(),
+            };
+            println!(
+                "layout {}.{}: {} at {offset}, {}",
+                layout.name,
+                field.name,
+                field.ty,
+                size_str(field.size)
+            );
+            after_dynamic |= field.size.is_some_and(|size| size.dynamic);
+        }
+    }
+    for layout in ctx.pack_layouts() {
+        println!(
+            "pack layout {}: LEN {}{}",
+            layout.adt_def.trimmed_name(),
+            layout
+                .len
+                .map_or("unknown".to_string(), |len| len.to_string()),
+            if layout.is_initialized {
+                ", IsInitialized"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+fn size_str(size: Option<BorshSize>) -> String {
+    match size {
+        Some(size) if size.dynamic => format!("at least {} bytes (dynamic)", size.size),
+        Some(size) => format!("{} bytes", size.size),
+        None => "unknown size".to_string(),
+    }
+}
+
+/// The predecessors, dominator tree and post-dominators of the blocks of
+/// `body`, as DOT comments following its graph.
+fn dominator_comments(body: &Body, fixpoints: &Fixpoints) -> String {
+    let preds = predecessors(body);
+    let dominator_tree: BTreeMap<_, _> = fixpoints
+        .dominators(body.blocks.len(), &preds)
+        .dominator_tree()
+        .into_iter()
+        .map(|(node, mut children)| {
+            children.sort();
+            (node, children)
+        })
+        .collect();
+    let post_dominators: BTreeMap<_, BTreeSet<_>> = compute_postdominators(body)
+        .into_iter()
+        .map(|(block, post_dominators)| (block, post_dominators.into_iter().collect()))
+        .collect();
+    format!(
+        "// predecessors: {preds:?}\n// dominator tree: {dominator_tree:?}\n// post-dominators: {post_dominators:?}\n"
+    )
+}
+
+fn compute_postdominators(body: &Body) -> HashMap<usize, HashSet<usize>> {
+    let mut postdoms: HashMap<usize, HashSet<usize>> = HashMap::new();
+    let num_blocks = body.blocks.len();
+    let mut exit_nodes = HashSet::new();
+
+    // Find all exit nodes (blocks with no successors).
+    for i in 0..num_blocks {
+        if body.blocks[i].terminator.successors().is_empty() {
+            exit_nodes.insert(i);
+        }
+    }
+
+    // Initialize post-dominator sets.
+    for i in 0..num_blocks {
+        if exit_nodes.contains(&i) {
+            let mut pd_set = HashSet::new();
+            pd_set.insert(i);
+            postdoms.insert(i, pd_set);
+        } else {
+            let all_blocks: HashSet<usize> = (0..num_blocks).collect();
+            postdoms.insert(i, all_blocks);
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        // The algorithm iterates until there are no changes to the post-dominator sets.
+        // We iterate over all nodes except the exit nodes.
+        for i in (0..num_blocks).rev() {
+            // Iterating in reverse can improve performance but is not required for correctness.
+            if !exit_nodes.contains(&i) {
+                let succs = body.blocks[i].terminator.successors();
+
+                // Intersect the post-dominator sets of all successors.
+                let mut intersection = (0..num_blocks).collect::<HashSet<usize>>();
+
+                let mut first_succ = true;
+                for s in succs {
+                    if let Some(succ_pds) = postdoms.get(&s) {
+                        if first_succ {
+                            intersection = succ_pds.clone();
+                            first_succ = false;
+                        } else {
+                            intersection = &intersection & succ_pds;
+                        }
+                    }
+                }
+
+                // Add the current block to its own post-dominator set.
+                intersection.insert(i);
+
+                if let Some(current_pds) = postdoms.get_mut(&i)
+                    && *current_pds != intersection
+                {
+                    *current_pds = intersection;
+                    changed = true;
+                }
+            }
+        }
+    }
+    postdoms
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_from_args() {
+        let args = [
+            "rustc",
+            "--crate-name",
+            "vault",
+            LAYOUTS_FLAG,
+            REPORT_FLAG,
+            "vault.json",
+            "--analyzer-diff=main.json",
+            "src/lib.rs",
+            TARGET_CRATE_FLAG,
+            "vault",
+        ];
+        let options = AnalyzerOptions::from_args(args.map(String::from)).unwrap();
+        assert_eq!(
+            options.rustc_args,
+            ["rustc", "--crate-name", "vault", "src/lib.rs"]
+        );
+        assert!(options.layouts);
+        assert_eq!(options.report.as_deref(), Some("vault.json"));
+        assert_eq!(options.diff.as_deref(), Some("main.json"));
+        assert_eq!(options.target_crate.as_deref(), Some("vault"));
+        assert!(
+            AnalyzerOptions::from_args([EXPECT_PROGRAM_ID_FLAG, "0xzz"].map(String::from)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_analyzer_flags() {
+        let args = [
+            "rustc",
+            "--crate-name",
+            "vault",
+            "--analyzer-checkers=unchecked_unpack, missing_writeback",
+            "--edition=2021",
+            "--analyzer-skip=missing_writeback",
+            "--cfg",
+            "feature=\"analyzer-checkers\"",
+            "src/lib.rs",
+        ];
+        let options = AnalyzerOptions::parse(
+            args.map(String::from),
+            ["--analyzer-skip=float_round", LIST_CHECKERS_FLAG],
+        )
+        .unwrap();
+        assert_eq!(
+            options.rustc_args,
+            [
+                "rustc",
+                "--crate-name",
+                "vault",
+                "--edition=2021",
+                "--cfg",
+                "feature=\"analyzer-checkers\"",
+                "src/lib.rs"
+            ]
+        );
+        assert!(options.list_checkers);
+        assert!(options.checkers.is_selected("unchecked_unpack"));
+        assert!(!options.checkers.is_selected("missing_writeback"));
+        assert!(!options.checkers.is_selected("float_round"));
+        assert!(!options.checkers.is_selected("duplicate_mutable_account"));
+        assert_eq!(
+            AnalyzerOptions::parse(["--analyzer-checkes=seeds".to_string()], []).unwrap_err(),
+            OptionsError::UnknownFlag("--analyzer-checkes=seeds".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deny() {
+        let options = AnalyzerOptions::parse(["src/lib.rs".to_string()], []).unwrap();
+        assert_eq!(options.deny, None);
+        let options = AnalyzerOptions::parse([], ["--analyzer-deny=warning"]).unwrap();
+        assert_eq!(options.deny, Some(Severity::Warning));
+        assert_eq!(
+            AnalyzerOptions::parse(["--analyzer-deny=high".to_string()], []).unwrap_err(),
+            OptionsError::UnknownSeverity("high".to_string())
+        );
+    }
+
+    #[test]
+    fn test_baseline_file() {
+        let args = [
+            "--analyzer-baseline=baseline.json",
+            "--analyzer-update-baseline",
+        ];
+        let options = AnalyzerOptions::parse(args.map(String::from), []).unwrap();
+        assert_eq!(options.baseline_file.as_deref(), Some("baseline.json"));
+        assert!(options.update_baseline);
+        assert_eq!(
+            AnalyzerOptions::parse(["--analyzer-update-baseline".to_string()], []).unwrap_err(),
+            OptionsError::UpdateBaselineWithoutFile
+        );
+    }
+
+    #[test]
+    fn test_output_format() {
+        let options = AnalyzerOptions::parse(["src/lib.rs".to_string()], []).unwrap();
+        assert_eq!(options.format, OutputFormat::Text);
+        let args = ["--analyzer-format=json", "--analyzer-output=report.json"];
+        let options = AnalyzerOptions::parse(args.map(String::from), []).unwrap();
+        assert_eq!(options.format, OutputFormat::Json);
+        assert_eq!(options.output.as_deref(), Some("report.json"));
+        let options = AnalyzerOptions::parse(["--analyzer-format=sarif".to_string()], []).unwrap();
+        assert_eq!(options.format, OutputFormat::Sarif);
+        assert_eq!(
+            AnalyzerOptions::parse(["--analyzer-format=yaml".to_string()], []).unwrap_err(),
+            OptionsError::UnknownFormat("yaml".to_string())
+        );
+    }
+}
@@ -1,13 +1,55 @@
-// #![feature(rustc_private)]
-// #![feature(assert_matches)]
-// #![feature(let_chains)]
-
-// extern crate rustc_driver;
-// extern crate rustc_interface;
-// #[macro_use]
-// extern crate rustc_smir;
-// extern crate rustc_middle;
-// extern crate stable_mir;
-
-// pub mod analysis;
+//! The Solana program analyzer as a library.
+//!
+//! With the default `driver` feature, [`run_analysis`] compiles a crate with
+//! the `rustc_public` driver and analyzes it; this needs the nightly toolchain
+//! of `rust-toolchain.toml` with its `rustc-dev` component. Without it, only the
+//! report types are built, e.g. for tools reading the JSON reports of
+//! `--report`, `--analyzer-format=json` or `cargo solana-analyze --format json`
+//! on a stable toolchain.
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+#![cfg_attr(feature = "driver", feature(assert_matches))]
+#![cfg_attr(feature = "driver", feature(internal_output_capture))]
+#![cfg_attr(all(test, feature = "driver"), feature(test))]
+
+#[cfg(feature = "driver")]
+extern crate rustc_driver;
+#[cfg(feature = "driver")]
+extern crate rustc_interface;
+#[cfg(feature = "driver")]
+extern crate rustc_middle;
+#[cfg(feature = "driver")]
+extern crate rustc_public;
+
+pub mod aggregate;
 pub mod metadata;
+pub mod target;
+
+#[cfg(feature = "driver")]
+mod analysis;
+#[cfg(feature = "driver")]
+mod anchor_info;
+#[cfg(feature = "driver")]
+mod checker;
+#[cfg(feature = "driver")]
+mod config;
+#[cfg(feature = "driver")]
+mod driver;
+#[cfg(feature = "driver")]
+mod native_info;
+#[cfg(feature = "driver")]
+mod report;
+
+pub use aggregate::{AnalysisReport, ReportFinding as Finding};
+#[cfg(feature = "driver")]
+pub use anchor_info::program_id::{ProgramId, ProgramIdError};
+#[cfg(feature = "driver")]
+pub use anchor_info::{AnchorAccounts, HandlerInfo};
+#[cfg(feature = "driver")]
+pub use checker::CheckerSelection;
+#[cfg(feature = "driver")]
+pub use driver::{
+    AnalysisError, AnalyzerOptions, FLAGS_ENV, LIST_CHECKERS_FLAG, OptionsError, OutputFormat,
+    checker_descriptions, run_analysis,
+};
+#[cfg(feature = "driver")]
+pub use report::Severity;
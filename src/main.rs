@@ -1,207 +1,129 @@
 #![feature(rustc_private)]
-#![feature(assert_matches)]
 
 extern crate rustc_driver;
-extern crate rustc_interface;
-extern crate rustc_middle;
-extern crate rustc_public;
 
-use rustc_public::mir::mono::Instance;
-use rustc_public::mir::Body;
-use rustc_public::mir::TerminatorKind;
-use rustc_public::ty::RigidTy;
-use rustc_public::ty::TyKind;
-use rustc_public::CompilerError;
-use rustc_public::run;
-use rustc_public::ItemKind;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::ops::ControlFlow;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
 use std::process::ExitCode;
 
-use crate::anchor_info::entry_instance;
-use crate::anchor_info::{extract_discriminators, extract_program_id};
-use crate::checker::detect_duplicate_mutable_account;
-use crate::checker::detect_float_round_fn;
+use crate::manifest::{MANIFEST_PATH_FLAG, analyze_manifest};
+use solana_program_analyzer::aggregate::{
+    AnalysisReport, DENIED_EXIT_CODE, DENIED_FILE_ENV, ReportError, combine,
+};
+use solana_program_analyzer::{
+    AnalysisError, AnalyzerOptions, LIST_CHECKERS_FLAG, checker_descriptions, run_analysis,
+};
 
-mod analysis;
-mod anchor_info;
-mod checker;
+mod manifest;
+
+/// Combine the reports written with `--report` for several crates.
+const COMBINE_FLAG: &str = "--combine";
+/// Print the id, description and severity of each checker; as
+/// `LIST_CHECKERS_FLAG`, which can also be given among the rustc args.
+const LIST_FLAG: &str = "--list-checkers";
 
 fn main() -> ExitCode {
-    let rustc_args: Vec<_> = std::env::args().collect();
-    let result = run!(&rustc_args, demo_analysis);
-    match result {
-        Ok(_) | Err(CompilerError::Skipped | CompilerError::Interrupted(_)) => ExitCode::SUCCESS,
-        _ => ExitCode::FAILURE,
+    let mut rustc_args: Vec<_> = std::env::args().collect();
+    if rustc_args.get(1).map(String::as_str) == Some(MANIFEST_PATH_FLAG) {
+        let analyzer_args = rustc_args.get(3..).unwrap_or_default();
+        return match analyze_manifest(rustc_args.get(2).map(String::as_str), analyzer_args) {
+            Ok(code) => code,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        };
     }
-}
-
-fn demo_analysis() -> ControlFlow<()> {
-    println!("Analyzing");
-    let local_crate = rustc_public::local_crate();
-    println!("crate: {}", local_crate.name);
-    if local_crate.name != "cfx_stake_core" {
-        return ControlFlow::Continue(());
+    if rustc_args.get(1).map(String::as_str) == Some(COMBINE_FLAG) {
+        return match combine_reports(&rustc_args[2..]) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        };
     }
-
-    let program_id = extract_program_id();
-    println!("{:?}", program_id);
-
-    let discriminators = extract_discriminators();
-    println!("{:?}", discriminators);
-
-    if let Some(entry) = entry_instance()
-        && let Some(body) = entry.body()
-    {
-        
-        let preds = compute_preds(&body);
-        println!("{:?}", preds);
-
-        let dominators = compute_dominators(&body, &preds);
-        println!("{:?}", dominators);
-
-        let post_dominators = compute_postdominators(&body);
-        println!("{:?}", post_dominators);
+    if matches!(
+        rustc_args.get(1).map(String::as_str),
+        Some(LIST_FLAG | LIST_CHECKERS_FLAG)
+    ) {
+        list_checkers();
+        return ExitCode::SUCCESS;
     }
-
-    detect_float_round_fn();
-    // detect_duplicate_mutable_account();
-
-    ControlFlow::Continue(())
-}
-
-fn compute_preds(body: &Body) -> HashMap<usize, HashSet<usize>> {
-    let mut preds: HashMap<usize, HashSet<usize>> = HashMap::new();
-    let mut worklist: Vec<usize> = (0..body.blocks.len()).collect();
-
-    while let Some(bb) = worklist.pop() {
-        // Get the successors of the current block.
-        let succs = body.blocks[bb].terminator.successors();
-
-        for succ in succs {
-            let pred_set = preds.entry(succ).or_default();
-
-            if pred_set.insert(bb) {
-                // If a new predecessor was found for `succ`,
-                // add `succ` to the worklist to propagate the information.
-                worklist.push(succ);
-            }
+    // As `RUSTC_WORKSPACE_WRAPPER`, cargo passes the path of the real rustc first.
+    if rustc_args.get(1).map(Path::new).and_then(Path::file_stem) == Some("rustc".as_ref()) {
+        rustc_args.remove(1);
+    }
+    let options = match AnalyzerOptions::from_args(rustc_args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
         }
+    };
+    if options.list_checkers {
+        list_checkers();
+        return ExitCode::SUCCESS;
     }
-    preds
-}
-
-fn compute_dominators(body: &Body, preds: &HashMap<usize, HashSet<usize>>) -> HashMap<usize, HashSet<usize>> {
-    let mut doms: HashMap<usize, HashSet<usize>> = HashMap::new();
-    let num_blocks = body.blocks.len();
-
-    // The entry block (block 0) dominates itself.
-    let mut entry_dom_set = HashSet::new();
-    entry_dom_set.insert(0);
-    doms.insert(0, entry_dom_set);
-
-    // All other nodes initially have a dominator set containing all nodes.
-    for i in 1..num_blocks {
-        let all_blocks: HashSet<usize> = (0..num_blocks).collect();
-        doms.insert(i, all_blocks);
+    if !options.is_analyzed() {
+        return compile(&options.rustc_args);
     }
-
-    let mut changed = true;
-    while changed {
-        changed = false;
-        // The algorithm iterates until there are no changes to the dominator sets.
-        for i in 1..num_blocks {
-            if let Some(predecessors) = preds.get(&i) {
-                // Intersect the dominator sets of all predecessors.
-                let mut intersection = (0..num_blocks).collect::<HashSet<usize>>();
-                
-                let mut first_pred = true;
-                for &p in predecessors {
-                    if let Some(pred_doms) = doms.get(&p) {
-                        if first_pred {
-                            intersection = pred_doms.clone();
-                            first_pred = false;
-                        } else {
-                            intersection = &intersection & pred_doms;
-                        }
-                    }
-                }
-                
-                // Add the current block to its own dominator set.
-                intersection.insert(i);
-
-                if let Some(current_doms) = doms.get_mut(&i) {
-                    if *current_doms != intersection {
-                        *current_doms = intersection;
-                        changed = true;
-                    }
-                }
-            }
+    match run_analysis(options) {
+        Ok(_) | Err(AnalysisError::Skipped) => ExitCode::SUCCESS,
+        // The analysis stops the compilation when the crate fails a gate such as
+        // `--expect-program-id`.
+        Err(AnalysisError::Rejected(_)) => ExitCode::FAILURE,
+        Err(ref err @ AnalysisError::Denied(ref report)) => {
+            eprintln!("error: {err}");
+            denied(&report.crate_name)
         }
+        Err(AnalysisError::Failed) => ExitCode::FAILURE,
     }
-    doms
 }
 
-fn compute_postdominators(body: &Body) -> HashMap<usize, HashSet<usize>> {
-    let mut postdoms: HashMap<usize, HashSet<usize>> = HashMap::new();
-    let num_blocks = body.blocks.len();
-    let mut exit_nodes = HashSet::new();
-    
-    // Find all exit nodes (blocks with no successors).
-    for i in 0..num_blocks {
-        if body.blocks[i].terminator.successors().is_empty() {
-            exit_nodes.insert(i);
-        }
+/// Exit with `DENIED_EXIT_CODE`, or, under a wrapper that set
+/// `DENIED_FILE_ENV`, record the crate in its file and let cargo carry on.
+fn denied(crate_name: &str) -> ExitCode {
+    let Some(path) = std::env::var_os(DENIED_FILE_ENV) else {
+        return ExitCode::from(DENIED_EXIT_CODE);
+    };
+    let recorded = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{crate_name}"));
+    match recorded {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(_) => ExitCode::from(DENIED_EXIT_CODE),
     }
+}
 
-    // Initialize post-dominator sets.
-    for i in 0..num_blocks {
-        if exit_nodes.contains(&i) {
-            let mut pd_set = HashSet::new();
-            pd_set.insert(i);
-            postdoms.insert(i, pd_set);
-        } else {
-            let all_blocks: HashSet<usize> = (0..num_blocks).collect();
-            postdoms.insert(i, all_blocks);
-        }
+fn list_checkers() {
+    for (id, description, severity) in checker_descriptions() {
+        println!("{id} ({severity}): {description}");
     }
+}
 
-    let mut changed = true;
-    while changed {
-        changed = false;
-        // The algorithm iterates until there are no changes to the post-dominator sets.
-        // We iterate over all nodes except the exit nodes.
-        for i in (0..num_blocks).rev() { // Iterating in reverse can improve performance but is not required for correctness.
-            if !exit_nodes.contains(&i) {
-                let succs = body.blocks[i].terminator.successors();
-                
-                // Intersect the post-dominator sets of all successors.
-                let mut intersection = (0..num_blocks).collect::<HashSet<usize>>();
-                
-                let mut first_succ = true;
-                for s in succs {
-                    if let Some(succ_pds) = postdoms.get(&s) {
-                        if first_succ {
-                            intersection = succ_pds.clone();
-                            first_succ = false;
-                        } else {
-                            intersection = &intersection & succ_pds;
-                        }
-                    }
-                }
-                
-                // Add the current block to its own post-dominator set.
-                intersection.insert(i);
-
-                if let Some(current_pds) = postdoms.get_mut(&i) {
-                    if *current_pds != intersection {
-                        *current_pds = intersection;
-                        changed = true;
-                    }
-                }
-            }
-        }
+/// Compile a crate we do not analyze as rustc would.
+fn compile(rustc_args: &[String]) -> ExitCode {
+    struct Compile;
+    impl rustc_driver::Callbacks for Compile {}
+    let code =
+        rustc_driver::catch_with_exit_code(|| rustc_driver::run_compiler(rustc_args, &mut Compile));
+    if code == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
     }
-    postdoms
-}
\ No newline at end of file
+}
+
+/// Print the crates of the reports at `paths` and the CPIs between them.
+fn combine_reports(paths: &[String]) -> Result<(), ReportError> {
+    let reports = paths
+        .iter()
+        .map(|path| AnalysisReport::read(Path::new(path)))
+        .collect::<Result<Vec<_>, _>>()?;
+    print!("{}", combine(reports));
+    Ok(())
+}
@@ -0,0 +1,92 @@
+//! `solana-program-analyzer --manifest-path <path>`: analyze a crate without
+//! assembling rustc args by hand.
+//!
+//! We re-run cargo on the crate with ourselves as `RUSTC_WORKSPACE_WRAPPER`, so
+//! only the crate's workspace members go through the analyzer while their
+//! dependencies are built by the toolchain we were built with.
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use solana_program_analyzer::aggregate::{DENIED_EXIT_CODE, DENIED_FILE_ENV};
+use solana_program_analyzer::metadata::{SolanaMetadataError, parse_toml_in_crate_path};
+use thiserror::Error;
+
+use solana_program_analyzer::target::TARGET_ENV;
+
+pub const MANIFEST_PATH_FLAG: &str = "--manifest-path";
+
+/// rustup sets this while building us; the analyzed crate's dependencies must be
+/// compiled by the same toolchain to be loadable by the analyzer.
+const TOOLCHAIN: Option<&str> = option_env!("RUSTUP_TOOLCHAIN");
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("missing path after {MANIFEST_PATH_FLAG}")]
+    MissingPath,
+    #[error("failed to read the manifest of {0}: {1}")]
+    Metadata(PathBuf, SolanaMetadataError),
+    #[error("failed to locate the analyzer executable: {0}")]
+    CurrentExe(io::Error),
+    #[error("failed to run cargo: {0}")]
+    Cargo(io::Error),
+}
+
+/// Check the library of the crate whose manifest is `manifest_path` with the
+/// analyzer and return the exit code: a failure when cargo fails, and
+/// `DENIED_EXIT_CODE` when the crate builds but has findings denied by
+/// `--analyzer-deny`. The path may name the `Cargo.toml` itself or the
+/// directory holding it. `analyzer_args` are analyzer flags such as
+/// `--layouts`, passed on with the rustc args.
+pub fn analyze_manifest(
+    manifest_path: Option<&str>,
+    analyzer_args: &[String],
+) -> Result<ExitCode, ManifestError> {
+    let path = Path::new(manifest_path.ok_or(ManifestError::MissingPath)?);
+    let crate_dir = if path.file_name().is_some_and(|name| name == "Cargo.toml") {
+        path.parent().unwrap_or(Path::new("."))
+    } else {
+        path
+    };
+    let (crate_name, _) = parse_toml_in_crate_path(&crate_dir.to_string_lossy())
+        .map_err(|err| ManifestError::Metadata(crate_dir.to_path_buf(), err))?;
+    let analyzer = std::env::current_exe().map_err(ManifestError::CurrentExe)?;
+    // A fresh cfg value makes cargo re-check the crate instead of reusing its
+    // cached metadata, which would skip the analyzer.
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    let denied_file = std::env::temp_dir().join(format!(
+        "solana-analyzer-denied-{}-{nonce}",
+        std::process::id()
+    ));
+    let mut cargo = Command::new("cargo");
+    cargo
+        .arg("rustc")
+        .arg(MANIFEST_PATH_FLAG)
+        .arg(crate_dir.join("Cargo.toml"))
+        .args(["--lib", "--profile", "check", "--"])
+        .arg("--cfg")
+        .arg(format!("solana_analyzer_run=\"{nonce}\""))
+        .args(analyzer_args)
+        .env("RUSTC_WORKSPACE_WRAPPER", analyzer)
+        .env(TARGET_ENV, crate_name)
+        .env(DENIED_FILE_ENV, &denied_file)
+        // A caching wrapper would replay the output instead of running us.
+        .env_remove("RUSTC_WRAPPER")
+        .env_remove("RUSTC");
+    if let Some(toolchain) = TOOLCHAIN {
+        cargo.env("RUSTUP_TOOLCHAIN", toolchain);
+    }
+    let status = cargo.status().map_err(ManifestError::Cargo)?;
+    let denied = denied_file.is_file();
+    let _ = std::fs::remove_file(&denied_file);
+    Ok(if !status.success() {
+        ExitCode::FAILURE
+    } else if denied {
+        ExitCode::from(DENIED_EXIT_CODE)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
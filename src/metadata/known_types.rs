@@ -0,0 +1,177 @@
+//! Where the types and fns the analyses look for live in the SDK a program is
+//! written against.
+//!
+//! `solana-program` re-exports the modular `solana-account-info`,
+//! `solana-pubkey`, .. crates, and Anchor re-exports `solana-program`, so a
+//! path as rustc prints it may start with any of them. Pinocchio defines its
+//! own `AccountInfo`, sysvars and CPI fns, and makes `Pubkey` an alias of
+//! `[u8; 32]`.
+use std::env;
+use std::sync::OnceLock;
+
+use super::parser::{ParsedDependency, ProgramType, check_program_type, parse_toml_in_crate_path};
+
+/// Crates the Solana SDK types can be named through.
+const SOLANA_CRATES: [&str; 11] = [
+    "solana_program",
+    "solana_sdk",
+    "anchor_lang",
+    "solana_account_info",
+    "solana_instruction",
+    "solana_pubkey",
+    "solana_cpi",
+    "solana_rent",
+    "solana_clock",
+    "solana_sysvar",
+    "solana_program_entrypoint",
+];
+const PINOCCHIO_CRATES: [&str; 3] = ["pinocchio", "pinocchio_pubkey", "pinocchio_log"];
+
+/// A type or fn of the SDK that the analyses look for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Concept {
+    AccountInfo,
+    Pubkey,
+    /// The fns performing a CPI.
+    Invoke,
+    Rent,
+    Clock,
+    AccountMeta,
+}
+
+impl Concept {
+    /// Last path segments naming it, in any flavor of the SDK.
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Concept::AccountInfo => &["AccountInfo"],
+            Concept::Pubkey => &["Pubkey"],
+            Concept::Invoke => &[
+                "invoke",
+                "invoke_signed",
+                "invoke_unchecked",
+                "invoke_signed_unchecked",
+                "invoke_with_bounds",
+                "invoke_signed_with_bounds",
+                "slice_invoke",
+                "slice_invoke_signed",
+            ],
+            Concept::Rent => &["Rent"],
+            Concept::Clock => &["Clock"],
+            Concept::AccountMeta => &["AccountMeta"],
+        }
+    }
+}
+
+/// The SDK flavor of the analyzed program and the crates its types are named
+/// through.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KnownTypes {
+    pub program_type: ProgramType,
+    crates: Vec<&'static str>,
+}
+
+impl Default for KnownTypes {
+    fn default() -> Self {
+        Self::new(ProgramType::Other)
+    }
+}
+
+impl KnownTypes {
+    pub fn new(program_type: ProgramType) -> Self {
+        let crates = match program_type {
+            // Pinocchio programs may still take `Pubkey` or a sysvar from the
+            // modular Solana crates.
+            ProgramType::Pinocchio => PINOCCHIO_CRATES
+                .iter()
+                .chain(SOLANA_CRATES.iter())
+                .copied()
+                .collect(),
+            ProgramType::Anchor | ProgramType::SolanaNative | ProgramType::Other => {
+                SOLANA_CRATES.to_vec()
+            }
+        };
+        Self {
+            program_type,
+            crates,
+        }
+    }
+
+    pub fn from_deps(deps: &[ParsedDependency]) -> Self {
+        Self::new(check_program_type(deps))
+    }
+
+    /// Whether `path`, a def path as rustc prints it, e.g.
+    /// `solana_program::account_info::AccountInfo`, names `concept`. Generic
+    /// arguments are ignored, and so are the underscores of re-exports such as
+    /// `__Pubkey`.
+    pub fn is(&self, concept: Concept, path: &str) -> bool {
+        let path = path.split('<').next().unwrap_or(path);
+        let Some((krate, rest)) = path.split_once("::") else {
+            return false;
+        };
+        let name = rest.rsplit("::").next().unwrap_or(rest);
+        let name = name.trim_start_matches('_');
+        self.crates.contains(&krate) && concept.names().contains(&name)
+    }
+}
+
+/// The known types of the crate being compiled, from the manifest cargo
+/// compiles it for; the Solana SDK when it cannot be read.
+pub fn known_types() -> &'static KnownTypes {
+    static KNOWN_TYPES: OnceLock<KnownTypes> = OnceLock::new();
+    KNOWN_TYPES.get_or_init(|| {
+        env::var("CARGO_MANIFEST_DIR")
+            .ok()
+            .and_then(|dir| parse_toml_in_crate_path(&dir).ok())
+            .map_or_else(KnownTypes::default, |(_, deps)| {
+                KnownTypes::from_deps(&deps)
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(name: &str) -> ParsedDependency {
+        ParsedDependency {
+            name: name.to_string(),
+            version: None,
+        }
+    }
+
+    #[test]
+    fn test_solana_paths() {
+        let known = KnownTypes::from_deps(&[dep("solana-program")]);
+        assert_eq!(known.program_type, ProgramType::SolanaNative);
+        assert!(known.is(
+            Concept::AccountInfo,
+            "solana_program::account_info::AccountInfo<'_>"
+        ));
+        assert!(known.is(Concept::AccountInfo, "solana_account_info::AccountInfo"));
+        assert!(known.is(
+            Concept::Pubkey,
+            "solana_program::solana_program_entrypoint::__Pubkey"
+        ));
+        assert!(known.is(Concept::Invoke, "solana_cpi::invoke_signed"));
+        assert!(known.is(Concept::Clock, "anchor_lang::prelude::Clock"));
+        assert!(known.is(
+            Concept::AccountMeta,
+            "solana_instruction::account_meta::AccountMeta"
+        ));
+        assert!(!known.is(Concept::AccountInfo, "pinocchio::account_info::AccountInfo"));
+        assert!(!known.is(Concept::Pubkey, "my_program::state::Pubkey"));
+        assert!(!known.is(Concept::Rent, "Rent"));
+    }
+
+    #[test]
+    fn test_pinocchio_paths() {
+        let known = KnownTypes::from_deps(&[dep("pinocchio"), dep("pinocchio-pubkey")]);
+        assert_eq!(known.program_type, ProgramType::Pinocchio);
+        assert!(known.is(Concept::AccountInfo, "pinocchio::account_info::AccountInfo"));
+        assert!(known.is(Concept::Invoke, "pinocchio::cpi::slice_invoke_signed"));
+        assert!(known.is(Concept::Rent, "pinocchio::sysvars::rent::Rent"));
+        assert!(known.is(Concept::Clock, "pinocchio::sysvars::clock::Clock"));
+        assert!(known.is(Concept::Pubkey, "solana_pubkey::Pubkey"));
+    }
+}
@@ -1,11 +1,18 @@
 //! Find meta info for Programs written in native Rust or Anchor frameworks.
 //! 1. Decide if the curren package is Solana/Anchor/Not.
 //! 2. Get the package/library name and the dep versions of solana-sdk/Anchor.
+//! 3. Find the other programs of the workspace the package belongs to.
 
+pub mod known_types;
 pub mod parser;
 pub mod vulnerability;
+pub mod workspace;
+pub use known_types::{Concept, KnownTypes, known_types};
 pub use parser::{
-    ParsedDependency, ProgramType, SolanaMetadataError, check_program_type,
+    ParsedDependency, ProgramType, SolanaMetadataError, check_program_type, parse_package_version,
     parse_toml_in_crate_path,
 };
-pub use vulnerability::detect_vulnerable_dep;
+pub use vulnerability::{
+    Advisory, AdvisoryDb, AdvisoryError, AdvisorySeverity, VulnerableDep, detect_vulnerable_dep,
+};
+pub use workspace::{WorkspaceProgram, discover_workspace_programs, find_workspace_root};
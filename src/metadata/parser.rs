@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap; // Import HashMap for parsing dependency tables
 use std::fs;
 use std::path::Path;
@@ -10,6 +10,9 @@ use tracing::debug;
 #[derive(Debug, Deserialize)]
 struct Package {
     name: String,
+    // A string, or `{ workspace = true }` when inherited from the workspace.
+    #[serde(default)]
+    version: Option<Value>,
 }
 
 // This struct will hold the extracted information for each dependency.
@@ -36,30 +39,14 @@ pub enum SolanaMetadataError {
     CargoTomlNotFound,
     #[error("Cargo.toml fails to parse")]
     CargoTomlParseFailure,
+    #[error("Anchor.toml fails to parse")]
+    AnchorTomlParseFailure,
 }
 
 pub fn parse_toml_in_crate_path(
     crate_path_str: &str,
 ) -> Result<(String, Vec<ParsedDependency>), SolanaMetadataError> {
-    // Get the path to the Cargo.toml file from the environment variable PROGRAM_PATH.
-    let crate_path = Path::new(&crate_path_str);
-    let cargo_toml_path = crate_path.join("Cargo.toml");
-
-    debug!("Attempting to parse: {}", cargo_toml_path.display());
-
-    // Read the content of the Cargo.toml file.
-    let toml_content = match fs::read_to_string(&cargo_toml_path) {
-        Ok(content) => content,
-        Err(_) => {
-            return Err(SolanaMetadataError::CargoTomlNotFound);
-        }
-    };
-
-    // Parse the TOML content into our CargoTomlRaw struct.
-    let cargo_toml_raw: CargoTomlRaw = match toml::from_str(&toml_content) {
-        Ok(parsed_toml) => parsed_toml,
-        Err(_) => return Err(SolanaMetadataError::CargoTomlParseFailure),
-    };
+    let cargo_toml_raw = read_cargo_toml(crate_path_str)?;
 
     // Extract the original package name.
     let original_name = cargo_toml_raw.package.name;
@@ -82,26 +69,72 @@ pub fn parse_toml_in_crate_path(
     Ok((crate_name, parsed_dependencies))
 }
 
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+/// The version of the package in the crate path, `None` when it is inherited
+/// from the workspace or left out.
+pub fn parse_package_version(crate_path_str: &str) -> Result<Option<String>, SolanaMetadataError> {
+    let cargo_toml_raw = read_cargo_toml(crate_path_str)?;
+    Ok(match cargo_toml_raw.package.version {
+        Some(Value::String(version)) => Some(version),
+        _ => None,
+    })
+}
+
+fn read_cargo_toml(crate_path_str: &str) -> Result<CargoTomlRaw, SolanaMetadataError> {
+    // Get the path to the Cargo.toml file from the environment variable PROGRAM_PATH.
+    let crate_path = Path::new(&crate_path_str);
+    let cargo_toml_path = crate_path.join("Cargo.toml");
+
+    debug!("Attempting to parse: {}", cargo_toml_path.display());
+
+    // Read the content of the Cargo.toml file.
+    let toml_content = match fs::read_to_string(&cargo_toml_path) {
+        Ok(content) => content,
+        Err(_) => {
+            return Err(SolanaMetadataError::CargoTomlNotFound);
+        }
+    };
+
+    // Parse the TOML content into our CargoTomlRaw struct.
+    match toml::from_str(&toml_content) {
+        Ok(parsed_toml) => Ok(parsed_toml),
+        Err(_) => Err(SolanaMetadataError::CargoTomlParseFailure),
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ProgramType {
     Anchor,
     SolanaNative,
+    Pinocchio,
     Other,
 }
 
+/// Crates a native program is written against: the SDK, `solana-program`, or
+/// the modular crates split out of it.
+const SOLANA_NATIVE_DEPS: [&str; 6] = [
+    "solana-sdk",
+    "solana-program",
+    "solana-account-info",
+    "solana-pubkey",
+    "solana-program-entrypoint",
+    "solana-cpi",
+];
+const PINOCCHIO: &str = "pinocchio";
+
+/// The framework of a program by its dependencies: Anchor over Pinocchio over
+/// the plain Solana SDK, as the first two build on parts of the last.
 pub fn check_program_type(deps: &[ParsedDependency]) -> ProgramType {
-    let mut program_type = ProgramType::Other;
-    for dep in deps {
-        if &dep.name == "anchor-lang" {
-            program_type = ProgramType::Anchor;
-            break;
-        } else if (&dep.name == "solana-sdk" || &dep.name == "solana-program")
-            && program_type == ProgramType::Other
-        {
-            program_type = ProgramType::SolanaNative;
-        }
+    let has = |names: &[&str]| deps.iter().any(|dep| names.contains(&dep.name.as_str()));
+    if has(&["anchor-lang"]) {
+        ProgramType::Anchor
+    } else if has(&[PINOCCHIO]) {
+        ProgramType::Pinocchio
+    } else if has(&SOLANA_NATIVE_DEPS) {
+        ProgramType::SolanaNative
+    } else {
+        ProgramType::Other
     }
-    program_type
 }
 
 // Helper function to extract a version string from a toml::Value,
@@ -144,4 +177,37 @@ mod tests {
         }
         println!("--------------");
     }
+
+    #[test]
+    fn test_check_program_type() {
+        let deps = |names: &[&str]| -> Vec<ParsedDependency> {
+            names
+                .iter()
+                .map(|name| ParsedDependency {
+                    name: name.to_string(),
+                    version: None,
+                })
+                .collect()
+        };
+        assert_eq!(
+            check_program_type(&deps(&["solana-program", "anchor-lang"])),
+            ProgramType::Anchor
+        );
+        assert_eq!(
+            check_program_type(&deps(&["solana-pubkey", "pinocchio"])),
+            ProgramType::Pinocchio
+        );
+        assert_eq!(
+            check_program_type(&deps(&["solana-account-info", "borsh"])),
+            ProgramType::SolanaNative
+        );
+        assert_eq!(check_program_type(&deps(&["serde"])), ProgramType::Other);
+    }
+
+    #[test]
+    fn test_parse_package_version() {
+        let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/access_control");
+        let version = parse_package_version(fixture).unwrap();
+        assert_eq!(version.as_deref(), Some("0.1.0"));
+    }
 }
@@ -1,47 +1,171 @@
 use super::ParsedDependency;
-use semver::{Version, VersionReq};
+use semver::{Comparator, Op, Version, VersionReq};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
 
-const SAFE_SPL_VERSION: &str = ">=3.1.1";
-const VUL_SPL_VERSION: &str = "3.1.0";
+/// The advisories checked unless the config turns them off, in the format of
+/// `AdvisoryDb::load`.
+const BUILTIN_ADVISORIES: &str = include_str!("advisories.toml");
 
-pub fn detect_vulnerable_dep(deps: &[ParsedDependency]) -> Option<String> {
-    for dep in deps {
-        if &dep.name == "spl-token"
-            && dep.version.is_some()
-            && !safe_spl_version(dep.version.as_ref().unwrap())
-        {
-            return Some(format!(
-                "{}: {} does not satisfy {}",
-                dep.name,
-                dep.version.as_ref().unwrap(),
-                SAFE_SPL_VERSION
-            ));
+#[derive(Error, Debug)]
+pub enum AdvisoryError {
+    #[error("failed to read {0}")]
+    Read(PathBuf),
+    #[error("failed to parse {0}: {1}")]
+    Toml(PathBuf, toml::de::Error),
+    #[error("failed to parse {0}: {1}")]
+    Json(PathBuf, serde_json::Error),
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AdvisorySeverity {
+    #[default]
+    Warning,
+    Error,
+}
+
+/// A range of versions of a crate known to be vulnerable.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Advisory {
+    /// E.g. `RUSTSEC-2024-0344`.
+    pub id: String,
+    /// The crate, as named in `[dependencies]`.
+    pub package: String,
+    pub affected: VersionReq,
+    #[serde(default)]
+    pub severity: AdvisorySeverity,
+    pub title: String,
+}
+
+/// Advisories from a TOML or JSON file, each an entry of its `advisory` list:
+///
+/// ```toml
+/// [[advisory]]
+/// id = "RUSTSEC-2024-0344"
+/// package = "curve25519-dalek"
+/// affected = "<4.1.3"
+/// severity = "warning"
+/// title = "Timing variability in curve25519-dalek"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AdvisoryDb {
+    #[serde(default, rename = "advisory")]
+    pub advisories: Vec<Advisory>,
+}
+
+impl AdvisoryDb {
+    pub fn builtin() -> Self {
+        toml::from_str(BUILTIN_ADVISORIES).expect("the builtin advisories parse")
+    }
+
+    /// Read the advisories of `path`, as JSON if it ends in `.json` and as TOML
+    /// otherwise.
+    pub fn load(path: &Path) -> Result<Self, AdvisoryError> {
+        let content =
+            fs::read_to_string(path).map_err(|_| AdvisoryError::Read(path.to_path_buf()))?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content)
+                .map_err(|err| AdvisoryError::Json(path.to_path_buf(), err))
+        } else {
+            toml::from_str(&content).map_err(|err| AdvisoryError::Toml(path.to_path_buf(), err))
         }
     }
-    None
+
+    pub fn extend(&mut self, other: AdvisoryDb) {
+        self.advisories.extend(other.advisories);
+    }
 }
 
-fn safe_spl_version(version: &str) -> bool {
-    let vul_version = Version::parse(VUL_SPL_VERSION).unwrap();
-    let precise_version = Version::parse(version);
-    match precise_version {
-        Ok(v) => {
-            let safe_version = VersionReq::parse(SAFE_SPL_VERSION).unwrap();
-            safe_version.matches(&v)
-        }
-        Err(_) => {
-            let current_version = VersionReq::parse(version).unwrap();
-            !current_version.matches(&vul_version)
+/// A dependency whose version requirement allows a version `advisory` affects.
+#[derive(Debug)]
+pub struct VulnerableDep<'a> {
+    pub name: String,
+    /// The requirement of the manifest, e.g. `3.1.0` or `~3.1`.
+    pub version: String,
+    pub advisory: &'a Advisory,
+}
+
+/// The dependencies of `deps` affected by an advisory of `db`. A requirement
+/// such as `3.0` is matched by the lowest version it allows, which a lockfile
+/// may still pin; dependencies without a version, e.g. path or workspace
+/// ones, and versions that do not parse are left out.
+pub fn detect_vulnerable_dep<'a>(
+    deps: &[ParsedDependency],
+    db: &'a AdvisoryDb,
+) -> Vec<VulnerableDep<'a>> {
+    let mut vulnerable = vec![];
+    for dep in deps {
+        let Some(version) = &dep.version else {
+            continue;
+        };
+        let Ok(req) = VersionReq::parse(version) else {
+            continue;
+        };
+        let lowest = lowest_version(&req);
+        for advisory in &db.advisories {
+            if advisory.package == dep.name && advisory.affected.matches(&lowest) {
+                vulnerable.push(VulnerableDep {
+                    name: dep.name.clone(),
+                    version: version.clone(),
+                    advisory,
+                });
+            }
         }
     }
+    vulnerable
+}
+
+/// The lowest version `req` allows, e.g. `3.1.0` for `~3.1` and `0.0.0` for
+/// `<2`.
+fn lowest_version(req: &VersionReq) -> Version {
+    req.comparators
+        .iter()
+        .filter_map(lower_bound)
+        .max()
+        .unwrap_or(Version::new(0, 0, 0))
+}
+
+fn lower_bound(comparator: &Comparator) -> Option<Version> {
+    let Comparator {
+        op,
+        major,
+        minor,
+        patch,
+        pre,
+    } = comparator;
+    let version = match (op, minor, patch) {
+        (Op::Less | Op::LessEq, ..) => return None,
+        (Op::Greater, Some(minor), Some(patch)) => Version::new(*major, *minor, patch + 1),
+        (Op::Greater, Some(minor), None) => Version::new(*major, minor + 1, 0),
+        (Op::Greater, None, _) => Version::new(major + 1, 0, 0),
+        _ => {
+            let mut version = Version::new(*major, minor.unwrap_or(0), patch.unwrap_or(0));
+            version.pre = pre.clone();
+            version
+        }
+    };
+    Some(version)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn is_vulnerable(name: &str, version: &str, db: &AdvisoryDb) -> bool {
+        let deps = [ParsedDependency {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+        }];
+        !detect_vulnerable_dep(&deps, db).is_empty()
+    }
+
     #[test]
     fn test_safe_spl_version() {
+        let db = AdvisoryDb::builtin();
+        let safe_spl_version = |version| !is_vulnerable("spl-token", version, &db);
         let vul_version = "2.0.0";
         assert!(!safe_spl_version(vul_version));
         let vul_version = "3.0.0";
@@ -65,4 +189,54 @@ mod tests {
         let vul_version = "4.0.0";
         assert!(safe_spl_version(vul_version));
     }
+
+    #[test]
+    fn test_lowest_version() {
+        let lowest = |req| lowest_version(&VersionReq::parse(req).unwrap()).to_string();
+        assert_eq!(lowest("3.1"), "3.1.0");
+        assert_eq!(lowest(">=3.0, <8"), "3.0.0");
+        assert_eq!(lowest(">3.1"), "3.2.0");
+        assert_eq!(lowest(">3.1.1"), "3.1.2");
+        assert_eq!(lowest("<2"), "0.0.0");
+        assert_eq!(lowest("*"), "0.0.0");
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("advisories-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let toml_path = dir.join("advisories.toml");
+        fs::write(
+            &toml_path,
+            "[[advisory]]\nid = \"X-1\"\npackage = \"anchor-lang\"\naffected = \"<0.32\"\n\
+             severity = \"error\"\ntitle = \"t\"\n",
+        )
+        .unwrap();
+        let json_path = dir.join("advisories.json");
+        fs::write(
+            &json_path,
+            r#"{"advisory": [{"id": "X-2", "package": "borsh", "affected": ">=0.9, <0.10.3", "title": "t"}]}"#,
+        )
+        .unwrap();
+
+        let mut db = AdvisoryDb::load(&toml_path).unwrap();
+        db.extend(AdvisoryDb::load(&json_path).unwrap());
+        assert_eq!(db.advisories[0].severity, AdvisorySeverity::Error);
+        assert_eq!(db.advisories[1].severity, AdvisorySeverity::Warning);
+        assert!(is_vulnerable("anchor-lang", "0.31.1", &db));
+        assert!(!is_vulnerable("anchor-lang", "0.32", &db));
+        assert!(is_vulnerable("borsh", "0.10", &db));
+        assert!(!is_vulnerable("borsh", "1", &db));
+
+        fs::write(&toml_path, "[[advisory]]\nid = \"X-3\"\n").unwrap();
+        assert!(matches!(
+            AdvisoryDb::load(&toml_path),
+            Err(AdvisoryError::Toml(..))
+        ));
+        assert!(matches!(
+            AdvisoryDb::load(&dir.join("missing.toml")),
+            Err(AdvisoryError::Read(_))
+        ));
+        fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,237 @@
+//! The program crates of a workspace, found from its `Cargo.toml` members and
+//! the `[programs.*]` tables of its `Anchor.toml`.
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use toml::Value;
+use tracing::debug;
+
+use super::parser::{
+    ProgramType, SolanaMetadataError, check_program_type, parse_toml_in_crate_path,
+};
+
+/// The macro declaring a program id, as in `declare_id!("…")` or
+/// `solana_program::declare_id!("…")`.
+const DECLARE_ID: &str = "declare_id!(";
+
+/// A program crate of a workspace.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WorkspaceProgram {
+    /// The crate name, with hyphens replaced by underscores.
+    pub name: String,
+    /// The directory holding the crate's `Cargo.toml`.
+    pub path: PathBuf,
+    /// The program id, in base58, from `Anchor.toml` or else from the
+    /// `declare_id!` in `src/lib.rs`.
+    pub program_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WorkspaceTomlRaw {
+    #[serde(default)]
+    workspace: Option<Workspace>,
+    #[serde(default)]
+    package: Option<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Workspace {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AnchorTomlRaw {
+    /// Program ids by cluster, then by program name.
+    #[serde(default)]
+    programs: HashMap<String, HashMap<String, Value>>,
+}
+
+/// The program crates of the workspace rooted at `root`, sorted by name: its
+/// members, and the root package itself, depending on Anchor, Pinocchio, or the
+/// Solana SDK. Members are given as paths or as globs with `*` in their last
+/// component. Members whose manifest does not parse are left out.
+pub fn discover_workspace_programs(
+    root: &Path,
+) -> Result<Vec<WorkspaceProgram>, SolanaMetadataError> {
+    let manifest: WorkspaceTomlRaw =
+        read_toml(&root.join("Cargo.toml"))?.ok_or(SolanaMetadataError::CargoTomlNotFound)?;
+    let anchor_ids = anchor_program_ids(root)?;
+
+    let mut dirs = vec![];
+    if manifest.package.is_some() {
+        dirs.push(root.to_path_buf());
+    }
+    let workspace = manifest.workspace.unwrap_or_default();
+    let excluded: Vec<PathBuf> = workspace.exclude.iter().map(|dir| root.join(dir)).collect();
+    for member in &workspace.members {
+        for dir in expand_member(root, member) {
+            if !excluded.contains(&dir) && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+
+    let mut programs = vec![];
+    for dir in dirs {
+        let Ok((name, deps)) = parse_toml_in_crate_path(&dir.to_string_lossy()) else {
+            debug!("Skipping workspace member {}", dir.display());
+            continue;
+        };
+        if check_program_type(&deps) == ProgramType::Other {
+            continue;
+        }
+        let program_id = anchor_ids
+            .get(&name)
+            .cloned()
+            .or_else(|| declared_id(&dir.join("src/lib.rs")));
+        programs.push(WorkspaceProgram {
+            name,
+            path: dir,
+            program_id,
+        });
+    }
+    programs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(programs)
+}
+
+/// The nearest ancestor of `crate_dir`, itself included, whose `Cargo.toml`
+/// has a `[workspace]` table, or `crate_dir` when none has.
+pub fn find_workspace_root(crate_dir: &Path) -> PathBuf {
+    crate_dir
+        .ancestors()
+        .find(|dir| {
+            matches!(
+                read_toml::<WorkspaceTomlRaw>(&dir.join("Cargo.toml")),
+                Ok(Some(WorkspaceTomlRaw {
+                    workspace: Some(_),
+                    ..
+                }))
+            )
+        })
+        .unwrap_or(crate_dir)
+        .to_path_buf()
+}
+
+/// The crate directories `member` names under `root`.
+fn expand_member(root: &Path, member: &str) -> Vec<PathBuf> {
+    let member = Path::new(member);
+    let pattern = member
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if !pattern.contains('*') {
+        return vec![root.join(member)];
+    }
+    let parent = root.join(member.parent().unwrap_or(Path::new("")));
+    let Ok(entries) = fs::read_dir(&parent) else {
+        return vec![];
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.join("Cargo.toml").is_file())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| matches_glob(&pattern, &name.to_string_lossy()))
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Whether `name` matches `pattern`, where each `*` stands for any run of
+/// characters.
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Program ids by crate name from the `Anchor.toml` at `root`, if any. The
+/// first cluster listing a program wins, in the order `localnet`, `devnet`,
+/// `mainnet`, then the others by name.
+fn anchor_program_ids(root: &Path) -> Result<HashMap<String, String>, SolanaMetadataError> {
+    let Some(anchor) = read_toml::<AnchorTomlRaw>(&root.join("Anchor.toml"))
+        .map_err(|_| SolanaMetadataError::AnchorTomlParseFailure)?
+    else {
+        return Ok(HashMap::new());
+    };
+    let mut clusters: Vec<(&String, &HashMap<String, Value>)> = anchor.programs.iter().collect();
+    clusters.sort_by_key(|(cluster, _)| {
+        let rank = ["localnet", "devnet", "mainnet"]
+            .iter()
+            .position(|known| known == cluster)
+            .unwrap_or(usize::MAX);
+        (rank, cluster.as_str())
+    });
+    let mut ids = HashMap::new();
+    for (_, programs) in clusters {
+        for (name, value) in programs {
+            // A program is its id, or a table with the id as `address`.
+            let id = match value {
+                Value::String(id) => id,
+                Value::Table(table) => match table.get("address") {
+                    Some(Value::String(id)) => id,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            ids.entry(name.replace('-', "_"))
+                .or_insert_with(|| id.clone());
+        }
+    }
+    Ok(ids)
+}
+
+/// The id in the first `declare_id!` of the source file at `path`.
+fn declared_id(path: &Path) -> Option<String> {
+    let source = fs::read_to_string(path).ok()?;
+    let (_, rest) = source.split_once(DECLARE_ID)?;
+    let (_, rest) = rest.split_once('"')?;
+    let (id, _) = rest.split_once('"')?;
+    Some(id.to_string())
+}
+
+/// The TOML file at `path` parsed as `T`, `None` when there is no such file.
+fn read_toml<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>, SolanaMetadataError> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|_| SolanaMetadataError::CargoTomlParseFailure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_glob() {
+        assert!(matches_glob("*", "vault"));
+        assert!(matches_glob("*-program", "vault-program"));
+        assert!(matches_glob("cfx_*_core", "cfx_stake_core"));
+        assert!(!matches_glob("cfx_*_core", "cfx_stake"));
+        assert!(!matches_glob("a*a", "a"));
+        assert!(matches_glob("vault", "vault"));
+        assert!(!matches_glob("vault", "vaults"));
+    }
+}
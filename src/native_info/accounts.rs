@@ -0,0 +1,278 @@
+//! What a native handler does with each account it takes from the accounts
+//! slice with `next_account_info`, the native counterpart of the Accounts
+//! structs of Anchor programs.
+//!
+//! A handler takes its accounts in order, so the n-th `next_account_info` call
+//! is the n-th account of the instruction. Its role is inferred from the uses
+//! of the `&AccountInfo` and of the values computed from it: reads of
+//! `is_signer` and `owner` count as checks wherever they happen.
+use std::collections::BTreeSet;
+use std::fmt;
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{Body, StatementKind, TerminatorKind, VarDebugInfoContents};
+use rustc_public::ty::{RigidTy, Ty};
+
+use crate::analysis::condition::{Definitions, field_path};
+use crate::analysis::taint::{Taint, labels_of, operand_local, propagate};
+use crate::analysis::types::is_known_ty;
+use crate::anchor_info::access::short_name;
+use crate::anchor_info::remaining::{callee_name, read_place};
+use crate::metadata::{Concept, known_types};
+
+const NEXT_ACCOUNT_INFO: &str = "next_account_info";
+const IS_SIGNER: &str = "AccountInfo.is_signer";
+const OWNER: &str = "AccountInfo.owner";
+const LAMPORTS: &str = "AccountInfo.lamports";
+const DATA: &str = "AccountInfo.data";
+/// `RefCell::borrow_mut` and `try_borrow_mut`, on the `lamports` or `data`
+/// field of an account.
+const BORROW_MUT: [&str; 2] = ["::borrow_mut", "::try_borrow_mut"];
+const BORROW_MUT_LAMPORTS: &str = "::try_borrow_mut_lamports";
+const BORROW_MUT_DATA: &str = "::try_borrow_mut_data";
+/// Fns decoding account data into a type, by last path segment: Borsh, `Pack`
+/// and bytemuck.
+pub(crate) const DESERIALIZE_FNS: [&str; 7] = [
+    "try_from_slice",
+    "deserialize",
+    "unpack",
+    "unpack_unchecked",
+    "unpack_from_slice",
+    "from_bytes",
+    "try_from_bytes",
+];
+const RESULT: &str = "std::result::Result";
+const OPTION: &str = "std::option::Option";
+
+/// The accounts of one native handler, in the order it takes them.
+pub struct NativeAccountsModel {
+    pub handler: Instance,
+    pub accounts: Vec<NativeAccount>,
+}
+
+/// An account a native handler takes with `next_account_info`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NativeAccount {
+    /// Position in the accounts of the instruction.
+    pub position: usize,
+    /// The variable the handler binds it to, if any.
+    pub name: Option<String>,
+    pub signer_checked: bool,
+    pub owner_checked: bool,
+    /// Short name of the type its data is deserialized into.
+    pub deserialized_as: Option<String>,
+    pub lamports_written: bool,
+    pub data_written: bool,
+    /// Blocks borrowing its lamports or data mutably.
+    pub writes: BTreeSet<usize>,
+    /// Passed to `invoke` or `invoke_signed`.
+    pub invoked: bool,
+}
+
+impl NativeAccount {
+    pub fn writable(&self) -> bool {
+        self.lamports_written || self.data_written
+    }
+}
+
+impl fmt::Display for NativeAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_no = |checked: bool| if checked { "yes" } else { "no" };
+        write!(
+            f,
+            "{}",
+            if self.writable() {
+                "writable"
+            } else {
+                "readonly"
+            }
+        )?;
+        if let Some(ty) = &self.deserialized_as {
+            write!(f, ", deserialized as {ty}")?;
+        }
+        if self.invoked {
+            write!(f, ", passed to invoke")?;
+        }
+        write!(
+            f,
+            ", signer-checked: {}, owner-checked: {}",
+            yes_no(self.signer_checked),
+            yes_no(self.owner_checked)
+        )
+    }
+}
+
+/// The accounts `handler` takes with `next_account_info` and what it does
+/// with each.
+pub fn native_accounts(handler: Instance) -> NativeAccountsModel {
+    let accounts = handler
+        .body()
+        .map(|body| infer_accounts(&body))
+        .unwrap_or_default();
+    NativeAccountsModel { handler, accounts }
+}
+
+/// Locals of `body` holding an account taken with `next_account_info`, or a
+/// value computed from it, labeled with the account's position.
+pub(crate) fn account_positions(body: &Body) -> Taint<usize> {
+    // Each `next_account_info` call, in block order, takes the next account.
+    let mut sources: Taint<usize> = Taint::new();
+    for block in &body.blocks {
+        if let TerminatorKind::Call {
+            func, destination, ..
+        } = &block.terminator.kind
+            && callee_name(body, func).is_some_and(|name| name.ends_with(NEXT_ACCOUNT_INFO))
+        {
+            sources.insert(destination.local, BTreeSet::from([sources.len()]));
+        }
+    }
+    if sources.is_empty() {
+        return sources;
+    }
+    propagate(body, sources)
+}
+
+fn infer_accounts(body: &Body) -> Vec<NativeAccount> {
+    let taint = account_positions(body);
+    let count = taint
+        .values()
+        .flatten()
+        .max()
+        .map_or(0, |position| position + 1);
+    let mut accounts: Vec<NativeAccount> = (0..count)
+        .map(|position| NativeAccount {
+            position,
+            ..NativeAccount::default()
+        })
+        .collect();
+    if accounts.is_empty() {
+        return accounts;
+    }
+    let defs = Definitions::new(body);
+
+    // The `lamports` and `data` cells of each account, by position.
+    let mut lamports: Taint<usize> = Taint::new();
+    let mut data: Taint<usize> = Taint::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            let StatementKind::Assign(lhs, rvalue) = &stmt.kind else {
+                continue;
+            };
+            let Some(place) = read_place(rvalue) else {
+                continue;
+            };
+            let Some(positions) = taint.get(&place.local) else {
+                continue;
+            };
+            let Some((ty, field_idx)) = defs.field_parent(place) else {
+                continue;
+            };
+            let cells = match field_path(ty, field_idx).as_str() {
+                IS_SIGNER => {
+                    for &position in positions {
+                        accounts[position].signer_checked = true;
+                    }
+                    continue;
+                }
+                OWNER => {
+                    for &position in positions {
+                        accounts[position].owner_checked = true;
+                    }
+                    continue;
+                }
+                LAMPORTS => &mut lamports,
+                DATA => &mut data,
+                _ => continue,
+            };
+            cells.insert(lhs.local, positions.clone());
+        }
+    }
+    let lamports = propagate(body, lamports);
+    let data = propagate(body, data);
+
+    for (bb, block) in body.blocks.iter().enumerate() {
+        let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &block.terminator.kind
+        else {
+            continue;
+        };
+        let Some(name) = callee_name(body, func) else {
+            continue;
+        };
+        let arg_locals = || args.iter().filter_map(operand_local);
+        let fn_name = name.rsplit("::").next().unwrap_or(&name);
+        if BORROW_MUT.iter().any(|suffix| name.ends_with(suffix)) {
+            for position in labels_of(&lamports, arg_locals()) {
+                accounts[position].lamports_written = true;
+                accounts[position].writes.insert(bb);
+            }
+            for position in labels_of(&data, arg_locals()) {
+                accounts[position].data_written = true;
+                accounts[position].writes.insert(bb);
+            }
+        } else if name.ends_with(BORROW_MUT_LAMPORTS) {
+            for position in labels_of(&taint, arg_locals()) {
+                accounts[position].lamports_written = true;
+                accounts[position].writes.insert(bb);
+            }
+        } else if name.ends_with(BORROW_MUT_DATA) {
+            for position in labels_of(&taint, arg_locals()) {
+                accounts[position].data_written = true;
+                accounts[position].writes.insert(bb);
+            }
+        } else if DESERIALIZE_FNS.contains(&fn_name) {
+            let Some(ty) = destination.ty(body.locals()).ok().and_then(decoded_type) else {
+                continue;
+            };
+            for position in labels_of(&taint, arg_locals()) {
+                accounts[position].deserialized_as.get_or_insert(ty.clone());
+            }
+        } else if known_types().is(Concept::Invoke, &name) {
+            // The account infos are the second argument of every CPI fn.
+            let infos = args.get(1).and_then(operand_local);
+            for position in labels_of(&taint, infos) {
+                accounts[position].invoked = true;
+            }
+        }
+    }
+
+    // The variable bound to exactly one account's `&AccountInfo`.
+    for info in &body.var_debug_info {
+        if let VarDebugInfoContents::Place(place) = &info.value
+            && place.projection.is_empty()
+            && let Some(positions) = taint.get(&place.local)
+            && positions.len() == 1
+            && let Some(&position) = positions.first()
+            && is_account_info_ref(body.locals()[place.local].ty)
+        {
+            accounts[position].name.get_or_insert(info.name.clone());
+        }
+    }
+    accounts
+}
+
+/// Short name of the type a deserializing fn returns `ty` for, looking through
+/// `Result`, `Option` and references.
+pub(crate) fn decoded_type(ty: Ty) -> Option<String> {
+    match ty.kind().rigid()? {
+        RigidTy::Ref(_, inner, _) => decoded_type(*inner),
+        RigidTy::Adt(adt_def, args) => {
+            let name = adt_def.name();
+            if name == RESULT || name == OPTION {
+                decoded_type(*args.0.first()?.ty()?)
+            } else {
+                Some(short_name(&name))
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_account_info_ref(ty: Ty) -> bool {
+    matches!(ty.kind().rigid(), Some(RigidTy::Ref(..))) && is_known_ty(Concept::AccountInfo, ty)
+}
@@ -0,0 +1,241 @@
+//! Reconstruct how a native program routes its instruction data to handlers.
+//!
+//! The fn `entrypoint!` is given, or a `Processor::process` it delegates to,
+//! either matches on the first byte of the data, or decodes the data into an
+//! instruction enum, with Borsh's `try_from_slice` or a hand-written `unpack`,
+//! and matches on the variant. Each arm calls the handler of one instruction.
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::{AggregateKind, Body, Local, Rvalue, StatementKind, TerminatorKind};
+use rustc_public::ty::{AdtDef, AdtKind, RigidTy, Ty, UintTy};
+
+use crate::analysis::callgraph::callee;
+use crate::analysis::graph::{Dominators, Fixpoints};
+use crate::analysis::loops::predecessors;
+use crate::analysis::taint::{Taint, operand_local, propagate};
+
+/// One instruction of a native program.
+#[derive(Clone, Debug)]
+pub struct NativeInstruction {
+    /// The value of the leading data the program selects the instruction by,
+    /// usually its first byte.
+    pub tag: u128,
+    /// The variant of the instruction enum, when the data is decoded into one.
+    pub variant: Option<String>,
+    /// The fn the instruction's arm calls.
+    pub handler: Instance,
+}
+
+/// The instructions of the first fn in `reachable`, the local fns reachable
+/// from a native entrypoint in call-graph order, that dispatches on its
+/// instruction data, by tag.
+pub fn native_instructions(
+    reachable: &[Instance],
+    fixpoints: &Fixpoints,
+) -> Vec<NativeInstruction> {
+    for &instance in reachable {
+        let Some(body) = instance.body() else {
+            continue;
+        };
+        let data = instruction_data(&body);
+        if data.is_empty() {
+            continue;
+        }
+        let mut instructions = dispatch(&body, &data, fixpoints);
+        if !instructions.is_empty() {
+            instructions.sort_by_key(|instruction| instruction.tag);
+            return instructions;
+        }
+    }
+    vec![]
+}
+
+/// The locals of `body` holding instruction data: its `&[u8]` arguments and the
+/// values computed from them.
+fn instruction_data(body: &Body) -> Taint<()> {
+    let sources: Taint<()> = body
+        .arg_locals()
+        .iter()
+        .enumerate()
+        .filter(|(_, decl)| is_byte_slice_ref(decl.ty))
+        .map(|(idx, _)| (idx + 1, BTreeSet::from([()])))
+        .collect();
+    if sources.is_empty() {
+        return sources;
+    }
+    propagate(body, sources)
+}
+
+/// The arms of the first `switchInt` of `body` on its instruction data, or on
+/// the variant of an instruction enum decoded from it, that call local fns.
+fn dispatch(body: &Body, data: &Taint<()>, fixpoints: &Fixpoints) -> Vec<NativeInstruction> {
+    // Instruction enums by the locals holding their discriminant. Other enums,
+    // e.g. the `ControlFlow` of a `?`, are no dispatch.
+    let mut discriminants: HashMap<Local, Option<AdtDef>> = HashMap::new();
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            let StatementKind::Assign(lhs, Rvalue::Discriminant(place)) = &stmt.kind else {
+                continue;
+            };
+            discriminants.insert(lhs.local, None);
+            if data.contains_key(&place.local)
+                && let Ok(ty) = place.ty(body.locals())
+                && let Some(RigidTy::Adt(adt_def, _)) = ty.kind().rigid()
+                && adt_def.kind() == AdtKind::Enum
+                && adt_def.krate().is_local
+            {
+                discriminants.insert(lhs.local, Some(*adt_def));
+            }
+        }
+    }
+    let preds = predecessors(body);
+    let dominators = fixpoints.dominators(body.blocks.len(), &preds);
+    for block in &body.blocks {
+        let TerminatorKind::SwitchInt { discr, targets } = &block.terminator.kind else {
+            continue;
+        };
+        let Some(discr_local) = operand_local(discr) else {
+            continue;
+        };
+        let instructions: Vec<NativeInstruction> =
+            if let Some(discriminant) = discriminants.get(&discr_local) {
+                let Some(adt_def) = discriminant else {
+                    continue;
+                };
+                let tags = unpacked_tags(body, data, *adt_def, fixpoints);
+                targets
+                    .branches()
+                    .filter_map(|(value, target)| {
+                        let variant = adt_def.variants_iter().find(|variant| {
+                            adt_def.discriminant_for_variant(variant.idx).val == value
+                        })?;
+                        let name = variant.name();
+                        Some(NativeInstruction {
+                            // Borsh writes the variant index as the tag.
+                            tag: tags.get(&name).copied().unwrap_or(value),
+                            variant: Some(name),
+                            handler: arm_handler(body, &dominators, target)?,
+                        })
+                    })
+                    .collect()
+            } else if data.contains_key(&discr_local)
+                && discr.ty(body.locals()).is_ok_and(|ty| {
+                    matches!(ty.kind().rigid(), Some(RigidTy::Int(_) | RigidTy::Uint(_)))
+                })
+            {
+                targets
+                    .branches()
+                    .filter_map(|(tag, target)| {
+                        Some(NativeInstruction {
+                            tag,
+                            variant: None,
+                            handler: arm_handler(body, &dominators, target)?,
+                        })
+                    })
+                    .collect()
+            } else {
+                continue;
+            };
+        if !instructions.is_empty() {
+            return instructions;
+        }
+    }
+    vec![]
+}
+
+/// The first local fn called in the blocks only reachable through `target`.
+fn arm_handler(body: &Body, dominators: &Dominators<usize>, target: usize) -> Option<Instance> {
+    let mut seen = BTreeSet::from([target]);
+    let mut queue = VecDeque::from([target]);
+    while let Some(block) = queue.pop_front() {
+        if let Some(instance) = callee(body, block)
+            && instance.def.krate().is_local
+        {
+            return Some(instance);
+        }
+        for succ in body.blocks[block].terminator.successors() {
+            if dominators.dominates(&target, &succ) && seen.insert(succ) {
+                queue.push_back(succ);
+            }
+        }
+    }
+    None
+}
+
+/// The tag of each variant of the instruction enum `adt_def`, by variant name,
+/// as the local fn `body` decodes the data with, e.g. `unpack`, maps them:
+/// each arm of its `switchInt` on the data builds one variant.
+fn unpacked_tags(
+    body: &Body,
+    data: &Taint<()>,
+    adt_def: AdtDef,
+    fixpoints: &Fixpoints,
+) -> HashMap<String, u128> {
+    for block in 0..body.blocks.len() {
+        let TerminatorKind::Call { args, .. } = &body.blocks[block].terminator.kind else {
+            continue;
+        };
+        if !args
+            .iter()
+            .any(|arg| operand_local(arg).is_some_and(|local| data.contains_key(&local)))
+        {
+            continue;
+        }
+        let Some(unpack) = callee(body, block).filter(|callee| callee.def.krate().is_local) else {
+            continue;
+        };
+        let Some(unpack_body) = unpack.body() else {
+            continue;
+        };
+        let tags = variant_tags(&unpack_body, adt_def, fixpoints);
+        if !tags.is_empty() {
+            return tags;
+        }
+    }
+    HashMap::new()
+}
+
+/// The tags `body` builds each variant of `adt_def` for, by variant name.
+fn variant_tags(body: &Body, adt_def: AdtDef, fixpoints: &Fixpoints) -> HashMap<String, u128> {
+    let data = instruction_data(body);
+    let preds = predecessors(body);
+    let dominators = fixpoints.dominators(body.blocks.len(), &preds);
+    let mut tags = HashMap::new();
+    for block in &body.blocks {
+        let TerminatorKind::SwitchInt { discr, targets } = &block.terminator.kind else {
+            continue;
+        };
+        if !operand_local(discr).is_some_and(|local| data.contains_key(&local)) {
+            continue;
+        }
+        for (tag, target) in targets.branches() {
+            let built = (0..body.blocks.len())
+                .filter(|&arm_block| dominators.dominates(&target, &arm_block))
+                .flat_map(|arm_block| &body.blocks[arm_block].statements)
+                .find_map(|stmt| match &stmt.kind {
+                    StatementKind::Assign(
+                        _,
+                        Rvalue::Aggregate(AggregateKind::Adt(def, idx, ..), _),
+                    ) if *def == adt_def => def.variant(*idx),
+                    _ => None,
+                });
+            if let Some(variant) = built {
+                tags.entry(variant.name()).or_insert(tag);
+            }
+        }
+    }
+    tags
+}
+
+/// Whether `ty` is `&[u8]`.
+fn is_byte_slice_ref(ty: Ty) -> bool {
+    let Some(RigidTy::Ref(_, inner, _)) = ty.kind().rigid().cloned() else {
+        return false;
+    };
+    matches!(
+        inner.kind().rigid(),
+        Some(RigidTy::Slice(elem)) if matches!(elem.kind().rigid(), Some(RigidTy::Uint(UintTy::U8)))
+    )
+}
@@ -0,0 +1,10 @@
+//! The program model of native programs, which route instructions by hand
+//! from the fn given to `entrypoint!` rather than through Anchor's
+//! `#[program]`.
+pub mod accounts;
+pub mod dispatch;
+pub mod pack;
+
+pub use accounts::{NativeAccountsModel, native_accounts};
+pub use dispatch::{NativeInstruction, native_instructions};
+pub use pack::{PackLayout, pack_layouts};
@@ -0,0 +1,72 @@
+//! Account types native programs lay out with `Pack`, the SPL alternative to
+//! Borsh: the type sets its packed size with `LEN` and writes its fields by
+//! hand in `pack_into_slice`.
+use rustc_public::ty::{AdtDef, AdtKind, RigidTy};
+use rustc_public::{CrateDef, ItemKind};
+
+use crate::anchor_info::constants::const_value;
+use crate::anchor_info::constraint::Value;
+
+/// `solana_program::program_pack::Pack`, as re-exported from its own crate.
+const PACK: &str = "program_pack::Pack";
+const IS_INITIALIZED: &str = "program_pack::IsInitialized";
+/// The item of the `LEN` a `Pack` impl sets, after `<Type as `.
+const PACK_LEN: &str = "program_pack::Pack>::LEN";
+/// The `Pack` fn decoding account data without the `is_initialized` check
+/// `unpack` makes.
+pub const UNPACK_UNCHECKED: &str = "unpack_unchecked";
+
+/// A local type implementing `Pack`.
+#[derive(Clone, Debug)]
+pub struct PackLayout {
+    pub adt_def: AdtDef,
+    /// The packed size, `Pack::LEN`, when it evaluates to an integer.
+    pub len: Option<u64>,
+    /// Whether it implements `IsInitialized`, which `unpack` checks.
+    pub is_initialized: bool,
+}
+
+/// The local structs implementing `Pack`, sorted by name.
+pub fn pack_layouts() -> Vec<PackLayout> {
+    let mut layouts: Vec<PackLayout> = vec![];
+    let mut initialized = vec![];
+    for trait_impl in rustc_public::all_trait_impls() {
+        let trait_ref = trait_impl.trait_impl().value;
+        let trait_name = trait_ref.def_id.name();
+        let Some(RigidTy::Adt(adt_def, _)) = trait_ref.self_ty().kind().rigid().cloned() else {
+            continue;
+        };
+        if !adt_def.krate().is_local || adt_def.kind() != AdtKind::Struct {
+            continue;
+        }
+        if trait_name.ends_with(PACK) {
+            layouts.push(PackLayout {
+                adt_def,
+                len: pack_len(adt_def),
+                is_initialized: false,
+            });
+        } else if trait_name.ends_with(IS_INITIALIZED) {
+            initialized.push(adt_def);
+        }
+    }
+    for layout in &mut layouts {
+        layout.is_initialized = initialized.contains(&layout.adt_def);
+    }
+    layouts.sort_by_key(|layout| layout.adt_def.name());
+    layouts
+}
+
+/// The value of `<adt_def as Pack>::LEN`.
+fn pack_len(adt_def: AdtDef) -> Option<u64> {
+    let prefix = format!("<{} as ", adt_def.name());
+    let item = rustc_public::all_local_items().into_iter().find(|item| {
+        let name = item.name();
+        matches!(item.kind(), ItemKind::Const)
+            && name.starts_with(&prefix)
+            && name.ends_with(PACK_LEN)
+    })?;
+    match const_value(item)? {
+        Value::Int(len) => len.try_into().ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,125 @@
+//! The fingerprints of the findings a program already has, e.g. when it starts
+//! using the analyzer, which `--analyzer-baseline` demotes so that
+//! `--analyzer-deny` only fails on new findings.
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::Finding;
+use super::json::ReportFinding;
+
+#[derive(Debug, Error)]
+pub enum BaselineError {
+    #[error("failed to read {0}: {1}")]
+    Read(String, io::Error),
+    #[error("failed to write {0}: {1}")]
+    Write(String, io::Error),
+    #[error("failed to parse {0}: {1}")]
+    Parse(String, serde_json::Error),
+}
+
+/// A baseline file: a JSON list of checker ids and fingerprints, sorted so
+/// that updating it gives a reviewable diff.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct BaselineEntry {
+    pub checker: String,
+    pub fingerprint: String,
+}
+
+impl Baseline {
+    /// The baseline of `findings`, by checker and then fingerprint.
+    pub fn new(findings: &[Finding]) -> Self {
+        let mut entries: Vec<_> = findings
+            .iter()
+            .map(|finding| BaselineEntry {
+                checker: finding.checker_id.to_string(),
+                fingerprint: finding.fingerprint.clone(),
+            })
+            .collect();
+        entries.sort();
+        entries.dedup();
+        Self { entries }
+    }
+
+    /// The baseline at `path`, or `None` before it is first written.
+    pub fn read(path: &Path) -> Result<Option<Self>, BaselineError> {
+        let display = path.display().to_string();
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(BaselineError::Read(display, err)),
+        };
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|err| BaselineError::Parse(display, err))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), BaselineError> {
+        let json = serde_json::to_string_pretty(self).expect("baselines serialize to JSON");
+        fs::write(path, json + "\n")
+            .map_err(|err| BaselineError::Write(path.display().to_string(), err))
+    }
+
+    /// Whether `finding` was there when the baseline was written. The file may
+    /// have been edited by hand, so it is not assumed to be sorted.
+    pub fn contains(&self, finding: &Finding) -> bool {
+        self.entries.iter().any(|entry| entry.matches(finding))
+    }
+}
+
+impl BaselineEntry {
+    /// Whether `finding` is the one this entry was written for.
+    pub fn matches(&self, finding: &Finding) -> bool {
+        self.checker == finding.checker_id && self.fingerprint == finding.fingerprint
+    }
+}
+
+impl From<&ReportFinding> for BaselineEntry {
+    fn from(finding: &ReportFinding) -> Self {
+        Self {
+            checker: finding.checker.clone(),
+            fingerprint: finding.fingerprint.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_baseline() {
+        let findings = [
+            Finding::warning("index_overflow", "b".to_string()),
+            Finding::error("float_round", "a".to_string()),
+            Finding::warning("index_overflow", "b".to_string()),
+        ];
+        let baseline = Baseline::new(&findings);
+        let checkers: Vec<_> = baseline
+            .entries
+            .iter()
+            .map(|entry| entry.checker.as_str())
+            .collect();
+        assert_eq!(checkers, ["float_round", "index_overflow"]);
+        assert!(findings.iter().all(|finding| baseline.contains(finding)));
+        assert!(!baseline.contains(&Finding::warning("index_overflow", "c".to_string())));
+
+        let json = serde_json::to_value(&baseline).unwrap();
+        assert_eq!(json[0]["checker"], "float_round");
+        assert_eq!(json[1]["fingerprint"], findings[0].fingerprint.as_str());
+        assert!(
+            Baseline::read(Path::new("/nonexistent/baseline.json"))
+                .unwrap()
+                .is_none()
+        );
+    }
+}
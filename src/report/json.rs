@@ -0,0 +1,7 @@
+//! The JSON report of `--analyzer-format=json` and `--report`. Its types live
+//! in `crate::aggregate`, which builds without the `driver` feature so that
+//! `cargo solana-analyze` can read and combine the reports of a workspace.
+pub use crate::aggregate::{
+    AnalysisReport, ModelSummary, ReportAccounts, ReportArg, ReportCpi, ReportFinding,
+    ReportHandler, ReportInstruction, ReportNativeInstruction, ReportTarget,
+};
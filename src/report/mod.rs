@@ -0,0 +1,513 @@
+//! Findings of the checkers, filtered by `#[cfx::allow(..)]` and the per-checker
+//! levels of the config before they are printed.
+pub mod baseline;
+pub mod json;
+pub mod sarif;
+pub mod text;
+pub mod timings;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use rustc_public::CrateDef;
+use rustc_public::mir::mono::Instance;
+use rustc_public::ty::Span;
+use serde::{Deserialize, Serialize};
+
+pub use crate::aggregate::SourceLocation;
+use crate::analysis::internal::reachability::reachable_from;
+use crate::checker::{CheckerSelection, InstructionHandler};
+use crate::config::Config;
+use baseline::{Baseline, BaselineEntry};
+use json::ReportFinding;
+
+/// Tool attribute path suppressing checkers on the item it is placed on.
+const ALLOW_ATTR: [&str; 2] = ["cfx", "allow"];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    /// A suggestion rather than a defect, e.g. to save compute.
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The level a checker is configured to, overriding its own severity.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Off,
+    Warning,
+    Error,
+}
+
+impl SourceLocation {
+    /// Where `span` starts.
+    pub fn from_span(span: &Span) -> Self {
+        let lines = span.get_lines();
+        Self {
+            file: span.get_filename(),
+            line: lines.start_line,
+            column: lines.start_col,
+        }
+    }
+}
+
+/// What a checker found, built with `warning` or `error` and attributed to
+/// the item it is about with `at` or `in_fn`.
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub checker_id: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// The item the finding is attributed to, unless `span` points closer.
+    pub primary_span: Option<SourceLocation>,
+    /// Other places the finding involves, e.g. the handlers taking an Accounts
+    /// struct.
+    pub related: Vec<SourceLocation>,
+    /// Identifies the finding across runs, see `fingerprint`.
+    pub fingerprint: String,
+    /// Whether the fingerprint is in the `--analyzer-baseline` file: the
+    /// finding is still printed, but not denied.
+    pub baselined: bool,
+    /// Checkers allowed on the item the finding originates in.
+    pub allowed: Vec<String>,
+    /// Explanations printed under the message, e.g. how a value was computed.
+    pub notes: Vec<String>,
+    /// The fn the finding originates in, if it is about one.
+    pub origin: Option<Instance>,
+    /// Path of the item the finding is attributed to.
+    item: Option<String>,
+    /// Names within the item, e.g. fields, telling its findings apart.
+    keys: Vec<String>,
+}
+
+impl Finding {
+    pub fn info(checker: &'static str, message: String) -> Self {
+        Self::new(checker, Severity::Info, message)
+    }
+
+    pub fn warning(checker: &'static str, message: String) -> Self {
+        Self::new(checker, Severity::Warning, message)
+    }
+
+    pub fn error(checker: &'static str, message: String) -> Self {
+        Self::new(checker, Severity::Error, message)
+    }
+
+    fn new(checker_id: &'static str, severity: Severity, message: String) -> Self {
+        let mut finding = Self {
+            checker_id,
+            severity,
+            message,
+            primary_span: None,
+            related: vec![],
+            fingerprint: String::new(),
+            baselined: false,
+            allowed: vec![],
+            notes: vec![],
+            origin: None,
+            item: None,
+            keys: vec![],
+        };
+        finding.fingerprint = finding.fingerprint();
+        finding
+    }
+
+    pub fn note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    /// Attribute the finding to the fn or struct `def`, picking up the checkers
+    /// its `#[cfx::allow(..)]` attributes suppress.
+    pub fn at(mut self, def: &impl CrateDef) -> Self {
+        let path = ALLOW_ATTR.map(String::from);
+        for attr in def.tool_attrs(&path) {
+            self.allowed.extend(allowed_checkers(attr.as_str()));
+        }
+        self.primary_span = Some(SourceLocation::from_span(&def.span()));
+        self.item = Some(def.name());
+        self.fingerprint = self.fingerprint();
+        self
+    }
+
+    /// Point the finding at `span` within the item it is attributed to.
+    pub fn span(mut self, span: &Span) -> Self {
+        self.primary_span = Some(SourceLocation::from_span(span));
+        self
+    }
+
+    /// Point the finding at `location`, for findings outside the Rust source,
+    /// e.g. in `Cargo.toml`.
+    pub fn located(mut self, location: SourceLocation) -> Self {
+        self.primary_span = Some(location);
+        self
+    }
+
+    pub fn related(mut self, span: &Span) -> Self {
+        self.related.push(SourceLocation::from_span(span));
+        self
+    }
+
+    /// Name a part of the item the finding is about, e.g. a field of an
+    /// Accounts struct or the block of a fn where a call is made, so that
+    /// findings on different parts of one item get different fingerprints.
+    pub fn key(mut self, key: &str) -> Self {
+        self.keys.push(key.to_string());
+        self.fingerprint = self.fingerprint();
+        self
+    }
+
+    /// A hash of the checker id, the path of the item and the keys, which
+    /// unlike the message and spans survives edits elsewhere in the file.
+    /// Findings not attributed to an item hash their message instead.
+    fn fingerprint(&self) -> String {
+        let mut parts = vec![self.checker_id];
+        match &self.item {
+            Some(item) => {
+                parts.push(item);
+                parts.extend(self.keys.iter().map(String::as_str));
+            }
+            None => parts.push(&self.message),
+        }
+        format!("{:016x}", fnv1a(parts.join("\0").as_bytes()))
+    }
+
+    /// Attribute the finding to the fn `instance`, as `at` does, and remember it
+    /// so the finding can be grouped under the handlers reaching it.
+    pub fn in_fn(mut self, instance: Instance) -> Self {
+        self.origin = Some(instance);
+        self.at(&instance.def)
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Find {}", self.severity)?;
+        if self.baselined {
+            write!(f, " (baseline)")?;
+        }
+        write!(f, ": {}", self.message)?;
+        for note in &self.notes {
+            write!(f, "\n    note: {note}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The finding as the JSON report has it.
+impl From<&Finding> for ReportFinding {
+    fn from(finding: &Finding) -> Self {
+        Self {
+            checker: finding.checker_id.to_string(),
+            severity: finding.severity.to_string(),
+            message: finding.message.clone(),
+            location: finding.primary_span.clone(),
+            related: finding.related.clone(),
+            fingerprint: finding.fingerprint.clone(),
+            baselined: finding.baselined,
+            notes: finding.notes.clone(),
+        }
+    }
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is the same across Rust
+/// versions, so fingerprints can be compared between runs.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// `file.rs:line` of `span`, for pointing at code in a message.
+pub fn location(span: &Span) -> String {
+    let filename = span.get_filename();
+    let file = Path::new(&filename)
+        .file_name()
+        .map_or(filename.clone(), |file| file.to_string_lossy().into_owned());
+    format!("{file}:{}", span.get_lines().start_line)
+}
+
+/// Checker names in the argument list of `#[cfx::allow(a, b)]`.
+fn allowed_checkers(attr: &str) -> Vec<String> {
+    let Some((_, args)) = attr.split_once('(') else {
+        return vec![];
+    };
+    // Rendered attributes end with a newline after the `]`.
+    let args = args.trim_end().trim_end_matches([']', ')']);
+    args.split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Collects the findings of all checkers; `finish` sorts them, so the output
+/// does not depend on the order checkers visit items in.
+pub struct Reporter<'a> {
+    config: &'a Config,
+    selection: CheckerSelection,
+    /// The program root, which the excludes of the config are relative to.
+    root: PathBuf,
+    findings: RefCell<Vec<Finding>>,
+    /// The findings of an earlier run, see `Reporter::diff_against`.
+    previous: Option<Vec<ReportFinding>>,
+    /// The fingerprints of `Reporter::demote`.
+    baseline: Baseline,
+}
+
+impl<'a> Reporter<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self {
+            config,
+            selection: CheckerSelection::default(),
+            root: std::env::var_os("CARGO_MANIFEST_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_default(),
+            findings: RefCell::new(vec![]),
+            previous: None,
+            baseline: Baseline::default(),
+        }
+    }
+
+    /// Only print the findings missing from `baseline`, the findings of an
+    /// earlier run, followed by those of `baseline` no longer found.
+    pub fn diff_against(&mut self, baseline: Vec<ReportFinding>) {
+        self.previous = Some(baseline);
+    }
+
+    /// Mark the findings whose fingerprint is in `baseline` as `baselined`.
+    pub fn demote(&mut self, baseline: Baseline) {
+        self.baseline = baseline;
+    }
+
+    /// Only keep the findings of the checkers of `selection`.
+    pub fn select(&mut self, selection: CheckerSelection) {
+        self.selection = selection;
+    }
+
+    /// Keep `finding` unless it is allowed where it originates, its file is
+    /// excluded, or its checker is turned off or left out of the selection.
+    /// Returns whether it was kept outside the baseline, so that it fails a
+    /// gate such as `--analyzer-deny`.
+    pub fn report(&self, mut finding: Finding) -> bool {
+        if !self.selection.is_selected(finding.checker_id) {
+            return false;
+        }
+        if finding
+            .allowed
+            .iter()
+            .any(|name| name == finding.checker_id)
+        {
+            return false;
+        }
+        if finding
+            .primary_span
+            .as_ref()
+            .is_some_and(|span| self.config.is_excluded(&span.file, &self.root))
+        {
+            return false;
+        }
+        match self.config.checkers.get(finding.checker_id) {
+            Some(Level::Off) => return false,
+            Some(Level::Warning) => finding.severity = Severity::Warning,
+            Some(Level::Error) => finding.severity = Severity::Error,
+            None => {}
+        }
+        finding.baselined = self.baseline.contains(&finding);
+        let counts = !finding.baselined;
+        self.findings.borrow_mut().push(finding);
+        counts
+    }
+
+    /// Whether `finish` prints the difference with an earlier run, which then
+    /// replaces the `text::TextReport` of the findings.
+    pub fn diffs(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    /// Return the kept findings, by checker and then message. With
+    /// `diff_against`, the difference is printed.
+    pub fn finish(self) -> Vec<Finding> {
+        let mut findings = self.findings.into_inner();
+        sort_findings(&mut findings);
+        if let Some(baseline) = &self.previous {
+            let (new, resolved) = diff_findings(&findings, baseline);
+            for finding in &new {
+                println!("{finding}");
+            }
+            for finding in &resolved {
+                println!("Resolved {}: {}", finding.severity, finding.message);
+            }
+            println!(
+                "baseline: {} new, {} unchanged, {} resolved",
+                new.len(),
+                findings.len() - new.len(),
+                resolved.len()
+            );
+        }
+        findings
+    }
+}
+
+/// The findings of `findings` that are not in `baseline`, and those of
+/// `baseline` that are not in `findings`. Findings match by checker and
+/// fingerprint, as with a `Baseline`, one to one, rather than by location and
+/// message: the fingerprint keys the fields and blocks a finding is about,
+/// so it tells findings apart as well, but survives code moving around it.
+fn diff_findings<'f, 'b>(
+    findings: &'f [Finding],
+    baseline: &'b [ReportFinding],
+) -> (Vec<&'f Finding>, Vec<&'b ReportFinding>) {
+    let mut resolved: Vec<(BaselineEntry, &ReportFinding)> = baseline
+        .iter()
+        .map(|old| (BaselineEntry::from(old), old))
+        .collect();
+    let mut new = vec![];
+    for finding in findings {
+        match resolved
+            .iter()
+            .position(|(entry, _)| entry.matches(finding))
+        {
+            Some(idx) => {
+                resolved.remove(idx);
+            }
+            None => new.push(finding),
+        }
+    }
+    (new, resolved.into_iter().map(|(_, old)| old).collect())
+}
+
+/// The findings originating in each of `handlers` or in a local fn it calls,
+/// or attributed to its Accounts struct, by handler name, in the order of
+/// `handlers`. A finding in a helper or an Accounts struct shared by several
+/// handlers is listed under each; other findings, e.g. about the dependencies,
+/// and handlers without findings are left out.
+pub fn group_by_handler<'d>(
+    findings: &'d [Finding],
+    handlers: &[InstructionHandler],
+) -> Vec<(String, Vec<&'d Finding>)> {
+    let mut groups = vec![];
+    for handler in handlers {
+        let reachable: HashSet<Instance> = reachable_from(&[handler.instance]);
+        let in_handler: Vec<&Finding> = findings
+            .iter()
+            .filter(|finding| match finding.origin {
+                Some(origin) => reachable.contains(&origin),
+                None => finding.item.is_some() && finding.item == handler.accounts,
+            })
+            .collect();
+        if !in_handler.is_empty() {
+            groups.push((handler.name.clone(), in_handler));
+        }
+    }
+    groups
+}
+
+fn sort_findings(findings: &mut [Finding]) {
+    findings.sort_by(|a, b| (a.checker_id, &a.message).cmp(&(b.checker_id, &b.message)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_checkers() {
+        assert_eq!(
+            allowed_checkers("#[cfx::allow(fragile_timestamp_check)]\n"),
+            ["fragile_timestamp_check"]
+        );
+        assert_eq!(
+            allowed_checkers("#[cfx::allow(compute_heavy_loop, stale_read_after_cpi)]"),
+            ["compute_heavy_loop", "stale_read_after_cpi"]
+        );
+        assert!(allowed_checkers("#[cfx::allow]").is_empty());
+    }
+
+    #[test]
+    fn test_sort_findings() {
+        let mut findings = vec![
+            Finding::warning("index_overflow", "b".to_string()),
+            Finding::error("float_round", "c".to_string()),
+            Finding::warning("index_overflow", "a".to_string()),
+        ];
+        sort_findings(&mut findings);
+        let sorted: Vec<_> = findings
+            .iter()
+            .map(|finding| (finding.checker_id, finding.message.as_str()))
+            .collect();
+        assert_eq!(
+            sorted,
+            [
+                ("float_round", "c"),
+                ("index_overflow", "a"),
+                ("index_overflow", "b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_findings() {
+        let old = |checker: &'static str, message: &str| ReportFinding {
+            checker: checker.to_string(),
+            severity: "warning".to_string(),
+            message: message.to_string(),
+            location: None,
+            related: vec![],
+            fingerprint: Finding::warning(checker, message.to_string()).fingerprint,
+            baselined: false,
+            notes: vec![],
+        };
+        let findings = vec![
+            Finding::warning("index_overflow", "a".to_string()),
+            Finding::warning("index_overflow", "a".to_string()),
+            Finding::warning("index_overflow", "b".to_string()),
+        ];
+        let baseline = vec![
+            old("index_overflow", "a"),
+            old("float_round", "b"),
+            old("index_overflow", "c"),
+        ];
+        let (new, resolved) = diff_findings(&findings, &baseline);
+        // A baseline finding only matches one of two identical findings.
+        let new: Vec<_> = new.iter().map(|finding| finding.message.as_str()).collect();
+        assert_eq!(new, ["a", "b"]);
+        let resolved: Vec<_> = resolved
+            .iter()
+            .map(|finding| (finding.checker.as_str(), finding.message.as_str()))
+            .collect();
+        assert_eq!(resolved, [("float_round", "b"), ("index_overflow", "c")]);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let finding = Finding::warning("index_overflow", "a".to_string());
+        assert_eq!(finding.fingerprint.len(), 16);
+        assert_eq!(
+            finding.fingerprint,
+            Finding::warning("index_overflow", "a".to_string()).fingerprint
+        );
+        assert_ne!(
+            finding.fingerprint,
+            Finding::warning("index_overflow", "b".to_string()).fingerprint
+        );
+        // Without an item, the message already tells findings apart.
+        assert_eq!(
+            finding.clone().key("vault").fingerprint,
+            finding.fingerprint
+        );
+    }
+}
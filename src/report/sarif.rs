@@ -0,0 +1,264 @@
+//! `--analyzer-format=sarif`: a SARIF 2.1.0 log, as GitHub code scanning and
+//! most security dashboards ingest, with one run whose rules are the
+//! registered checkers.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::{Finding, Severity, SourceLocation};
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+/// The base the artifact URIs of the results are relative to.
+const SRCROOT: &str = "%SRCROOT%";
+/// Key of our fingerprint among the partial fingerprints of a result.
+const FINGERPRINT_KEY: &str = "solanaAnalyzer/v1";
+
+#[derive(Debug, Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Run {
+    pub tool: Tool,
+    pub original_uri_base_ids: BTreeMap<&'static str, ArtifactLocation>,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub driver: ToolComponent,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolComponent {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub information_uri: &'static str,
+    pub rules: Vec<Rule>,
+}
+
+/// A `reportingDescriptor`: one checker.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub short_description: Message,
+    pub help_uri: String,
+    pub default_configuration: RuleConfiguration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleConfiguration {
+    pub level: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    /// Index of the rule in `rules`; left out for findings the driver reports
+    /// itself, such as `fixpoint_limit` and `expect_program_id`, which have
+    /// no rule.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_index: Option<usize>,
+    pub level: &'static str,
+    pub message: Message,
+    pub locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<Location>,
+    pub partial_fingerprints: BTreeMap<&'static str, String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<usize>,
+    pub physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    pub region: Region,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactLocation {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri_base_id: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: usize,
+    pub start_column: usize,
+}
+
+impl SarifLog {
+    /// The log of `findings`, with a rule for each of `checkers`, given as id,
+    /// description and default severity. Paths are made relative to `root`,
+    /// the workspace root, which rustc's relative paths are also relative to.
+    pub fn new(
+        findings: &[Finding],
+        checkers: &[(&'static str, &'static str, Severity)],
+        root: &Path,
+    ) -> Self {
+        let rules = checkers
+            .iter()
+            .map(|&(id, description, severity)| Rule {
+                id: id.to_string(),
+                short_description: Message {
+                    text: description.to_string(),
+                },
+                help_uri: format!("{}#readme", env!("CARGO_PKG_REPOSITORY")),
+                default_configuration: RuleConfiguration {
+                    level: level(severity),
+                },
+            })
+            .collect();
+        let results = findings
+            .iter()
+            .map(|finding| SarifResult {
+                rule_id: finding.checker_id.to_string(),
+                rule_index: checkers
+                    .iter()
+                    .position(|(id, ..)| *id == finding.checker_id),
+                level: level(finding.severity),
+                message: Message {
+                    text: finding.message.clone(),
+                },
+                locations: finding
+                    .primary_span
+                    .iter()
+                    .map(|span| location(span, None, root))
+                    .collect(),
+                related_locations: finding
+                    .related
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, span)| location(span, Some(idx), root))
+                    .collect(),
+                partial_fingerprints: BTreeMap::from([(
+                    FINGERPRINT_KEY,
+                    finding.fingerprint.clone(),
+                )]),
+            })
+            .collect();
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: env!("CARGO_PKG_REPOSITORY"),
+                        rules,
+                    },
+                },
+                original_uri_base_ids: BTreeMap::from([(
+                    SRCROOT,
+                    ArtifactLocation {
+                        uri: file_uri(root, true),
+                        uri_base_id: None,
+                    },
+                )]),
+                results,
+            }],
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("SARIF logs serialize to JSON")
+    }
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+fn location(span: &SourceLocation, id: Option<usize>, root: &Path) -> Location {
+    Location {
+        id,
+        physical_location: PhysicalLocation {
+            artifact_location: artifact(&span.file, root),
+            region: Region {
+                start_line: span.line.max(1),
+                start_column: span.column.max(1),
+            },
+        },
+    }
+}
+
+/// `file` relative to `root`, or as an absolute `file://` URI when it is
+/// outside, e.g. in a dependency.
+fn artifact(file: &str, root: &Path) -> ArtifactLocation {
+    let path = Path::new(file);
+    let absolute = if path.is_relative() {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    } else {
+        path.to_path_buf()
+    };
+    match absolute.strip_prefix(root) {
+        Ok(relative) => ArtifactLocation {
+            uri: relative.to_string_lossy().replace('\\', "/"),
+            uri_base_id: Some(SRCROOT),
+        },
+        Err(_) => ArtifactLocation {
+            uri: file_uri(&absolute, false),
+            uri_base_id: None,
+        },
+    }
+}
+
+/// The `file://` URI of `path`, ending in `/` for a directory as SARIF asks
+/// of base URIs.
+fn file_uri(path: &Path, dir: bool) -> String {
+    let path: PathBuf = path.components().collect();
+    let mut uri = format!("file://{}", path.to_string_lossy().replace('\\', "/"));
+    if dir && !uri.ends_with('/') {
+        uri.push('/');
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact() {
+        let root = Path::new("/work/programs");
+        let inside = artifact("/work/programs/vault/src/lib.rs", root);
+        assert_eq!(inside.uri, "vault/src/lib.rs");
+        assert_eq!(inside.uri_base_id, Some(SRCROOT));
+        let outside = artifact("/home/user/.cargo/registry/lib.rs", root);
+        assert_eq!(outside.uri, "file:///home/user/.cargo/registry/lib.rs");
+        assert_eq!(outside.uri_base_id, None);
+        assert_eq!(file_uri(root, true), "file:///work/programs/");
+    }
+}
@@ -0,0 +1,189 @@
+//! The default text output: the findings of a crate under the instruction
+//! handler they are found in, followed by how many each checker reported and
+//! the time the analysis took. The time of each pass is left to the table of
+//! `--analyzer-timings`.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use super::{Finding, group_by_handler};
+use crate::checker::InstructionHandler;
+
+/// Starts the lines between the findings, which start with `Find `.
+const SECTION: &str = "--";
+
+pub struct TextReport<'f> {
+    crate_name: String,
+    /// The declared program id, in base58.
+    program_id: Option<String>,
+    /// The handlers with findings, each with the findings originating in it or
+    /// in a local fn it calls, in handler order.
+    handlers: Vec<(String, Vec<&'f Finding>)>,
+    /// The handlers without findings.
+    clean: Vec<String>,
+    /// The findings outside every handler, e.g. about an Accounts struct or
+    /// the dependencies of the crate.
+    unattributed: Vec<&'f Finding>,
+    /// Findings per checker id.
+    counts: BTreeMap<&'static str, usize>,
+    total: usize,
+    elapsed: Duration,
+}
+
+impl<'f> TextReport<'f> {
+    /// Group `findings`, sorted, under the `handlers` of the crate, by name.
+    /// `elapsed` is the time the whole analysis took.
+    pub fn new(
+        crate_name: String,
+        program_id: Option<String>,
+        findings: &'f [Finding],
+        handlers: &[InstructionHandler],
+        elapsed: Duration,
+    ) -> Self {
+        let grouped = group_by_handler(findings, handlers);
+        let clean = handlers
+            .iter()
+            .map(|handler| &handler.name)
+            .filter(|name| !grouped.iter().any(|(handler, _)| handler == *name))
+            .cloned()
+            .collect();
+        let unattributed = findings
+            .iter()
+            .filter(|finding| {
+                !grouped.iter().any(|(_, in_handler)| {
+                    in_handler
+                        .iter()
+                        .any(|attributed| std::ptr::eq(*attributed, *finding))
+                })
+            })
+            .collect();
+        let mut counts = BTreeMap::new();
+        for finding in findings {
+            *counts.entry(finding.checker_id).or_default() += 1;
+        }
+        Self {
+            crate_name,
+            program_id,
+            handlers: grouped,
+            clean,
+            unattributed,
+            counts,
+            total: findings.len(),
+            elapsed,
+        }
+    }
+}
+
+impl fmt::Display for TextReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "== {}", self.crate_name)?;
+        if let Some(program_id) = &self.program_id {
+            write!(f, " (program {program_id})")?;
+        }
+        writeln!(f, " ==")?;
+        for (handler, findings) in &self.handlers {
+            writeln!(f, "{SECTION} handler {handler}: {}", plural(findings.len()))?;
+            for finding in findings {
+                writeln!(f, "{finding}")?;
+            }
+        }
+        if !self.clean.is_empty() {
+            writeln!(f, "{SECTION} clean: {}", self.clean.join(", "))?;
+        }
+        if !self.unattributed.is_empty() {
+            writeln!(
+                f,
+                "{SECTION} outside handlers: {}",
+                plural(self.unattributed.len())
+            )?;
+            for finding in &self.unattributed {
+                writeln!(f, "{finding}")?;
+            }
+        }
+        if !self.counts.is_empty() {
+            let counts: Vec<String> = self
+                .counts
+                .iter()
+                .map(|(checker, count)| format!("{checker} {count}"))
+                .collect();
+            writeln!(f, "{SECTION} by checker: {}", counts.join(", "))?;
+        }
+        write!(
+            f,
+            "{SECTION} {} in {:.2}s",
+            plural(self.total),
+            self.elapsed.as_secs_f64()
+        )
+    }
+}
+
+fn plural(findings: usize) -> String {
+    match findings {
+        1 => "1 finding".to_string(),
+        findings => format!("{findings} findings"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_report() {
+        let shared = Finding::warning(
+            "fragile_timestamp_check",
+            "`at_deadline` compares".to_string(),
+        );
+        let own = Finding::error("user_controlled_seeds", "`claim` derives".to_string());
+        let accounts = Finding::error(
+            "duplicate_mutable_account",
+            "two mutable accounts".to_string(),
+        )
+        .note("in `Claim`".to_string());
+        let report = TextReport {
+            crate_name: "auction".to_string(),
+            program_id: Some("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS".to_string()),
+            handlers: vec![
+                ("claim".to_string(), vec![&shared, &own]),
+                ("refund".to_string(), vec![&shared]),
+            ],
+            clean: vec!["open".to_string(), "touch".to_string()],
+            unattributed: vec![&accounts],
+            counts: BTreeMap::from([
+                ("duplicate_mutable_account", 1),
+                ("fragile_timestamp_check", 1),
+                ("user_controlled_seeds", 1),
+            ]),
+            total: 3,
+            elapsed: Duration::from_millis(1234),
+        };
+        assert_eq!(
+            report.to_string(),
+            "\
+== auction (program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS) ==
+-- handler claim: 2 findings
+Find warning: `at_deadline` compares
+Find error: `claim` derives
+-- handler refund: 1 finding
+Find warning: `at_deadline` compares
+-- clean: open, touch
+-- outside handlers: 1 finding
+Find error: two mutable accounts
+    note: in `Claim`
+-- by checker: duplicate_mutable_account 1, fragile_timestamp_check 1, user_controlled_seeds 1
+-- 3 findings in 1.23s"
+        );
+
+        let empty = TextReport {
+            crate_name: "helpers".to_string(),
+            program_id: None,
+            handlers: vec![],
+            clean: vec![],
+            unattributed: vec![],
+            counts: BTreeMap::new(),
+            total: 0,
+            elapsed: Duration::ZERO,
+        };
+        assert_eq!(empty.to_string(), "== helpers ==\n-- 0 findings in 0.00s");
+    }
+}
@@ -0,0 +1,69 @@
+//! What each pass of a run took: the parts of the `AnalysisContext` extracted
+//! and the checkers run over them, printed with `--analyzer-timings` and kept
+//! in the JSON report.
+use std::fmt;
+
+pub use crate::aggregate::{PassKind, PassTiming};
+
+/// The passes of a run, slowest first, as a table.
+pub struct TimingTable<'t>(pub &'t [PassTiming]);
+
+impl fmt::Display for TimingTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut timings: Vec<&PassTiming> = self.0.iter().collect();
+        timings.sort_by(|a, b| b.micros.cmp(&a.micros).then_with(|| a.pass.cmp(&b.pass)));
+        let width = timings
+            .iter()
+            .map(|timing| timing.pass.len())
+            .max()
+            .unwrap_or_default()
+            .max("pass".len());
+        writeln!(
+            f,
+            "{:<width$}  {:<10}  {:>10}  {:>6}",
+            "pass", "kind", "ms", "items"
+        )?;
+        for timing in timings {
+            writeln!(
+                f,
+                "{:<width$}  {:<10}  {:>10.3}  {:>6}",
+                timing.pass,
+                timing.kind.to_string(),
+                timing.micros as f64 / 1000.0,
+                timing.items
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_table_slowest_first() {
+        let timings = [
+            PassTiming::new(
+                "anchor_accounts",
+                PassKind::Extraction,
+                Duration::from_micros(1500),
+                3,
+            ),
+            PassTiming::new(
+                "unchecked_unpack",
+                PassKind::Checker,
+                Duration::from_micros(12_250),
+                1,
+            ),
+        ];
+        assert_eq!(
+            TimingTable(&timings).to_string(),
+            "pass              kind                ms   items\n\
+             unchecked_unpack  checker         12.250       1\n\
+             anchor_accounts   extraction       1.500       3\n"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Which crates of a build the analyzer runs on.
+//!
+//! Cargo runs us as the `rustc` of every crate in the build, dependencies
+//! included, so the choice is made from the rustc args and the environment
+//! cargo sets before anything is compiled. Crates that are not analyzed go
+//! straight to the compiler, without the `rustc_public` driver.
+use std::path::Path;
+
+use crate::metadata::{ProgramType, check_program_type, parse_toml_in_crate_path};
+
+/// Analyze only the crate with the given name.
+pub const TARGET_CRATE_FLAG: &str = "--target-crate";
+/// The crate to analyze when `TARGET_CRATE_FLAG` is not given.
+pub const TARGET_ENV: &str = "SOLANA_ANALYZER_TARGET";
+/// Set by cargo for the packages it was asked to build, rather than their
+/// dependencies.
+const PRIMARY_PACKAGE_ENV: &str = "CARGO_PRIMARY_PACKAGE";
+
+/// Whether the crate compiled with `rustc_args` is analyzed. With a target,
+/// from `--target-crate` or else `SOLANA_ANALYZER_TARGET`, only the crate of
+/// that name is. Without one, the library of a package cargo was asked to
+/// build is, when its `Cargo.toml` depends on Anchor, Pinocchio, or the Solana
+/// SDK; dependencies, build scripts and test harnesses are not.
+pub fn is_analyzed(rustc_args: &[String], target: Option<&str>) -> bool {
+    let Some(crate_name) = crate_name(rustc_args) else {
+        return false;
+    };
+    if let Some(target) = target {
+        return crate_name == target;
+    }
+    if std::env::var_os(PRIMARY_PACKAGE_ENV).is_none()
+        || rustc_args.iter().any(|arg| arg == "--test")
+    {
+        return false;
+    }
+    let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") else {
+        return false;
+    };
+    is_program(Path::new(&manifest_dir), crate_name)
+}
+
+/// The target named by `--target-crate`, or else by `SOLANA_ANALYZER_TARGET`.
+pub fn target(flag: Option<String>) -> Option<String> {
+    flag.or_else(|| std::env::var(TARGET_ENV).ok())
+}
+
+/// Whether the package in `manifest_dir` is a program and `crate_name` its
+/// library, rather than e.g. its build script.
+fn is_program(manifest_dir: &Path, crate_name: &str) -> bool {
+    match parse_toml_in_crate_path(&manifest_dir.to_string_lossy()) {
+        Ok((package, deps)) => {
+            package == crate_name && check_program_type(&deps) != ProgramType::Other
+        }
+        Err(_) => false,
+    }
+}
+
+/// The value of `--crate-name` in `rustc_args`.
+fn crate_name(rustc_args: &[String]) -> Option<&str> {
+    rustc_args
+        .iter()
+        .position(|arg| arg == "--crate-name")
+        .and_then(|idx| rustc_args.get(idx + 1))
+        .map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_target_by_name() {
+        let rustc_args = args(&["rustc", "--crate-name", "vault", "src/lib.rs"]);
+        assert!(is_analyzed(&rustc_args, Some("vault")));
+        assert!(!is_analyzed(&rustc_args, Some("router")));
+        assert!(!is_analyzed(&args(&["rustc", "-vV"]), Some("vault")));
+    }
+
+    #[test]
+    fn test_program_manifest() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert!(is_program(
+            &root.join("tests/fixtures/cpi_callee"),
+            "cpi_callee"
+        ));
+        assert!(!is_program(
+            &root.join("tests/fixtures/cpi_callee"),
+            "build_script_build"
+        ));
+        assert!(!is_program(&root.join("examples/fee_math"), "fee_math"));
+    }
+}
@@ -0,0 +1,35 @@
+mod common;
+
+#[test]
+fn test_access_control_guards() {
+    let output = common::analyze("access_control");
+    let guards: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("access control "))
+        .collect();
+    assert_eq!(
+        guards,
+        [
+            "access control set_fee at lib.rs:15: is_config reads [config], checks key of [config]",
+            "access control set_fee at lib.rs:15: is_admin reads [config, admin], checks key of [admin]",
+            "access control set_fee_by_key at lib.rs:24: is_config_key reads [config], checks key of [config]",
+            "access control set_fee_unpinned at lib.rs:29: is_positive reads [], checks key of []",
+            "access control read_price at lib.rs:35: ReadPrice::<'_>::check_price reads [price], checks key of [price]",
+        ]
+    );
+}
+
+#[test]
+fn test_guards_pin_accounts() {
+    let output = common::analyze("access_control");
+    let findings: Vec<&str> = output
+        .lines()
+        .filter(|line| line.contains("oracle account") || line.contains("singleton account"))
+        .collect();
+    assert_eq!(
+        findings,
+        [
+            "Find error: singleton account `config` (Config) in `SetFeeUnpinned` is missing an `address` or `seeds` constraint"
+        ]
+    );
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+/// Write the account graph of `fixture` to `file` and read it back.
+fn account_graph(fixture: &str, file: &str) -> String {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(file);
+    fs::remove_file(&path).ok();
+    common::analyze_with(
+        fixture,
+        &[&format!("--analyzer-account-graph={}", path.display())],
+    );
+    fs::read_to_string(&path).unwrap()
+}
+
+#[test]
+fn test_dot() {
+    let dot = account_graph("account_metas", "account_graph.dot");
+    for line in [
+        r#"    "instruction:deposit" [label="deposit\nContext<Deposit>", shape=box, style=filled, fillcolor=lightgrey];"#,
+        r#"    "account:Vault" [label="Vault", shape=ellipse, style=filled, fillcolor=lightblue];"#,
+        r#"    "account:Signer" [label="Signer", shape=diamond, style=filled, fillcolor=palegreen];"#,
+        r#"    "instruction:deposit" -> "account:Vault" [label="vault: mut", style=bold];"#,
+        r#"    "instruction:deposit" -> "account:Signer" [label="payer: mut, signer", style=bold];"#,
+        r#"    "instruction:deposit" -> "account:Signer" [label="authority: signer"];"#,
+        r#"    "instruction:deposit" -> "account:SystemAccount" [label="recipient"];"#,
+        r#"    "instruction:deposit" -> "account:UncheckedAccount" [label="delegate: signer"];"#,
+    ] {
+        assert!(dot.contains(line), "missing `{line}` in {dot}");
+    }
+}
+
+#[test]
+fn test_json() {
+    let graph: Value =
+        serde_json::from_str(&account_graph("cpi_calls", "account_graph.json")).unwrap();
+    let instructions = graph["instructions"].as_array().unwrap();
+    assert!(!instructions.is_empty(), "{graph:#}");
+    for edge in graph["edges"].as_array().unwrap() {
+        let ends = |key: &str, nodes: &str| {
+            graph[nodes]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|node| node["id"] == edge[key])
+        };
+        assert!(
+            ends("instruction", "instructions") && ends("account", "accounts"),
+            "{edge}"
+        );
+    }
+}
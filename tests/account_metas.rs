@@ -0,0 +1,24 @@
+mod common;
+
+#[test]
+fn test_account_meta_signer_flags() {
+    let output = common::analyze("account_metas");
+    for prefix in ["meta Deposit.", "cpi meta Deposit."] {
+        let metas: Vec<_> = output
+            .lines()
+            .filter_map(|line| line.strip_prefix(prefix))
+            .collect();
+        assert_eq!(
+            metas,
+            [
+                "payer: writable, signer",
+                "authority: readonly, signer",
+                "vault: writable",
+                "recipient: readonly",
+                // The `signer` constraint is only visible in the meta.
+                "delegate: readonly, signer",
+            ],
+            "{prefix}"
+        );
+    }
+}
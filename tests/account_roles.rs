@@ -0,0 +1,23 @@
+mod common;
+
+#[test]
+fn test_conflicting_account_roles() {
+    let output = common::analyze_with(
+        "conflicting_roles",
+        &["--analyzer-checkers=conflicting_account_roles"],
+    );
+    let findings: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("Find "))
+        .collect();
+    // `Open` creates `escrow` from a keypair, and `payer` in `Open` and `Pay` is
+    // a mutable `Signer`, which is not a conflict.
+    assert_eq!(
+        findings,
+        [
+            "Find warning: account `escrow` in `Settle` is a mutable `Escrow` account that must also sign; accounts holding program data are not signers once created",
+            "Find warning: account `treasury` in `Sweep` must be the same account as `authority` (`address = authority.key()`), making it both a signer and a mutable `Escrow` account",
+        ],
+        "{output}"
+    );
+}
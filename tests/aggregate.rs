@@ -0,0 +1,50 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+
+use solana_program_analyzer::aggregate::{AnalysisReport, combine};
+
+#[test]
+fn test_combine_reports() {
+    let tmp = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let mut paths = vec![];
+    for fixture in ["cpi_callee", "cpi_caller"] {
+        let path = tmp.join(format!("{fixture}.json"));
+        let path_arg = path.to_string_lossy().into_owned();
+        common::analyze_with(fixture, &["--report", &path_arg]);
+        paths.push(path);
+    }
+
+    let reports: Vec<_> = paths
+        .iter()
+        .map(|path| AnalysisReport::read(path).unwrap())
+        .collect();
+    let combined = combine(reports);
+    let resolved: Vec<_> = combined
+        .cross_program_cpis
+        .iter()
+        .map(|cpi| (cpi.handler.as_str(), cpi.callee.as_str()))
+        .collect();
+    assert_eq!(resolved, [("cpi_caller::forward", "cpi_callee")]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_solana-program-analyzer"))
+        .arg("--combine")
+        .args(&paths)
+        .output()
+        .expect("failed to run the analyzer");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains(
+            "cross-program cpi at lib.rs:16 in cpi_caller::forward: cpi_callee::cpi::deposit into cpi_callee"
+        ),
+        "{stdout}"
+    );
+}
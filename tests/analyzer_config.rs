@@ -0,0 +1,55 @@
+mod common;
+
+use common::findings;
+
+#[test]
+fn test_config_file() {
+    let output = common::analyze("analyzer_config");
+    // `duplicate_mutable_account` is off, `generated::scratch` is excluded and
+    // the loop in `digest_all` costs less than `min_loop_cu`.
+    assert_eq!(
+        findings(&output),
+        [
+            "Find warning: `analyzer_config::fill` declares 2081 bytes of variables, more than the 2048 bytes of a stack frame"
+        ]
+    );
+    assert!(
+        output.contains(
+            "warning: unknown key `stack.max_depth` in solana-analyzer.toml, ignoring it"
+        ),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_config_flags_override_file() {
+    let output = common::analyze_with(
+        "analyzer_config",
+        &[
+            "--config",
+            "checkers.duplicate_mutable_account = \"warning\"",
+            "--config",
+            "stack.max_frame_bytes = 4096",
+            "--config",
+            "compute.min_loop_cu = 0",
+        ],
+    );
+    assert_eq!(
+        findings(&output),
+        [
+            "Find warning: loop at bb4 in `analyzer_config::digest_all` calls `hash::hash` (bb7) each iteration, ~85 CU per iteration",
+            "Find warning: two mutable accounts of the same type in the same Context `Swap`: `left` and `right` (Vault)",
+        ]
+    );
+}
+
+#[test]
+fn test_config_flag_unknown_key() {
+    let output = common::analyze_with("analyzer_config", &["--config", "stack.frame = 1"]);
+    assert!(
+        output.contains(
+            "warning: unknown key `stack.frame` in --config `stack.frame = 1`, ignoring it"
+        ),
+        "{output}"
+    );
+}
@@ -0,0 +1,18 @@
+mod common;
+
+#[test]
+fn test_authority_substitution() {
+    let output = common::analyze("authority_substitution");
+    let errors: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("whose authority it never checks"))
+        .collect();
+    // `withdraw_checked` writes the vault it checked, and `sweep` has Anchor
+    // check `target` with `has_one`.
+    assert_eq!(
+        errors,
+        [
+            "Find error: `authority_substitution::withdraw` checks `owner` against `Withdraw.source` but writes `target`, another `Vault` whose authority it never checks"
+        ]
+    );
+}
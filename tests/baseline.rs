@@ -0,0 +1,69 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::path::Path;
+
+use solana_program_analyzer::aggregate::{AnalysisReport, ReportFinding, SourceLocation};
+
+fn finding_lines(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter(|line| line.starts_with("Find ") || line.starts_with("Resolved "))
+        .collect()
+}
+
+#[test]
+fn test_baseline() {
+    let tmp = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let path = tmp.join("baseline_sysvar_accounts.json");
+    let path_arg = path.to_string_lossy().into_owned();
+    common::analyze_with("sysvar_accounts", &["--report", &path_arg]);
+
+    // Keep the finding on `stamp` and add one the code no longer has.
+    let mut baseline = AnalysisReport::read(&path).unwrap();
+    assert_eq!(baseline.findings.len(), 2, "{:#?}", baseline.findings);
+    baseline
+        .findings
+        .retain(|finding| finding.message.contains("`stamp`"));
+    baseline.findings.push(ReportFinding {
+        checker: "unchecked_sysvar_account".to_string(),
+        severity: "warning".to_string(),
+        message: "fixed since".to_string(),
+        location: Some(SourceLocation {
+            file: "sysvar_accounts/src/lib.rs".to_string(),
+            line: 1,
+            column: 1,
+        }),
+        related: vec![],
+        fingerprint: "0000000000000000".to_string(),
+        baselined: false,
+        notes: vec![],
+    });
+    baseline.write(&path).unwrap();
+
+    let diff_arg = format!("--analyzer-diff={path_arg}");
+    let output = common::analyze_with("sysvar_accounts", &[&diff_arg]);
+    let lines = finding_lines(&output);
+    assert_eq!(lines.len(), 2, "{output}");
+    assert!(lines[0].starts_with("Find warning:"), "{output}");
+    assert!(!lines[0].contains("`stamp`"), "{output}");
+    assert_eq!(lines[1], "Resolved warning: fixed since");
+    assert!(
+        output.contains("baseline: 1 new, 1 unchanged, 1 resolved"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_missing_baseline() {
+    let (success, output) =
+        common::try_analyze("sysvar_accounts", &["--analyzer-diff=/nonexistent.json"]);
+    assert!(!success, "{output}");
+    assert!(
+        output.contains("error: failed to read /nonexistent.json"),
+        "{output}"
+    );
+    // The run stops before the checkers.
+    assert!(finding_lines(&output).is_empty(), "{output}");
+}
@@ -0,0 +1,24 @@
+mod common;
+
+#[test]
+fn test_missing_bump_persistence() {
+    let output = common::analyze("bump_persistence");
+    let findings: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("Find info"))
+        .collect();
+    // The profile PDA passes its stored bump in `update_profile`.
+    assert_eq!(
+        findings,
+        [
+            "Find info: PDA with seeds [b\"vault\", owner.key()] is derived with `find_program_address` by instructions `deposit`, `withdraw` (`Deposit.vault`, `Withdraw.vault`); store its bump in the account and check it with `bump = <account>.bump`"
+        ],
+        "{output}"
+    );
+}
+
+#[test]
+fn test_stored_bump() {
+    let output = common::analyze("pda_seeds");
+    assert!(!output.contains("Find info"), "{output}");
+}
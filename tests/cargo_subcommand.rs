@@ -0,0 +1,32 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+use std::path::Path;
+use std::process::Command;
+
+use solana_program_analyzer::aggregate::CombinedReport;
+
+#[test]
+fn test_cargo_subcommand_json() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/Cargo.toml");
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-solana-analyze"))
+        .arg("solana-analyze")
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .args(["-p", "cpi_callee", "--offline", "--format", "json"])
+        .env_remove("SOLANA_ANALYZER_TARGET")
+        .output()
+        .expect("failed to run cargo-solana-analyze");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stdout}{stderr}");
+
+    // Only the report goes to stdout; cargo and the analyzer print to stderr.
+    let combined: CombinedReport = serde_json::from_str(&stdout).expect("stdout is the report");
+    let crates: Vec<&str> = combined
+        .reports
+        .iter()
+        .map(|report| report.crate_name.as_str())
+        .collect();
+    assert_eq!(crates, ["cpi_callee"], "{stderr}");
+    assert!(stderr.contains("crate: cpi_callee"), "{stderr}");
+}
@@ -0,0 +1,22 @@
+mod common;
+
+#[test]
+fn test_dump_cfg() {
+    let output = common::analyze_with("cfg_dump", &["--dump-cfg", "double"]);
+    for line in [
+        r#"digraph "double" {"#,
+        r#"    "0" [label="bb0: Call"];"#,
+        r#"    "1" [label="bb1: Return"];"#,
+        r#"    "0" -> "1" [label="Call return"];"#,
+        "// dominator tree: {0: [1], 1: []}",
+        "// post-dominators: {0: {0, 1}, 1: {1}}",
+    ] {
+        assert!(output.contains(line), "missing `{line}` in {output}");
+    }
+    // Only the chosen fn is dumped.
+    assert!(!output.contains(r#"digraph "pick""#));
+
+    let output = common::analyze("cfg_dump");
+    assert!(!output.contains("digraph"), "{output}");
+    assert!(!output.contains("dominator"), "{output}");
+}
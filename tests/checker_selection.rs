@@ -0,0 +1,55 @@
+use std::process::Command;
+
+mod common;
+
+fn findings(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter(|line| line.starts_with("Find "))
+        .collect()
+}
+
+#[test]
+fn test_skip_checkers() {
+    let all = common::analyze("close_target");
+    assert!(all.contains("is closed into"));
+    // Skipping another checker keeps the findings of this one.
+    let output = common::analyze_with(
+        "close_target",
+        &["--analyzer-skip=duplicate_mutable_account"],
+    );
+    assert_eq!(findings(&output), findings(&all));
+    let output = common::analyze_with("close_target", &["--analyzer-skip=bad_close_destination"]);
+    assert!(!output.contains("is closed into"));
+}
+
+#[test]
+fn test_only_checkers_from_env() {
+    let mut command = common::command("close_target", &[]);
+    command.env(
+        "SOLANA_ANALYZER_FLAGS",
+        "--analyzer-checkers=bad_close_destination",
+    );
+    let (success, output) = common::run(&mut command);
+    assert!(success, "{output}");
+    let findings = findings(&output);
+    assert_eq!(findings.len(), 3);
+    assert!(findings.iter().all(|line| line.contains("is closed into")));
+}
+
+#[test]
+fn test_list_checkers() {
+    let output = Command::new(env!("CARGO_BIN_EXE_solana-program-analyzer"))
+        .arg("--analyzer-list-checkers")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().any(|line| line
+        == "bad_close_destination (error): close = destination constraints whose destination cannot take the lamports"));
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.starts_with("non_signer_payer (warning): "))
+    );
+}
@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_bad_close_destination() {
+    let output = common::analyze("close_target");
+    let errors: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("is closed into"))
+        .collect();
+    // `Refund` closes `vault` into a mutable signer, which is fine.
+    assert_eq!(
+        errors,
+        [
+            "Find error: account `vault` in `Burn` is closed into itself, which burns its lamports",
+            "Find error: account `vault` in `Lock` is closed into `authority`, which is not declared mut and cannot receive its lamports",
+            "Find error: account `vault` in `Stuck` is closed into `system_program`, which is a program and cannot receive its lamports",
+        ]
+    );
+}
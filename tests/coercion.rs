@@ -0,0 +1,32 @@
+mod common;
+
+#[test]
+fn test_owner_check_through_box() {
+    let output = common::analyze("coercion");
+    let errors: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("as a token account"))
+        .collect();
+    // `read_boxed_checked` reads the owner through the raw pointer a `Box` is
+    // dereferenced with, and `read_dyn_checked` through `&dyn AsRef`.
+    assert_eq!(
+        errors,
+        [
+            "Find error: `source` in `ReadRaw` is deserialized as a token account in `coercion::read_boxed` without checking that its owner is the Token program",
+            "Find error: `source` in `ReadRaw` is deserialized as a token account in `coercion::read_dyn` without checking that its owner is the Token program",
+        ]
+    );
+}
+
+#[test]
+fn test_field_coerced_to_trait_object() {
+    let output = common::analyze("coercion");
+    // `&ctx.accounts.target` reaches the write as a `&dyn ToAccountInfo`.
+    assert!(
+        output.contains(
+            "Find error: account `target` in `CopyTyped` is owned by another program but `coercion::copy_typed` writes its data"
+        ),
+        "{output}"
+    );
+    assert!(!output.contains("`target` in `CopyTyped` is marked mut but never written"));
+}
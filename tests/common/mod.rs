@@ -0,0 +1,103 @@
+//! Run the analyzer over a crate of the `tests/fixtures` workspace.
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Check `fixture` with the analyzer as its `rustc` and return what it printed.
+// Each test binary compiles its own copy of this module.
+#[allow(dead_code)]
+pub fn analyze(fixture: &str) -> String {
+    analyze_with(fixture, &[])
+}
+
+/// `analyze` with analyzer flags such as `--layouts`.
+pub fn analyze_with(fixture: &str, analyzer_args: &[&str]) -> String {
+    let (success, output) = try_analyze(fixture, analyzer_args);
+    assert!(success, "analyzing {fixture} failed:\n{output}");
+    output
+}
+
+/// `analyze_with` for runs that may fail, with whether cargo succeeded.
+#[allow(dead_code)]
+pub fn try_analyze(fixture: &str, analyzer_args: &[&str]) -> (bool, String) {
+    run(&mut command(fixture, analyzer_args))
+}
+
+/// The cargo invocation checking `fixture` with the analyzer as its `rustc`.
+pub fn command(fixture: &str, analyzer_args: &[&str]) -> Command {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    // A fresh cfg value makes cargo re-check the fixture instead of reusing its
+    // cached metadata, which would skip the analyzer.
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let mut command = Command::new(env!("CARGO"));
+    command
+        .current_dir(&fixtures)
+        .args([
+            "rustc",
+            "--offline",
+            "-q",
+            "-p",
+            fixture,
+            "--lib",
+            "--profile",
+            "check",
+        ])
+        .arg("--")
+        .arg("--cfg")
+        .arg(format!("solana_analyzer_run=\"{nonce}\""))
+        .args(analyzer_args)
+        .env("RUSTC", env!("CARGO_BIN_EXE_solana-program-analyzer"))
+        .env("SOLANA_ANALYZER_TARGET", fixture)
+        .env_remove("RUSTC_WORKSPACE_WRAPPER")
+        .env_remove("RUSTC_WRAPPER");
+    command
+}
+
+/// Run `command` and return whether it succeeded, with what it printed.
+pub fn run(command: &mut Command) -> (bool, String) {
+    let output = command.output().expect("failed to run cargo");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    (output.status.success(), format!("{stdout}{stderr}"))
+}
+
+/// `output` without the time the analysis took, which ends the text report
+/// and changes from run to run.
+#[allow(dead_code)]
+pub fn without_elapsed(output: &str) -> String {
+    output
+        .lines()
+        .map(|line| match line.rsplit_once(" in ") {
+            Some((count, elapsed)) if line.starts_with("-- ") && elapsed.ends_with('s') => count,
+            _ => line,
+        })
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+/// The constraint list printed for each field of the Accounts struct `accounts`.
+#[allow(dead_code)]
+pub fn constraint_lines(output: &str, accounts: &str) -> Vec<String> {
+    let prefix = format!("{accounts}.");
+    output
+        .lines()
+        .filter(|line| line.starts_with(&prefix))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The findings printed in `output`, once each and by message, so that they do
+/// not depend on the handlers the text report groups them under.
+#[allow(dead_code)]
+pub fn findings(output: &str) -> Vec<&str> {
+    let mut findings: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("Find "))
+        .collect();
+    findings.sort_by_key(|line| line.split_once(": ").map(|(_, message)| message));
+    findings.dedup();
+    findings
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::constraint_lines;
+
+#[test]
+fn test_account_constraints() {
+    let output = common::analyze("account_constraints");
+    let lines = constraint_lines(&output, "Update");
+    assert_eq!(
+        lines[0],
+        r#"Update.vault: [HasOne("authority"), Raw("vault.limit < 100")]"#
+    );
+    assert_eq!(
+        lines[1],
+        r#"Update.admin: [Address(Pubkey("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"))]"#
+    );
+    assert!(
+        lines[2].starts_with(r#"Update.record: [Owner(Const("ID", Some(["#),
+        "{}",
+        lines[2]
+    );
+    assert_eq!(lines[3], r#"Update.config: [Raw("config.enabled")]"#);
+    assert_eq!(lines.len(), 4);
+}
+
+#[test]
+fn test_lifecycle_constraints() {
+    let output = common::analyze("account_lifecycle");
+    assert_eq!(
+        constraint_lines(&output, "Open"),
+        [r#"Open.vault: [Init { payer: "payer", space: Some(48), if_needed: false }]"#]
+    );
+    assert_eq!(
+        constraint_lines(&output, "Reopen"),
+        [
+            r#"Reopen.vault: [Seeds([Unknown]), CanonicalBump, Init { payer: "funder", space: Some(48), if_needed: true }]"#
+        ]
+    );
+    assert_eq!(
+        constraint_lines(&output, "Close"),
+        [r#"Close.vault: [HasOne("authority"), Close { destination: "receiver" }]"#]
+    );
+}
@@ -0,0 +1,38 @@
+mod common;
+
+/// The parts of the analysis context are extracted once and then shared by the
+/// checkers asking for them; run with `--nocapture` to see what each took.
+#[test]
+fn test_context_extracts_once() {
+    let (success, output) =
+        common::run(common::command("duplicate_mutable", &[]).env("SOLANA_ANALYZER_STATS", "1"));
+    assert!(success, "{output}");
+    let stats: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("context "))
+        .collect();
+    println!("{}", stats.join("\n"));
+
+    // Every checker over the Accounts structs asks for them, and none walks
+    // the crate's items for them again.
+    let requests = |part: &str| -> usize {
+        let line = stats
+            .iter()
+            .find(|line| line.starts_with(&format!("context {part}: extracted once")))
+            .unwrap_or_else(|| panic!("no stats for {part}:\n{output}"));
+        let (_, requested) = line.rsplit_once("requested ").unwrap();
+        requested.trim_end_matches(" times").parse().unwrap()
+    };
+    assert!(requests("anchor_accounts") >= 5, "{output}");
+    assert!(requests("handlers_by_accounts") >= 5, "{output}");
+
+    // The bodies the call graph walks are reused by the checkers.
+    let bodies = stats
+        .iter()
+        .find_map(|line| line.strip_prefix("context bodies: "))
+        .expect("no body stats");
+    let (built, reused) = bodies.split_once(" built, ").unwrap();
+    let reused: usize = reused.trim_end_matches(" reused").parse().unwrap();
+    assert!(built.parse::<usize>().unwrap() > 0);
+    assert!(reused > 0, "{bodies}");
+}
@@ -0,0 +1,23 @@
+mod common;
+
+#[test]
+fn test_cpi_calls() {
+    let output = common::analyze("cpi_calls");
+    let calls: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("cpi at "))
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            // In the helper `transfer_tokens`, with the fields it was passed.
+            "cpi at lib.rs:88 in cpi_calls::move_tokens: anchor_spl::token::transfer on token_program with [from, to, authority]",
+            "cpi at lib.rs:22 in cpi_calls::pay: anchor_lang::system_program::transfer on system_program with [payer, receiver]",
+            // The program is the one `Program` among the accounts passed.
+            "cpi at lib.rs:26 in cpi_calls::pay_invoke: anchor_lang::solana_program::system_instruction::transfer on system_program with [payer, receiver, system_program]",
+            // `system_program::ID`.
+            "cpi at lib.rs:60 in cpi_calls::ping: <instruction> on 11111111111111111111111111111111 with [target]",
+            "cpi at lib.rs:70 in cpi_calls::call_any: <instruction> on dynamic with [target]",
+        ]
+    );
+}
@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_cpi_signer_propagation() {
+    let output = common::analyze("cpi_signers");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("Find warning: ") && line.contains(" as signer"))
+        .collect();
+    // `sweep` marks the vault it signs for, `approve` checks the authority and
+    // `forward` passes on the `is_signer` of the account.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: `withdraw` calls `invoke_signed` at lib.rs:47 with signer seeds, but marks no account of the instruction as signer, so the PDA does not sign",
+            "Find warning: `delegate` calls `invoke` at lib.rs:87 marking account #1 `authority` as signer without signer seeds, and never checks that it signed",
+        ]
+    );
+}
@@ -0,0 +1,16 @@
+mod common;
+
+#[test]
+fn test_unchecked_data_length() {
+    let output = common::analyze("data_length");
+    let findings = common::findings(&output);
+    // The entrypoint ignores its `program_id`, see `native_program_id`.
+    assert_eq!(
+        findings,
+        [
+            "Find warning: `process_instruction` never uses its `program_id` argument, while the program declares its id as Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS; redeployed under another id, the program rejects its own accounts and trusts those the old id owns",
+            "Find warning: account data is decoded with `try_from_slice` and unwrapped at lib.rs:55 in `read_state` without checking its length",
+            "Find warning: account data is sliced as `[0..8]` at lib.rs:39 in `read_tag` without checking its length",
+        ]
+    );
+}
@@ -0,0 +1,83 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use solana_program_analyzer::aggregate::DENIED_EXIT_CODE;
+
+const DENIED: i32 = DENIED_EXIT_CODE as i32;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Analyze `fixture` with `--manifest-path` and return the exit code and what
+/// went to stderr.
+fn manifest_exit_code(name: &str, analyzer_args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_solana-program-analyzer"))
+        .arg("--manifest-path")
+        .arg(fixture(name))
+        .args(analyzer_args)
+        .env_remove("SOLANA_ANALYZER_TARGET")
+        .output()
+        .expect("failed to run the analyzer");
+    (
+        output.status.code(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn test_denied_exit_code_is_distinct() {
+    // 1 is a failed build, 101 a cargo failure or a compiler crash.
+    assert_ne!(DENIED, 1);
+    assert_ne!(DENIED, 101);
+}
+
+#[test]
+fn test_deny_manifest_path() {
+    // `spl-token` matches an advisory of severity error.
+    let (code, stderr) = manifest_exit_code("vulnerable_deps", &["--analyzer-deny=error"]);
+    assert_eq!(code, Some(DENIED), "{stderr}");
+    assert!(
+        stderr
+            .contains("`vulnerable_deps` has findings at or above the `--analyzer-deny` severity"),
+        "{stderr}"
+    );
+    // The crate still compiled: cargo did not fail.
+    assert!(!stderr.contains("could not compile"), "{stderr}");
+
+    let (code, stderr) = manifest_exit_code("vulnerable_deps", &[]);
+    assert_eq!(code, Some(0), "{stderr}");
+    let (code, stderr) = manifest_exit_code("cfg_dump", &["--analyzer-deny=warning"]);
+    assert_eq!(code, Some(0), "{stderr}");
+}
+
+#[test]
+fn test_compile_error_is_not_denied() {
+    // rustc rejects a second edition: the build fails before any analysis.
+    let (code, stderr) = manifest_exit_code(
+        "vulnerable_deps",
+        &["--analyzer-deny=error", "--edition=1999"],
+    );
+    assert_eq!(code, Some(1), "{stderr}");
+}
+
+#[test]
+fn test_deny_cargo_subcommand() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-solana-analyze"))
+        .arg("solana-analyze")
+        .arg("--manifest-path")
+        .arg(fixture("Cargo.toml"))
+        .args(["-p", "vulnerable_deps", "--offline", "--deny", "error"])
+        .env_remove("SOLANA_ANALYZER_TARGET")
+        .output()
+        .expect("failed to run cargo-solana-analyze");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(DENIED), "{stdout}{stderr}");
+    // The report is printed all the same.
+    assert!(stdout.contains("vulnerable_deps"), "{stdout}");
+}
@@ -0,0 +1,12 @@
+mod common;
+
+/// Extracted data and findings come out in the same order on every run, even
+/// though the compiler hands out items and trait impls in no fixed order.
+#[test]
+fn test_output_is_deterministic() {
+    for fixture in ["stake", "duplicate_mutable"] {
+        let first = common::without_elapsed(&common::analyze(fixture));
+        let second = common::without_elapsed(&common::analyze(fixture));
+        assert_eq!(first, second, "output of {fixture} changed between runs");
+    }
+}
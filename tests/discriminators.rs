@@ -0,0 +1,26 @@
+mod common;
+
+#[test]
+fn test_discriminator_collision() {
+    let output = common::analyze("discriminators");
+    let accounts: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("account "))
+        .collect();
+    // `sha256("account:<Name>")[..8]` unless overridden.
+    assert!(accounts.contains(&"account Vault discriminator: d308e82b02987577"));
+    assert!(accounts.contains(&"account Config discriminator: 9b0caae01efacc82"));
+    assert!(accounts.contains(&"account Legacy discriminator: 01"));
+    assert!(accounts.contains(&"account LegacyV2 discriminator: 0102"));
+
+    let collisions: Vec<&str> = output
+        .lines()
+        .filter(|line| line.contains("colliding discriminators"))
+        .collect();
+    assert_eq!(
+        collisions,
+        [
+            "Find error: account types `Legacy` (01) and `LegacyV2` (0102) have colliding discriminators"
+        ]
+    );
+}
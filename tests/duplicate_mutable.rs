@@ -0,0 +1,90 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::path::Path;
+
+use solana_program_analyzer::aggregate::AnalysisReport;
+
+#[test]
+fn test_field_indices_with_unchecked_accounts() {
+    let output = common::analyze("duplicate_mutable");
+    let errors: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("two mutable accounts of the same type"))
+        .collect();
+    // A leading `UncheckedAccount`, `AccountInfo`, `SystemAccount` or a field
+    // of a type we do not model (`Box<Account<..>>` in `Boxed`) used to shift
+    // the mutability of every later field by one, reporting `target` and
+    // `source` of `CopyBalance`, `Pay` and `Boxed`.
+    assert_eq!(
+        errors,
+        [
+            "Find error: two mutable accounts of the same type in the same Context `Swap`: `left` and `right` (Vault)"
+        ]
+    );
+}
+
+#[test]
+fn test_writable_flags() {
+    let output = common::analyze("duplicate_mutable");
+    let metas: Vec<_> = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("meta Swap."))
+        .collect();
+    // `duplicate_mutable_account` pairs fields by these flags.
+    assert_eq!(
+        metas,
+        ["memo: readonly", "left: writable", "right: writable"]
+    );
+}
+
+#[test]
+fn test_cpi_client_flags() {
+    let output = common::analyze("duplicate_mutable");
+    let metas: Vec<_> = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("cpi meta Pay."))
+        .collect();
+    // The CPI client holds `AccountInfo`s and takes their keys, so the
+    // `SystemAccount` and `AccountInfo` fields are found as well.
+    assert_eq!(
+        metas,
+        [
+            "payer: writable",
+            "memo: writable",
+            "target: writable",
+            "source: readonly",
+        ]
+    );
+}
+
+#[test]
+fn test_location_and_fingerprint() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("duplicate_mutable.json");
+    let path_arg = path.to_string_lossy().into_owned();
+    common::analyze_with("duplicate_mutable", &["--report", &path_arg]);
+
+    let report = AnalysisReport::read(&path).unwrap();
+    let swap = report
+        .findings
+        .iter()
+        .find(|finding| finding.message.contains("`Swap`"))
+        .unwrap();
+    // Attributed to the `Swap` struct, and hashed from it and the two fields.
+    assert!(
+        swap.location
+            .as_ref()
+            .is_some_and(|location| location.file.ends_with("lib.rs")),
+        "{:?}",
+        swap.location
+    );
+    assert_eq!(swap.fingerprint.len(), 16);
+    // `memo` and `payer` of `Pay` are told apart by their field names.
+    let fingerprints: std::collections::HashSet<_> = report
+        .findings
+        .iter()
+        .map(|finding| finding.fingerprint.as_str())
+        .collect();
+    assert_eq!(fingerprints.len(), report.findings.len());
+}
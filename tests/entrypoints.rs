@@ -0,0 +1,34 @@
+mod common;
+
+#[test]
+fn test_anchor_entrypoint() {
+    let output = common::analyze("logging");
+    let entrypoints: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("entrypoint "))
+        .collect();
+    assert_eq!(entrypoints, ["entrypoint anchor: entry"]);
+}
+
+#[test]
+fn test_native_entrypoint() {
+    let output = common::analyze("native_entry");
+    let entrypoints: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("entrypoint "))
+        .collect();
+    assert_eq!(entrypoints, ["entrypoint native: process_instruction"]);
+    // The fns reached from `process_instruction` are analyzed like those of
+    // an Anchor program.
+    let findings: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("log in ") || line.starts_with("Find "))
+        .collect();
+    assert_eq!(
+        findings,
+        [
+            r#"log in process_instruction: "instruction of {} bytes" (usize)"#,
+            "Find warning: loop at bb9 in `process_instruction` calls `hash::hash` (bb14) each iteration, ~85 CU per iteration",
+        ]
+    );
+}
@@ -0,0 +1,22 @@
+mod common;
+
+#[test]
+fn test_error_codes() {
+    let output = common::analyze("error_codes");
+    let errors: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("error "))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            "error 6000 VaultError::ZeroAmount: Amount must be positive",
+            "error 6001 VaultError::Locked: Vault is locked",
+            // Without `#[msg(..)]` Anchor reports the variant name.
+            "error 6002 VaultError::NoMessage: NoMessage",
+            // `#[error_code(offset = 9000)]`
+            "error 9000 AdminError::NotAdmin: Signer is not the admin",
+            "error 9001 AdminError::RotationPending: Admin key rotation is pending",
+        ]
+    );
+}
@@ -0,0 +1,29 @@
+mod common;
+
+#[test]
+fn test_events() {
+    let output = common::analyze("events");
+    let events: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("event "))
+        .collect();
+    // `sha256("event:<Name>")[..8]`, then the Borsh-encoded fields.
+    for line in [
+        "event Deposited discriminator: 6f8d1a2da1236439, 49 bytes",
+        "event Deposited.owner: anchor_lang::prelude::Pubkey",
+        "event Deposited.amount: u64",
+        "event Deposited.bump: u8",
+        // A unit enum is its `u8` variant index.
+        "event Settled discriminator: e8d228118e7c91ee, 41 bytes",
+        "event Settled.amounts: [u64; 4]",
+        "event Renamed discriminator: 94e820b3e709e867, at least 49 bytes (dynamic)",
+        "event Renamed.name: std::string::String (dynamic)",
+        "event Renamed.tags: std::vec::Vec<u8> (dynamic)",
+        "event Renamed.note: std::option::Option<u64> (dynamic)",
+    ] {
+        assert!(events.contains(&line), "missing `{line}` in {events:#?}");
+    }
+
+    // Events carry a discriminator but are not account types.
+    assert!(!output.contains("account Deposited"));
+}
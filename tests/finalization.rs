@@ -0,0 +1,18 @@
+mod common;
+
+#[test]
+fn test_skipped_finalization() {
+    let output = common::analyze("finalization");
+    let warnings: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| line.contains("can return without calling"))
+        .collect();
+    // `deposit` ends with the check and `settle` calls it on both branches.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: `finalization::rebalance` can return without calling `check_invariants`, branching away from it at lib.rs:34",
+            "Find warning: `finalization::withdraw` can return without calling `check_invariants`, branching away from it at lib.rs:22",
+        ]
+    );
+}
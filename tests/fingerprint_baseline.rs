@@ -0,0 +1,134 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+use solana_program_analyzer::aggregate::AnalysisReport;
+
+fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn test_new_finding_after_baseline() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fingerprint_baseline.json");
+    fs::remove_file(&path).ok();
+    let baseline_arg = format!("--analyzer-baseline={}", path.display());
+
+    let output = common::analyze_with(
+        "baseline_fingerprints",
+        &[&baseline_arg, "--analyzer-update-baseline"],
+    );
+    assert!(
+        output.contains("baseline: wrote 2 fingerprints"),
+        "{output}"
+    );
+    let baseline = read_json(&path);
+    let entries = baseline.as_array().unwrap();
+    assert_eq!(entries.len(), 2, "{baseline:#}");
+    assert!(entries[0]["fingerprint"].as_str() < entries[1]["fingerprint"].as_str());
+    assert!(
+        entries
+            .iter()
+            .all(|entry| entry["checker"] == "unchecked_sysvar_account")
+    );
+
+    // Everything is in the baseline: the findings are printed, not denied.
+    let (success, output) = common::try_analyze(
+        "baseline_fingerprints",
+        &[&baseline_arg, "--analyzer-deny=warning"],
+    );
+    assert!(success, "{output}");
+    assert_eq!(
+        output.matches("Find warning (baseline): ").count(),
+        2,
+        "{output}"
+    );
+
+    // A finding missing from the baseline, as one introduced after it, is.
+    let mut baseline = baseline;
+    baseline.as_array_mut().unwrap().remove(0);
+    fs::write(&path, baseline.to_string()).unwrap();
+    let (success, output) = common::try_analyze(
+        "baseline_fingerprints",
+        &[&baseline_arg, "--analyzer-deny=warning"],
+    );
+    assert!(!success, "{output}");
+    assert_eq!(
+        output.matches("Find warning (baseline): ").count(),
+        1,
+        "{output}"
+    );
+    assert_eq!(output.matches("Find warning: ").count(), 1, "{output}");
+    assert!(
+        output.contains("has findings at or above the `--analyzer-deny` severity"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_fingerprints_survive_whitespace_edits() {
+    let tmp = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let mut runs = vec![];
+    for (name, cfg) in [("original", None), ("reformatted", Some("reformatted"))] {
+        let baseline = tmp.join(format!("fingerprint_baseline_{name}.json"));
+        let report = tmp.join(format!("fingerprint_report_{name}.json"));
+        fs::remove_file(&baseline).ok();
+        let baseline_arg = format!("--analyzer-baseline={}", baseline.display());
+        let report_arg = report.to_string_lossy().into_owned();
+        let mut args = vec![
+            baseline_arg.as_str(),
+            "--analyzer-update-baseline",
+            "--report",
+            &report_arg,
+        ];
+        args.extend(cfg.into_iter().flat_map(|cfg| ["--cfg", cfg]));
+        common::analyze_with("baseline_fingerprints", &args);
+        runs.push((
+            fs::read_to_string(&baseline).unwrap(),
+            AnalysisReport::read(&report).unwrap(),
+        ));
+    }
+
+    let locations = |report: &AnalysisReport| -> Vec<_> {
+        report
+            .findings
+            .iter()
+            .map(|finding| finding.location.clone())
+            .collect()
+    };
+    // The findings moved to other lines of another file, but kept their
+    // fingerprints.
+    assert_eq!(runs[0].1.findings.len(), 2);
+    assert_ne!(locations(&runs[0].1), locations(&runs[1].1));
+    assert_eq!(runs[0].0, runs[1].0);
+
+    // `--analyzer-diff` matches findings by fingerprint too, so the moved
+    // findings are neither new nor resolved.
+    let diff_arg = format!(
+        "--analyzer-diff={}",
+        tmp.join("fingerprint_report_original.json").display()
+    );
+    let output = common::analyze_with(
+        "baseline_fingerprints",
+        &[&diff_arg, "--cfg", "reformatted"],
+    );
+    assert!(
+        output.contains("baseline: 0 new, 2 unchanged, 0 resolved"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_unreadable_baseline() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fingerprint_baseline_broken.json");
+    fs::write(&path, "not json").unwrap();
+    let baseline_arg = format!("--analyzer-baseline={}", path.display());
+    let (success, output) = common::try_analyze("baseline_fingerprints", &[&baseline_arg]);
+    assert!(!success, "{output}");
+    assert!(output.contains("error: failed to parse"), "{output}");
+    assert!(!output.contains("Find warning"), "{output}");
+}
@@ -0,0 +1,103 @@
+//! Handlers guarded with `#[access_control]`: the guards pin the `Config`, given
+//! `ctx` or its key, and the price feed. Only `set_fee_unpinned` leaves `config`
+//! unpinned; its `is_positive` guard checks no account.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const CONFIG_ACCOUNT: Pubkey = pubkey!("7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE");
+pub const PRICE_ACCOUNT: Pubkey = pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+
+#[program]
+pub mod access_control {
+    use super::*;
+
+    #[access_control(is_config(&ctx) is_admin(&ctx))]
+    pub fn set_fee(ctx: Context<SetFee>, fee: u64) -> Result<()> {
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    pub fn set_fee_by_key(ctx: Context<SetFeeByKey>, fee: u64) -> Result<()> {
+        // `access_control` cannot take nested calls; a `?` check written first
+        // in the body is a guard all the same.
+        is_config_key(ctx.accounts.config.key())?;
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    #[access_control(is_positive(fee))]
+    pub fn set_fee_unpinned(ctx: Context<SetFeeUnpinned>, fee: u64) -> Result<()> {
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.check_price())]
+    pub fn read_price(ctx: Context<ReadPrice>) -> Result<()> {
+        msg!("{}", ctx.accounts.price.price);
+        Ok(())
+    }
+}
+
+fn is_config(ctx: &Context<SetFee>) -> Result<()> {
+    require_keys_eq!(ctx.accounts.config.key(), CONFIG_ACCOUNT);
+    Ok(())
+}
+
+fn is_config_key(config: Pubkey) -> Result<()> {
+    require_keys_eq!(config, CONFIG_ACCOUNT);
+    Ok(())
+}
+
+fn is_admin(ctx: &Context<SetFee>) -> Result<()> {
+    require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.config.admin);
+    Ok(())
+}
+
+fn is_positive(fee: u64) -> Result<()> {
+    require_gt!(fee, 0);
+    Ok(())
+}
+
+impl ReadPrice<'_> {
+    fn check_price(&self) -> Result<()> {
+        require_keys_eq!(self.price.key(), PRICE_ACCOUNT);
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee: u64,
+}
+
+#[account]
+pub struct PriceUpdateV2 {
+    pub price: i64,
+}
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeByKey<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeUnpinned<'info> {
+    #[account(mut, has_one = admin)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReadPrice<'info> {
+    pub price: Account<'info, PriceUpdateV2>,
+}
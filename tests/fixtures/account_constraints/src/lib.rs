@@ -0,0 +1,46 @@
+//! One field per kind of key check: `has_one`, `address`, `owner`, and raw
+//! `constraint`s with and without a custom error.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const ADMIN: Pubkey = pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+
+#[program]
+pub mod account_constraints {
+    use super::*;
+
+    pub fn update(ctx: Context<Update>, limit: u64) -> Result<()> {
+        ctx.accounts.vault.limit = limit;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut, has_one = authority, constraint = vault.limit < 100 @ VaultError::LimitTooHigh)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+    #[account(address = ADMIN)]
+    pub admin: Account<'info, Config>,
+    #[account(owner = crate::ID)]
+    pub record: Account<'info, Config>,
+    #[account(constraint = config.enabled)]
+    pub config: Account<'info, Config>,
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub limit: u64,
+}
+
+#[account]
+pub struct Config {
+    pub enabled: bool,
+}
+
+#[error_code]
+pub enum VaultError {
+    LimitTooHigh,
+}
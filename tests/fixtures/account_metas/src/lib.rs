@@ -0,0 +1,33 @@
+//! An Accounts struct whose client metas carry every combination of the
+//! writable and signer flags.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod account_metas {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance += amount;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub recipient: SystemAccount<'info>,
+    /// CHECK: only its signature is required.
+    #[account(signer)]
+    pub delegate: UncheckedAccount<'info>,
+}
@@ -0,0 +1,6 @@
+//! Stands for code generated by a tool, excluded from the findings.
+
+pub fn scratch(byte: u8) -> u64 {
+    let scratch = [byte; 3000];
+    scratch.iter().map(|byte| *byte as u64).sum()
+}
@@ -0,0 +1,56 @@
+//! Checked with the `solana-analyzer.toml` next to it: `duplicate_mutable_account`
+//! is off, so `Swap` is not reported; frames over 2 KiB are, so `fill` is but
+//! not `generated::scratch`, whose file is excluded; `digest_all` hashes in a
+//! loop for less than the `min_loop_cu` reported.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+
+mod generated;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod analyzer_config {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>) -> Result<()> {
+        let accounts = ctx.accounts;
+        std::mem::swap(&mut accounts.left.balance, &mut accounts.right.balance);
+        Ok(())
+    }
+
+    pub fn fill(ctx: Context<Fill>, byte: u8) -> Result<()> {
+        let buffer = [byte; 2048];
+        ctx.accounts.vault.balance = buffer.iter().map(|byte| *byte as u64).sum();
+        ctx.accounts.vault.balance += generated::scratch(byte);
+        Ok(())
+    }
+
+    pub fn digest_all(ctx: Context<Fill>, leaves: Vec<[u8; 32]>) -> Result<()> {
+        for leaf in leaves.iter() {
+            ctx.accounts.vault.root = hash::hash(leaf).to_bytes();
+        }
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+    pub root: [u8; 32],
+}
+
+/// `left` and `right` can be the same vault.
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(mut)]
+    pub left: Account<'info, Vault>,
+    #[account(mut)]
+    pub right: Account<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct Fill<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+}
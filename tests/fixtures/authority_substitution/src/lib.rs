@@ -0,0 +1,66 @@
+//! `withdraw` checks the signer against the authority of `source` and then
+//! debits `target`, another `Vault` anyone can pass. `withdraw_checked` debits
+//! the vault it checked, and `sweep` has Anchor check `target` with `has_one`.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod authority_substitution {
+    use super::*;
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require_keys_eq!(ctx.accounts.source.authority, ctx.accounts.owner.key());
+        ctx.accounts.target.balance -= amount;
+        Ok(())
+    }
+
+    pub fn withdraw_checked(ctx: Context<WithdrawChecked>, amount: u64) -> Result<()> {
+        if ctx.accounts.source.authority != ctx.accounts.owner.key() {
+            return err!(VaultError::Unauthorized);
+        }
+        ctx.accounts.source.balance -= amount;
+        Ok(())
+    }
+
+    pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.source.authority, ctx.accounts.owner.key());
+        ctx.accounts.target.balance = 0;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub source: Account<'info, Vault>,
+    #[account(mut)]
+    pub target: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawChecked<'info> {
+    #[account(mut)]
+    pub source: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    pub source: Account<'info, Vault>,
+    #[account(mut, has_one = authority)]
+    pub target: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+#[error_code]
+pub enum VaultError {
+    Unauthorized,
+}
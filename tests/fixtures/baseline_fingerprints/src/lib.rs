@@ -0,0 +1,7 @@
+//! The program of `sysvar_accounts`, in `program.rs`, and the same program
+//! with only whitespace changed, in `reformatted.rs`, which `--cfg
+//! reformatted` compiles instead: its findings move, their fingerprints stay.
+#[cfg(not(reformatted))]
+include!("program.rs");
+#[cfg(reformatted)]
+include!("reformatted.rs");
@@ -0,0 +1,66 @@
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::sysvar::{self, Sysvar};
+
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.first() {
+        Some(0) => stamp(accounts),
+        Some(1) => stamp_checked(accounts),
+        Some(2) => charge(accounts),
+        Some(3) => stamp_late(accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn stamp(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let clock_info = next_account_info(accounts_iter)?;
+    let clock = Clock::from_account_info(clock_info)?;
+    msg!("slot {}", clock.slot);
+    Ok(())
+}
+
+fn stamp_checked(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let clock_info = next_account_info(accounts_iter)?;
+    if *clock_info.key != sysvar::clock::ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let clock = Clock::from_account_info(clock_info)?;
+    msg!("slot {}", clock.slot);
+    Ok(())
+}
+
+fn charge(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let rent_info = next_account_info(accounts_iter)?;
+    if !sysvar::rent::check_id(rent_info.key) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let rent = Rent::from_account_info(rent_info)?;
+    msg!("{} needs {}", payer.key, rent.minimum_balance(payer.data_len()));
+    Ok(())
+}
+
+fn stamp_late(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let clock_info = next_account_info(accounts_iter)?;
+    let clock = Clock::from_account_info(clock_info)?;
+    if *clock_info.key != sysvar::clock::ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    msg!("slot {}", clock.slot);
+    Ok(())
+}
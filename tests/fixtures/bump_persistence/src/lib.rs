@@ -0,0 +1,79 @@
+//! `deposit` and `withdraw` both derive the vault PDA with a plain `bump`,
+//! and nothing stores it; the profile PDA has its bump stored at `init` and
+//! passed back with `bump = profile.bump`.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod bump_persistence {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance += amount;
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance -= amount;
+        Ok(())
+    }
+
+    pub fn create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+        ctx.accounts.profile.bump = ctx.bumps.profile;
+        Ok(())
+    }
+
+    pub fn update_profile(ctx: Context<UpdateProfile>, score: u64) -> Result<()> {
+        ctx.accounts.profile.score = score;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault", owner.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"vault", owner.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProfile<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Profile::INIT_SPACE,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProfile<'info> {
+    #[account(mut, seeds = [b"profile", owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, Profile>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Profile {
+    pub score: u64,
+    pub bump: u8,
+}
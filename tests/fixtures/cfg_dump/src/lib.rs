@@ -0,0 +1,10 @@
+//! Small fns whose CFGs `--dump-cfg` prints.
+
+/// A call, then the return: two blocks.
+pub fn double(x: u64) -> u64 {
+    x.wrapping_mul(2)
+}
+
+pub fn pick(flag: bool, a: u64, b: u64) -> u64 {
+    if flag { double(a) } else { b }
+}
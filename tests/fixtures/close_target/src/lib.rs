@@ -0,0 +1,62 @@
+//! `Burn` closes `vault` into itself, `Lock` into a readonly account and
+//! `Stuck` into the System program. Only `Refund` closes it where its lamports
+//! can go.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod close_target {
+    use super::*;
+
+    pub fn burn(_ctx: Context<Burn>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn lock(_ctx: Context<Lock>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn stuck(_ctx: Context<Stuck>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn refund(_ctx: Context<Refund>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Burn<'info> {
+    #[account(mut, close = vault, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Lock<'info> {
+    #[account(mut, close = authority, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Stuck<'info> {
+    #[account(mut, close = system_program, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut, close = authority, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+}
@@ -0,0 +1,72 @@
+//! Token accounts behind coercions: `&Account<T>` and `UncheckedAccount` turned
+//! into trait objects, and `AccountInfo`s moved into a `Box`. The owner checks
+//! made through them still count.
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod coercion {
+    use super::*;
+
+    pub fn read_boxed(ctx: Context<ReadRaw>) -> Result<()> {
+        let info: Box<AccountInfo> = Box::new(ctx.accounts.source.to_account_info());
+        let data = info.try_borrow_data()?;
+        let source = TokenAccount::try_deserialize(&mut &data[..])?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    pub fn read_boxed_checked(ctx: Context<ReadRaw>) -> Result<()> {
+        let info: Box<AccountInfo> = Box::new(ctx.accounts.source.to_account_info());
+        require_keys_eq!(*info.owner, token::ID);
+        let data = info.try_borrow_data()?;
+        let source = TokenAccount::try_deserialize(&mut &data[..])?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    pub fn read_dyn(ctx: Context<ReadRaw>) -> Result<()> {
+        let source: &dyn ToAccountInfo = &ctx.accounts.source;
+        let info = source.to_account_info();
+        let data = info.try_borrow_data()?;
+        let source = TokenAccount::try_deserialize(&mut &data[..])?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    pub fn read_dyn_checked(ctx: Context<ReadRaw>) -> Result<()> {
+        let source: &dyn AsRef<AccountInfo> = &ctx.accounts.source;
+        require_keys_eq!(*source.as_ref().owner, token::ID);
+        let data = ctx.accounts.source.try_borrow_data()?;
+        let source = TokenAccount::try_deserialize(&mut &data[..])?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    pub fn copy_typed(ctx: Context<CopyTyped>) -> Result<()> {
+        let source: &dyn AsRef<AccountInfo> = &ctx.accounts.source;
+        overwrite(source.as_ref(), &ctx.accounts.target)
+    }
+}
+
+/// Copies the data of a token account into an account of ours.
+fn overwrite(source: &AccountInfo, target: &dyn ToAccountInfo) -> Result<()> {
+    let target = target.to_account_info();
+    target.try_borrow_mut_data()?.copy_from_slice(&source.try_borrow_data()?);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReadRaw<'info> {
+    /// CHECK: deserialized in the handler
+    pub source: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CopyTyped<'info> {
+    pub source: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub target: Account<'info, TokenAccount>,
+}
@@ -0,0 +1,68 @@
+//! `settle` requires `escrow`, a mutable `Escrow` account, to sign, and
+//! `sweep` pins `treasury`, another mutable `Escrow`, to the key of its
+//! `authority` signer. `open` creates `escrow` from a keypair, which signs the
+//! transaction creating it, and `pay` takes a mutable `payer` signer; both are
+//! fine.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod conflicting_roles {
+    use super::*;
+
+    pub fn open(ctx: Context<Open>, amount: u64) -> Result<()> {
+        ctx.accounts.escrow.amount = amount;
+        Ok(())
+    }
+
+    pub fn settle(ctx: Context<Settle>) -> Result<()> {
+        ctx.accounts.escrow.amount = 0;
+        Ok(())
+    }
+
+    pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+        ctx.accounts.treasury.amount = 0;
+        Ok(())
+    }
+
+    pub fn pay(ctx: Context<Pay>, amount: u64) -> Result<()> {
+        ctx.accounts.escrow.amount += amount;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Open<'info> {
+    #[account(init, payer = payer, space = 8 + 8)]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Settle<'info> {
+    #[account(mut, signer)]
+    pub escrow: Account<'info, Escrow>,
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut, address = authority.key())]
+    pub treasury: Account<'info, Escrow>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Pay<'info> {
+    #[account(mut)]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+#[account]
+pub struct Escrow {
+    pub amount: u64,
+}
@@ -0,0 +1,25 @@
+//! The program `cpi_caller` calls into.
+use anchor_lang::prelude::*;
+
+declare_id!("Vau1t11111111111111111111111111111111111111");
+
+#[program]
+pub mod cpi_callee {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance += amount;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+}
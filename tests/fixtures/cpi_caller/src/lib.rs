@@ -0,0 +1,26 @@
+//! Forwards deposits to `cpi_callee` through its generated CPI helpers.
+use anchor_lang::prelude::*;
+use cpi_callee::program::CpiCallee;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod cpi_caller {
+    use super::*;
+
+    pub fn forward(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = cpi_callee::cpi::accounts::Deposit {
+            vault: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.callee_program.to_account_info(), accounts);
+        cpi_callee::cpi::deposit(cpi_ctx, amount)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Forward<'info> {
+    /// CHECK: checked by `cpi_callee`.
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    pub callee_program: Program<'info, CpiCallee>,
+}
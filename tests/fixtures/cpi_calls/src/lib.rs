@@ -0,0 +1,119 @@
+//! CPIs through Anchor's `CpiContext` helpers and through `invoke`, with the
+//! program taken from a `Program` field, a constant or an unchecked account.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod cpi_calls {
+    use super::*;
+
+    pub fn pay(ctx: Context<Pay>, amount: u64) -> Result<()> {
+        let accounts = system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.receiver.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), accounts);
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    pub fn pay_invoke(ctx: Context<Pay>, amount: u64) -> Result<()> {
+        invoke(
+            &system_instruction::transfer(
+                ctx.accounts.payer.key,
+                ctx.accounts.receiver.key,
+                amount,
+            ),
+            &[
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.receiver.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn move_tokens(ctx: Context<MoveTokens>, amount: u64) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"authority"];
+        let signer = &[seeds];
+        transfer_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.from,
+            &ctx.accounts.to,
+            &ctx.accounts.authority,
+            signer,
+            amount,
+        )
+    }
+
+    pub fn ping(ctx: Context<Ping>) -> Result<()> {
+        let ix = Instruction {
+            program_id: system_program::ID,
+            accounts: vec![],
+            data: vec![],
+        };
+        invoke_signed(&ix, &[ctx.accounts.target.to_account_info()], &[])?;
+        Ok(())
+    }
+
+    pub fn call_any(ctx: Context<Ping>, data: Vec<u8>) -> Result<()> {
+        let ix = Instruction {
+            program_id: ctx.accounts.target.key(),
+            accounts: vec![],
+            data,
+        };
+        invoke(&ix, &[ctx.accounts.target.to_account_info()])?;
+        Ok(())
+    }
+}
+
+fn transfer_tokens<'info>(
+    token_program: &Program<'info, Token>,
+    from: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    authority: &UncheckedAccount<'info>,
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let accounts = token::Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+        authority: authority.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), accounts, signer),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct Pay<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub receiver: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MoveTokens<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    /// CHECK: the PDA signing for `from`.
+    #[account(seeds = [b"authority"], bump)]
+    pub authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Ping<'info> {
+    /// CHECK: the program called.
+    pub target: UncheckedAccount<'info>,
+}
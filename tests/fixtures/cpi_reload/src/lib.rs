@@ -0,0 +1,77 @@
+//! `pool` is read before and after an `invoke` it takes part in. Only `deposit`
+//! reads it again without `reload()`; `touch` invokes without `pool`.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod cpi_reload {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let before = ctx.accounts.pool.amount;
+        invoke(
+            &credit(&ctx.accounts.target, &ctx.accounts.pool.key(), amount),
+            &[
+                ctx.accounts.pool.to_account_info(),
+                ctx.accounts.target.to_account_info(),
+            ],
+        )?;
+        let after = ctx.accounts.pool.amount;
+        require!(after >= before, PoolError::Shrunk);
+        Ok(())
+    }
+
+    pub fn deposit_reloaded(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let before = ctx.accounts.pool.amount;
+        invoke(
+            &credit(&ctx.accounts.target, &ctx.accounts.pool.key(), amount),
+            &[
+                ctx.accounts.pool.to_account_info(),
+                ctx.accounts.target.to_account_info(),
+            ],
+        )?;
+        ctx.accounts.pool.reload()?;
+        let after = ctx.accounts.pool.amount;
+        require!(after >= before, PoolError::Shrunk);
+        Ok(())
+    }
+
+    pub fn touch(ctx: Context<Deposit>) -> Result<()> {
+        let before = ctx.accounts.pool.amount;
+        invoke(
+            &credit(&ctx.accounts.target, &ctx.accounts.target.key(), 0),
+            &[ctx.accounts.target.to_account_info()],
+        )?;
+        require!(ctx.accounts.pool.amount == before, PoolError::Shrunk);
+        Ok(())
+    }
+}
+
+fn credit(program: &UncheckedAccount, account: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: program.key(),
+        accounts: vec![AccountMeta::new(*account, false)],
+        data: amount.to_le_bytes().to_vec(),
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    /// CHECK: the program credited by the CPI.
+    pub target: UncheckedAccount<'info>,
+}
+
+#[account]
+pub struct Pool {
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum PoolError {
+    Shrunk,
+}
@@ -0,0 +1,125 @@
+//! Each handler builds the `Instruction` of its CPI by hand. `withdraw` signs
+//! for the vault with seeds but marks no account as signer, so the signature
+//! is dropped, and `delegate` marks the authority as signer without seeds and
+//! without checking that it signed. `sweep` marks the vault it signs for,
+//! `approve` checks the authority first, and `forward` passes on the signer
+//! flag of the account.
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::{invoke, invoke_signed};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (&tag, rest) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match tag {
+        0 => withdraw(accounts, rest),
+        1 => sweep(accounts, rest),
+        2 => delegate(accounts, rest),
+        3 => approve(accounts, rest),
+        4 => forward(accounts, rest),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn withdraw(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+    let target = next_account_info(accounts_iter)?;
+    let instruction = Instruction {
+        program_id: *target.key,
+        accounts: vec![
+            AccountMeta::new(*vault.key, false),
+            AccountMeta::new(*recipient.key, false),
+        ],
+        data: data.to_vec(),
+    };
+    invoke_signed(
+        &instruction,
+        &[vault.clone(), recipient.clone(), target.clone()],
+        &[&[b"vault", &[data[0]]]],
+    )
+}
+
+fn sweep(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+    let target = next_account_info(accounts_iter)?;
+    let instruction = Instruction {
+        program_id: *target.key,
+        accounts: vec![
+            AccountMeta::new(*vault.key, true),
+            AccountMeta::new(*recipient.key, false),
+        ],
+        data: data.to_vec(),
+    };
+    invoke_signed(
+        &instruction,
+        &[vault.clone(), recipient.clone(), target.clone()],
+        &[&[b"vault", &[data[0]]]],
+    )
+}
+
+fn delegate(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let stake = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let target = next_account_info(accounts_iter)?;
+    let instruction = Instruction::new_with_bytes(
+        *target.key,
+        data,
+        vec![
+            AccountMeta::new(*stake.key, false),
+            AccountMeta::new_readonly(*authority.key, true),
+        ],
+    );
+    invoke(
+        &instruction,
+        &[stake.clone(), authority.clone(), target.clone()],
+    )
+}
+
+fn approve(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let stake = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let target = next_account_info(accounts_iter)?;
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let instruction = Instruction::new_with_bytes(
+        *target.key,
+        data,
+        vec![
+            AccountMeta::new(*stake.key, false),
+            AccountMeta::new_readonly(*authority.key, true),
+        ],
+    );
+    invoke(
+        &instruction,
+        &[stake.clone(), authority.clone(), target.clone()],
+    )
+}
+
+fn forward(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let target = next_account_info(accounts_iter)?;
+    let instruction = Instruction::new_with_bytes(
+        *target.key,
+        data,
+        vec![AccountMeta::new(*user.key, user.is_signer)],
+    );
+    invoke(&instruction, &[user.clone(), target.clone()])
+}
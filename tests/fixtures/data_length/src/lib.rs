@@ -0,0 +1,64 @@
+//! `read_tag` slices the first 8 bytes of the account data and `read_state`
+//! unwraps its Borsh decoding, neither checking the length first.
+//! `read_tag_checked` compares the length before slicing and `read_state_or_err`
+//! returns the decoding error, which are fine.
+use borsh::BorshDeserialize;
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+#[derive(BorshDeserialize)]
+pub struct State {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let account = next_account_info(&mut accounts.iter())?;
+    match data.first() {
+        Some(0) => read_tag(account),
+        Some(1) => read_tag_checked(account),
+        Some(2) => read_state(account),
+        _ => read_state_or_err(account),
+    }
+}
+
+fn read_tag(account: &AccountInfo) -> ProgramResult {
+    let data = account.data.borrow();
+    let tag = &data[0..8];
+    msg!("tag {:?}", tag);
+    Ok(())
+}
+
+fn read_tag_checked(account: &AccountInfo) -> ProgramResult {
+    let data = account.data.borrow();
+    if data.len() < 8 {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    let tag = &data[0..8];
+    msg!("tag {:?}", tag);
+    Ok(())
+}
+
+fn read_state(account: &AccountInfo) -> ProgramResult {
+    let state = State::try_from_slice(&account.data.borrow()).unwrap();
+    msg!("balance {}", state.balance);
+    Ok(())
+}
+
+fn read_state_or_err(account: &AccountInfo) -> ProgramResult {
+    let state = State::try_from_slice(&account.data.borrow())?;
+    msg!("balance {}", state.balance);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+//! `Vault` and `Config` keep their default discriminators. `Legacy` overrides
+//! its discriminator with a prefix of `LegacyV2`'s.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod discriminators {
+    use super::*;
+
+    pub fn touch(ctx: Context<Touch>) -> Result<()> {
+        msg!("{} {}", ctx.accounts.vault.amount, ctx.accounts.config.fee);
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub amount: u64,
+}
+
+#[account]
+pub struct Config {
+    pub fee: u64,
+}
+
+#[account(discriminator = 1)]
+pub struct Legacy {
+    pub version: u8,
+}
+
+#[account(discriminator = [1, 2])]
+pub struct LegacyV2 {
+    pub version: u8,
+}
+
+#[derive(Accounts)]
+pub struct Touch<'info> {
+    pub vault: Account<'info, Vault>,
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,83 @@
+//! Mutable accounts of the same type next to accounts Anchor does not
+//! deserialize or we do not model, which must not shift the field indices of
+//! the others.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod duplicate_mutable {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>) -> Result<()> {
+        let accounts = ctx.accounts;
+        std::mem::swap(&mut accounts.left.balance, &mut accounts.right.balance);
+        Ok(())
+    }
+
+    pub fn copy(ctx: Context<CopyBalance>) -> Result<()> {
+        ctx.accounts.target.balance = ctx.accounts.source.balance;
+        Ok(())
+    }
+
+    pub fn pay(ctx: Context<Pay>) -> Result<()> {
+        ctx.accounts.target.balance = ctx.accounts.source.balance;
+        Ok(())
+    }
+
+    pub fn boxed(ctx: Context<Boxed>) -> Result<()> {
+        ctx.accounts.config.balance = 0;
+        ctx.accounts.target.balance = ctx.accounts.source.balance;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+/// `left` and `right` can be the same vault.
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    /// CHECK: only logged
+    pub memo: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub left: Account<'info, Vault>,
+    #[account(mut)]
+    pub right: Account<'info, Vault>,
+}
+
+/// Only `target` is mutable.
+#[derive(Accounts)]
+pub struct CopyBalance<'info> {
+    /// CHECK: only logged
+    #[account(mut)]
+    pub memo: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub target: Account<'info, Vault>,
+    pub source: Account<'info, Vault>,
+}
+
+/// Only `target` is mutable.
+#[derive(Accounts)]
+pub struct Pay<'info> {
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+    /// CHECK: only logged
+    #[account(mut)]
+    pub memo: AccountInfo<'info>,
+    #[account(mut)]
+    pub target: Account<'info, Vault>,
+    pub source: Account<'info, Vault>,
+}
+
+/// Only `target` is mutable; the boxed account is not modeled.
+#[derive(Accounts)]
+pub struct Boxed<'info> {
+    #[account(mut)]
+    pub config: Box<Account<'info, Vault>>,
+    #[account(mut)]
+    pub target: Account<'info, Vault>,
+    pub source: Account<'info, Vault>,
+}
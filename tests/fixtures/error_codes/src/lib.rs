@@ -0,0 +1,40 @@
+//! A default `#[error_code]` enum, one variant without a message, and a second
+//! enum with a custom offset.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod error_codes {
+    use super::*;
+
+    pub fn withdraw(_ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, VaultError::ZeroAmount);
+        if amount > 100 {
+            return err!(AdminError::NotAdmin);
+        }
+        Ok(())
+    }
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Amount must be positive")]
+    ZeroAmount,
+    #[msg("Vault is locked")]
+    Locked,
+    NoMessage,
+}
+
+#[error_code(offset = 9000)]
+pub enum AdminError {
+    #[msg("Signer is not the admin")]
+    NotAdmin,
+    #[msg("Admin key rotation is pending")]
+    RotationPending,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub authority: Signer<'info>,
+}
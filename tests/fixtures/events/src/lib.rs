@@ -0,0 +1,72 @@
+//! Events with fixed-size fields only, with a unit enum and an array, and with
+//! dynamically sized fields.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod events {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Act>, amount: u64) -> Result<()> {
+        emit!(Deposited {
+            owner: ctx.accounts.owner.key(),
+            amount,
+            bump: 255,
+        });
+        emit!(Settled {
+            amounts: [amount; 4],
+            side: Side::Bid,
+        });
+        Ok(())
+    }
+
+    pub fn rename(ctx: Context<Act>, name: String) -> Result<()> {
+        emit!(Renamed {
+            owner: ctx.accounts.owner.key(),
+            name,
+            tags: vec![],
+            note: None,
+        });
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+#[event]
+pub struct Deposited {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct Settled {
+    pub amounts: [u64; 4],
+    pub side: Side,
+}
+
+#[event]
+pub struct Renamed {
+    pub owner: Pubkey,
+    pub name: String,
+    pub tags: Vec<u8>,
+    pub note: Option<u64>,
+}
+
+#[account]
+pub struct Vault {
+    pub owner: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct Act<'info> {
+    #[account(has_one = owner)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
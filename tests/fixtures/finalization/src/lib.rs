@@ -0,0 +1,74 @@
+//! Handlers that must end with `check_invariants`, listed under
+//! `[finalization]` in `solana-analyzer.toml`. `withdraw` returns early on a
+//! zero amount and `rebalance` leaves with `?` before the check; `deposit`
+//! always checks, and `settle` checks on both of its branches.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod finalization {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.total += amount;
+        pool.reserves += amount;
+        check_invariants(pool)
+    }
+
+    pub fn withdraw(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        if amount == 0 {
+            return Ok(());
+        }
+        pool.total -= amount;
+        pool.reserves -= amount;
+        check_invariants(pool)?;
+        Ok(())
+    }
+
+    pub fn rebalance(ctx: Context<Update>, reserves: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.reserves = reserves;
+        require!(pool.total > 0, PoolError::Empty);
+        check_invariants(pool)
+    }
+
+    pub fn settle(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        if amount > pool.reserves {
+            pool.reserves = 0;
+            check_invariants(pool)
+        } else {
+            pool.reserves -= amount;
+            check_invariants(pool)
+        }
+    }
+}
+
+fn check_invariants(pool: &Pool) -> Result<()> {
+    require!(pool.reserves <= pool.total, PoolError::Inconsistent);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct Pool {
+    pub total: u64,
+    pub reserves: u64,
+}
+
+#[error_code]
+pub enum PoolError {
+    #[msg("reserves exceed the total")]
+    Inconsistent,
+    #[msg("the pool is empty")]
+    Empty,
+}
@@ -0,0 +1,72 @@
+//! Writes to the data of accounts owned by other programs, which the runtime
+//! rejects, next to writes it accepts.
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::TokenAccount;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod foreign_write {
+    use super::*;
+
+    pub fn write_program(ctx: Context<Pay>) -> Result<()> {
+        let mut data = ctx.accounts.system_program.try_borrow_mut_data()?;
+        data[0] = 1;
+        Ok(())
+    }
+
+    pub fn write_token(ctx: Context<Tokens>) -> Result<()> {
+        let info = ctx.accounts.vault.to_account_info();
+        info.data.borrow_mut()[0] = 0;
+        Ok(())
+    }
+
+    pub fn write_own(ctx: Context<Tokens>) -> Result<()> {
+        let info = ctx.accounts.state.to_account_info();
+        info.try_borrow_mut_data()?[8] = 1;
+        Ok(())
+    }
+
+    /// Crediting lamports and a CPI are fine.
+    pub fn pay(ctx: Context<Pay>, amount: u64) -> Result<()> {
+        **ctx.accounts.receiver.try_borrow_mut_lamports()? += 1;
+        let accounts = system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.receiver.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), accounts);
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    pub fn wipe(ctx: Context<Pay>) -> Result<()> {
+        zero(&ctx.accounts.receiver);
+        Ok(())
+    }
+}
+
+fn zero(info: &AccountInfo) {
+    info.data.borrow_mut().fill(0);
+}
+
+#[account]
+pub struct State {
+    pub count: u64,
+}
+
+#[derive(Accounts)]
+pub struct Pay<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub receiver: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Tokens<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub state: Account<'info, State>,
+}
@@ -0,0 +1,51 @@
+//! `claim` and `refund` both check the deadline with `at_deadline`, whose exact
+//! timestamp equality is one finding listed under both handlers; `bump` has its
+//! own and `touch` none.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod grouped_findings {
+    use super::*;
+
+    pub fn claim(ctx: Context<Touch>) -> Result<()> {
+        require!(at_deadline(&ctx.accounts.auction)?, AuctionError::Closed);
+        Ok(())
+    }
+
+    pub fn refund(ctx: Context<Touch>) -> Result<()> {
+        require!(!at_deadline(&ctx.accounts.auction)?, AuctionError::Closed);
+        Ok(())
+    }
+
+    pub fn bump(_ctx: Context<Touch>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now != 1_700_000_000, AuctionError::Closed);
+        Ok(())
+    }
+
+    pub fn touch(ctx: Context<Touch>) -> Result<()> {
+        msg!("end {}", ctx.accounts.auction.end);
+        Ok(())
+    }
+}
+
+fn at_deadline(auction: &Auction) -> Result<bool> {
+    Ok(Clock::get()?.unix_timestamp == auction.end)
+}
+
+#[account]
+pub struct Auction {
+    pub end: i64,
+}
+
+#[error_code]
+pub enum AuctionError {
+    Closed,
+}
+
+#[derive(Accounts)]
+pub struct Touch<'info> {
+    pub auction: Account<'info, Auction>,
+}
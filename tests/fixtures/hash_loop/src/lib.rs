@@ -0,0 +1,51 @@
+//! `commit_all` hashes inside a `for` loop and `grind` calls keccak inside a
+//! `while` loop; `commit_one` hashes once, which is fine.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash, keccak};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod hash_loop {
+    use super::*;
+
+    pub fn commit_all(ctx: Context<Commit>, leaves: Vec<[u8; 32]>) -> Result<()> {
+        let mut root = [0u8; 32];
+        for leaf in leaves.iter() {
+            root = hash::hashv(&[&root, leaf]).to_bytes();
+        }
+        ctx.accounts.store.root = root;
+        Ok(())
+    }
+
+    pub fn grind(ctx: Context<Commit>, rounds: u32) -> Result<()> {
+        let mut digest = ctx.accounts.store.root;
+        let mut i = 0;
+        while i < rounds {
+            digest = mix(&digest);
+            i += 1;
+        }
+        ctx.accounts.store.root = digest;
+        Ok(())
+    }
+
+    pub fn commit_one(ctx: Context<Commit>, leaf: [u8; 32]) -> Result<()> {
+        ctx.accounts.store.root = hash::hash(&leaf).to_bytes();
+        Ok(())
+    }
+}
+
+fn mix(digest: &[u8; 32]) -> [u8; 32] {
+    keccak::hash(digest).to_bytes()
+}
+
+#[derive(Accounts)]
+pub struct Commit<'info> {
+    #[account(mut)]
+    pub store: Account<'info, Store>,
+}
+
+#[account]
+pub struct Store {
+    pub root: [u8; 32],
+}
@@ -0,0 +1,51 @@
+//! Checks whose outcome is computed and then dropped, next to checks that
+//! branch on it or propagate it with `?`.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+const MAX_AMOUNT: u64 = 1_000_000;
+
+#[program]
+pub mod ignored_check {
+    use super::*;
+
+    pub fn withdraw_unchecked(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let _ = ctx.accounts.authority.key() == ctx.accounts.vault.authority;
+        let _ = validate(amount);
+        ctx.accounts.vault.balance -= amount;
+        Ok(())
+    }
+
+    pub fn withdraw_checked(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.vault.authority);
+        require!(amount <= MAX_AMOUNT, VaultError::TooLarge);
+        validate(amount)?;
+        ctx.accounts.vault.balance -= amount;
+        Ok(())
+    }
+}
+
+fn validate(amount: u64) -> Result<()> {
+    require!(amount > 0, VaultError::Zero);
+    Ok(())
+}
+
+#[error_code]
+pub enum VaultError {
+    Zero,
+    TooLarge,
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
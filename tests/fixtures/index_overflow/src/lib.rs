@@ -0,0 +1,54 @@
+//! Indices computed from instruction arguments with and without overflow
+//! checks.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod index_overflow {
+    use super::*;
+
+    pub fn read_at(_ctx: Context<Read>, data: Vec<u8>, start: u64, len: u64) -> Result<u8> {
+        Ok(data[(start + len) as usize])
+    }
+
+    pub fn read_slot(ctx: Context<Read>, slot: u8) -> Result<u64> {
+        let index = slot as usize - 1;
+        Ok(ctx.accounts.book.slots[index])
+    }
+
+    pub fn read_copied(ctx: Context<Read>, slot: u8) -> Result<u64> {
+        let index = slot as usize + 1;
+        // Plain copies are propagated away even without optimizations; the
+        // tuple and its field are kept.
+        let pair = (index, slot);
+        let copied = pair.0;
+        Ok(ctx.accounts.book.slots[copied])
+    }
+
+    pub fn read_checked(_ctx: Context<Read>, data: Vec<u8>, start: u64, len: u64) -> Result<u8> {
+        let end = start.checked_add(len).ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(data[end as usize])
+    }
+
+    pub fn read_total(ctx: Context<Read>, start: u64, len: u64) -> Result<u64> {
+        // Overflowing, but only compared, never used as an index.
+        require!(start + len < 8, BookError::OutOfRange);
+        Ok(ctx.accounts.book.slots[start as usize])
+    }
+}
+
+#[error_code]
+pub enum BookError {
+    OutOfRange,
+}
+
+#[account]
+pub struct Book {
+    pub slots: [u64; 8],
+}
+
+#[derive(Accounts)]
+pub struct Read<'info> {
+    pub book: Account<'info, Book>,
+}
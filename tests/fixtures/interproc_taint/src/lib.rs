@@ -0,0 +1,67 @@
+//! `transfer` moves the lamports `scale` computes from `amount`, so the
+//! amount reaches `invoke` through the helper; `transfer_fee` passes it to
+//! `fee`, which ignores it. `countdown` and `step` call each other, and only
+//! return their argument through one another.
+#![allow(deprecated)]
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (tag, rest) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let amount = rest.first().copied().unwrap_or_default() as u64;
+    match tag {
+        0 => transfer(accounts, amount),
+        1 => transfer_fee(accounts, amount),
+        _ => transfer(accounts, countdown(amount)),
+    }
+}
+
+fn transfer(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let from = next_account_info(accounts_iter)?;
+    let to = next_account_info(accounts_iter)?;
+    let lamports = scale(amount);
+    invoke(
+        &system_instruction::transfer(from.key, to.key, lamports),
+        &[from.clone(), to.clone()],
+    )
+}
+
+fn transfer_fee(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let from = next_account_info(accounts_iter)?;
+    let to = next_account_info(accounts_iter)?;
+    let lamports = fee(amount);
+    invoke(
+        &system_instruction::transfer(from.key, to.key, lamports),
+        &[from.clone(), to.clone()],
+    )
+}
+
+fn scale(amount: u64) -> u64 {
+    amount / 2
+}
+
+fn fee(_amount: u64) -> u64 {
+    5000
+}
+
+fn countdown(n: u64) -> u64 {
+    if n == 0 { 0 } else { step(n - 1) }
+}
+
+fn step(n: u64) -> u64 {
+    if n % 2 == 0 { countdown(n) } else { n }
+}
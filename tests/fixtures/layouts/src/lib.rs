@@ -0,0 +1,53 @@
+//! Account types with fixed-size fields, a dynamically sized field in the
+//! middle, and a custom discriminator.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod layouts {
+    use super::*;
+
+    pub fn update(ctx: Context<Update>, name: String) -> Result<()> {
+        ctx.accounts.profile.name = name;
+        ctx.accounts.vault.balance += 1;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum Tier {
+    Free,
+    Paid { until: i64 },
+}
+
+#[account]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub bump: u8,
+    pub history: [u32; 4],
+}
+
+#[account]
+pub struct Profile {
+    pub owner: Pubkey,
+    pub name: String,
+    pub score: u16,
+    pub tier: Tier,
+}
+
+#[account(discriminator = [7, 7])]
+pub struct Flag {
+    pub on: bool,
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut, has_one = owner)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut, has_one = owner)]
+    pub profile: Account<'info, Profile>,
+    pub flag: Account<'info, Flag>,
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,58 @@
+//! Handlers logging plain messages, formatted values and a PDA bump seed.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::log::sol_log;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod logging {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        msg!("Initializing vault");
+        ctx.accounts.vault.bump = ctx.bumps.vault;
+        msg!("vault {} bump {}", ctx.accounts.vault.key(), ctx.bumps.vault);
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        helpers::log_deposit(&ctx.accounts.owner.key(), amount);
+        Ok(())
+    }
+}
+
+mod helpers {
+    use super::*;
+
+    pub fn log_deposit(owner: &Pubkey, amount: u64) {
+        sol_log("deposit");
+        msg!("{} deposited {:?}", owner, amount);
+    }
+}
+
+/// Never called by a handler.
+pub fn unused_log(secret: &[u8; 32]) {
+    msg!("secret {:?}", secret);
+}
+
+#[account]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = owner, space = 8 + 33, seeds = [b"vault", owner.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault", owner.key().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,49 @@
+//! A hand-maintained client whose account metas drifted from the Accounts
+//! struct they mirror.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod meta_mismatch {
+    use super::*;
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance -= amount;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+/// Written before `vault` became `mut`, and `authority` sent writable by
+/// mistake.
+pub mod client {
+    pub mod __client_accounts_withdraw {
+        use anchor_lang::prelude::*;
+
+        pub struct Withdraw {
+            pub vault: Pubkey,
+            pub authority: Pubkey,
+        }
+
+        impl anchor_lang::ToAccountMetas for Withdraw {
+            fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+                let mut account_metas = vec![];
+                account_metas.push(AccountMeta::new_readonly(self.vault, false));
+                account_metas.push(AccountMeta::new(self.authority, true));
+                account_metas
+            }
+        }
+    }
+}
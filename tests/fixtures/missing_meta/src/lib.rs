@@ -0,0 +1,99 @@
+//! Hand-maintained clients that fell behind the Accounts struct they mirror:
+//! one never sends a field added later, the other still sends a field since
+//! removed.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod missing_meta {
+    use super::*;
+
+    pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance -= amount;
+        ctx.accounts.recipient.balance += amount;
+        if let Some(treasury) = &mut ctx.accounts.treasury {
+            treasury.balance += 1;
+        }
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+#[derive(Accounts)]
+pub struct Common<'info> {
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The generated clients send every field, `common` through its own metas and
+/// `treasury` as the program id when it is `None`.
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub common: Common<'info>,
+    #[account(mut)]
+    pub treasury: Option<Account<'info, Vault>>,
+    #[account(mut)]
+    pub recipient: Account<'info, Vault>,
+}
+
+pub mod client {
+    /// Written before `recipient` was added.
+    pub mod __client_accounts_transfer {
+        use anchor_lang::prelude::*;
+
+        pub struct Transfer {
+            pub vault: Pubkey,
+            pub common: Vec<AccountMeta>,
+            pub treasury: Option<Pubkey>,
+        }
+
+        impl anchor_lang::ToAccountMetas for Transfer {
+            fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+                let mut account_metas = vec![];
+                account_metas.push(AccountMeta::new(self.vault, false));
+                account_metas.extend(self.common.clone());
+                if let Some(treasury) = &self.treasury {
+                    account_metas.push(AccountMeta::new(*treasury, false));
+                } else {
+                    account_metas.push(AccountMeta::new_readonly(crate::ID, false));
+                }
+                account_metas
+            }
+        }
+    }
+
+    /// Still sends the `fee_payer` that followed `recipient`.
+    pub mod __cpi_client_accounts_transfer {
+        use anchor_lang::prelude::*;
+
+        pub struct Transfer<'info> {
+            pub vault: AccountInfo<'info>,
+            pub common: Vec<AccountMeta>,
+            pub treasury: Option<AccountInfo<'info>>,
+            pub recipient: AccountInfo<'info>,
+            pub fee_payer: AccountInfo<'info>,
+        }
+
+        impl<'info> anchor_lang::ToAccountMetas for Transfer<'info> {
+            fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+                let mut account_metas = vec![];
+                account_metas.push(AccountMeta::new(self.vault.key(), false));
+                account_metas.extend(self.common.clone());
+                match &self.treasury {
+                    Some(treasury) => account_metas.push(AccountMeta::new(treasury.key(), false)),
+                    None => account_metas.push(AccountMeta::new_readonly(crate::ID, false)),
+                }
+                account_metas.push(AccountMeta::new(self.recipient.key(), false));
+                account_metas.push(AccountMeta::new(self.fee_payer.key(), true));
+                account_metas
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Each handler decodes the vault state with Borsh. `deposit` serializes it
+//! back after changing it; `withdraw` never does, and `skim` returns early on
+//! one path before it does. `balance` only reads the state.
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let amount = data
+        .get(1..9)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match data.first() {
+        Some(0) => deposit(accounts, amount),
+        Some(1) => withdraw(accounts, amount),
+        Some(2) => skim(accounts, amount),
+        _ => balance(accounts),
+    }
+}
+
+fn deposit(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let vault = next_account_info(&mut accounts.iter())?;
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    state.balance = state
+        .balance
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn withdraw(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let vault = next_account_info(&mut accounts.iter())?;
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    state.balance = state
+        .balance
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    msg!("withdrew {}", amount);
+    Ok(())
+}
+
+fn skim(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let vault = next_account_info(&mut accounts.iter())?;
+    let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+    state.balance -= amount.min(state.balance);
+    if state.balance == 0 {
+        return Ok(());
+    }
+    state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn balance(accounts: &[AccountInfo]) -> ProgramResult {
+    let vault = next_account_info(&mut accounts.iter())?;
+    let state = Vault::try_from_slice(&vault.data.borrow())?;
+    msg!("balance {}", state.balance);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! A native program written against the crates split out of `solana-program`
+//! rather than `solana-program` itself. `transfer` takes its accounts with
+//! `next_account_info` and forwards them to another program with `invoke`.
+use solana_account_info::{AccountInfo, next_account_info};
+use solana_cpi::invoke;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_msg::msg;
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_pubkey::Pubkey;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program_entrypoint::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.first() {
+        Some(0) => transfer(program_id, accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn transfer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let from = next_account_info(accounts_iter)?;
+    let to = next_account_info(accounts_iter)?;
+    let target_program = next_account_info(accounts_iter)?;
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if to.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    msg!("Instruction: Transfer");
+    let instruction = Instruction {
+        program_id: *target_program.key,
+        accounts: vec![
+            AccountMeta::new(*from.key, true),
+            AccountMeta::new(*to.key, false),
+        ],
+        data: vec![],
+    };
+    invoke(&instruction, &[from.clone(), to.clone()])
+}
@@ -0,0 +1,133 @@
+//! A native vault whose `unpack` decodes the first byte of the instruction data
+//! into `VaultInstruction`, which `Processor::process` routes to one fn per
+//! variant. Each handler takes its accounts in order with `next_account_info`.
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::msg;
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    Processor::process(program_id, accounts, data)
+}
+
+pub enum VaultInstruction {
+    Initialize,
+    Deposit { amount: u64 },
+    Withdraw { amount: u64 },
+}
+
+impl VaultInstruction {
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = data
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::Initialize,
+            1 => Self::Deposit {
+                amount: Self::unpack_amount(rest)?,
+            },
+            2 => Self::Withdraw {
+                amount: Self::unpack_amount(rest)?,
+            },
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+
+    fn unpack_amount(rest: &[u8]) -> Result<u64, ProgramError> {
+        let bytes = rest
+            .get(..8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct VaultState {
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+pub struct Processor;
+
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        match VaultInstruction::unpack(data)? {
+            VaultInstruction::Initialize => {
+                msg!("Instruction: Initialize");
+                Self::process_initialize(program_id, accounts)
+            }
+            VaultInstruction::Deposit { amount } => {
+                msg!("Instruction: Deposit");
+                Self::process_deposit(accounts, amount)
+            }
+            VaultInstruction::Withdraw { amount } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(accounts, amount)
+            }
+        }
+    }
+
+    fn process_initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let vault = next_account_info(accounts_iter)?;
+        let authority = next_account_info(accounts_iter)?;
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if vault.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let state = VaultState {
+            authority: *authority.key,
+            balance: 0,
+        };
+        state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+        Ok(())
+    }
+
+    fn process_deposit(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let vault = next_account_info(accounts_iter)?;
+        let depositor = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        invoke(
+            &system_instruction::transfer(depositor.key, vault.key, amount),
+            &[depositor.clone(), vault.clone(), system_program.clone()],
+        )?;
+        let mut state = VaultState::try_from_slice(&vault.data.borrow())?;
+        state.balance += amount;
+        state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+        Ok(())
+    }
+
+    fn process_withdraw(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let vault = next_account_info(accounts_iter)?;
+        let authority = next_account_info(accounts_iter)?;
+        let recipient = next_account_info(accounts_iter)?;
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut state = VaultState::try_from_slice(&vault.data.borrow())?;
+        if state.authority != *authority.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        state.balance -= amount;
+        **vault.try_borrow_mut_lamports()? -= amount;
+        **recipient.try_borrow_mut_lamports()? += amount;
+        state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+//! Points moved between two user accounts. Nothing in `transfer` stops a
+//! client from passing the same account as `from` and `to`: the second write
+//! then overwrites the first and the points are minted. `transfer_checked`
+//! rejects equal keys first; `transfer_late` only after writing.
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct UserPoints {
+    pub owner: Pubkey,
+    pub points: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Config {
+    pub reward: u64,
+}
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let amount = data
+        .get(1..9)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match data.first() {
+        Some(0) => transfer(accounts, amount),
+        Some(1) => transfer_checked(accounts, amount),
+        Some(2) => transfer_late(accounts, amount),
+        Some(3) => reward(accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn transfer(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let from = next_account_info(accounts)?;
+    let to = next_account_info(accounts)?;
+    let mut from_points = UserPoints::try_from_slice(&from.data.borrow())?;
+    let mut to_points = UserPoints::try_from_slice(&to.data.borrow())?;
+    from_points.points = from_points
+        .points
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    to_points.points += amount;
+    from_points.serialize(&mut &mut from.data.borrow_mut()[..])?;
+    to_points.serialize(&mut &mut to.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn transfer_checked(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let from = next_account_info(accounts)?;
+    let to = next_account_info(accounts)?;
+    if from.key == to.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut from_points = UserPoints::try_from_slice(&from.data.borrow())?;
+    let mut to_points = UserPoints::try_from_slice(&to.data.borrow())?;
+    from_points.points = from_points
+        .points
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    to_points.points += amount;
+    from_points.serialize(&mut &mut from.data.borrow_mut()[..])?;
+    to_points.serialize(&mut &mut to.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn transfer_late(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let from = next_account_info(accounts)?;
+    let to = next_account_info(accounts)?;
+    let mut from_points = UserPoints::try_from_slice(&from.data.borrow())?;
+    let mut to_points = UserPoints::try_from_slice(&to.data.borrow())?;
+    from_points.points = from_points
+        .points
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    to_points.points += amount;
+    from_points.serialize(&mut &mut from.data.borrow_mut()[..])?;
+    to_points.serialize(&mut &mut to.data.borrow_mut()[..])?;
+    if from.key == to.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+/// Writes two accounts of different types.
+fn reward(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let user = next_account_info(accounts)?;
+    let config = next_account_info(accounts)?;
+    let mut user_points = UserPoints::try_from_slice(&user.data.borrow())?;
+    let mut settings = Config::try_from_slice(&config.data.borrow())?;
+    user_points.points += settings.reward;
+    settings.reward /= 2;
+    user_points.serialize(&mut &mut user.data.borrow_mut()[..])?;
+    settings.serialize(&mut &mut config.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! A native program: `entrypoint!` hands every instruction to
+//! `process_instruction`, which logs and hashes the key of each account.
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::hash::hash;
+use solana_program::msg;
+use solana_program::pubkey::Pubkey;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    msg!("instruction of {} bytes", data.len());
+    for account in accounts {
+        hash(account.key.as_ref());
+    }
+    Ok(())
+}
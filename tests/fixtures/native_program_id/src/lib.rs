@@ -0,0 +1,54 @@
+//! `deposit` checks that the vault is owned by the `program_id` the runtime
+//! passes in, `withdraw` and `close` against the `ID` declared at build time.
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.first() {
+        Some(0) => deposit(program_id, accounts),
+        Some(1) => withdraw(accounts),
+        Some(2) => close(accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn deposit(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    **vault.try_borrow_mut_lamports()? += 1;
+    Ok(())
+}
+
+fn withdraw(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    if *vault.owner != ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    **vault.try_borrow_mut_lamports()? -= 1;
+    Ok(())
+}
+
+fn close(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    if !check_id(vault.owner) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    **vault.try_borrow_mut_lamports()? = 0;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Oracle accounts pinned in different ways; only `ReadUnpinned::price`,
+//! `ReadFeed::feed` (a type added through `solana-analyzer.toml`) and both
+//! fields of `ReadPair` are unpinned.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const PRICE_ACCOUNT: Pubkey = pubkey!("7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE");
+pub const ORACLE_PROGRAM: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+#[program]
+pub mod oracle_pinning {
+    use super::*;
+
+    pub fn read_unpinned(ctx: Context<ReadUnpinned>) -> Result<()> {
+        msg!("{}", ctx.accounts.price.price);
+        Ok(())
+    }
+
+    pub fn read_by_address(ctx: Context<ReadByAddress>) -> Result<()> {
+        msg!("{}", ctx.accounts.price.price);
+        Ok(())
+    }
+
+    pub fn read_by_owner(ctx: Context<ReadByOwner>) -> Result<()> {
+        msg!("{}", ctx.accounts.price.price);
+        Ok(())
+    }
+
+    pub fn read_checked(ctx: Context<ReadChecked>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.price.key(), PRICE_ACCOUNT);
+        msg!("{}", ctx.accounts.price.price);
+        Ok(())
+    }
+
+    pub fn read_feed(ctx: Context<ReadFeed>) -> Result<()> {
+        msg!("{}", ctx.accounts.feed.value);
+        Ok(())
+    }
+
+    pub fn read_pair(ctx: Context<ReadPair>) -> Result<()> {
+        msg!("{} {}", ctx.accounts.price.price, ctx.accounts.feed.value);
+        Ok(())
+    }
+}
+
+#[account]
+pub struct PriceUpdateV2 {
+    pub price: i64,
+}
+
+#[account]
+pub struct PriceFeed {
+    pub value: i64,
+}
+
+#[derive(Accounts)]
+pub struct ReadUnpinned<'info> {
+    pub price: Account<'info, PriceUpdateV2>,
+}
+
+#[derive(Accounts)]
+pub struct ReadByAddress<'info> {
+    #[account(address = PRICE_ACCOUNT)]
+    pub price: Account<'info, PriceUpdateV2>,
+}
+
+#[derive(Accounts)]
+pub struct ReadByOwner<'info> {
+    #[account(owner = ORACLE_PROGRAM)]
+    pub price: Account<'info, PriceUpdateV2>,
+}
+
+#[derive(Accounts)]
+pub struct ReadChecked<'info> {
+    pub price: Account<'info, PriceUpdateV2>,
+}
+
+#[derive(Accounts)]
+pub struct ReadFeed<'info> {
+    pub feed: Box<Account<'info, PriceFeed>>,
+}
+
+#[derive(Accounts)]
+pub struct ReadPair<'info> {
+    pub price: Account<'info, PriceUpdateV2>,
+    pub feed: Account<'info, PriceFeed>,
+}
@@ -0,0 +1,63 @@
+//! `Deposit::config` is requested as `mut` but the handler only reads it.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod over_permissioned {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, fee: u64) -> Result<()> {
+        ctx.accounts.config.fee = fee;
+        ctx.accounts.config.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let fee = ctx.accounts.config.fee;
+        let vault = &mut ctx.accounts.vault;
+        vault.total = vault.total.checked_add(amount - fee).unwrap();
+        Ok(())
+    }
+
+    pub fn close_vault(_ctx: Context<CloseVault>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Config {
+    pub authority: Pubkey,
+    pub fee: u64,
+}
+
+#[account]
+pub struct Vault {
+    pub total: u64,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = authority, space = 8 + 32 + 8)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, has_one = authority)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(mut, close = receiver)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub receiver: SystemAccount<'info>,
+}
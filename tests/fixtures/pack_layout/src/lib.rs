@@ -0,0 +1,97 @@
+//! A native escrow keeping its state with `Pack` instead of Borsh.
+//! `initialize` decodes the state with `unpack_unchecked` and rejects it if it
+//! is already initialized, and `deposit` uses `unpack`, which checks; `release`
+//! decodes it with `unpack_unchecked` and trusts its fields all the same.
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::{IsInitialized, Pack, Sealed};
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub struct Escrow {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+impl Sealed for Escrow {}
+
+impl IsInitialized for Escrow {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Escrow {
+    const LEN: usize = 41;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = self.is_initialized as u8;
+        dst[1..33].copy_from_slice(self.owner.as_ref());
+        dst[33..41].copy_from_slice(&self.amount.to_le_bytes());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let owner = Pubkey::try_from(&src[1..33]).map_err(|_| ProgramError::InvalidAccountData)?;
+        let amount = src[33..41]
+            .try_into()
+            .map(u64::from_le_bytes)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(Self {
+            is_initialized: src[0] == 1,
+            owner,
+            amount,
+        })
+    }
+}
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.first() {
+        Some(0) => initialize(accounts),
+        Some(1) => deposit(accounts, 1),
+        Some(2) => release(accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn initialize(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let escrow = next_account_info(accounts)?;
+    let owner = next_account_info(accounts)?;
+    let mut state = Escrow::unpack_unchecked(&escrow.data.borrow())?;
+    if state.is_initialized() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    state.is_initialized = true;
+    state.owner = *owner.key;
+    Escrow::pack(state, &mut escrow.data.borrow_mut())
+}
+
+fn deposit(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let escrow = next_account_info(&mut accounts.iter())?;
+    let mut state = Escrow::unpack(&escrow.data.borrow())?;
+    state.amount += amount;
+    Escrow::pack(state, &mut escrow.data.borrow_mut())
+}
+
+fn release(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let escrow = next_account_info(accounts)?;
+    let owner = next_account_info(accounts)?;
+    let state = Escrow::unpack_unchecked(&escrow.data.borrow())?;
+    if state.owner != *owner.key || !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    **escrow.try_borrow_mut_lamports()? -= state.amount;
+    **owner.try_borrow_mut_lamports()? += state.amount;
+    Ok(())
+}
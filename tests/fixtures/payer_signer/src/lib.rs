@@ -0,0 +1,60 @@
+//! `Open` creates `vault` with `payer = user`, a bare `AccountInfo`, and `Fund`
+//! with a `SystemAccount`; neither is checked to sign. Only `OpenSigned` pays
+//! with a `Signer`.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod payer_signer {
+    use super::*;
+
+    pub fn open(ctx: Context<Open>) -> Result<()> {
+        ctx.accounts.vault.owner = ctx.accounts.user.key();
+        Ok(())
+    }
+
+    pub fn fund(ctx: Context<Fund>) -> Result<()> {
+        ctx.accounts.vault.owner = ctx.accounts.funder.key();
+        Ok(())
+    }
+
+    pub fn open_signed(ctx: Context<OpenSigned>) -> Result<()> {
+        ctx.accounts.vault.owner = ctx.accounts.user.key();
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Open<'info> {
+    #[account(init, payer = user, space = 8 + Vault::INIT_SPACE)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: meant to be the fee payer, but never checked to sign.
+    #[account(mut)]
+    pub user: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(init, payer = funder, space = 8 + Vault::INIT_SPACE)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub funder: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenSigned<'info> {
+    #[account(init, payer = user, space = 8 + Vault::INIT_SPACE)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub owner: Pubkey,
+}
@@ -0,0 +1,64 @@
+//! PDA fields with literal, `const`, key and instruction-argument seeds, using
+//! both the canonical `bump` and a stored `bump = vault.bump`.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const VAULT_SEED: &[u8] = b"vault";
+
+#[program]
+pub mod pda_seeds {
+    use super::*;
+
+    pub fn open(ctx: Context<Open>, index: u64) -> Result<()> {
+        ctx.accounts.vault.bump = ctx.bumps.vault;
+        ctx.accounts.vault.index = index;
+        Ok(())
+    }
+
+    pub fn touch(ctx: Context<Touch>) -> Result<()> {
+        ctx.accounts.vault.index += 1;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct Open<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [VAULT_SEED, owner.key().as_ref(), &index.to_le_bytes()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Touch<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref(), &vault.index.to_le_bytes()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub index: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+}
@@ -0,0 +1,27 @@
+//! A program keeping the id of its previous deployment next to the one
+//! `declare_id!` generates; the `check_id` fn tells which one is current.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub mod legacy {
+    use super::*;
+
+    pub const ID: Pubkey = pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+}
+
+#[program]
+pub mod program_ids {
+    use super::*;
+
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        require_keys_eq!(*ctx.accounts.old_program.key, legacy::ID);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    /// CHECK: compared against the legacy id.
+    pub old_program: UncheckedAccount<'info>,
+}
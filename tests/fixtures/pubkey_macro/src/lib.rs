@@ -0,0 +1,49 @@
+//! The id is declared with `pubkey!` rather than `declare_id!`, and built from
+//! the bytes of another const. `withdraw` checks the vault against it.
+use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey;
+use solana_program::pubkey::Pubkey;
+
+/// The id as bytes, for clients that do not depend on `Pubkey`.
+pub const PROGRAM_ID_BYTES: [u8; 32] =
+    pubkey!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS").to_bytes();
+pub const ID: Pubkey = Pubkey::new_from_array(PROGRAM_ID_BYTES);
+/// The owner of the vault, under the name of the first release.
+pub const PROGRAM_ID: Pubkey = ID;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.first() {
+        Some(0) => deposit(program_id, accounts),
+        Some(1) => withdraw(accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn deposit(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    **vault.try_borrow_mut_lamports()? += 1;
+    Ok(())
+}
+
+fn withdraw(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    if *vault.owner != PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    **vault.try_borrow_mut_lamports()? -= 1;
+    Ok(())
+}
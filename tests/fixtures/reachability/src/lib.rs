@@ -0,0 +1,51 @@
+//! Handlers reached only through a dispatch table of fn pointers, helpers
+//! reached only from closures and as the instance of a generic fn, and a fn
+//! nothing reaches.
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+type Handler = fn(&[AccountInfo], &[u8]) -> ProgramResult;
+
+const HANDLERS: [Handler; 2] = [credit, debit];
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (tag, rest) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let handler = HANDLERS
+        .get(*tag as usize)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    handler(accounts, rest)
+}
+
+fn credit(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    accounts.iter().for_each(|account| log_account(account));
+    Ok(())
+}
+
+fn debit(_accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    log_value(data.len());
+    Ok(())
+}
+
+fn log_account(account: &AccountInfo) {
+    msg!("account {}", account.key);
+}
+
+fn log_value<T: std::fmt::Debug>(value: T) {
+    msg!("value {:?}", value);
+}
+
+pub fn unused() {
+    msg!("never called");
+}
@@ -0,0 +1,66 @@
+//! Handlers reading `remaining_accounts` by index, with and without checking
+//! how many were passed, iterating over them, and only counting them.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod remaining_accounts {
+    use super::*;
+
+    pub fn route(ctx: Context<Route>) -> Result<()> {
+        let hop = &ctx.remaining_accounts[1];
+        msg!("routing through {}", hop.key);
+        Ok(())
+    }
+
+    pub fn route_checked(ctx: Context<Route>) -> Result<()> {
+        require!(ctx.remaining_accounts.len() >= 2, RouteError::MissingHop);
+        let hop = &ctx.remaining_accounts[1];
+        msg!("routing through {}", hop.key);
+        Ok(())
+    }
+
+    pub fn route_tail(ctx: Context<Route>) -> Result<()> {
+        for hop in &ctx.remaining_accounts[2..] {
+            msg!("routing through {}", hop.key);
+        }
+        Ok(())
+    }
+
+    pub fn route_first(ctx: Context<Route>) -> Result<()> {
+        if let Some(hop) = ctx.remaining_accounts.get(0) {
+            msg!("routing through {}", hop.key);
+        }
+        Ok(())
+    }
+
+    pub fn route_all(ctx: Context<Route>) -> Result<()> {
+        for hop in ctx.remaining_accounts.iter() {
+            msg!("routing through {}", hop.key);
+        }
+        Ok(())
+    }
+
+    pub fn route_nth(ctx: Context<Route>, hop: u8) -> Result<()> {
+        let hop = &ctx.remaining_accounts[hop as usize];
+        msg!("routing through {}", hop.key);
+        Ok(())
+    }
+
+    pub fn count(ctx: Context<Route>) -> Result<()> {
+        msg!("{} hops", ctx.remaining_accounts.len());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Route<'info> {
+    pub payer: Signer<'info>,
+}
+
+#[error_code]
+pub enum RouteError {
+    #[msg("missing hop account")]
+    MissingHop,
+}
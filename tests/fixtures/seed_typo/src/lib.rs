@@ -0,0 +1,65 @@
+//! The same vault PDA derived from `b"vault"` in one instruction and from a
+//! mistyped `b"vaulr"` in another, next to deliberately numbered tier seeds.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[constant]
+pub const VAULT_SEED: &[u8] = b"vault";
+#[constant]
+pub const MAX_DEPOSIT: u64 = 1_000_000;
+
+#[program]
+pub mod seed_typo {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(amount <= MAX_DEPOSIT, VaultError::TooLarge);
+        ctx.accounts.vault.balance += amount;
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance -= amount;
+        Ok(())
+    }
+
+    pub fn promote(ctx: Context<Promote>) -> Result<()> {
+        ctx.accounts.tier2.balance = ctx.accounts.tier1.balance;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [VAULT_SEED, owner.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"vaulr", owner.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Promote<'info> {
+    #[account(seeds = [b"tier1", owner.key().as_ref()], bump)]
+    pub tier1: Account<'info, Vault>,
+    #[account(mut, seeds = [b"tier2", owner.key().as_ref()], bump)]
+    pub tier2: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("deposit too large")]
+    TooLarge,
+}
@@ -0,0 +1,64 @@
+//! `withdraw` signs for the vault with `&[user_seed_bytes]`, taken from the
+//! instruction data alone, so a caller can have the program sign for any PDA
+//! of its choosing. `sweep` namespaces the seeds with `b"vault"` and the
+//! owner's key, and `refund` with the owner's key only.
+use solana_program::account_info::{AccountInfo, next_account_info};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program::invoke_signed;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (&tag, rest) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match tag {
+        0 => withdraw(accounts, rest),
+        1 => sweep(accounts, rest),
+        2 => refund(accounts, rest),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn withdraw(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+    let (amount, user_seed_bytes) = data.split_at(8);
+    let amount = u64::from_le_bytes(amount.try_into().unwrap());
+    invoke_signed(
+        &system_instruction::transfer(vault.key, recipient.key, amount),
+        &[vault.clone(), recipient.clone()],
+        &[&[user_seed_bytes]],
+    )
+}
+
+fn sweep(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+    let bump = data[0];
+    invoke_signed(
+        &system_instruction::transfer(vault.key, owner.key, vault.lamports()),
+        &[vault.clone(), owner.clone()],
+        &[&[b"vault", owner.key.as_ref(), &[bump]]],
+    )
+}
+
+fn refund(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+    invoke_signed(
+        &system_instruction::transfer(vault.key, owner.key, vault.lamports()),
+        &[vault.clone(), owner.clone()],
+        &[&[owner.key.as_ref(), &data[..1]]],
+    )
+}
@@ -0,0 +1,86 @@
+//! A global `Config`, listed as a singleton in `solana-analyzer.toml`, pinned in
+//! different ways; only `SetFeeUnpinned::config` is unpinned. `Vault` is not a
+//! singleton, so `Withdraw::vault` is left alone.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const CONFIG_ACCOUNT: Pubkey = pubkey!("7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE");
+
+#[program]
+pub mod singleton_config {
+    use super::*;
+
+    pub fn set_fee_unpinned(ctx: Context<SetFeeUnpinned>, fee: u64) -> Result<()> {
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    pub fn set_fee_by_address(ctx: Context<SetFeeByAddress>, fee: u64) -> Result<()> {
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    pub fn set_fee_by_seeds(ctx: Context<SetFeeBySeeds>, fee: u64) -> Result<()> {
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    pub fn set_fee_checked(ctx: Context<SetFeeChecked>, fee: u64) -> Result<()> {
+        require_keys_eq!(ctx.accounts.config.key(), CONFIG_ACCOUNT);
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.balance -= amount;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee: u64,
+}
+
+#[account]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeUnpinned<'info> {
+    #[account(mut, has_one = admin)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeByAddress<'info> {
+    #[account(mut, address = CONFIG_ACCOUNT, has_one = admin)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeBySeeds<'info> {
+    #[account(mut, seeds = [b"config"], bump, has_one = admin)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeChecked<'info> {
+    #[account(mut, has_one = admin)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, has_one = owner)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
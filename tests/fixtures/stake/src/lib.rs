@@ -0,0 +1,63 @@
+//! A staking pool whose instructions take none or several arguments, with
+//! `unstake` forwarding to a helper in another module.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod stake {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, reward_rate: u64) -> Result<()> {
+        ctx.accounts.pool.authority = ctx.accounts.authority.key();
+        ctx.accounts.pool.reward_rate = reward_rate;
+        Ok(())
+    }
+
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        ctx.accounts.pool.total_staked += amount;
+        Ok(())
+    }
+
+    pub fn unstake(ctx: Context<Stake>, amount: u64, memo: Vec<u8>) -> Result<()> {
+        helpers::unstake(ctx, amount, &memo)
+    }
+
+    pub fn claim_rewards(ctx: Context<Stake>) -> Result<()> {
+        msg!("{}", ctx.accounts.pool.reward_rate);
+        Ok(())
+    }
+}
+
+mod helpers {
+    use super::*;
+
+    pub fn unstake(ctx: Context<Stake>, amount: u64, _memo: &[u8]) -> Result<()> {
+        ctx.accounts.pool.total_staked -= amount;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub reward_rate: u64,
+    pub total_staked: u64,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = authority, space = 8 + Pool::INIT_SPACE)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    pub staker: Signer<'info>,
+}
@@ -0,0 +1,58 @@
+//! Siblings that differ only in `#[cfx::allow(..)]`: `deadline_allowed` and
+//! `WithdrawAllowed` are suppressed, `deadline` and `Withdraw` are reported.
+//! `cfx.toml` turns `compute_heavy_loop` off and raises
+//! `over_permissioned_account` to an error.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod suppression {
+    use super::*;
+
+    pub fn deadline(_ctx: Context<Withdraw>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now == 1_700_000_000, VaultError::Closed);
+        Ok(())
+    }
+
+    #[cfx::allow(fragile_timestamp_check)]
+    pub fn deadline_allowed(_ctx: Context<WithdrawAllowed>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now == 1_700_000_000, VaultError::Closed);
+        Ok(())
+    }
+
+    pub fn digest(_ctx: Context<Withdraw>, rounds: u8) -> Result<()> {
+        let mut digest = [0u8; 32];
+        for _ in 0..rounds {
+            digest = hash(&digest).to_bytes();
+        }
+        msg!("{:?}", digest);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+}
+
+#[cfx::allow(over_permissioned_account)]
+#[derive(Accounts)]
+pub struct WithdrawAllowed<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+}
+
+#[account]
+pub struct Vault {
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum VaultError {
+    Closed,
+}
@@ -0,0 +1,64 @@
+//! `claim_at` uses an exact deadline and `bid` a one-second window; `vest` uses a
+//! day-long window, `expire` a single bound and `phase` one bound on each
+//! branch, which are fine.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod timestamp_window {
+    use super::*;
+
+    pub fn claim_at(_ctx: Context<Touch>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now == 1_700_000_000, WindowError::Closed);
+        Ok(())
+    }
+
+    pub fn bid(ctx: Context<Touch>) -> Result<()> {
+        let now = ctx.accounts.clock.unix_timestamp;
+        let start = ctx.accounts.auction.start;
+        require!(now >= start && now < start + 1, WindowError::Closed);
+        Ok(())
+    }
+
+    pub fn vest(_ctx: Context<Touch>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= 1_700_000_000 && now <= 1_700_086_400, WindowError::Closed);
+        Ok(())
+    }
+
+    pub fn expire(ctx: Context<Touch>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now > ctx.accounts.auction.end, WindowError::Closed);
+        Ok(())
+    }
+
+    pub fn phase(ctx: Context<Touch>, late: bool) -> Result<()> {
+        let now = ctx.accounts.clock.unix_timestamp;
+        let start = ctx.accounts.auction.start;
+        if late {
+            require!(now >= start, WindowError::Closed);
+        } else {
+            require!(now < start + 1, WindowError::Closed);
+        }
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Auction {
+    pub start: i64,
+    pub end: i64,
+}
+
+#[error_code]
+pub enum WindowError {
+    Closed,
+}
+
+#[derive(Accounts)]
+pub struct Touch<'info> {
+    pub auction: Account<'info, Auction>,
+    pub clock: Sysvar<'info, Clock>,
+}
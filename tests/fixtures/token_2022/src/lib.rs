@@ -0,0 +1,62 @@
+//! Token-2022-aware accounts: `InterfaceAccount` and `Interface` accept both
+//! the Token and the Token-2022 program.
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod token_2022 {
+    use super::*;
+
+    pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)
+    }
+
+    pub fn sync(ctx: Context<Sync>) -> Result<()> {
+        ctx.accounts.copy.balance = ctx.accounts.vault.balance;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+static VAULT_OWNERS: [Pubkey; 1] = [ID];
+
+/// Lets `Vault` be loaded as an `InterfaceAccount` too.
+impl anchor_lang::Owners for Vault {
+    fn owners() -> &'static [Pubkey] {
+        &VAULT_OWNERS
+    }
+}
+
+/// `from` and `to` can be the same token account.
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(mut)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// `Account<Vault>` and `InterfaceAccount<Vault>` can alias as well.
+#[derive(Accounts)]
+pub struct Sync<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub copy: InterfaceAccount<'info, Vault>,
+}
@@ -0,0 +1,73 @@
+//! Token accounts deserialized by hand from an `UncheckedAccount`. Only the
+//! handlers that pin the owner to the Token program are safe.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token::state::Account as SplAccount;
+use anchor_spl::token::{self, TokenAccount};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod token_owner {
+    use super::*;
+
+    pub fn read_unchecked(ctx: Context<ReadRaw>) -> Result<()> {
+        let data = ctx.accounts.source.try_borrow_data()?;
+        let source = TokenAccount::try_deserialize(&mut &data[..])?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    pub fn read_checked(ctx: Context<ReadRaw>) -> Result<()> {
+        require_keys_eq!(*ctx.accounts.source.owner, token::ID);
+        let data = ctx.accounts.source.try_borrow_data()?;
+        let source = TokenAccount::try_deserialize(&mut &data[..])?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    /// Comparing the owner against our own program id does not help.
+    pub fn read_wrong_owner(ctx: Context<ReadRaw>) -> Result<()> {
+        require_keys_eq!(*ctx.accounts.source.owner, crate::ID);
+        let data = ctx.accounts.source.try_borrow_data()?;
+        let source = TokenAccount::try_deserialize(&mut &data[..])?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    pub fn read_constrained(ctx: Context<ReadOwned>) -> Result<()> {
+        let data = ctx.accounts.source.try_borrow_data()?;
+        let source = TokenAccount::try_deserialize(&mut &data[..])?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    pub fn unpack_unchecked(ctx: Context<ReadRaw>) -> Result<()> {
+        let source = SplAccount::unpack(&ctx.accounts.source.data.borrow())?;
+        msg!("{}", source.amount);
+        Ok(())
+    }
+
+    pub fn read_typed(ctx: Context<ReadTyped>) -> Result<()> {
+        msg!("{}", ctx.accounts.source.amount);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReadRaw<'info> {
+    /// CHECK: deserialized in the handler
+    pub source: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReadOwned<'info> {
+    /// CHECK: owned by the Token program
+    #[account(owner = token::ID)]
+    pub source: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReadTyped<'info> {
+    pub source: Account<'info, TokenAccount>,
+}
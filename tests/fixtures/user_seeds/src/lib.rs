@@ -0,0 +1,51 @@
+//! `Create` seeds `order` with nothing but the user's `id`; `CreateOwned` adds
+//! a namespace and the owner's key.
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod user_seeds {
+    use super::*;
+
+    pub fn create(ctx: Context<Create>, id: u64) -> Result<()> {
+        ctx.accounts.order.id = id;
+        Ok(())
+    }
+
+    pub fn create_owned(ctx: Context<CreateOwned>, id: u64) -> Result<()> {
+        ctx.accounts.order.id = id;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct Create<'info> {
+    #[account(init, payer = payer, space = 8 + 8, seeds = [&id.to_le_bytes()], bump)]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateOwned<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 8,
+        seeds = [b"order", payer.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Order {
+    pub id: u64,
+}
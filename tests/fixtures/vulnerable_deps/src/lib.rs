@@ -0,0 +1,20 @@
+//! `spl-token` allows 3.0.0, which the builtin advisory on spl-token before
+//! 3.1.1 affects, and `solana-program` 2.3.0, which the first advisory of
+//! `advisories.json` affects.
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    for account in accounts {
+        let _ = spl_token::state::Account::unpack(&account.data.borrow())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_write_to_foreign_owned_account() {
+    let output = common::analyze("foreign_write");
+    let errors: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| line.contains("is owned by another program"))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            // Through the local helper `zero`.
+            "Find error: account `receiver` in `Pay` is owned by another program but `foreign_write::wipe` writes its data",
+            "Find error: account `system_program` in `Pay` is owned by another program but `foreign_write::write_program` writes its data",
+            "Find error: account `vault` in `Tokens` is owned by another program but `foreign_write::write_token` writes its data",
+        ]
+    );
+}
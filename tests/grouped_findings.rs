@@ -0,0 +1,45 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::path::Path;
+
+use solana_program_analyzer::aggregate::AnalysisReport;
+
+#[test]
+fn test_findings_by_handler() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("grouped_findings.json");
+    let path_arg = path.to_string_lossy().into_owned();
+    common::analyze_with("grouped_findings", &["--report", &path_arg]);
+
+    let report = AnalysisReport::read(&path).unwrap();
+    let handlers: Vec<(&str, Vec<&str>)> = report
+        .handlers
+        .iter()
+        .map(|handler| {
+            let findings = handler
+                .findings
+                .iter()
+                .map(|finding| finding.message.as_str())
+                .collect();
+            (handler.handler.as_str(), findings)
+        })
+        .collect();
+    // The finding in the shared `at_deadline` is listed under both of its
+    // callers; `touch` has none.
+    let helper =
+        "`at_deadline` compares `unix_timestamp` == Auction.end (bb4), which clock drift can skip";
+    assert_eq!(
+        handlers,
+        [
+            ("claim", vec![helper]),
+            ("refund", vec![helper]),
+            (
+                "bump",
+                vec![
+                    "`grouped_findings::bump` compares `unix_timestamp` == 1700000000 (bb4), which clock drift can skip"
+                ]
+            ),
+        ]
+    );
+}
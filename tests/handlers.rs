@@ -0,0 +1,40 @@
+mod common;
+
+#[test]
+fn test_program_handlers() {
+    let output = common::analyze("stake");
+    let handlers: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("instruction "))
+        .collect();
+    assert_eq!(
+        handlers,
+        [
+            "instruction initialize: stake::initialize(Context<Initialize>, reward_rate: u64)",
+            "instruction stake: stake::stake(Context<Stake>, amount: u64)",
+            "instruction unstake: stake::unstake(Context<Stake>, amount: u64, memo: std::vec::Vec<u8>)",
+            "instruction claim_rewards: stake::claim_rewards(Context<Stake>)",
+        ]
+    );
+}
+
+#[test]
+fn test_discriminators() {
+    let output = common::analyze("stake");
+    // `sha256("global:<instruction>")[..8]`.
+    let instructions: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("instruction::"))
+        .collect();
+    assert_eq!(
+        instructions,
+        [
+            "instruction::ClaimRewards discriminator: 0490844774179750",
+            "instruction::Initialize discriminator: afaf6d1f0d989bed",
+            "instruction::Stake discriminator: ceb0ca12c8d1b36c",
+            "instruction::Unstake discriminator: 5a5f6b2acd7c32e1",
+        ]
+    );
+    // `sha256("account:Pool")[..8]`, kept apart from the instructions.
+    assert!(output.contains("account Pool discriminator: f19a6d0411b16dbc"));
+}
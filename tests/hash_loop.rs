@@ -0,0 +1,18 @@
+mod common;
+
+#[test]
+fn test_hash_in_loop() {
+    let output = common::analyze("hash_loop");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("Find warning: loop at "))
+        .collect();
+    // `commit_one` hashes once, outside any loop.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: loop at bb4 in `hash_loop::commit_all` calls `hash::hashv` (bb7) each iteration, ~85 CU per iteration",
+            "Find warning: loop at bb2 in `hash_loop::grind` calls `solana_keccak_hasher::hash` via `mix` (bb3) each iteration, ~85 CU per iteration",
+        ]
+    );
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+#[test]
+fn test_emit_idl() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("events_idl.json");
+    let path_arg = path.to_string_lossy().into_owned();
+    common::analyze_with("events", &["--emit-idl", &path_arg]);
+    let idl: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+    assert_eq!(
+        idl["address"],
+        "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"
+    );
+    assert_eq!(
+        idl["metadata"],
+        json!({ "name": "events", "version": "0.1.0", "spec": "0.1.0" })
+    );
+    assert_eq!(
+        idl["instructions"][0],
+        json!({
+            "name": "deposit",
+            "discriminator": [242, 35, 198, 137, 82, 225, 242, 182],
+            "accounts": [{ "name": "vault" }, { "name": "owner", "signer": true }],
+            "args": [{ "name": "amount", "type": "u64" }],
+        })
+    );
+    assert_eq!(
+        idl["instructions"][1]["args"],
+        json!([{ "name": "name", "type": "string" }])
+    );
+    assert_eq!(
+        idl["accounts"],
+        json!([{ "name": "Vault", "discriminator": [211, 8, 232, 43, 2, 152, 117, 119] }])
+    );
+    let names = |key: &str| -> Vec<String> {
+        idl[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(names("events"), ["Deposited", "Settled", "Renamed"]);
+    assert_eq!(
+        names("types"),
+        ["Deposited", "Renamed", "Settled", "Side", "Vault"]
+    );
+    let types = idl["types"].as_array().unwrap();
+    assert_eq!(
+        types[1]["type"]["fields"],
+        json!([
+            { "name": "owner", "type": "pubkey" },
+            { "name": "name", "type": "string" },
+            { "name": "tags", "type": "bytes" },
+            { "name": "note", "type": { "option": "u64" } },
+        ])
+    );
+    assert_eq!(
+        types[2]["type"]["fields"],
+        json!([
+            { "name": "amounts", "type": { "array": ["u64", 4] } },
+            { "name": "side", "type": { "defined": { "name": "Side" } } },
+        ])
+    );
+    assert_eq!(
+        types[3]["type"],
+        json!({ "kind": "enum", "variants": [{ "name": "Bid" }, { "name": "Ask" }] })
+    );
+}
+
+#[test]
+fn test_compare_stale_idl() {
+    let idl = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/events/stale_idl.json");
+    let output = common::analyze_with("events", &["--compare-idl", &idl.to_string_lossy()]);
+    let findings: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("Find") && line.contains("IDL"))
+        .collect();
+    assert_eq!(
+        findings,
+        [
+            "Find error: `deposit.vault` is writable in the IDL but not in the program",
+            "Find error: discriminator of account type `Vault` is d308e82b02987576 in the IDL but d308e82b02987577 in the program",
+            "Find error: discriminator of instruction `deposit` is f223c68952e1f2b7 in the IDL but f223c68952e1f2b6 in the program",
+            "Find warning: error `Locked` of the IDL is not in the program",
+            "Find warning: event `Settled` of the program is not in the IDL",
+            "Find warning: instruction `rename` of the program is not in the IDL",
+            "Find warning: instruction `withdraw` of the IDL is not in the program",
+        ]
+    );
+}
@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_ignored_check_result() {
+    let output = common::analyze("ignored_check");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("is never used"))
+        .collect();
+    // `withdraw_checked` runs the same checks through `require_keys_eq!`,
+    // `require!` and `?`.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: result of `eq` at lib.rs:14 in `ignored_check::withdraw_unchecked` is never used",
+            "Find warning: result of `validate()` at lib.rs:15 in `ignored_check::withdraw_unchecked` is never used",
+        ]
+    );
+}
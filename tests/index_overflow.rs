@@ -0,0 +1,35 @@
+mod common;
+
+#[test]
+fn test_index_overflow() {
+    let output = common::analyze("index_overflow");
+    let findings: Vec<&str> = output
+        .lines()
+        .filter(|line| line.contains("that can overflow"))
+        .collect();
+    // `read_checked` uses `checked_add`, and the sum in `read_total` is only
+    // compared, not used as an index.
+    assert_eq!(
+        findings,
+        [
+            "Find warning: index at lib.rs:12 in `index_overflow::read_at` is computed with arithmetic on `start`, `len` that can overflow",
+            "Find warning: index at lib.rs:17 in `index_overflow::read_slot` is computed with arithmetic on `slot` that can overflow",
+            "Find warning: index at lib.rs:26 in `index_overflow::read_copied` is computed with arithmetic on `slot` that can overflow",
+        ]
+    );
+}
+
+#[test]
+fn test_index_overflow_slice() {
+    let output = common::analyze("index_overflow");
+    let lines: Vec<&str> = output.lines().collect();
+    let finding = lines
+        .iter()
+        .position(|line| line.contains("`index_overflow::read_copied`"))
+        .expect("no finding for `read_copied`");
+    // The sum reaches the index through the tuple `pair` and its field `copied`.
+    assert_eq!(
+        lines[finding + 1],
+        "    note: computed at lib.rs:21, lib.rs:24, lib.rs:25"
+    );
+}
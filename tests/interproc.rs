@@ -0,0 +1,63 @@
+mod common;
+
+/// The `flow` line of `function` in `output`.
+fn flow<'o>(output: &'o str, function: &str) -> Option<&'o str> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix(&format!("flow {function}: ")))
+}
+
+fn analyze_flows() -> String {
+    common::analyze_with("interproc_taint", &["--analyzer-flows"])
+}
+
+#[test]
+fn test_flows_flag() {
+    let output = common::analyze("interproc_taint");
+    assert!(
+        !output.lines().any(|line| line.starts_with("flow ")),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_taint_through_helper() {
+    let output = analyze_flows();
+    // `amount`, argument 1, reaches `invoke` through the return value of
+    // `scale`, but not through `fee`, which ignores it. The result of `invoke`
+    // carries none of its arguments, so `amount` does not reach the return
+    // value; `accounts` does, through the error `next_account_info` may return,
+    // as calls into other crates pass on the taint of all their arguments.
+    assert_eq!(
+        flow(&output, "transfer"),
+        Some(
+            "arg 0 -> return, arg 0 -> solana_program::program::invoke, arg 1 -> solana_program::program::invoke"
+        ),
+        "{output}"
+    );
+    assert_eq!(
+        flow(&output, "transfer_fee"),
+        Some("arg 0 -> return, arg 0 -> solana_program::program::invoke"),
+        "{output}"
+    );
+    assert_eq!(flow(&output, "scale"), Some("arg 0 -> return"), "{output}");
+    assert_eq!(flow(&output, "fee"), None, "{output}");
+    // The instruction data reaches `invoke` through `transfer`.
+    assert!(
+        flow(&output, "process_instruction")
+            .is_some_and(|flow| flow.contains("arg 2 -> solana_program::program::invoke")),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_recursive_summaries() {
+    let output = analyze_flows();
+    // `countdown` only returns its argument through `step`, which calls it back.
+    assert_eq!(
+        flow(&output, "countdown"),
+        Some("arg 0 -> return"),
+        "{output}"
+    );
+    assert_eq!(flow(&output, "step"), Some("arg 0 -> return"), "{output}");
+}
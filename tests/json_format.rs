@@ -0,0 +1,88 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+use solana_program_analyzer::AnalysisReport;
+
+/// Set to rewrite the snapshots from the current output.
+const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// The JSON report of `fixture`, printed to stdout, with the analyzer version
+/// and the other fixtures, by absolute path, left out.
+fn json_report(fixture: &str) -> Value {
+    let output = common::command(fixture, &["--analyzer-format=json"])
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let mut report: Value = serde_json::from_slice(&output.stdout).unwrap_or_else(|err| {
+        panic!(
+            "stdout is not JSON: {err}\n{}",
+            String::from_utf8_lossy(&output.stdout)
+        )
+    });
+    report["tool"]["version"] = Value::from("<version>");
+    report["workspace_programs"] = Value::from("<fixtures>");
+    report
+}
+
+/// Compare the JSON report of `fixture` with `tests/snapshots/<fixture>.json`,
+/// so changes to the schema show up in review.
+fn assert_snapshot(fixture: &str) {
+    let report = json_report(fixture);
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{fixture}.json"));
+    let actual = serde_json::to_string_pretty(&report).unwrap() + "\n";
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        actual == expected,
+        "the JSON report of {fixture} differs from {}; rerun with {UPDATE_ENV}=1 if \
+         the change is intended\n{actual}",
+        path.display()
+    );
+}
+
+#[test]
+fn test_anchor_snapshot() {
+    assert_snapshot("cpi_calls");
+}
+
+#[test]
+fn test_native_snapshot() {
+    assert_snapshot("signed_seeds");
+}
+
+#[test]
+fn test_output_file() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let path = dir.join("json_format.json");
+    let report_path = dir.join("json_format_report.json");
+    let output = common::analyze_with(
+        "signed_seeds",
+        &[
+            "--analyzer-format=json",
+            &format!("--analyzer-output={}", path.display()),
+            "--report",
+            &report_path.to_string_lossy(),
+        ],
+    );
+    // The text is still printed when the report goes to a file.
+    assert!(output.contains("native instruction 0"), "{output}");
+    let report = AnalysisReport::read(&path).unwrap();
+    assert_eq!(report.crate_name, "signed_seeds");
+    assert_eq!(report.model.native_instructions.len(), 3);
+    // `--report` writes the same report.
+    assert_eq!(AnalysisReport::read(&report_path).unwrap(), report);
+}
@@ -0,0 +1,29 @@
+mod common;
+
+#[test]
+fn test_account_layouts() {
+    let output = common::analyze_with("layouts", &["--layouts"]);
+    let layouts: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("layout "))
+        .collect();
+    for line in [
+        "layout Vault: 65 bytes, discriminator 8 bytes",
+        "layout Vault.owner: anchor_lang::prelude::Pubkey at 8, 32 bytes",
+        "layout Vault.balance: u64 at 40, 8 bytes",
+        "layout Vault.bump: u8 at 48, 1 bytes",
+        "layout Vault.history: [u32; 4] at 49, 16 bytes",
+        // Fields after `name` start at least at the printed offset.
+        "layout Profile: at least 47 bytes (dynamic), discriminator 8 bytes",
+        "layout Profile.name: std::string::String at 40, at least 4 bytes (dynamic)",
+        "layout Profile.score: u16 at 44+, 2 bytes",
+        // Variants of different sizes make the enum dynamic.
+        "layout Profile.tier: Tier at 46+, at least 1 bytes (dynamic)",
+        "layout Flag: 3 bytes, discriminator 2 bytes",
+        "layout Flag.on: bool at 2, 1 bytes",
+    ] {
+        assert!(layouts.contains(&line), "missing `{line}` in {layouts:#?}");
+    }
+
+    assert!(!common::analyze("layouts").contains("layout "));
+}
@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_logged_messages() {
+    let output = common::analyze("logging");
+    let logs: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("log in "))
+        .collect();
+    assert_eq!(
+        logs,
+        [
+            r#"log in logging::initialize: "Initializing vault" ()"#,
+            r#"log in logging::initialize: "vault {} bump {}" (anchor_lang::prelude::Pubkey, u8)"#,
+            r#"log in helpers::log_deposit: "deposit" ()"#,
+            r#"log in helpers::log_deposit: "{} deposited {:?}" (&anchor_lang::prelude::Pubkey, u64)"#,
+        ]
+    );
+}
@@ -0,0 +1,32 @@
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_manifest_path() {
+    let example = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/fee_math");
+    let output = Command::new(env!("CARGO_BIN_EXE_solana-program-analyzer"))
+        .arg("--manifest-path")
+        .arg(&example)
+        .env_remove("SOLANA_ANALYZER_TARGET")
+        .output()
+        .expect("failed to run the analyzer");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    // The target is the crate named in the manifest, not the default.
+    assert!(stdout.contains("crate: fee_math"), "{stdout}");
+    assert!(
+        stdout.contains("Find warning: Contains f32::round or f64::round"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_manifest_path_missing_crate() {
+    let output = Command::new(env!("CARGO_BIN_EXE_solana-program-analyzer"))
+        .args(["--manifest-path", "no/such/crate"])
+        .output()
+        .expect("failed to run the analyzer");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cargo.toml not found"));
+}
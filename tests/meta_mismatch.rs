@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_account_meta_mismatch() {
+    let output = common::analyze("meta_mismatch");
+    let errors: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("declared mut"))
+        .collect();
+    // The generated clients of `Withdraw` agree with the struct; only the
+    // hand-written one is reported.
+    assert_eq!(
+        errors,
+        [
+            "Find error: account `authority` in `Withdraw` is not declared mut but the client sends it writable",
+            "Find error: account `vault` in `Withdraw` is declared mut but the client sends it readonly",
+        ]
+    );
+}
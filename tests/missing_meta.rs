@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_missing_account_meta() {
+    let output = common::analyze("missing_meta");
+    let errors: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("meta in the") || line.contains("sends a meta"))
+        .collect();
+    // The generated clients send every field, the optional `treasury` and the
+    // nested `common` included; only the stale hand-written ones are reported.
+    assert_eq!(
+        errors,
+        [
+            "Find error: account `recipient` in `Transfer` has no meta in the client `client::__client_accounts_transfer::Transfer`",
+            "Find error: the CPI client `client::__cpi_client_accounts_transfer::Transfer` sends a meta for `fee_payer`, which `Transfer` does not have",
+        ]
+    );
+}
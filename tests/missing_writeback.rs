@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_missing_writeback() {
+    let output = common::analyze("missing_writeback");
+    let warnings: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| line.contains("without serializing it back"))
+        .collect();
+    // `deposit` writes the vault back on its only `Ok` path, and `balance`
+    // does not change it.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: `skim` changes the `Vault` decoded from the data of `vault` at lib.rs:62 without serializing it back on every path",
+            "Find warning: `withdraw` changes the `Vault` decoded from the data of `vault` at lib.rs:51 without serializing it back on every path",
+        ]
+    );
+}
@@ -0,0 +1,24 @@
+mod common;
+
+#[test]
+fn test_modular_sdk_accounts() {
+    let output = common::analyze("modular_sdk");
+    assert!(
+        output.contains("entrypoint native: process_instruction"),
+        "{output}"
+    );
+    let accounts: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("native account "))
+        .collect();
+    // `AccountInfo` comes from `solana_account_info` and `invoke` from
+    // `solana_cpi`, with no `solana_program` to re-export them.
+    assert_eq!(
+        accounts,
+        [
+            "native account transfer #0 from: readonly, passed to invoke, signer-checked: yes, owner-checked: no",
+            "native account transfer #1 to: readonly, passed to invoke, signer-checked: no, owner-checked: yes",
+            "native account transfer #2 target_program: readonly, signer-checked: no, owner-checked: no",
+        ]
+    );
+}
@@ -0,0 +1,43 @@
+mod common;
+
+#[test]
+fn test_native_instructions() {
+    let output = common::analyze("native_dispatch");
+    let instructions: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("native instruction "))
+        .collect();
+    // The tags are those `unpack` decodes, the handlers those the arms of
+    // `Processor::process` call.
+    assert_eq!(
+        instructions,
+        [
+            "native instruction 0 Initialize: Processor::process_initialize",
+            "native instruction 1 Deposit: Processor::process_deposit",
+            "native instruction 2 Withdraw: Processor::process_withdraw",
+        ]
+    );
+}
+
+#[test]
+fn test_native_accounts() {
+    let output = common::analyze("native_dispatch");
+    let accounts: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("native account "))
+        .collect();
+    // Positions follow the `next_account_info` calls of each handler.
+    assert_eq!(
+        accounts,
+        [
+            "native account Processor::process_initialize #0 vault: writable, signer-checked: no, owner-checked: yes",
+            "native account Processor::process_initialize #1 authority: readonly, signer-checked: yes, owner-checked: no",
+            "native account Processor::process_deposit #0 vault: writable, deserialized as VaultState, passed to invoke, signer-checked: no, owner-checked: no",
+            "native account Processor::process_deposit #1 depositor: readonly, passed to invoke, signer-checked: no, owner-checked: no",
+            "native account Processor::process_deposit #2 system_program: readonly, passed to invoke, signer-checked: no, owner-checked: no",
+            "native account Processor::process_withdraw #0 vault: writable, deserialized as VaultState, signer-checked: no, owner-checked: no",
+            "native account Processor::process_withdraw #1 authority: readonly, signer-checked: yes, owner-checked: no",
+            "native account Processor::process_withdraw #2 recipient: writable, signer-checked: no, owner-checked: no",
+        ]
+    );
+}
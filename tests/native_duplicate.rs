@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_native_duplicate_mutable_account() {
+    let output = common::analyze("native_duplicate");
+    let errors: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| line.contains("without checking that their keys differ"))
+        .collect();
+    // `transfer_checked` compares the keys before writing, and `reward` writes
+    // accounts of two different types; `transfer_late` compares them too late.
+    assert_eq!(
+        errors,
+        [
+            "Find error: `transfer_late` writes accounts #0 `from` and #1 `to`, both deserialized as `UserPoints`, without checking that their keys differ",
+            "Find error: `transfer` writes accounts #0 `from` and #1 `to`, both deserialized as `UserPoints`, without checking that their keys differ",
+        ]
+    );
+}
@@ -0,0 +1,34 @@
+mod common;
+
+#[test]
+fn test_owner_checked_against_declared_id() {
+    let output = common::analyze("native_program_id");
+    let warnings: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| {
+            line.contains("hardcoded_program_id") || line.contains("declared program id")
+        })
+        .collect();
+    // `deposit` checks against the `program_id` the entrypoint passes it.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: `close` compares an account owner with the declared program id at lib.rs:49 instead of the `program_id` passed to the entrypoint; redeployed under another id, the program rejects its own accounts and trusts those the old id owns",
+            "Find warning: `withdraw` compares an account owner with the declared program id at lib.rs:39 instead of the `program_id` passed to the entrypoint; redeployed under another id, the program rejects its own accounts and trusts those the old id owns",
+        ]
+    );
+}
+
+#[test]
+fn test_unused_program_id_argument() {
+    // `pack_layout` declares its id but names the argument `_program_id`.
+    let output = common::analyze("pack_layout");
+    assert!(
+        output.contains(
+            "Find warning: `process_instruction` never uses its `program_id` argument, while the program declares its id as Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"
+        ),
+        "{output}"
+    );
+    let output = common::analyze("native_program_id");
+    assert!(!output.contains("never uses its `program_id`"), "{output}");
+}
@@ -0,0 +1,40 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use solana_program_analyzer::AnalysisReport;
+
+#[test]
+fn test_unpinned_oracles() {
+    let output = common::analyze("oracle_pinning");
+    let errors: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| line.starts_with("Find error: oracle account "))
+        .collect();
+    // `ReadByAddress` and `ReadByOwner` pin the price with a constraint and
+    // `read_checked` compares its key.
+    assert_eq!(
+        errors,
+        [
+            "Find error: oracle account `feed` (PriceFeed) in `ReadFeed` is missing an `address` or `owner` constraint",
+            "Find error: oracle account `feed` (PriceFeed) in `ReadPair` is missing an `address` or `owner` constraint",
+            "Find error: oracle account `price` (PriceUpdateV2) in `ReadPair` is missing an `address` or `owner` constraint",
+            "Find error: oracle account `price` (PriceUpdateV2) in `ReadUnpinned` is missing an `address` or `owner` constraint",
+        ]
+    );
+}
+
+#[test]
+fn test_fields_of_one_struct_have_their_own_fingerprints() {
+    let output = common::command("oracle_pinning", &["--analyzer-format=json"])
+        .output()
+        .expect("failed to run cargo");
+    let report: AnalysisReport = serde_json::from_slice(&output.stdout).unwrap();
+    let pair: Vec<_> = report
+        .findings
+        .iter()
+        .filter(|finding| finding.message.contains("in `ReadPair`"))
+        .collect();
+    assert_eq!(pair.len(), 2, "{pair:?}");
+    assert_ne!(pair[0].fingerprint, pair[1].fingerprint);
+}
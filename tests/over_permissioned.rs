@@ -0,0 +1,51 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::path::Path;
+
+use solana_program_analyzer::aggregate::AnalysisReport;
+
+#[test]
+fn test_mut_never_written() {
+    let output = common::analyze("over_permissioned");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("is marked mut but never written"))
+        .collect();
+    // `receiver` of `CloseVault` takes the lamports of the closed vault and
+    // `authority` of `Initialize` pays for the new config, so both are written.
+    assert_eq!(
+        warnings,
+        ["Find warning: account `config` in `Deposit` is marked mut but never written"]
+    );
+}
+
+#[test]
+fn test_grouped_under_handler() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("over_permissioned.json");
+    let path_arg = path.to_string_lossy().into_owned();
+    let output = common::analyze_with("over_permissioned", &["--report", &path_arg]);
+    // The finding is about `Deposit`, which `deposit` takes.
+    let lines: Vec<_> = output
+        .lines()
+        .skip_while(|line| !line.starts_with("== "))
+        .collect();
+    assert_eq!(
+        lines[1..4],
+        [
+            "-- handler deposit: 1 finding",
+            "Find warning: account `config` in `Deposit` is marked mut but never written",
+            "-- clean: initialize, close_vault",
+        ],
+        "{output}"
+    );
+
+    let report = AnalysisReport::read(&path).unwrap();
+    let handlers: Vec<_> = report
+        .handlers
+        .iter()
+        .map(|handler| (handler.handler.as_str(), handler.findings.len()))
+        .collect();
+    assert_eq!(handlers, [("deposit", 1)]);
+}
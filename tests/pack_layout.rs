@@ -0,0 +1,33 @@
+mod common;
+
+#[test]
+fn test_pack_layout() {
+    let output = common::analyze_with("pack_layout", &["--layouts"]);
+    let layouts: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("pack layout "))
+        .collect();
+    assert_eq!(layouts, ["pack layout Escrow: LEN 41, IsInitialized"]);
+    // `unpack` and `unpack_unchecked` decode the escrow like Borsh would.
+    assert!(
+        output.contains(
+            "native account release #0 escrow: writable, deserialized as Escrow, signer-checked: no, owner-checked: no"
+        ),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_unchecked_unpack() {
+    let output = common::analyze("pack_layout");
+    let findings = common::findings(&output);
+    // `initialize` checks `is_initialized()` after `unpack_unchecked`. The
+    // entrypoint ignores its `program_id`, see `native_program_id`.
+    assert_eq!(
+        findings,
+        [
+            "Find warning: `process_instruction` never uses its `program_id` argument, while the program declares its id as Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS; redeployed under another id, the program rejects its own accounts and trusts those the old id owns",
+            "Find warning: `release` decodes `Escrow` with `unpack_unchecked` at lib.rs:90 and reads its fields without checking `is_initialized`",
+        ]
+    );
+}
@@ -0,0 +1,18 @@
+mod common;
+
+#[test]
+fn test_non_signer_payer() {
+    let output = common::analyze("payer_signer");
+    let warnings: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| line.contains("is initialized with payer"))
+        .collect();
+    // `OpenSigned` pays with a `Signer`, which is fine.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: account `vault` in `Fund` is initialized with payer `funder`, which is a `SystemAccount` rather than a `Signer`",
+            "Find warning: account `vault` in `Open` is initialized with payer `user`, which is an unchecked account rather than a `Signer`",
+        ]
+    );
+}
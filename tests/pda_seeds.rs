@@ -0,0 +1,22 @@
+mod common;
+
+use common::constraint_lines;
+
+#[test]
+fn test_pda_seeds() {
+    let output = common::analyze("pda_seeds");
+    assert_eq!(
+        constraint_lines(&output, "Open"),
+        [
+            r#"Open.vault: [Seeds([Bytes([118, 97, 117, 108, 116]), Key("owner"), Arg("index")]), CanonicalBump, Init { payer: "owner", space: Some(17), if_needed: false }]"#,
+            // `[b"config"]` is promoted to a constant the tracer can't read.
+            r#"Open.config: [Seeds([Unknown]), CanonicalBump]"#,
+        ]
+    );
+    assert_eq!(
+        constraint_lines(&output, "Touch"),
+        [
+            r#"Touch.vault: [Seeds([Bytes([118, 97, 117, 108, 116]), Key("owner"), Data("vault", "index")]), Bump(Data("vault", "bump"))]"#,
+        ]
+    );
+}
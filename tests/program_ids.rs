@@ -0,0 +1,93 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use solana_program_analyzer::AnalysisReport;
+
+#[test]
+fn test_program_id_read_by_check_id() {
+    let output = common::analyze("program_ids");
+    let ids: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("program id"))
+        .collect();
+    assert_eq!(
+        ids,
+        ["program id: Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"]
+    );
+}
+
+#[test]
+fn test_expect_program_id() {
+    let (success, output) = common::try_analyze(
+        "program_ids",
+        &[
+            "--expect-program-id",
+            "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS",
+        ],
+    );
+    assert!(success, "{output}");
+    assert!(!output.contains("does not match"), "{output}");
+
+    let (success, output) = common::try_analyze(
+        "program_ids",
+        &[
+            "--expect-program-id",
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        ],
+    );
+    assert!(!success);
+    assert!(
+        output.contains(
+            "Find error: program id Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS does not match the expected TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        ),
+        "{output}"
+    );
+
+    // The mismatch is a finding of the reports like any other.
+    let output = common::command(
+        "program_ids",
+        &[
+            "--expect-program-id",
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "--analyzer-format=json",
+        ],
+    )
+    .output()
+    .expect("failed to run cargo");
+    assert!(!output.status.success());
+    let report: AnalysisReport = serde_json::from_slice(&output.stdout).unwrap();
+    let mismatch = report
+        .findings
+        .iter()
+        .find(|finding| finding.checker == "expect_program_id")
+        .expect("no expect_program_id finding in the report");
+    assert_eq!(mismatch.severity, "error");
+}
+
+#[test]
+fn test_expect_malformed_program_id() {
+    let (success, output) =
+        common::try_analyze("program_ids", &["--expect-program-id", "not-base58"]);
+    assert!(!success);
+    assert!(
+        output.contains("error: malformed program id `not-base58`: not base58"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_program_id_from_pubkey_macro() {
+    // `ID` is built from the bytes of a `pubkey!`, and aliased by `PROGRAM_ID`.
+    let output = common::analyze("pubkey_macro");
+    assert!(
+        output.contains("program id: Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"),
+        "{output}"
+    );
+    assert!(
+        output.contains(
+            "Find warning: `withdraw` compares an account owner with the declared program id at lib.rs:44"
+        ),
+        "{output}"
+    );
+}
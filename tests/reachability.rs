@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_reachable_through_fn_pointers_and_closures() {
+    let output = common::analyze("reachability");
+    let logs: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("log in "))
+        .collect();
+    // The handlers are only named in the `HANDLERS` table, `log_account` in a
+    // closure run by `for_each`; `unused` is reached from nothing.
+    assert_eq!(
+        logs,
+        [
+            r#"log in log_value::<usize>: "value {:?}" (usize)"#,
+            r#"log in log_account: "account {}" (&solana_program::solana_program_entrypoint::__Pubkey)"#,
+        ]
+    );
+}
@@ -0,0 +1,38 @@
+mod common;
+
+#[test]
+fn test_unchecked_remaining_accounts() {
+    let output = common::analyze("remaining_accounts");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("remaining accounts without checking"))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: `remaining_accounts[1]` at lib.rs:12 in `remaining_accounts::route` assumes at least 2 remaining accounts without checking their number",
+            "Find warning: `remaining_accounts[2..]` at lib.rs:25 in `remaining_accounts::route_tail` assumes at least 2 remaining accounts without checking their number",
+        ]
+    );
+}
+
+#[test]
+fn test_remaining_accounts_use() {
+    let output = common::analyze("remaining_accounts");
+    let uses: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("remaining accounts "))
+        .collect();
+    assert_eq!(
+        uses,
+        [
+            "remaining accounts route: 2",
+            "remaining accounts route_checked: 2",
+            "remaining accounts route_tail: 2 or more",
+            "remaining accounts route_first: 1",
+            "remaining accounts route_all: any number",
+            "remaining accounts route_nth: any number",
+            "remaining accounts count: length only",
+        ]
+    );
+}
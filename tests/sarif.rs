@@ -0,0 +1,137 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use jsonschema::Validator;
+use serde_json::Value;
+use solana_program_analyzer::checker_descriptions;
+
+/// The SARIF log of `fixture`, printed to stdout.
+fn sarif_log(fixture: &str) -> Value {
+    let output = common::command(fixture, &["--analyzer-format=sarif"])
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap_or_else(|err| {
+        panic!(
+            "stdout is not JSON: {err}\n{}",
+            String::from_utf8_lossy(&output.stdout)
+        )
+    })
+}
+
+/// Validates against `tests/schemas/sarif-2.1.0-subset.json`.
+fn validator() -> Validator {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/schemas/sarif-2.1.0-subset.json");
+    let schema: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    jsonschema::validator_for(&schema).expect("the vendored schema is valid")
+}
+
+/// The violations of the schema by `log`, at their JSON pointer.
+fn violations(log: &Value) -> Vec<String> {
+    validator()
+        .iter_errors(log)
+        .map(|error| format!("{}: {error}", error.instance_path))
+        .collect()
+}
+
+fn assert_valid(log: &Value) {
+    let violations = violations(log);
+    assert!(violations.is_empty(), "{violations:#?}\n{log:#}");
+}
+
+#[test]
+fn test_schema_rejects() {
+    let log = serde_json::json!({
+        "version": "2.0.0",
+        "runs": [{ "tool": { "driver": {} }, "results": [{ "message": {}, "ruleIndex": -2 }] }],
+        "extra": true,
+    });
+    let violations = violations(&log);
+    for at in [
+        "/version",
+        "/runs/0/tool/driver",
+        "/runs/0/results/0/message",
+        "/runs/0/results/0/ruleIndex",
+    ] {
+        assert!(
+            violations
+                .iter()
+                .any(|violation| violation.starts_with(&format!("{at}: "))),
+            "no violation at {at}: {violations:#?}"
+        );
+    }
+    assert!(
+        violations
+            .iter()
+            .any(|violation| violation.contains("extra")),
+        "{violations:#?}"
+    );
+}
+
+#[test]
+fn test_sarif_log() {
+    let log = sarif_log("sysvar_accounts");
+    assert_valid(&log);
+    let run = &log["runs"][0];
+    let driver = &run["tool"]["driver"];
+    assert_eq!(driver["name"], "solana-program-analyzer");
+    let rules = driver["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), checker_descriptions().len());
+    assert!(rules.iter().all(|rule| rule["helpUri"].is_string()));
+    assert!(
+        run["originalUriBaseIds"]["%SRCROOT%"]["uri"]
+            .as_str()
+            .unwrap()
+            .ends_with("tests/fixtures/")
+    );
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2, "{results:#?}");
+    let stamp = results
+        .iter()
+        .find(|result| {
+            result["message"]["text"]
+                .as_str()
+                .unwrap()
+                .contains("`stamp`")
+        })
+        .unwrap();
+    assert_eq!(stamp["ruleId"], "unchecked_sysvar_account");
+    let rule = &rules[stamp["ruleIndex"].as_u64().unwrap() as usize];
+    assert_eq!(rule["id"], "unchecked_sysvar_account");
+    let location = &stamp["locations"][0]["physicalLocation"];
+    assert_eq!(
+        location["artifactLocation"]["uri"],
+        "sysvar_accounts/src/lib.rs"
+    );
+    assert_eq!(location["artifactLocation"]["uriBaseId"], "%SRCROOT%");
+    assert_eq!(location["region"]["startLine"], 33);
+    assert_eq!(
+        stamp["partialFingerprints"]["solanaAnalyzer/v1"]
+            .as_str()
+            .unwrap()
+            .len(),
+        16
+    );
+}
+
+#[test]
+fn test_related_locations() {
+    let log = sarif_log("duplicate_mutable");
+    assert_valid(&log);
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    assert!(
+        results.iter().any(|result| result["relatedLocations"]
+            .as_array()
+            .is_some_and(|r| !r.is_empty())),
+        "{results:#?}"
+    );
+}
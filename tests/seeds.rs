@@ -0,0 +1,37 @@
+mod common;
+
+#[test]
+fn test_extract_constants() {
+    let output = common::analyze("seed_typo");
+    let constants: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("constant ") || line.starts_with("seed "))
+        .collect();
+    assert_eq!(
+        constants,
+        [
+            "constant ID_CONST: anchor_lang::prelude::Pubkey = Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS",
+            "constant MAX_DEPOSIT: u64 = 1000000",
+            "constant VAULT_SEED: &'static [u8] = b\"vault\"",
+            "seed Deposit.vault[0]: [u8; 5] = b\"vault\"",
+            "seed Promote.tier1[0]: [u8; 5] = b\"tier1\"",
+            "seed Promote.tier2[0]: [u8; 5] = b\"tier2\"",
+            "seed Withdraw.vault[0]: [u8; 5] = b\"vaulr\"",
+        ]
+    );
+}
+
+#[test]
+fn test_near_miss_seed() {
+    let output = common::analyze("seed_typo");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("differ by a single byte"))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: seeds b\"vaulr\" in `Withdraw.vault` and b\"vault\" in `Deposit.vault` differ by a single byte"
+        ]
+    );
+}
@@ -0,0 +1,17 @@
+mod common;
+
+#[test]
+fn test_user_signer_seeds() {
+    let output = common::analyze("signed_seeds");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("with signer seeds made only of"))
+        .collect();
+    // `sweep` namespaces its seeds with `b"vault"`, `refund` with the owner's key.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: `withdraw` calls `invoke_signed` at lib.rs:36 with signer seeds made only of instruction arguments, without a constant or an account key"
+        ]
+    );
+}
@@ -0,0 +1,16 @@
+mod common;
+
+#[test]
+fn test_unpinned_singleton_account() {
+    let output = common::analyze("singleton_config");
+    let errors: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("singleton account"))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            "Find error: singleton account `config` (Config) in `SetFeeUnpinned` is missing an `address` or `seeds` constraint"
+        ]
+    );
+}
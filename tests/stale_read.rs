@@ -0,0 +1,16 @@
+mod common;
+
+#[test]
+fn test_read_after_cpi_without_reload() {
+    let output = common::analyze("cpi_reload");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("after a CPI"))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: account `pool` in `Deposit` is read at bb13 in `cpi_reload::deposit` after a CPI at bb5 without `reload()`"
+        ]
+    );
+}
@@ -0,0 +1,24 @@
+mod common;
+
+fn findings(output: &str) -> Vec<&str> {
+    common::findings(output)
+        .into_iter()
+        .filter(|line| line.contains("suppression::") || line.contains("Withdraw"))
+        .collect()
+}
+
+#[test]
+fn test_allow_attribute_and_levels() {
+    let output = common::analyze("suppression");
+    assert_eq!(
+        findings(&output),
+        [
+            // `deadline_allowed` is allowed.
+            "Find warning: `suppression::deadline` compares `unix_timestamp` == 1700000000 (bb4), which clock drift can skip",
+            // Raised from a warning by `cfx.toml`; `WithdrawAllowed` is allowed.
+            "Find error: account `vault` in `Withdraw` is marked mut but never written",
+        ]
+    );
+    // Turned off by `cfx.toml`.
+    assert!(!output.contains("CU per iteration"), "{output}");
+}
@@ -0,0 +1,20 @@
+mod common;
+
+#[test]
+fn test_unchecked_sysvar_account() {
+    let output = common::analyze("sysvar_accounts");
+    let warnings: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| line.contains("without first checking its key against the sysvar id"))
+        .collect();
+    // `stamp_checked` compares the key with `sysvar::clock::ID` and `charge`
+    // calls `check_id` before the read; the comparison in `stamp_late` comes
+    // after it.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: `stamp_late` reads `Clock` from account #0 `clock_info` at lib.rs:64 without first checking its key against the sysvar id",
+            "Find warning: `stamp` reads `Clock` from account #0 `clock_info` at lib.rs:33 without first checking its key against the sysvar id",
+        ]
+    );
+}
@@ -0,0 +1,20 @@
+mod common;
+
+#[test]
+fn test_target_crate_flag() {
+    let (success, output) = common::run(
+        common::command("cpi_callee", &["--target-crate", "cpi_callee"])
+            .env_remove("SOLANA_ANALYZER_TARGET"),
+    );
+    assert!(success, "{output}");
+    assert!(output.contains("crate: cpi_callee"), "{output}");
+
+    // The flag wins over the environment, and other crates compile without
+    // going through the analyzer.
+    let (success, output) = common::run(
+        common::command("cpi_callee", &["--target-crate", "cpi_caller"])
+            .env("SOLANA_ANALYZER_TARGET", "cpi_callee"),
+    );
+    assert!(success, "{output}");
+    assert!(!output.contains("Analyzing"), "{output}");
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+/// Set to rewrite the snapshots from the current output.
+const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// The findings section of the text output of `fixture`, from its `== crate`
+/// header on, without the time it took.
+fn text_report(fixture: &str) -> String {
+    let output = common::command(fixture, &[])
+        .output()
+        .expect("failed to run cargo");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    let start = stdout
+        .find("\n== ")
+        .unwrap_or_else(|| panic!("no findings header\n{stdout}"));
+    common::without_elapsed(&stdout[start + 1..])
+}
+
+#[test]
+fn test_grouped_snapshot() {
+    let fixture = "grouped_findings";
+    let actual = text_report(fixture);
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{fixture}.txt"));
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        actual == expected,
+        "the text report of {fixture} differs from {}; rerun with {UPDATE_ENV}=1 if \
+         the change is intended\n{actual}",
+        path.display()
+    );
+}
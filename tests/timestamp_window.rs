@@ -0,0 +1,19 @@
+mod common;
+
+#[test]
+fn test_fragile_timestamps() {
+    let output = common::analyze("timestamp_window");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("Find warning: ") && line.contains("`unix_timestamp`"))
+        .collect();
+    // `vest` allows a day, `expire` has no upper bound, and the two bounds of
+    // `phase` sit on different branches.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: `timestamp_window::claim_at` compares `unix_timestamp` == 1700000000 (bb4), which clock drift can skip",
+            "Find warning: `timestamp_window::bid` accepts `unix_timestamp` only within [Auction.start, Auction.start + 1), a 1s window",
+        ]
+    );
+}
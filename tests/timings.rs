@@ -0,0 +1,60 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use solana_program_analyzer::{AnalysisReport, checker_descriptions};
+
+/// `--analyzer-timings` times every registered checker and the parts of the
+/// context they extract, in the table and in the JSON report.
+#[test]
+fn test_timings_cover_every_checker() {
+    let output = common::command(
+        "duplicate_mutable",
+        &["--analyzer-format=json", "--analyzer-timings"],
+    )
+    .output()
+    .expect("failed to run cargo");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    // The text, table included, goes to stderr with the report on stdout.
+    assert!(
+        stderr
+            .lines()
+            .any(|line| line.starts_with("pass ") && line.ends_with(" items")),
+        "{stderr}"
+    );
+
+    let report: AnalysisReport = serde_json::from_slice(&output.stdout).unwrap();
+    let timings = serde_json::to_value(report.timings.expect("no timings in the report")).unwrap();
+    let passes = |kind: &str| -> BTreeSet<String> {
+        timings
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|timing| timing["kind"] == kind)
+            .map(|timing| timing["pass"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let checkers: BTreeSet<String> = checker_descriptions()
+        .into_iter()
+        .map(|(id, _, _)| id.to_string())
+        .collect();
+    assert_eq!(passes("checker"), checkers);
+    assert!(
+        passes("extraction").contains("anchor_accounts"),
+        "{timings}"
+    );
+    for timing in timings.as_array().unwrap() {
+        assert!(timing["micros"].is_u64(), "{timing}");
+        assert!(timing["items"].is_u64(), "{timing}");
+    }
+    // The duplicate accounts of the fixture are findings of their checker.
+    assert!(
+        timings.as_array().unwrap().iter().any(|timing| {
+            timing["pass"] == "duplicate_mutable_account" && timing["items"].as_u64() > Some(0)
+        }),
+        "{timings}"
+    );
+}
@@ -0,0 +1,18 @@
+mod common;
+
+#[test]
+fn test_interface_accounts() {
+    let output = common::analyze("token_2022");
+    let errors: Vec<_> = common::findings(&output)
+        .into_iter()
+        .filter(|line| line.contains("two mutable accounts of the same type"))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            // `Account<Vault>` and `InterfaceAccount<Vault>` hold the same data.
+            "Find error: two mutable accounts of the same type in the same Context `Sync`: `vault` and `copy` (Vault)",
+            "Find error: two mutable accounts of the same type in the same Context `Transfer`: `from` and `to` (anchor_spl::token_interface::TokenAccount)",
+        ]
+    );
+}
@@ -0,0 +1,23 @@
+mod common;
+
+#[test]
+fn test_unchecked_token_mint() {
+    let output = common::analyze_with("token_mint", &["--analyzer-checkers=unchecked_token_mint"]);
+    let findings: Vec<_> = output
+        .lines()
+        .filter(|line| line.starts_with("Find "))
+        .collect();
+    // `deposit_constrained` pins the vault with `token::mint`, and
+    // `deposit_usdc` compares the mint of `from` in the handler.
+    assert_eq!(
+        findings,
+        [
+            "Find warning: `anchor_spl::token::transfer` in `token_mint::deposit` moves tokens between `from` and `vault` without checking their mint; add a `token::mint` constraint to one of them or compare its `mint` with the expected one",
+        ],
+        "{output}"
+    );
+    assert!(
+        output.contains("DepositConstrained.vault: [TokenMint(Key(\"mint\"))]"),
+        "{output}"
+    );
+}
@@ -0,0 +1,18 @@
+mod common;
+
+#[test]
+fn test_token_account_without_owner_check() {
+    let output = common::analyze("token_owner");
+    let errors: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("as a token account"))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            "Find error: `source` in `ReadRaw` is deserialized as a token account in `token_owner::read_unchecked` without checking that its owner is the Token program",
+            "Find error: `source` in `ReadRaw` is deserialized as a token account in `token_owner::read_wrong_owner` without checking that its owner is the Token program",
+            "Find error: `source` in `ReadRaw` is deserialized as a token account in `token_owner::unpack_unchecked` without checking that its owner is the Token program",
+        ]
+    );
+}
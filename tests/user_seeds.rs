@@ -0,0 +1,17 @@
+mod common;
+
+#[test]
+fn test_user_controlled_seeds() {
+    let output = common::analyze("user_seeds");
+    let warnings: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("seeded only with instruction arguments"))
+        .collect();
+    // `CreateOwned` namespaces the same `id` with a literal and the payer's key.
+    assert_eq!(
+        warnings,
+        [
+            "Find warning: PDA `Create.order` is seeded only with instruction arguments [id], without a constant or an account key"
+        ]
+    );
+}
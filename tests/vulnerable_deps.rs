@@ -0,0 +1,49 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::path::Path;
+
+use solana_program_analyzer::aggregate::AnalysisReport;
+
+#[test]
+fn test_vulnerable_dependencies() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("vulnerable_deps.json");
+    let path_arg = path.to_string_lossy().into_owned();
+    let output = common::analyze_with("vulnerable_deps", &["--report", &path_arg]);
+    let findings: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("dependency `"))
+        .collect();
+    // `spl-token` matches a builtin advisory and `solana-program` the one of
+    // `advisories.json` listed in `solana-analyzer.toml`; the other advisory
+    // of the file is for versions the requirement does not allow.
+    assert_eq!(
+        findings,
+        [
+            "Find warning: dependency `solana-program` = \"2.3.0\" allows versions affected by TEST-0001 (>=2.0.0, <2.3.1): Example advisory on the pinned SDK",
+            "Find error: dependency `spl-token` = \">=3.0.0, <8\" allows versions affected by spl-token-3.1.0 (<3.1.1): spl-token before 3.1.1 is vulnerable",
+        ]
+    );
+
+    let report = AnalysisReport::read(&path).unwrap();
+    let locations: Vec<_> = report
+        .findings
+        .iter()
+        .filter(|finding| finding.checker == "vulnerable_dependency")
+        .filter_map(|finding| finding.location.as_ref().map(ToString::to_string))
+        .collect();
+    assert_eq!(locations.len(), 2);
+    assert!(locations[0].ends_with("vulnerable_deps/Cargo.toml:13:1"));
+    assert!(locations[1].ends_with("vulnerable_deps/Cargo.toml:14:1"));
+}
+
+#[test]
+fn test_builtin_advisories_off() {
+    let output = common::analyze_with(
+        "vulnerable_deps",
+        &["--config", "advisories.builtin = false"],
+    );
+    assert!(!output.contains("spl-token"), "{output}");
+    assert!(output.contains("TEST-0001"), "{output}");
+}
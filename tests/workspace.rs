@@ -0,0 +1,137 @@
+#![cfg_attr(feature = "driver", feature(rustc_private))]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use solana_program_analyzer::aggregate::AnalysisReport;
+use solana_program_analyzer::metadata::{discover_workspace_programs, find_workspace_root};
+
+#[test]
+fn test_discover_fixture_programs() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    assert_eq!(find_workspace_root(&fixtures.join("cpi_callee")), fixtures);
+    let programs = discover_workspace_programs(&fixtures).unwrap();
+    let callee = programs
+        .iter()
+        .find(|program| program.name == "cpi_callee")
+        .expect("cpi_callee is a program of the workspace");
+    assert_eq!(callee.path, fixtures.join("cpi_callee"));
+    assert_eq!(
+        callee.program_id.as_deref(),
+        Some("Vau1t11111111111111111111111111111111111111")
+    );
+    assert!(!programs.iter().any(|program| program.name == "target"));
+}
+
+#[test]
+fn test_discover_anchor_workspace() {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("anchor_workspace");
+    let _ = fs::remove_dir_all(&root);
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+    write(
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"programs/*\", \"cli\"]\nexclude = [\"programs/legacy\"]\n",
+    );
+    write(
+        "Anchor.toml",
+        "[programs.devnet]\nvault = \"Vau1tDev1111111111111111111111111111111111\"\n\
+         [programs.localnet]\nvault = \"Vau1t11111111111111111111111111111111111111\"\n",
+    );
+    let anchor = "[dependencies]\nanchor-lang = \"0.31.1\"\n";
+    write(
+        "programs/vault/Cargo.toml",
+        &format!("[package]\nname = \"vault\"\n{anchor}"),
+    );
+    write(
+        "programs/vault/src/lib.rs",
+        "declare_id!(\"Ignored111111111111111111111111111111111111\");\n",
+    );
+    write(
+        "programs/fee-router/Cargo.toml",
+        "[package]\nname = \"fee-router\"\n[dependencies]\nsolana-program = \"2\"\n",
+    );
+    write(
+        "programs/fee-router/src/lib.rs",
+        "solana_program::declare_id!(\"Fee111111111111111111111111111111111111111\");\n",
+    );
+    write(
+        "programs/legacy/Cargo.toml",
+        &format!("[package]\nname = \"legacy\"\n{anchor}"),
+    );
+    write(
+        "cli/Cargo.toml",
+        "[package]\nname = \"cli\"\n[dependencies]\nclap = \"4\"\n",
+    );
+
+    assert_eq!(find_workspace_root(&root.join("programs/vault")), root);
+    let programs: Vec<_> = discover_workspace_programs(&root)
+        .unwrap()
+        .into_iter()
+        .map(|program| (program.name, program.program_id))
+        .collect();
+    assert_eq!(
+        programs,
+        [
+            (
+                "fee_router".to_string(),
+                Some("Fee111111111111111111111111111111111111111".to_string())
+            ),
+            (
+                "vault".to_string(),
+                Some("Vau1t11111111111111111111111111111111111111".to_string())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_sibling_cpis() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("workspace_cpi_caller.json");
+    let path_arg = path.to_string_lossy().into_owned();
+    let output = common::analyze_with("cpi_caller", &["--report", &path_arg]);
+    assert!(
+        output
+            .contains("cpi at lib.rs:16 in cpi_caller::forward into workspace program cpi_callee"),
+        "{output}"
+    );
+
+    let report = AnalysisReport::read(&path).unwrap();
+    let callees: Vec<_> = report
+        .cpi_calls
+        .iter()
+        .map(|cpi| cpi.workspace_callee.as_deref())
+        .collect();
+    assert_eq!(callees, [Some("cpi_callee")]);
+    assert!(
+        report
+            .workspace_programs
+            .iter()
+            .any(|program| program.name == "cpi_callee")
+    );
+    assert!(
+        !report
+            .workspace_programs
+            .iter()
+            .any(|program| program.name == "cpi_caller")
+    );
+}
+
+#[test]
+fn test_untargeted_program() {
+    // Without `SOLANA_ANALYZER_TARGET`, the program cargo was asked to check is
+    // analyzed, and its dependencies are not.
+    let (success, output) =
+        common::run(common::command("cpi_callee", &[]).env_remove("SOLANA_ANALYZER_TARGET"));
+    assert!(success, "{output}");
+    assert!(
+        output.contains("program id: Vau1t11111111111111111111111111111111111111"),
+        "{output}"
+    );
+    assert_eq!(output.matches("program id: ").count(), 1, "{output}");
+}